@@ -0,0 +1,177 @@
+//! Brace blocks and media with parameters and element content
+
+use super::{
+    IntoChildren, ParametersExt, append, block_content, s, text, top_level_only, trim_breaks,
+    unwrap_nested,
+};
+use crate::{
+    BlockQuoteElement, Element, FootnoteElement, IncludeElement, MediaElement, Parameters,
+    RedirectElement, RubyElement, StyledElement,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BlockKind {
+    Styled,
+    BlockQuote,
+    Footnote,
+    Ruby,
+    Include,
+    Redirect,
+    Media,
+}
+
+/// Builder for elements made of parameters and children
+/// (`{{{#style ...}}}`, `{{{#quote}}}`, `{{{#fn}}}`, `[[...]]`, …)
+#[derive(Debug, Clone)]
+pub struct BlockBuilder {
+    kind: BlockKind,
+    parameters: Parameters,
+    children: Vec<Element>,
+}
+
+impl BlockBuilder {
+    fn new(kind: BlockKind) -> Self {
+        Self {
+            kind,
+            parameters: Parameters::new(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Set a parameter; the value is escaped
+    pub fn param(mut self, key: &str, value: &str) -> Self {
+        self.parameters.set(key, value);
+        self
+    }
+
+    /// Replace all parameters with an already escaped map
+    pub fn parameters(mut self, parameters: Parameters) -> Self {
+        self.parameters = parameters;
+        self
+    }
+
+    /// Append content after the existing children
+    pub fn content(mut self, children: impl IntoChildren) -> Self {
+        append(&mut self.children, children);
+        self
+    }
+
+    /// Build the element. Footnotes inside a footnote, links inside a link
+    /// and headers are unwrapped, and rules and comments dropped, as the parser
+    /// does not read them there; empty content becomes `[null]`.
+    pub fn build(self) -> Element {
+        let Self {
+            kind,
+            parameters,
+            children,
+        } = self;
+        let children = match kind {
+            BlockKind::Include | BlockKind::Redirect => trim_breaks(children),
+            BlockKind::Media => unwrap_nested(trim_breaks(children), &|el| {
+                top_level_only(el) || matches!(el, Element::Media(_))
+            }),
+            BlockKind::Footnote => block_content(unwrap_nested(children, &|el| {
+                matches!(el, Element::Footnote(_))
+            })),
+            BlockKind::Styled | BlockKind::BlockQuote | BlockKind::Ruby => block_content(children),
+        };
+        match kind {
+            BlockKind::Styled => Element::Styled(StyledElement {
+                span: s(),
+                open_span: s(),
+                close_span: s(),
+                parameters,
+                children,
+            }),
+            BlockKind::BlockQuote => Element::BlockQuote(BlockQuoteElement {
+                span: s(),
+                open_span: s(),
+                close_span: s(),
+                marker_spans: Vec::new(),
+                parameters,
+                children,
+            }),
+            BlockKind::Footnote => Element::Footnote(FootnoteElement {
+                span: s(),
+                open_span: s(),
+                close_span: s(),
+                footnote_index: 0,
+                parameters,
+                children,
+            }),
+            BlockKind::Ruby => Element::Ruby(RubyElement {
+                span: s(),
+                open_span: s(),
+                close_span: s(),
+                parameters,
+                children,
+            }),
+            BlockKind::Include => Element::Include(IncludeElement {
+                span: s(),
+                open_span: s(),
+                close_span: s(),
+                parameters,
+                children,
+                failure: None,
+            }),
+            BlockKind::Redirect => Element::Redirect(RedirectElement {
+                span: s(),
+                open_span: s(),
+                close_span: s(),
+                parameters,
+                children,
+            }),
+            BlockKind::Media => Element::Media(MediaElement {
+                span: s(),
+                open_span: s(),
+                close_span: s(),
+                parameters,
+                children,
+                resolved_info: None,
+            }),
+        }
+    }
+}
+
+impl Element {
+    /// `{{{#style="..." content}}}` and friends
+    pub fn styled() -> BlockBuilder {
+        BlockBuilder::new(BlockKind::Styled)
+    }
+
+    pub fn blockquote() -> BlockBuilder {
+        BlockBuilder::new(BlockKind::BlockQuote)
+    }
+
+    /// `{{{#fn}}}`; `footnote_index` is assigned by the parser
+    pub fn footnote() -> BlockBuilder {
+        BlockBuilder::new(BlockKind::Footnote)
+    }
+
+    pub fn ruby(annotation: &str) -> BlockBuilder {
+        BlockBuilder::new(BlockKind::Ruby).param("ruby", annotation)
+    }
+
+    /// `{{{#include page}}}`; template arguments are added with `param`
+    pub fn include(page: &str) -> BlockBuilder {
+        let mut builder = BlockBuilder::new(BlockKind::Include);
+        builder.children = text(page);
+        builder
+    }
+
+    pub fn redirect(page: &str) -> BlockBuilder {
+        let mut builder = BlockBuilder::new(BlockKind::Redirect);
+        builder.children = text(page);
+        builder
+    }
+
+    /// `[[...]]` with parameters only; see [`Element::link`] for the common case
+    pub fn media() -> BlockBuilder {
+        BlockBuilder::new(BlockKind::Media)
+    }
+
+    /// `[[#document="page" ...]]`
+    pub fn link(page: &str) -> BlockBuilder {
+        Element::media().param("document", page)
+    }
+}
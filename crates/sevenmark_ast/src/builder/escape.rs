@@ -0,0 +1,148 @@
+//! Text escaping for generated SevenMark
+//!
+//! Text is stored as `Text`/`Escape` element runs so that the formatter
+//! output never re-parses as markup the caller did not ask for.
+
+use crate::{Element, EscapeElement, Span, TextElement};
+
+/// Characters the inline parser treats as markup when doubled (`~~`, `__`, …)
+fn is_pair_delimiter(c: char) -> bool {
+    matches!(c, '~' | '_' | '^' | ',')
+}
+
+/// Whether `chars[i]` has to be escaped inside an inline text run
+fn needs_escape(chars: &[char], i: usize) -> bool {
+    let c = chars[i];
+    let prev = i.checked_sub(1).map(|p| chars[p]);
+    let next = chars.get(i + 1).copied();
+    match c {
+        '\\' | '*' | '[' | ']' | '{' | '}' => true,
+        // Run boundaries may touch generated delimiters (`~~x~~~`), so escape there too
+        c if is_pair_delimiter(c) => prev.is_none_or(|p| p == c) || next.is_none_or(|n| n == c),
+        '/' => next.is_none_or(|n| n == '/' || n == '*'),
+        '<' => next.is_none_or(|n| n == '@' || n == '#'),
+        _ => false,
+    }
+}
+
+/// Whether the text would start a line-level block (header, blockquote,
+/// rule or list marker) or be eaten as indentation or as the `||` that ends
+/// a parameter list if it began a line or an element's content
+fn line_start_marker(chars: &[char]) -> bool {
+    let Some(&first) = chars.first() else {
+        return false;
+    };
+    if matches!(first, '#' | '>' | '-' | '+' | '*' | ' ' | '\t') {
+        return true;
+    }
+    if first == '|' {
+        // A lone `|` may meet the next run's `|`
+        return chars.get(1).is_none_or(|&c| c == '|');
+    }
+    let marker_end = if first.is_ascii_digit() {
+        chars.iter().take_while(|c| c.is_ascii_digit()).count()
+    } else if first.is_ascii_alphabetic() {
+        1
+    } else {
+        return false;
+    };
+    matches!(chars.get(marker_end), Some('.' | ')')) && chars.get(marker_end + 1) == Some(&' ')
+}
+
+/// Convert a single-line text run into `Text`/`Escape` elements.
+/// With `line_start`, the first character is escaped when it would start a
+/// block; pass `false` only when the run is known to continue a line.
+pub fn escape_text(value: &str, line_start: bool, out: &mut Vec<Element>) {
+    let chars: Vec<char> = value.chars().collect();
+    let mut plain = String::new();
+
+    for i in 0..chars.len() {
+        let escape = needs_escape(&chars, i) || (i == 0 && line_start && line_start_marker(&chars));
+        if escape {
+            flush_text(&mut plain, out);
+            out.push(escape_char(chars[i]));
+        } else {
+            plain.push(chars[i]);
+        }
+    }
+    flush_text(&mut plain, out);
+}
+
+/// Convert a parameter value into elements; quotes, backslashes, brackets and
+/// braces (which could close an enclosing block) are escaped inside
+/// `#key="..."`. Line breaks become spaces.
+pub fn escape_param(value: &str) -> Vec<Element> {
+    let mut out = Vec::new();
+    let mut plain = String::new();
+    for c in value.chars() {
+        if matches!(c, '"' | '\\' | '[' | ']' | '{' | '}') {
+            flush_text(&mut plain, &mut out);
+            out.push(escape_char(c));
+        } else if c == '\n' || c == '\r' {
+            plain.push(' ');
+        } else {
+            plain.push(c);
+        }
+    }
+    flush_text(&mut plain, &mut out);
+    out
+}
+
+fn escape_char(c: char) -> Element {
+    Element::Escape(EscapeElement {
+        span: Span::synthesized(),
+        value: c.to_string(),
+    })
+}
+
+fn flush_text(plain: &mut String, out: &mut Vec<Element>) {
+    if plain.is_empty() {
+        return;
+    }
+    out.push(Element::Text(TextElement {
+        span: Span::synthesized(),
+        value: std::mem::take(plain),
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(value: &str, line_start: bool) -> String {
+        let mut out = Vec::new();
+        escape_text(value, line_start, &mut out);
+        out.iter()
+            .map(|el| match el {
+                Element::Text(t) => t.value.clone(),
+                Element::Escape(e) => format!("\\{}", e.value),
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn escapes_markup_characters() {
+        assert_eq!(render("a*b [c] {d}", false), "a\\*b \\[c\\] \\{d\\}");
+        assert_eq!(render("x~~y a_b", false), "x\\~\\~y a_b");
+        assert_eq!(render("1, 2", false), "1, 2");
+        assert_eq!(render("a//b a/b", false), "a\\//b a/b");
+    }
+
+    #[test]
+    fn escapes_line_start_markers() {
+        assert_eq!(render("# not a header", true), "\\# not a header");
+        assert_eq!(render("  indented", true), "\\  indented");
+        assert_eq!(render("1. not a list", true), "\\1. not a list");
+        assert_eq!(render("A) item", true), "\\A) item");
+        assert_eq!(render("|| cell", true), "\\|| cell");
+        assert_eq!(render("Apple", true), "Apple");
+        assert_eq!(render("# mid", false), "# mid");
+    }
+
+    #[test]
+    fn escapes_param_quotes() {
+        let out = escape_param("say \"hi\"");
+        assert_eq!(out.len(), 4);
+    }
+}
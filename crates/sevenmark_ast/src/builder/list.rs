@@ -0,0 +1,71 @@
+//! `{{{#list}}}` builder
+
+use super::{IntoChildren, ParametersExt, nested_content, s};
+use crate::{
+    Element, ListContentItem, ListElement, ListItemElement, ListKind, Parameter, Parameters,
+};
+
+/// Builder for `{{{#list}}}`; soft breaks inside items become `[br]`
+#[derive(Debug, Clone)]
+pub struct ListBuilder {
+    kind: ListKind,
+    parameters: Parameters,
+    items: Vec<ListContentItem>,
+}
+
+impl ListBuilder {
+    pub fn param(mut self, key: &str, value: &str) -> Self {
+        self.parameters.set(key, value);
+        self
+    }
+
+    pub fn item(mut self, children: impl IntoChildren) -> Self {
+        self.items.push(ListContentItem::Item(ListItemElement {
+            span: s(),
+            open_span: s(),
+            close_span: s(),
+            parameters: Parameters::new(),
+            children: nested_content(children.into_children()),
+        }));
+        self
+    }
+
+    /// Build the list; a list without items has no source form and builds
+    /// as [`Element::null`]
+    pub fn build(self) -> Element {
+        if self.items.is_empty() {
+            return Element::null();
+        }
+        Element::List(ListElement {
+            span: s(),
+            open_span: s(),
+            close_span: s(),
+            kind: self.kind,
+            parameters: self.parameters,
+            children: self.items,
+        })
+    }
+}
+
+impl Element {
+    pub fn list(kind: ListKind) -> ListBuilder {
+        // The parser keeps the `#1`/`#a`/… kind flag as the first parameter
+        let mut parameters = Parameters::new();
+        if kind.is_ordered() {
+            let key = kind.as_code().to_string();
+            parameters.insert(
+                key.clone(),
+                Parameter {
+                    span: s(),
+                    key,
+                    value: Vec::new(),
+                },
+            );
+        }
+        ListBuilder {
+            kind,
+            parameters,
+            items: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,463 @@
+//! Programmatic AST construction
+//!
+//! Builders create elements with synthesized spans for bots, importers and
+//! templated pages. Text is split into `Text`/`Escape` runs, so printing the
+//! result with `sevenmark_formatter` yields markup that parses back to the
+//! same tree whatever characters the text contains.
+//!
+//! ```
+//! use sevenmark_ast::Element;
+//!
+//! let table = Element::table()
+//!     .row(["Title", "Status"])
+//!     .row([Element::link("A [[tricky]] page").content("A [[tricky]] page").build()])
+//!     .cell("ok")
+//!     .build();
+//! assert!(matches!(table, Element::Table(_)));
+//! ```
+
+mod block;
+mod escape;
+mod list;
+mod params;
+mod table;
+
+pub use block::*;
+pub use escape::*;
+pub use list::*;
+pub use params::*;
+pub use table::*;
+
+use crate::{
+    AgeElement, AnchorElement, CategoryElement, ClearElement, CodeElement, CommentElement,
+    DdayElement, Element, EscapeElement, FoldElement, FoldInnerElement, FootnoteRefElement,
+    HLineElement, HardBreakElement, HeaderElement, NullElement, Parameters, SoftBreakElement, Span,
+    TeXElement, TextStyleElement, TocElement, Traversable, VariableElement,
+};
+
+fn s() -> Span {
+    Span::synthesized()
+}
+
+/// Values accepted wherever a builder takes element content.
+/// Strings go through [`text`].
+pub trait IntoChildren {
+    fn into_children(self) -> Vec<Element>;
+}
+
+impl IntoChildren for &str {
+    fn into_children(self) -> Vec<Element> {
+        text(self)
+    }
+}
+
+impl IntoChildren for String {
+    fn into_children(self) -> Vec<Element> {
+        text(&self)
+    }
+}
+
+impl IntoChildren for Element {
+    fn into_children(self) -> Vec<Element> {
+        vec![self]
+    }
+}
+
+impl IntoChildren for Vec<Element> {
+    fn into_children(self) -> Vec<Element> {
+        self
+    }
+}
+
+/// Plain text as escaped elements. Every line is treated as a possible line
+/// start, and `\n` becomes a soft break. A trailing space is escaped because
+/// blocks trim whitespace before their closing `}}}`.
+pub fn text(value: &str) -> Vec<Element> {
+    let mut out = Vec::new();
+    for (i, line) in value.split('\n').enumerate() {
+        if i > 0 {
+            out.push(Element::soft_break());
+        }
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        match line.char_indices().last() {
+            Some((at, c)) if at > 0 && c.is_whitespace() => {
+                escape_text(&line[..at], true, &mut out);
+                out.push(Element::Escape(EscapeElement {
+                    span: s(),
+                    value: c.to_string(),
+                }));
+            }
+            _ => escape_text(line, true, &mut out),
+        }
+    }
+    out
+}
+
+/// Append content so the sequence has a source form that parses back to it:
+/// adjacent text and adjacent runs of the same style (`*a**b*`) are merged,
+/// and line breaks after elements that consume their trailing blank lines
+/// (include, category, define) are dropped
+pub fn append(out: &mut Vec<Element>, children: impl IntoChildren) {
+    for el in children.into_children() {
+        match (out.last_mut(), el) {
+            (Some(Element::Text(prev)), Element::Text(next)) => prev.value.push_str(&next.value),
+            (
+                Some(Element::Include(_) | Element::Category(_) | Element::Define(_)),
+                Element::SoftBreak(_),
+            ) => {}
+            (Some(prev), el) if same_style(prev, &el) => {
+                if let (Some(prev), Some(next)) = (style_children(prev), style_children_owned(el)) {
+                    append(prev, next);
+                }
+            }
+            (_, el) => out.push(el),
+        }
+    }
+}
+
+fn same_style(a: &Element, b: &Element) -> bool {
+    matches!(
+        (a, b),
+        (Element::Bold(_), Element::Bold(_))
+            | (Element::Italic(_), Element::Italic(_))
+            | (Element::Strikethrough(_), Element::Strikethrough(_))
+            | (Element::Underline(_), Element::Underline(_))
+            | (Element::Superscript(_), Element::Superscript(_))
+            | (Element::Subscript(_), Element::Subscript(_))
+    )
+}
+
+fn style_children(el: &mut Element) -> Option<&mut Vec<Element>> {
+    match el {
+        Element::Bold(e)
+        | Element::Italic(e)
+        | Element::Strikethrough(e)
+        | Element::Underline(e)
+        | Element::Superscript(e)
+        | Element::Subscript(e) => Some(&mut e.children),
+        _ => None,
+    }
+}
+
+fn style_children_owned(el: Element) -> Option<Vec<Element>> {
+    match el {
+        Element::Bold(e)
+        | Element::Italic(e)
+        | Element::Strikethrough(e)
+        | Element::Underline(e)
+        | Element::Superscript(e)
+        | Element::Subscript(e) => Some(e.children),
+        _ => None,
+    }
+}
+
+/// Replace elements matching `nested` anywhere in `children` with their own
+/// children (rules and comments have none and are dropped). Used for
+/// constructs the parser does not read inside a container, such as a footnote
+/// in a footnote or a header anywhere but the top level.
+pub(crate) fn unwrap_nested(
+    children: Vec<Element>,
+    nested: &dyn Fn(&Element) -> bool,
+) -> Vec<Element> {
+    let mut out = Vec::new();
+    for mut el in children {
+        if nested(&el) {
+            append(&mut out, unwrap_nested(into_children(el), nested));
+        } else {
+            el.for_each_children_vec(&mut |children| {
+                *children = unwrap_nested(std::mem::take(children), nested);
+            });
+            append(&mut out, el);
+        }
+    }
+    out
+}
+
+/// Children of the elements [`unwrap_nested`] removes
+fn into_children(el: Element) -> Vec<Element> {
+    match el {
+        Element::Header(e) => e.children,
+        Element::Footnote(e) => e.children,
+        Element::Media(e) => e.children,
+        el => style_children_owned(el).unwrap_or_default(),
+    }
+}
+
+/// Line constructs the parser reads only at the top level of a document:
+/// headers, rules and `//` comments
+pub(crate) fn top_level_only(el: &Element) -> bool {
+    matches!(
+        el,
+        Element::Header(_) | Element::HLine(_) | Element::Comment(_)
+    )
+}
+
+/// The parser reads no empty content, so an empty container holds `[null]`
+pub(crate) fn or_null(children: Vec<Element>) -> Vec<Element> {
+    if children.is_empty() {
+        vec![Element::null()]
+    } else {
+        children
+    }
+}
+
+/// Raw code as the parser reads it back: leading whitespace is skipped
+/// after the opening delimiter, and a value ending in `}` would merge with
+/// the closing `}}}`, so it gets a trailing line break, the only separator the
+/// parser gives back unchanged
+fn raw_value(value: &str) -> String {
+    let mut value = value.trim_start().to_string();
+    if value.ends_with('}') {
+        value.push('\n');
+    }
+    value
+}
+
+/// Block content without the line breaks the parser trims after the opening
+/// and before the closing delimiter
+pub(crate) fn trim_breaks(mut children: Vec<Element>) -> Vec<Element> {
+    let leading = children
+        .iter()
+        .take_while(|el| matches!(el, Element::SoftBreak(_)))
+        .count();
+    children.drain(..leading);
+    while matches!(children.last(), Some(Element::SoftBreak(_))) {
+        children.pop();
+    }
+    children
+}
+
+/// Content of brace blocks, which are never at the top level where headers,
+/// rules and comments are read
+pub(crate) fn block_content(children: Vec<Element>) -> Vec<Element> {
+    or_null(unwrap_nested(trim_breaks(children), &top_level_only))
+}
+
+/// Content of list items and table cells. The formatter indents every line
+/// inside those blocks, and a reparse would keep that indentation as text, so
+/// line breaks are written as `[br]` and dropped next to block elements.
+pub(crate) fn nested_content(children: Vec<Element>) -> Vec<Element> {
+    let mut children = unwrap_nested(trim_breaks(children), &top_level_only);
+    hard_breaks(&mut children);
+    or_null(children)
+}
+
+fn hard_breaks(elements: &mut Vec<Element>) {
+    let is_block = |el: &Element| match el {
+        Element::List(_)
+        | Element::Table(_)
+        | Element::BlockQuote(_)
+        | Element::Code(_)
+        | Element::Fold(_) => true,
+        Element::TeX(tex) => tex.is_block,
+        _ => false,
+    };
+    let blocks: Vec<bool> = elements.iter().map(is_block).collect();
+    let mut i = 0;
+    elements.retain(|el| {
+        let next_to_block = (i > 0 && blocks[i - 1]) || blocks.get(i + 1) == Some(&true);
+        i += 1;
+        !(matches!(el, Element::SoftBreak(_)) && next_to_block)
+    });
+    for el in elements.iter_mut() {
+        if matches!(el, Element::SoftBreak(_)) {
+            *el = Element::hard_break();
+        }
+        el.for_each_children_vec(&mut hard_breaks);
+    }
+}
+
+/// Text style element. A style inside the same style and empty content
+/// have no source form (`****`), so nested styles are unwrapped and empty
+/// content becomes `[null]`.
+fn style(children: impl IntoChildren, wrap: fn(TextStyleElement) -> Element) -> Element {
+    let mut el = wrap(TextStyleElement {
+        span: s(),
+        children: Vec::new(),
+    });
+    let flat = unwrap_nested(children.into_children(), &|child| {
+        top_level_only(child) || same_style(&el, child)
+    });
+    if let Some(children) = style_children(&mut el) {
+        *children = or_null(flat);
+    }
+    el
+}
+
+impl Element {
+    pub fn soft_break() -> Self {
+        Element::SoftBreak(SoftBreakElement { span: s() })
+    }
+
+    pub fn hard_break() -> Self {
+        Element::HardBreak(HardBreakElement { span: s() })
+    }
+
+    pub fn hline() -> Self {
+        Element::HLine(HLineElement { span: s() })
+    }
+
+    pub fn clear() -> Self {
+        Element::Clear(ClearElement { span: s() })
+    }
+
+    pub fn toc() -> Self {
+        Element::Toc(TocElement { span: s() })
+    }
+
+    /// `[null]`, which renders nothing
+    pub fn null() -> Self {
+        Element::Null(NullElement { span: s() })
+    }
+
+    pub fn footnote_ref() -> Self {
+        Element::FootnoteRef(FootnoteRefElement { span: s() })
+    }
+
+    /// `[anchor(name)]`; the name is written verbatim and must not contain `)`
+    pub fn anchor(name: &str) -> Self {
+        Element::Anchor(AnchorElement {
+            span: s(),
+            name: name.to_string(),
+        })
+    }
+
+    /// `[var(name)]`; the name is written verbatim and must not contain `)`
+    pub fn variable(name: &str) -> Self {
+        Element::Variable(VariableElement {
+            span: s(),
+            name: name.to_string(),
+        })
+    }
+
+    pub fn age(date: &str) -> Self {
+        Element::Age(AgeElement {
+            span: s(),
+            date: date.to_string(),
+        })
+    }
+
+    pub fn dday(date: &str) -> Self {
+        Element::Dday(DdayElement {
+            span: s(),
+            date: date.to_string(),
+        })
+    }
+
+    /// Single-line comment; line breaks are replaced with spaces
+    pub fn comment(value: &str) -> Self {
+        Element::Comment(CommentElement {
+            span: s(),
+            value: value.replace(['\r', '\n'], " "),
+        })
+    }
+
+    /// Header of `level` 1-6; `section_index` is assigned by the parser
+    pub fn header(level: usize, children: impl IntoChildren) -> Self {
+        Element::Header(HeaderElement {
+            span: s(),
+            level: level.clamp(1, 6),
+            is_folded: false,
+            section_index: 0,
+            children: unwrap_nested(children.into_children(), &top_level_only),
+        })
+    }
+
+    pub fn bold(children: impl IntoChildren) -> Self {
+        style(children, Element::Bold)
+    }
+
+    /// Italic text. `***` opens bold first, so italic content that starts
+    /// with bold only reparses as such when the bold is its only child (it is
+    /// then rewritten as bold around italic).
+    pub fn italic(children: impl IntoChildren) -> Self {
+        let italic = style(children, Element::Italic);
+        // `***x***` always reads as bold outside italic
+        match italic {
+            Element::Italic(mut e) if matches!(e.children.as_slice(), [Element::Bold(_)]) => {
+                let Some(Element::Bold(bold)) = e.children.pop() else {
+                    unreachable!()
+                };
+                Element::bold(Element::italic(bold.children))
+            }
+            el => el,
+        }
+    }
+
+    pub fn strikethrough(children: impl IntoChildren) -> Self {
+        style(children, Element::Strikethrough)
+    }
+
+    pub fn underline(children: impl IntoChildren) -> Self {
+        style(children, Element::Underline)
+    }
+
+    pub fn superscript(children: impl IntoChildren) -> Self {
+        style(children, Element::Superscript)
+    }
+
+    pub fn subscript(children: impl IntoChildren) -> Self {
+        style(children, Element::Subscript)
+    }
+
+    /// `{{{#code}}}` block. Code is raw text, so `value` must not contain `}}}`;
+    /// leading whitespace is dropped and a value ending in `}` gets a trailing
+    /// line break. A value starting with `#` is only told apart from the
+    /// parameters after a `||`, so it gets `#lang="text"` when `lang` is `None`.
+    pub fn code(lang: Option<&str>, value: &str) -> Self {
+        let value = raw_value(value);
+        let mut parameters = Parameters::new();
+        match lang.filter(|l| !l.is_empty()) {
+            Some(lang) => parameters.set("lang", lang),
+            None if value.starts_with('#') => parameters.set("lang", "text"),
+            None => {}
+        }
+        Element::Code(CodeElement {
+            span: s(),
+            open_span: s(),
+            close_span: s(),
+            parameters,
+            value,
+        })
+    }
+
+    /// `{{{#tex}}}` formula. TeX is raw text, so `value` must not contain `}}}`;
+    /// leading whitespace is dropped and a value ending in `}` gets a trailing
+    /// line break.
+    pub fn tex(is_block: bool, value: &str) -> Self {
+        Element::TeX(TeXElement {
+            span: s(),
+            open_span: s(),
+            close_span: s(),
+            is_block,
+            value: raw_value(value),
+        })
+    }
+
+    pub fn category(name: &str) -> Self {
+        Element::Category(CategoryElement {
+            span: s(),
+            open_span: s(),
+            close_span: s(),
+            children: text(name),
+        })
+    }
+
+    pub fn fold(summary: impl IntoChildren, details: impl IntoChildren) -> Self {
+        let inner = |children| FoldInnerElement {
+            span: s(),
+            open_span: s(),
+            close_span: s(),
+            parameters: Parameters::new(),
+            children,
+        };
+        Element::Fold(FoldElement {
+            span: s(),
+            open_span: s(),
+            close_span: s(),
+            parameters: Parameters::new(),
+            summary: inner(block_content(summary.into_children())),
+            details: inner(block_content(details.into_children())),
+        })
+    }
+}
@@ -0,0 +1,78 @@
+//! `Parameters` helpers
+
+use super::escape::escape_param;
+use crate::{Element, Parameter, Parameters, Span};
+
+/// Building and reading `#key="value"` parameter maps with escaped values
+pub trait ParametersExt {
+    /// Map from `(key, value)` pairs, in order
+    fn from_pairs<'a>(pairs: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self;
+
+    /// Insert or replace `key` with a plain text value
+    fn set(&mut self, key: &str, value: &str);
+
+    /// Chaining form of [`ParametersExt::set`]
+    fn with(self, key: &str, value: &str) -> Self;
+
+    /// Plain text of a parameter value (`Text` and `Escape` elements only)
+    fn text(&self, key: &str) -> Option<String>;
+}
+
+impl ParametersExt for Parameters {
+    fn from_pairs<'a>(pairs: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let mut params = Parameters::new();
+        for (key, value) in pairs {
+            params.set(key, value);
+        }
+        params
+    }
+
+    fn set(&mut self, key: &str, value: &str) {
+        self.insert(
+            key.to_string(),
+            Parameter {
+                span: Span::synthesized(),
+                key: key.to_string(),
+                value: escape_param(value),
+            },
+        );
+    }
+
+    fn with(mut self, key: &str, value: &str) -> Self {
+        self.set(key, value);
+        self
+    }
+
+    fn text(&self, key: &str) -> Option<String> {
+        let param = self.get(key)?;
+        Some(
+            param
+                .value
+                .iter()
+                .filter_map(|el| match el {
+                    Element::Text(t) => Some(t.value.as_str()),
+                    Element::Escape(e) => Some(e.value.as_str()),
+                    _ => None,
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_are_escaped_and_read_back() {
+        let params = Parameters::from_pairs([("document", "A [[B]] \"C\"")]).with("anchor", "s");
+        assert_eq!(params.text("document").as_deref(), Some("A [[B]] \"C\""));
+        assert!(
+            params["document"]
+                .value
+                .iter()
+                .any(|el| matches!(el, Element::Escape(e) if e.value == "["))
+        );
+        assert_eq!(params.keys().collect::<Vec<_>>(), ["document", "anchor"]);
+    }
+}
@@ -0,0 +1,148 @@
+//! `{{{#table}}}` builder
+
+use super::{IntoChildren, ParametersExt, append, nested_content, s};
+use crate::{
+    Element, Parameters, TableCellElement, TableCellItem, TableElement, TableRowElement,
+    TableRowItem,
+};
+
+/// A table cell before it is placed in a row
+#[derive(Debug, Clone, Default)]
+pub struct CellBuilder {
+    parameters: Parameters,
+    children: Vec<Element>,
+}
+
+impl CellBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn param(mut self, key: &str, value: &str) -> Self {
+        self.parameters.set(key, value);
+        self
+    }
+
+    /// Replace all parameters with an already escaped map
+    pub fn parameters(mut self, parameters: Parameters) -> Self {
+        self.parameters = parameters;
+        self
+    }
+
+    /// Span `n` columns (`#x`)
+    pub fn colspan(self, n: usize) -> Self {
+        self.param("x", &n.to_string())
+    }
+
+    /// Span `n` rows (`#y`)
+    pub fn rowspan(self, n: usize) -> Self {
+        self.param("y", &n.to_string())
+    }
+
+    pub fn content(mut self, children: impl IntoChildren) -> Self {
+        append(&mut self.children, children);
+        self
+    }
+
+    fn build(self) -> TableCellItem {
+        // The parser reads `x`/`y` back from the cell parameters
+        let span_value = |key: &str| {
+            self.parameters
+                .get(key)
+                .map_or_else(Vec::new, |p| p.value.clone())
+        };
+        TableCellItem::Cell(TableCellElement {
+            span: s(),
+            open_span: s(),
+            close_span: s(),
+            x: span_value("x"),
+            y: span_value("y"),
+            children: nested_content(self.children),
+            parameters: self.parameters,
+        })
+    }
+}
+
+impl<T: IntoChildren> From<T> for CellBuilder {
+    fn from(children: T) -> Self {
+        CellBuilder::new().content(children)
+    }
+}
+
+/// Builder for `{{{#table}}}`; soft breaks inside cells become `[br]`
+#[derive(Debug, Clone, Default)]
+pub struct TableBuilder {
+    parameters: Parameters,
+    rows: Vec<TableRowElement>,
+}
+
+impl TableBuilder {
+    pub fn param(mut self, key: &str, value: &str) -> Self {
+        self.parameters.set(key, value);
+        self
+    }
+
+    /// Replace all parameters with an already escaped map
+    pub fn parameters(mut self, parameters: Parameters) -> Self {
+        self.parameters = parameters;
+        self
+    }
+
+    /// Start a new row with `cells`
+    pub fn row<C: Into<CellBuilder>>(mut self, cells: impl IntoIterator<Item = C>) -> Self {
+        self.rows.push(TableRowElement {
+            span: s(),
+            open_span: s(),
+            close_span: s(),
+            parameters: Parameters::new(),
+            children: cells.into_iter().map(|c| c.into().build()).collect(),
+        });
+        self
+    }
+
+    /// Set a parameter on the last row
+    pub fn row_param(mut self, key: &str, value: &str) -> Self {
+        if let Some(row) = self.rows.last_mut() {
+            row.parameters.set(key, value);
+        }
+        self
+    }
+
+    /// Append a cell to the last row, starting one if there is none
+    pub fn cell(mut self, cell: impl Into<CellBuilder>) -> Self {
+        if self.rows.is_empty() {
+            self = self.row(Vec::<CellBuilder>::new());
+        }
+        if let Some(row) = self.rows.last_mut() {
+            row.children.push(cell.into().build());
+        }
+        self
+    }
+
+    /// Build the table. Rows without cells are dropped, and a table without
+    /// cells has no source form and builds as [`Element::null`].
+    pub fn build(self) -> Element {
+        let rows: Vec<_> = self
+            .rows
+            .into_iter()
+            .filter(|row| !row.children.is_empty())
+            .map(TableRowItem::Row)
+            .collect();
+        if rows.is_empty() {
+            return Element::null();
+        }
+        Element::Table(TableElement {
+            span: s(),
+            open_span: s(),
+            close_span: s(),
+            parameters: self.parameters,
+            children: rows,
+        })
+    }
+}
+
+impl Element {
+    pub fn table() -> TableBuilder {
+        TableBuilder::default()
+    }
+}
@@ -1,6 +1,8 @@
 use serde::Serialize;
 
-use super::{Element, Expression, MentionType, Parameters, ResolvedMediaInfo, Span};
+use super::{
+    Element, Expression, IncludeFailure, MentionType, Parameters, ResolvedMediaInfo, Span,
+};
 
 // === Leaf nodes (span only) ===
 
@@ -288,6 +290,9 @@ pub struct IncludeElement {
     pub close_span: Span,
     pub parameters: Parameters,
     pub children: Vec<Element>,
+    /// 치환 실패 사유 (transform이 설정, 렌더러가 placeholder로 표시)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure: Option<IncludeFailure>,
 }
 
 /// 카테고리 {{{#category ...}}}
@@ -389,6 +394,9 @@ pub struct MentionElement {
 
 // === Headers ===
 
+/// Prefix of a header's section id (e.g., "s-1", "s-1.1")
+pub const SECTION_ID_PREFIX: &str = "s-";
+
 /// 헤더 = Title =
 #[derive(Debug, Clone, Serialize)]
 pub struct HeaderElement {
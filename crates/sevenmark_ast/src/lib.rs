@@ -7,13 +7,17 @@
 //! - `table`: Table-related structures
 //! - `list`: List-related structures
 //! - `traversable`: Traversable trait and implementation
+//! - `builder`: Element constructors and escaping for generated markup
+//! - `visit`: `Visitor`/`VisitorMut` traits with per-node hooks and paths
 
+pub mod builder;
 mod elements;
 mod expression;
 mod list;
 mod span;
 mod table;
 mod traversable;
+pub mod visit;
 
 // Re-export all public types
 pub use elements::*;
@@ -36,6 +40,16 @@ pub enum MentionType {
     User,
 }
 
+/// Include 치환 실패 사유
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IncludeFailure {
+    /// 대상 문서가 없음 (또는 현재 revision이 없음)
+    NotFound,
+    /// 대상 문서를 불러오지 못함 (저장소/DB 오류)
+    Unavailable,
+}
+
 /// 파일 resolve 결과 (DB에서 실제 URL을 가져옴)
 #[derive(Debug, Clone, Serialize, Default)]
 pub struct ResolvedFile {
@@ -194,4 +208,55 @@ impl Element {
             Element::If(e) => &e.span,
         }
     }
+
+    /// Variant name (`"Text"`, `"Table"`, …), as used in the serialized AST
+    pub fn name(&self) -> &'static str {
+        match self {
+            Element::Text(_) => "Text",
+            Element::Comment(_) => "Comment",
+            Element::Escape(_) => "Escape",
+            Element::Error(_) => "Error",
+            Element::Literal(_) => "Literal",
+            Element::Define(_) => "Define",
+            Element::Styled(_) => "Styled",
+            Element::Table(_) => "Table",
+            Element::List(_) => "List",
+            Element::Fold(_) => "Fold",
+            Element::BlockQuote(_) => "BlockQuote",
+            Element::Ruby(_) => "Ruby",
+            Element::Footnote(_) => "Footnote",
+            Element::Code(_) => "Code",
+            Element::TeX(_) => "TeX",
+            Element::Css(_) => "Css",
+            Element::Include(_) => "Include",
+            Element::Category(_) => "Category",
+            Element::Redirect(_) => "Redirect",
+            Element::Media(_) => "Media",
+            Element::ExternalMedia(_) => "ExternalMedia",
+            Element::Null(_) => "Null",
+            Element::FootnoteRef(_) => "FootnoteRef",
+            Element::TimeNow(_) => "TimeNow",
+            Element::Date(_) => "Date",
+            Element::DateTime(_) => "DateTime",
+            Element::Dday(_) => "Dday",
+            Element::PageCount(_) => "PageCount",
+            Element::Age(_) => "Age",
+            Element::Variable(_) => "Variable",
+            Element::Anchor(_) => "Anchor",
+            Element::Toc(_) => "Toc",
+            Element::Mention(_) => "Mention",
+            Element::Bold(_) => "Bold",
+            Element::Italic(_) => "Italic",
+            Element::Strikethrough(_) => "Strikethrough",
+            Element::Underline(_) => "Underline",
+            Element::Superscript(_) => "Superscript",
+            Element::Subscript(_) => "Subscript",
+            Element::SoftBreak(_) => "SoftBreak",
+            Element::HardBreak(_) => "HardBreak",
+            Element::Clear(_) => "Clear",
+            Element::HLine(_) => "HLine",
+            Element::Header(_) => "Header",
+            Element::If(_) => "If",
+        }
+    }
 }
@@ -0,0 +1,250 @@
+//! Visitor framework for AST passes
+//!
+//! [`Visitor`] and [`VisitorMut`] have one hook per node type (elements,
+//! parameters, expressions, table rows/cells, list items). Every default hook
+//! calls the matching `walk_*` function, which descends into *all* nested
+//! content: parameter values, `#x`/`#y` cell spans, fold summaries,
+//! table/list conditionals and expression string literals. Override a hook
+//! and call the `walk_*` function from it to keep descending.
+//!
+//! Each hook receives the [`VisitPath`] from the document root to the node.
+//! [`VisitorMut::flat_map_element`] can replace an element with any number of
+//! elements wherever elements are stored in a list.
+//!
+//! ```
+//! use sevenmark_ast::Element;
+//! use sevenmark_ast::visit::{NodeKind, VisitPath, Visitor, walk_element};
+//!
+//! #[derive(Default)]
+//! struct FootnoteText(Vec<String>);
+//!
+//! impl Visitor for FootnoteText {
+//!     fn visit_element(&mut self, element: &Element, path: &mut VisitPath) {
+//!         if let Element::Text(text) = element
+//!             && path.is_inside(NodeKind::Element("Footnote"))
+//!         {
+//!             self.0.push(text.value.clone());
+//!         }
+//!         walk_element(self, element, path);
+//!     }
+//! }
+//!
+//! let doc = vec![Element::bold(Element::footnote().content("note").build())];
+//! let mut visitor = FootnoteText::default();
+//! visitor.visit_document(&doc);
+//! assert_eq!(visitor.0, ["note"]);
+//! ```
+
+mod visitor;
+mod visitor_mut;
+
+pub use visitor::*;
+pub use visitor_mut::*;
+
+/// Kind of node that contains the visited one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    /// The top-level element list
+    Document,
+    /// An element, by [`Element::name`](crate::Element::name)
+    Element(&'static str),
+    /// Fold summary or details
+    FoldInner,
+    Parameter,
+    Expression,
+    TableRow,
+    TableCell,
+    /// `{{{#if ...}}}` around table rows
+    ConditionalRows,
+    /// `{{{#if ...}}}` around table cells
+    ConditionalCells,
+    ListItem,
+    /// `{{{#if ...}}}` around list items
+    ConditionalItems,
+}
+
+/// One step from a parent node into one of its fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathSegment {
+    pub parent: NodeKind,
+    /// Field of the parent (`"children"`, `"parameters"`, `"condition"`, …)
+    pub field: &'static str,
+    /// Position in the field; 0 for single-valued fields. In `VisitorMut`
+    /// this is the index before any elements were replaced.
+    pub index: usize,
+}
+
+/// Path from the document root to the node being visited
+#[derive(Debug, Clone, Default)]
+pub struct VisitPath {
+    segments: Vec<PathSegment>,
+}
+
+impl VisitPath {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// The step from the direct parent into the current node
+    pub fn parent(&self) -> Option<&PathSegment> {
+        self.segments.last()
+    }
+
+    pub fn depth(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Whether any ancestor is of the given kind
+    pub fn is_inside(&self, kind: NodeKind) -> bool {
+        self.segments.iter().any(|segment| segment.parent == kind)
+    }
+
+    fn push(&mut self, parent: NodeKind, field: &'static str, index: usize) {
+        self.segments.push(PathSegment {
+            parent,
+            field,
+            index,
+        });
+    }
+
+    fn pop(&mut self) {
+        self.segments.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ConditionalTableRows, Element, Expression, IfElement, Span, TableRowItem, VariableElement,
+    };
+
+    fn string_literal(value: &str) -> Expression {
+        Expression::StringLiteral {
+            span: Span::synthesized(),
+            value: crate::builder::text(value),
+        }
+    }
+
+    /// Table whose single row sits in `{{{#if "cond" :: ...}}}`
+    fn conditional_table() -> Element {
+        let Element::Table(mut table) = Element::table().row(["cell"]).build() else {
+            unreachable!()
+        };
+        let Some(TableRowItem::Row(row)) = table.children.pop() else {
+            unreachable!()
+        };
+        table
+            .children
+            .push(TableRowItem::Conditional(ConditionalTableRows {
+                span: Span::synthesized(),
+                open_span: Span::synthesized(),
+                close_span: Span::synthesized(),
+                condition: string_literal("cond"),
+                rows: vec![row],
+            }));
+        Element::Table(table)
+    }
+
+    #[derive(Default)]
+    struct Texts(Vec<(String, Vec<NodeKind>)>);
+
+    impl Visitor for Texts {
+        fn visit_element(&mut self, element: &Element, path: &mut VisitPath) {
+            if let Element::Text(text) = element {
+                let parents = path.segments().iter().map(|s| s.parent).collect();
+                self.0.push((text.value.clone(), parents));
+            }
+            walk_element(self, element, path);
+        }
+    }
+
+    #[test]
+    fn visits_parameters_conditionals_and_literals() {
+        let doc = vec![
+            Element::styled().param("style", "color:red").build(),
+            conditional_table(),
+            Element::fold("summary", "details"),
+        ];
+        let mut texts = Texts::default();
+        texts.visit_document(&doc);
+        let values: Vec<&str> = texts.0.iter().map(|(v, _)| v.as_str()).collect();
+        assert_eq!(values, ["color:red", "cond", "cell", "summary", "details"]);
+        assert_eq!(
+            texts.0[1].1,
+            [
+                NodeKind::Document,
+                NodeKind::Element("Table"),
+                NodeKind::ConditionalRows,
+                NodeKind::Expression,
+            ]
+        );
+        assert_eq!(
+            texts.0[2].1,
+            [
+                NodeKind::Document,
+                NodeKind::Element("Table"),
+                NodeKind::ConditionalRows,
+                NodeKind::TableRow,
+                NodeKind::TableCell,
+            ]
+        );
+    }
+
+    /// Replaces variables with their name as plain text and unwraps bold
+    struct Expand;
+
+    impl VisitorMut for Expand {
+        fn flat_map_element(&mut self, element: Element, path: &mut VisitPath) -> Vec<Element> {
+            match element {
+                Element::Bold(bold) => bold
+                    .children
+                    .into_iter()
+                    .flat_map(|child| self.flat_map_element(child, path))
+                    .collect(),
+                Element::Variable(var) => crate::builder::text(&format!("<{}>", var.name)),
+                mut element => {
+                    walk_element_mut(self, &mut element, path);
+                    vec![element]
+                }
+            }
+        }
+    }
+
+    fn variable(name: &str) -> Element {
+        Element::Variable(VariableElement {
+            span: Span::synthesized(),
+            name: name.to_string(),
+        })
+    }
+
+    #[test]
+    fn flat_map_replaces_elements_everywhere() {
+        let mut doc = vec![
+            Element::bold(vec![variable("a"), Element::italic("b")]),
+            Element::If(IfElement {
+                span: Span::synthesized(),
+                open_span: Span::synthesized(),
+                close_span: Span::synthesized(),
+                condition: Expression::Element(Box::new(Element::bold(variable("c")))),
+                children: vec![Element::bold("d")],
+            }),
+        ];
+        Expand.visit_document_mut(&mut doc);
+
+        assert!(matches!(&doc[0], Element::Text(t) if t.value == "<a>"));
+        assert!(matches!(&doc[1], Element::Italic(_)));
+        let Element::If(if_elem) = &doc[2] else {
+            panic!("{doc:?}")
+        };
+        // A boxed expression element cannot become several elements
+        assert!(
+            matches!(&if_elem.condition, Expression::Element(el) if matches!(**el, Element::Bold(_)))
+        );
+        assert!(matches!(&if_elem.children[..], [Element::Text(t)] if t.value == "d"));
+    }
+}
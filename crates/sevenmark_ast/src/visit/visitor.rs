@@ -0,0 +1,366 @@
+//! Read-only visitor
+
+use super::{NodeKind, VisitPath};
+use crate::{
+    Element, Expression, FoldInnerElement, ListContentItem, ListItemElement, Parameter, Parameters,
+    TableCellElement, TableCellItem, TableRowElement, TableRowItem,
+};
+
+/// Read-only AST visitor. Every hook defaults to the matching `walk_*`
+/// function, so overriding one hook keeps the traversal complete.
+pub trait Visitor {
+    /// Visit a top-level element list
+    fn visit_document(&mut self, elements: &[Element]) {
+        let mut path = VisitPath::new();
+        walk_elements(self, elements, NodeKind::Document, "elements", &mut path);
+    }
+
+    fn visit_element(&mut self, element: &Element, path: &mut VisitPath) {
+        walk_element(self, element, path);
+    }
+
+    fn visit_parameter(&mut self, parameter: &Parameter, path: &mut VisitPath) {
+        walk_parameter(self, parameter, path);
+    }
+
+    fn visit_expression(&mut self, expression: &Expression, path: &mut VisitPath) {
+        walk_expression(self, expression, path);
+    }
+
+    fn visit_table_row_item(&mut self, item: &TableRowItem, path: &mut VisitPath) {
+        walk_table_row_item(self, item, path);
+    }
+
+    fn visit_table_row(&mut self, row: &TableRowElement, path: &mut VisitPath) {
+        walk_table_row(self, row, path);
+    }
+
+    fn visit_table_cell_item(&mut self, item: &TableCellItem, path: &mut VisitPath) {
+        walk_table_cell_item(self, item, path);
+    }
+
+    fn visit_table_cell(&mut self, cell: &TableCellElement, path: &mut VisitPath) {
+        walk_table_cell(self, cell, path);
+    }
+
+    fn visit_list_content_item(&mut self, item: &ListContentItem, path: &mut VisitPath) {
+        walk_list_content_item(self, item, path);
+    }
+
+    fn visit_list_item(&mut self, item: &ListItemElement, path: &mut VisitPath) {
+        walk_list_item(self, item, path);
+    }
+}
+
+/// Visit each element of a list stored in `parent.field`
+pub fn walk_elements<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    elements: &[Element],
+    parent: NodeKind,
+    field: &'static str,
+    path: &mut VisitPath,
+) {
+    for (index, element) in elements.iter().enumerate() {
+        path.push(parent, field, index);
+        visitor.visit_element(element, path);
+        path.pop();
+    }
+}
+
+fn walk_parameters<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    parameters: &Parameters,
+    parent: NodeKind,
+    path: &mut VisitPath,
+) {
+    for (index, parameter) in parameters.values().enumerate() {
+        path.push(parent, "parameters", index);
+        visitor.visit_parameter(parameter, path);
+        path.pop();
+    }
+}
+
+fn walk_condition<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    condition: &Expression,
+    parent: NodeKind,
+    path: &mut VisitPath,
+) {
+    path.push(parent, "condition", 0);
+    visitor.visit_expression(condition, path);
+    path.pop();
+}
+
+fn walk_fold_inner<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    inner: &FoldInnerElement,
+    field: &'static str,
+    path: &mut VisitPath,
+) {
+    path.push(NodeKind::Element("Fold"), field, 0);
+    walk_parameters(visitor, &inner.parameters, NodeKind::FoldInner, path);
+    walk_elements(
+        visitor,
+        &inner.children,
+        NodeKind::FoldInner,
+        "children",
+        path,
+    );
+    path.pop();
+}
+
+/// Visit everything nested in an element, in source order
+pub fn walk_element<V: Visitor + ?Sized>(visitor: &mut V, element: &Element, path: &mut VisitPath) {
+    let kind = NodeKind::Element(element.name());
+    match element {
+        Element::Text(_)
+        | Element::Comment(_)
+        | Element::Escape(_)
+        | Element::Error(_)
+        | Element::TeX(_)
+        | Element::Css(_)
+        | Element::Null(_)
+        | Element::FootnoteRef(_)
+        | Element::TimeNow(_)
+        | Element::Date(_)
+        | Element::DateTime(_)
+        | Element::Dday(_)
+        | Element::PageCount(_)
+        | Element::Age(_)
+        | Element::Variable(_)
+        | Element::Anchor(_)
+        | Element::Toc(_)
+        | Element::Mention(_)
+        | Element::SoftBreak(_)
+        | Element::HardBreak(_)
+        | Element::Clear(_)
+        | Element::HLine(_) => {}
+
+        Element::Define(e) => walk_parameters(visitor, &e.parameters, kind, path),
+        Element::Code(e) => walk_parameters(visitor, &e.parameters, kind, path),
+        Element::ExternalMedia(e) => walk_parameters(visitor, &e.parameters, kind, path),
+
+        Element::Literal(e) => walk_elements(visitor, &e.children, kind, "children", path),
+        Element::Category(e) => walk_elements(visitor, &e.children, kind, "children", path),
+        Element::Header(e) => walk_elements(visitor, &e.children, kind, "children", path),
+        Element::Bold(e)
+        | Element::Italic(e)
+        | Element::Strikethrough(e)
+        | Element::Underline(e)
+        | Element::Superscript(e)
+        | Element::Subscript(e) => walk_elements(visitor, &e.children, kind, "children", path),
+
+        Element::Styled(e) => {
+            walk_parameters(visitor, &e.parameters, kind, path);
+            walk_elements(visitor, &e.children, kind, "children", path);
+        }
+        Element::BlockQuote(e) => {
+            walk_parameters(visitor, &e.parameters, kind, path);
+            walk_elements(visitor, &e.children, kind, "children", path);
+        }
+        Element::Ruby(e) => {
+            walk_parameters(visitor, &e.parameters, kind, path);
+            walk_elements(visitor, &e.children, kind, "children", path);
+        }
+        Element::Footnote(e) => {
+            walk_parameters(visitor, &e.parameters, kind, path);
+            walk_elements(visitor, &e.children, kind, "children", path);
+        }
+        Element::Include(e) => {
+            walk_parameters(visitor, &e.parameters, kind, path);
+            walk_elements(visitor, &e.children, kind, "children", path);
+        }
+        Element::Redirect(e) => {
+            walk_parameters(visitor, &e.parameters, kind, path);
+            walk_elements(visitor, &e.children, kind, "children", path);
+        }
+        Element::Media(e) => {
+            walk_parameters(visitor, &e.parameters, kind, path);
+            walk_elements(visitor, &e.children, kind, "children", path);
+        }
+
+        Element::If(e) => {
+            walk_condition(visitor, &e.condition, kind, path);
+            walk_elements(visitor, &e.children, kind, "children", path);
+        }
+
+        Element::Fold(e) => {
+            walk_parameters(visitor, &e.parameters, kind, path);
+            walk_fold_inner(visitor, &e.summary, "summary", path);
+            walk_fold_inner(visitor, &e.details, "details", path);
+        }
+
+        Element::Table(e) => {
+            walk_parameters(visitor, &e.parameters, kind, path);
+            for (index, item) in e.children.iter().enumerate() {
+                path.push(kind, "children", index);
+                visitor.visit_table_row_item(item, path);
+                path.pop();
+            }
+        }
+
+        Element::List(e) => {
+            walk_parameters(visitor, &e.parameters, kind, path);
+            for (index, item) in e.children.iter().enumerate() {
+                path.push(kind, "children", index);
+                visitor.visit_list_content_item(item, path);
+                path.pop();
+            }
+        }
+    }
+}
+
+pub fn walk_parameter<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    parameter: &Parameter,
+    path: &mut VisitPath,
+) {
+    walk_elements(
+        visitor,
+        &parameter.value,
+        NodeKind::Parameter,
+        "value",
+        path,
+    );
+}
+
+pub fn walk_expression<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    expression: &Expression,
+    path: &mut VisitPath,
+) {
+    match expression {
+        Expression::Or { left, right, .. }
+        | Expression::And { left, right, .. }
+        | Expression::Comparison { left, right, .. } => {
+            walk_operand(visitor, left, "left", 0, path);
+            walk_operand(visitor, right, "right", 0, path);
+        }
+        Expression::Not { inner, .. } | Expression::Group { inner, .. } => {
+            walk_operand(visitor, inner, "inner", 0, path);
+        }
+        Expression::FunctionCall { arguments, .. } => {
+            for (index, argument) in arguments.iter().enumerate() {
+                walk_operand(visitor, argument, "arguments", index, path);
+            }
+        }
+        Expression::StringLiteral { value, .. } => {
+            walk_elements(visitor, value, NodeKind::Expression, "value", path);
+        }
+        Expression::Element(element) => {
+            path.push(NodeKind::Expression, "element", 0);
+            visitor.visit_element(element, path);
+            path.pop();
+        }
+        Expression::NumberLiteral { .. }
+        | Expression::BoolLiteral { .. }
+        | Expression::Null { .. } => {}
+    }
+}
+
+fn walk_operand<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    expression: &Expression,
+    field: &'static str,
+    index: usize,
+    path: &mut VisitPath,
+) {
+    path.push(NodeKind::Expression, field, index);
+    visitor.visit_expression(expression, path);
+    path.pop();
+}
+
+/// A plain row is visited in place; conditional rows add a level
+pub fn walk_table_row_item<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    item: &TableRowItem,
+    path: &mut VisitPath,
+) {
+    match item {
+        TableRowItem::Row(row) => visitor.visit_table_row(row, path),
+        TableRowItem::Conditional(cond) => {
+            walk_condition(visitor, &cond.condition, NodeKind::ConditionalRows, path);
+            for (index, row) in cond.rows.iter().enumerate() {
+                path.push(NodeKind::ConditionalRows, "rows", index);
+                visitor.visit_table_row(row, path);
+                path.pop();
+            }
+        }
+    }
+}
+
+pub fn walk_table_row<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    row: &TableRowElement,
+    path: &mut VisitPath,
+) {
+    walk_parameters(visitor, &row.parameters, NodeKind::TableRow, path);
+    for (index, item) in row.children.iter().enumerate() {
+        path.push(NodeKind::TableRow, "children", index);
+        visitor.visit_table_cell_item(item, path);
+        path.pop();
+    }
+}
+
+pub fn walk_table_cell_item<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    item: &TableCellItem,
+    path: &mut VisitPath,
+) {
+    match item {
+        TableCellItem::Cell(cell) => visitor.visit_table_cell(cell, path),
+        TableCellItem::Conditional(cond) => {
+            walk_condition(visitor, &cond.condition, NodeKind::ConditionalCells, path);
+            for (index, cell) in cond.cells.iter().enumerate() {
+                path.push(NodeKind::ConditionalCells, "cells", index);
+                visitor.visit_table_cell(cell, path);
+                path.pop();
+            }
+        }
+    }
+}
+
+pub fn walk_table_cell<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    cell: &TableCellElement,
+    path: &mut VisitPath,
+) {
+    let kind = NodeKind::TableCell;
+    walk_parameters(visitor, &cell.parameters, kind, path);
+    walk_elements(visitor, &cell.x, kind, "x", path);
+    walk_elements(visitor, &cell.y, kind, "y", path);
+    walk_elements(visitor, &cell.children, kind, "children", path);
+}
+
+pub fn walk_list_content_item<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    item: &ListContentItem,
+    path: &mut VisitPath,
+) {
+    match item {
+        ListContentItem::Item(li) => visitor.visit_list_item(li, path),
+        ListContentItem::Conditional(cond) => {
+            walk_condition(visitor, &cond.condition, NodeKind::ConditionalItems, path);
+            for (index, li) in cond.items.iter().enumerate() {
+                path.push(NodeKind::ConditionalItems, "items", index);
+                visitor.visit_list_item(li, path);
+                path.pop();
+            }
+        }
+    }
+}
+
+pub fn walk_list_item<V: Visitor + ?Sized>(
+    visitor: &mut V,
+    item: &ListItemElement,
+    path: &mut VisitPath,
+) {
+    walk_parameters(visitor, &item.parameters, NodeKind::ListItem, path);
+    walk_elements(
+        visitor,
+        &item.children,
+        NodeKind::ListItem,
+        "children",
+        path,
+    );
+}
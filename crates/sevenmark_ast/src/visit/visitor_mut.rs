@@ -0,0 +1,397 @@
+//! Mutating visitor
+
+use super::{NodeKind, VisitPath};
+use crate::{
+    Element, Expression, FoldInnerElement, ListContentItem, ListItemElement, Parameter, Parameters,
+    TableCellElement, TableCellItem, TableRowElement, TableRowItem,
+};
+
+/// Mutating AST visitor, the counterpart of [`Visitor`](super::Visitor)
+pub trait VisitorMut {
+    /// Visit a top-level element list
+    fn visit_document_mut(&mut self, elements: &mut Vec<Element>) {
+        let mut path = VisitPath::new();
+        walk_elements_mut(self, elements, NodeKind::Document, "elements", &mut path);
+    }
+
+    /// Replace an element stored in a list with any number of elements.
+    /// Elements that are not part of a list (the element of an
+    /// [`Expression::Element`]) go to [`VisitorMut::visit_element_mut`] only.
+    fn flat_map_element(&mut self, mut element: Element, path: &mut VisitPath) -> Vec<Element> {
+        self.visit_element_mut(&mut element, path);
+        vec![element]
+    }
+
+    fn visit_element_mut(&mut self, element: &mut Element, path: &mut VisitPath) {
+        walk_element_mut(self, element, path);
+    }
+
+    fn visit_parameter_mut(&mut self, parameter: &mut Parameter, path: &mut VisitPath) {
+        walk_parameter_mut(self, parameter, path);
+    }
+
+    fn visit_expression_mut(&mut self, expression: &mut Expression, path: &mut VisitPath) {
+        walk_expression_mut(self, expression, path);
+    }
+
+    fn visit_table_row_item_mut(&mut self, item: &mut TableRowItem, path: &mut VisitPath) {
+        walk_table_row_item_mut(self, item, path);
+    }
+
+    fn visit_table_row_mut(&mut self, row: &mut TableRowElement, path: &mut VisitPath) {
+        walk_table_row_mut(self, row, path);
+    }
+
+    fn visit_table_cell_item_mut(&mut self, item: &mut TableCellItem, path: &mut VisitPath) {
+        walk_table_cell_item_mut(self, item, path);
+    }
+
+    fn visit_table_cell_mut(&mut self, cell: &mut TableCellElement, path: &mut VisitPath) {
+        walk_table_cell_mut(self, cell, path);
+    }
+
+    fn visit_list_content_item_mut(&mut self, item: &mut ListContentItem, path: &mut VisitPath) {
+        walk_list_content_item_mut(self, item, path);
+    }
+
+    fn visit_list_item_mut(&mut self, item: &mut ListItemElement, path: &mut VisitPath) {
+        walk_list_item_mut(self, item, path);
+    }
+}
+
+/// Pass each element of a list stored in `parent.field` through
+/// [`VisitorMut::flat_map_element`]
+pub fn walk_elements_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    elements: &mut Vec<Element>,
+    parent: NodeKind,
+    field: &'static str,
+    path: &mut VisitPath,
+) {
+    let original = std::mem::take(elements);
+    for (index, element) in original.into_iter().enumerate() {
+        path.push(parent, field, index);
+        let replaced = visitor.flat_map_element(element, path);
+        path.pop();
+        elements.extend(replaced);
+    }
+}
+
+fn walk_parameters_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    parameters: &mut Parameters,
+    parent: NodeKind,
+    path: &mut VisitPath,
+) {
+    for (index, parameter) in parameters.values_mut().enumerate() {
+        path.push(parent, "parameters", index);
+        visitor.visit_parameter_mut(parameter, path);
+        path.pop();
+    }
+}
+
+fn walk_condition_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    condition: &mut Expression,
+    parent: NodeKind,
+    path: &mut VisitPath,
+) {
+    path.push(parent, "condition", 0);
+    visitor.visit_expression_mut(condition, path);
+    path.pop();
+}
+
+fn walk_fold_inner_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    inner: &mut FoldInnerElement,
+    field: &'static str,
+    path: &mut VisitPath,
+) {
+    path.push(NodeKind::Element("Fold"), field, 0);
+    walk_parameters_mut(visitor, &mut inner.parameters, NodeKind::FoldInner, path);
+    walk_elements_mut(
+        visitor,
+        &mut inner.children,
+        NodeKind::FoldInner,
+        "children",
+        path,
+    );
+    path.pop();
+}
+
+/// Visit everything nested in an element, in source order
+pub fn walk_element_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    element: &mut Element,
+    path: &mut VisitPath,
+) {
+    let kind = NodeKind::Element(element.name());
+    match element {
+        Element::Text(_)
+        | Element::Comment(_)
+        | Element::Escape(_)
+        | Element::Error(_)
+        | Element::TeX(_)
+        | Element::Css(_)
+        | Element::Null(_)
+        | Element::FootnoteRef(_)
+        | Element::TimeNow(_)
+        | Element::Date(_)
+        | Element::DateTime(_)
+        | Element::Dday(_)
+        | Element::PageCount(_)
+        | Element::Age(_)
+        | Element::Variable(_)
+        | Element::Anchor(_)
+        | Element::Toc(_)
+        | Element::Mention(_)
+        | Element::SoftBreak(_)
+        | Element::HardBreak(_)
+        | Element::Clear(_)
+        | Element::HLine(_) => {}
+
+        Element::Define(e) => walk_parameters_mut(visitor, &mut e.parameters, kind, path),
+        Element::Code(e) => walk_parameters_mut(visitor, &mut e.parameters, kind, path),
+        Element::ExternalMedia(e) => walk_parameters_mut(visitor, &mut e.parameters, kind, path),
+
+        Element::Literal(e) => walk_elements_mut(visitor, &mut e.children, kind, "children", path),
+        Element::Category(e) => walk_elements_mut(visitor, &mut e.children, kind, "children", path),
+        Element::Header(e) => walk_elements_mut(visitor, &mut e.children, kind, "children", path),
+        Element::Bold(e)
+        | Element::Italic(e)
+        | Element::Strikethrough(e)
+        | Element::Underline(e)
+        | Element::Superscript(e)
+        | Element::Subscript(e) => {
+            walk_elements_mut(visitor, &mut e.children, kind, "children", path)
+        }
+
+        Element::Styled(e) => {
+            walk_parameters_mut(visitor, &mut e.parameters, kind, path);
+            walk_elements_mut(visitor, &mut e.children, kind, "children", path);
+        }
+        Element::BlockQuote(e) => {
+            walk_parameters_mut(visitor, &mut e.parameters, kind, path);
+            walk_elements_mut(visitor, &mut e.children, kind, "children", path);
+        }
+        Element::Ruby(e) => {
+            walk_parameters_mut(visitor, &mut e.parameters, kind, path);
+            walk_elements_mut(visitor, &mut e.children, kind, "children", path);
+        }
+        Element::Footnote(e) => {
+            walk_parameters_mut(visitor, &mut e.parameters, kind, path);
+            walk_elements_mut(visitor, &mut e.children, kind, "children", path);
+        }
+        Element::Include(e) => {
+            walk_parameters_mut(visitor, &mut e.parameters, kind, path);
+            walk_elements_mut(visitor, &mut e.children, kind, "children", path);
+        }
+        Element::Redirect(e) => {
+            walk_parameters_mut(visitor, &mut e.parameters, kind, path);
+            walk_elements_mut(visitor, &mut e.children, kind, "children", path);
+        }
+        Element::Media(e) => {
+            walk_parameters_mut(visitor, &mut e.parameters, kind, path);
+            walk_elements_mut(visitor, &mut e.children, kind, "children", path);
+        }
+
+        Element::If(e) => {
+            walk_condition_mut(visitor, &mut e.condition, kind, path);
+            walk_elements_mut(visitor, &mut e.children, kind, "children", path);
+        }
+
+        Element::Fold(e) => {
+            walk_parameters_mut(visitor, &mut e.parameters, kind, path);
+            walk_fold_inner_mut(visitor, &mut e.summary, "summary", path);
+            walk_fold_inner_mut(visitor, &mut e.details, "details", path);
+        }
+
+        Element::Table(e) => {
+            walk_parameters_mut(visitor, &mut e.parameters, kind, path);
+            for (index, item) in e.children.iter_mut().enumerate() {
+                path.push(kind, "children", index);
+                visitor.visit_table_row_item_mut(item, path);
+                path.pop();
+            }
+        }
+
+        Element::List(e) => {
+            walk_parameters_mut(visitor, &mut e.parameters, kind, path);
+            for (index, item) in e.children.iter_mut().enumerate() {
+                path.push(kind, "children", index);
+                visitor.visit_list_content_item_mut(item, path);
+                path.pop();
+            }
+        }
+    }
+}
+
+pub fn walk_parameter_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    parameter: &mut Parameter,
+    path: &mut VisitPath,
+) {
+    walk_elements_mut(
+        visitor,
+        &mut parameter.value,
+        NodeKind::Parameter,
+        "value",
+        path,
+    );
+}
+
+pub fn walk_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut Expression,
+    path: &mut VisitPath,
+) {
+    match expression {
+        Expression::Or { left, right, .. }
+        | Expression::And { left, right, .. }
+        | Expression::Comparison { left, right, .. } => {
+            walk_operand_mut(visitor, left, "left", 0, path);
+            walk_operand_mut(visitor, right, "right", 0, path);
+        }
+        Expression::Not { inner, .. } | Expression::Group { inner, .. } => {
+            walk_operand_mut(visitor, inner, "inner", 0, path);
+        }
+        Expression::FunctionCall { arguments, .. } => {
+            for (index, argument) in arguments.iter_mut().enumerate() {
+                walk_operand_mut(visitor, argument, "arguments", index, path);
+            }
+        }
+        Expression::StringLiteral { value, .. } => {
+            walk_elements_mut(visitor, value, NodeKind::Expression, "value", path);
+        }
+        Expression::Element(element) => {
+            path.push(NodeKind::Expression, "element", 0);
+            visitor.visit_element_mut(element, path);
+            path.pop();
+        }
+        Expression::NumberLiteral { .. }
+        | Expression::BoolLiteral { .. }
+        | Expression::Null { .. } => {}
+    }
+}
+
+fn walk_operand_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut Expression,
+    field: &'static str,
+    index: usize,
+    path: &mut VisitPath,
+) {
+    path.push(NodeKind::Expression, field, index);
+    visitor.visit_expression_mut(expression, path);
+    path.pop();
+}
+
+/// A plain row is visited in place; conditional rows add a level
+pub fn walk_table_row_item_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    item: &mut TableRowItem,
+    path: &mut VisitPath,
+) {
+    match item {
+        TableRowItem::Row(row) => visitor.visit_table_row_mut(row, path),
+        TableRowItem::Conditional(cond) => {
+            walk_condition_mut(
+                visitor,
+                &mut cond.condition,
+                NodeKind::ConditionalRows,
+                path,
+            );
+            for (index, row) in cond.rows.iter_mut().enumerate() {
+                path.push(NodeKind::ConditionalRows, "rows", index);
+                visitor.visit_table_row_mut(row, path);
+                path.pop();
+            }
+        }
+    }
+}
+
+pub fn walk_table_row_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    row: &mut TableRowElement,
+    path: &mut VisitPath,
+) {
+    walk_parameters_mut(visitor, &mut row.parameters, NodeKind::TableRow, path);
+    for (index, item) in row.children.iter_mut().enumerate() {
+        path.push(NodeKind::TableRow, "children", index);
+        visitor.visit_table_cell_item_mut(item, path);
+        path.pop();
+    }
+}
+
+pub fn walk_table_cell_item_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    item: &mut TableCellItem,
+    path: &mut VisitPath,
+) {
+    match item {
+        TableCellItem::Cell(cell) => visitor.visit_table_cell_mut(cell, path),
+        TableCellItem::Conditional(cond) => {
+            walk_condition_mut(
+                visitor,
+                &mut cond.condition,
+                NodeKind::ConditionalCells,
+                path,
+            );
+            for (index, cell) in cond.cells.iter_mut().enumerate() {
+                path.push(NodeKind::ConditionalCells, "cells", index);
+                visitor.visit_table_cell_mut(cell, path);
+                path.pop();
+            }
+        }
+    }
+}
+
+pub fn walk_table_cell_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    cell: &mut TableCellElement,
+    path: &mut VisitPath,
+) {
+    let kind = NodeKind::TableCell;
+    walk_parameters_mut(visitor, &mut cell.parameters, kind, path);
+    walk_elements_mut(visitor, &mut cell.x, kind, "x", path);
+    walk_elements_mut(visitor, &mut cell.y, kind, "y", path);
+    walk_elements_mut(visitor, &mut cell.children, kind, "children", path);
+}
+
+pub fn walk_list_content_item_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    item: &mut ListContentItem,
+    path: &mut VisitPath,
+) {
+    match item {
+        ListContentItem::Item(li) => visitor.visit_list_item_mut(li, path),
+        ListContentItem::Conditional(cond) => {
+            walk_condition_mut(
+                visitor,
+                &mut cond.condition,
+                NodeKind::ConditionalItems,
+                path,
+            );
+            for (index, li) in cond.items.iter_mut().enumerate() {
+                path.push(NodeKind::ConditionalItems, "items", index);
+                visitor.visit_list_item_mut(li, path);
+                path.pop();
+            }
+        }
+    }
+}
+
+pub fn walk_list_item_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    item: &mut ListItemElement,
+    path: &mut VisitPath,
+) {
+    walk_parameters_mut(visitor, &mut item.parameters, NodeKind::ListItem, path);
+    walk_elements_mut(
+        visitor,
+        &mut item.children,
+        NodeKind::ListItem,
+        "children",
+        path,
+    );
+}
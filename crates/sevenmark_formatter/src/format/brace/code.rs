@@ -3,7 +3,7 @@ use sevenmark_ast::CodeElement;
 
 use crate::FormatConfig;
 use crate::format::brace::common::needs_close_separator_for_raw_value;
-use crate::format::params::format_params;
+use crate::format::params::{format_params, format_params_block};
 
 pub fn format_code<'a>(
     a: &'a Arena<'a>,
@@ -16,8 +16,15 @@ pub fn format_code<'a>(
         a.nil()
     };
 
+    // A value starting with `#` would be read as another parameter
+    let params = if e.value.starts_with('#') {
+        format_params_block(a, &e.parameters, config)
+    } else {
+        format_params(a, &e.parameters, config)
+    };
+
     a.text("{{{#code")
-        .append(format_params(a, &e.parameters, config))
+        .append(params)
         .append(a.hardline())
         .append(a.text(e.value.clone()))
         .append(close_separator)
@@ -32,7 +32,6 @@ impl FormatContext {
     pub fn with_trailing_soft_break_policy(self, policy: TrailingSoftBreakPolicy) -> Self {
         Self {
             trailing_soft_break_policy: policy,
-            ..self
         }
     }
 }
@@ -0,0 +1,329 @@
+//! Property test: documents built with `sevenmark_ast::builder` and printed by
+//! the formatter must parse back to the same AST, whatever the text contains.
+
+use serde_json::Value;
+use sevenmark_ast::builder::{CellBuilder, append, text};
+use sevenmark_ast::{Element, ListKind};
+use sevenmark_formatter::{FormatConfig, format_document};
+use sevenmark_parser::core::parse_document;
+
+/// Markup fragments that break naive string concatenation
+const FRAGMENTS: &[&str] = &[
+    "[[",
+    "]]",
+    "[",
+    "]",
+    "{{{",
+    "}}}",
+    "{",
+    "}",
+    "**",
+    "*",
+    "~~",
+    "~",
+    "__",
+    "_",
+    "^^",
+    "^",
+    ",,",
+    ",",
+    "//",
+    "/*",
+    "*/",
+    "/",
+    "\\",
+    "#",
+    "##",
+    ">",
+    "-",
+    "----",
+    "+",
+    "1. ",
+    "a) ",
+    "<@",
+    "<#",
+    "<",
+    "\"",
+    "||",
+    "|",
+    "=",
+    "::",
+    "[br]",
+    "[fn]",
+    "#style=\"x\"",
+    "$$",
+    "%",
+    " ",
+    "  ",
+    "word",
+    "Title",
+    "문서",
+    "a",
+    "0",
+];
+
+/// xorshift64*: deterministic without extra dependencies
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn chance(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+}
+
+fn words(rng: &mut Rng, newlines: bool) -> String {
+    let mut out = String::new();
+    for _ in 0..1 + rng.below(5) {
+        if newlines && rng.chance(10) {
+            out.push('\n');
+        }
+        out.push_str(FRAGMENTS[rng.below(FRAGMENTS.len())]);
+    }
+    out
+}
+
+/// Inline content, possibly empty. Links and footnotes nest in themselves
+/// and headers appear inside them; the builders unwrap what the parser does
+/// not read there.
+fn inline(rng: &mut Rng, depth: usize) -> Vec<Element> {
+    let mut out = Vec::new();
+    for _ in 0..rng.below(4) {
+        let el = match rng.below(if depth == 0 { 2 } else { 12 }) {
+            2 => Element::bold(nested(rng, depth - 1)),
+            3 => {
+                let mut children = nested(rng, depth - 1);
+                if matches!(children.first(), Some(Element::Bold(_))) && children.len() > 1 {
+                    children.splice(0..0, text("x"));
+                }
+                Element::italic(children)
+            }
+            4 => Element::strikethrough(nested(rng, depth - 1)),
+            5 => Element::underline(nested(rng, depth - 1)),
+            6 => Element::superscript(nested(rng, depth - 1)),
+            7 => Element::link(&words(rng, false))
+                .content(nested(rng, depth - 1))
+                .build(),
+            8 => Element::footnote().content(nested(rng, depth - 1)).build(),
+            9 => Element::styled()
+                .param("style", &words(rng, false))
+                .content(nested(rng, depth - 1))
+                .build(),
+            10 => Element::hard_break(),
+            11 => Element::ruby(&words(rng, false))
+                .content(nested(rng, depth - 1))
+                .build(),
+            _ => {
+                append(&mut out, words(rng, false));
+                continue;
+            }
+        };
+        append(&mut out, el);
+    }
+    out
+}
+
+/// Content of a container, sometimes with a header, rule or comment the
+/// builder unwraps
+fn nested(rng: &mut Rng, depth: usize) -> Vec<Element> {
+    let out = inline(rng, depth);
+    with_top_level_only(rng, out, depth)
+}
+
+fn with_top_level_only(rng: &mut Rng, mut out: Vec<Element>, depth: usize) -> Vec<Element> {
+    if rng.chance(10) {
+        let at = rng.below(out.len() + 1);
+        let el = match rng.below(3) {
+            0 => Element::header(1 + rng.below(6), inline(rng, depth)),
+            1 => Element::hline(),
+            _ => Element::comment(&words(rng, false)),
+        };
+        out.insert(at, el);
+    }
+    out
+}
+
+/// Headers, rules, categories and includes only occur at the top level
+fn block(rng: &mut Rng, depth: usize, top: bool) -> Element {
+    let line_level = if top { 4 } else { 0 };
+    let containers = if depth == 0 { 0 } else { 5 };
+    let pick = rng.below(line_level + containers + 1);
+    if pick < line_level {
+        return match pick {
+            0 => Element::header(1 + rng.below(6), inline(rng, 1)),
+            1 => Element::hline(),
+            2 => Element::category(&words(rng, false)),
+            _ => Element::include(&words(rng, false))
+                .param("arg", &words(rng, true))
+                .build(),
+        };
+    }
+    match pick - line_level {
+        0 if containers > 0 => {
+            let mut table = Element::table().param("caption", &words(rng, false));
+            for _ in 0..rng.below(3) {
+                let cells: Vec<CellBuilder> = (0..rng.below(3))
+                    .map(|_| {
+                        let content = blocks(rng, depth - 1, false);
+                        let cell = CellBuilder::new().content(with_top_level_only(rng, content, 0));
+                        if rng.chance(20) {
+                            cell.colspan(2)
+                        } else {
+                            cell
+                        }
+                    })
+                    .collect();
+                table = table.row(cells);
+            }
+            table.build()
+        }
+        1 if containers > 0 => {
+            let mut list = Element::list(ListKind::OrderedAlphaLower);
+            for _ in 0..rng.below(3) {
+                let content = blocks(rng, depth - 1, false);
+                list = list.item(with_top_level_only(rng, content, 0));
+            }
+            list.build()
+        }
+        2 if containers > 0 => {
+            let content = blocks(rng, depth - 1, false);
+            Element::blockquote()
+                .content(with_top_level_only(rng, content, 0))
+                .build()
+        }
+        3 if containers > 0 => {
+            Element::fold(blocks(rng, depth - 1, false), blocks(rng, depth - 1, false))
+        }
+        4 if containers > 0 => Element::footnote().build(),
+        _ if rng.chance(50) => Element::code(Some("rust"), "fn f() {}"),
+        _ => Element::code(Some("rust"), "let s = \"[[x]]\";"),
+    }
+}
+
+/// Lines of blocks, inline content and multi-line text
+fn blocks(rng: &mut Rng, depth: usize, top: bool) -> Vec<Element> {
+    let mut out = Vec::new();
+    for i in 0..1 + rng.below(3) {
+        if i > 0 {
+            append(&mut out, Element::soft_break());
+        }
+        match rng.below(5) {
+            0 | 1 => append(&mut out, block(rng, depth, top)),
+            2 => append(&mut out, words(rng, true)),
+            _ => append(&mut out, inline(rng, depth)),
+        }
+    }
+    out
+}
+
+/// JSON without spans and parser-assigned numbering. The parser may
+/// split one text run into several `Text` elements, so those are merged.
+fn normalized(elements: &[Element]) -> Value {
+    fn text(value: &Value) -> Option<&str> {
+        value.get("Text")?.get("value")?.as_str()
+    }
+
+    fn strip(value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                // Spans are serialized when `include_locations` is enabled
+                map.retain(|key, _| {
+                    !key.ends_with("span")
+                        && !key.ends_with("spans")
+                        && key != "footnote_index"
+                        && key != "section_index"
+                });
+                map.values_mut().for_each(strip);
+            }
+            Value::Array(items) => {
+                let mut merged: Vec<Value> = Vec::new();
+                for mut item in std::mem::take(items) {
+                    strip(&mut item);
+                    match (merged.last().and_then(text), text(&item)) {
+                        (Some(prev), Some(next)) => {
+                            let joined = format!("{prev}{next}");
+                            merged.pop();
+                            merged.push(serde_json::json!({ "Text": { "value": joined } }));
+                        }
+                        _ => merged.push(item),
+                    }
+                }
+                *items = merged;
+            }
+            _ => {}
+        }
+    }
+    let mut value = serde_json::to_value(elements).unwrap();
+    strip(&mut value);
+    value
+}
+
+#[test]
+fn builder_output_reparses_to_equal_ast() {
+    for seed in 1..=2000u64 {
+        let mut rng = Rng(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+        let built = blocks(&mut rng, 2, true);
+        let source = format_document(&built, &FormatConfig::default());
+        let reparsed = parse_document(&source);
+        assert_eq!(
+            normalized(&reparsed),
+            normalized(&built),
+            "seed {seed}, source:\n{source}"
+        );
+    }
+}
+
+#[test]
+fn bracketed_titles_stay_links() {
+    let link = Element::link("Foo [bar] {{{baz}}}")
+        .content("Foo [bar] {{{baz}}}")
+        .build();
+    let source = format_document(&[link], &FormatConfig::default());
+    let reparsed = parse_document(&source);
+    assert_eq!(reparsed.len(), 1, "{source}");
+    assert!(matches!(reparsed[0], Element::Media(_)), "{source}");
+}
+
+#[test]
+fn unrepresentable_content_is_normalized() {
+    let cases = [
+        Element::footnote().build(),
+        Element::table().build(),
+        Element::list(ListKind::Unordered).build(),
+        Element::bold(Vec::new()),
+        Element::footnote()
+            .content(Element::footnote().content("inner").build())
+            .build(),
+        Element::link("outer")
+            .content(Element::link("inner").content("label").build())
+            .build(),
+        Element::table()
+            .row([Element::header(2, "title")])
+            .row([CellBuilder::new()])
+            .build(),
+        Element::code(None, "fn main() {}"),
+        Element::code(None, "\n    indented"),
+        Element::code(None, "#include <stdio.h>"),
+        Element::code(Some("c"), "#define X 1"),
+        Element::tex(false, "x^{2}"),
+    ];
+    for built in cases {
+        let source = format_document(std::slice::from_ref(&built), &FormatConfig::default());
+        let reparsed = parse_document(&source);
+        assert_eq!(
+            normalized(&reparsed),
+            normalized(std::slice::from_ref(&built)),
+            "source:\n{source}"
+        );
+    }
+}
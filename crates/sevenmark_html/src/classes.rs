@@ -83,8 +83,7 @@ pub const SECTION_FOLDED: &str = "sm-folded";
 pub const SECTION_CONTENT: &str = "sm-section-content";
 pub const SECTION_PATH: &str = "sm-section-path";
 pub const HEADER_CONTENT: &str = "sm-header-content";
-/// Section ID prefix (e.g., "s-1", "s-1.1")
-pub const SECTION_ID_PREFIX: &str = "s-";
+pub use sevenmark_ast::SECTION_ID_PREFIX;
 
 // Ruby
 pub const RUBY: &str = "sm-ruby";
@@ -116,6 +115,7 @@ pub const MENTION_DISCUSSION: &str = "sm-mention-discussion";
 
 // Include
 pub const INCLUDE: &str = "sm-include";
+pub const INCLUDE_FAILED: &str = "sm-include-failed";
 
 // Errors
 pub const ERROR: &str = "sm-error";
@@ -131,6 +131,39 @@ pub fn render(
     }
 }
 
+/// Render footnote list (used at document end and for mid-flush)
+pub fn render_list(ctx: &RenderContext) -> Markup {
+    render_footnote_entries(&ctx.footnotes, ctx)
+}
+
+/// Render a list of footnote entries
+pub fn render_footnote_entries(entries: &[FootnoteEntry], ctx: &RenderContext) -> Markup {
+    if entries.is_empty() {
+        return html! {};
+    }
+
+    let mut inner_ctx = ctx.child();
+    inner_ctx.in_footnote = true;
+
+    html! {
+        section class=(classes::FOOTNOTE_LIST) {
+            ol {
+                @for entry in entries {
+                    li id=(footnote_id(entry)) {
+                        a class=(classes::FOOTNOTE_BACK) href=(format!("#{}", footnote_ref_id(entry))) {
+                            "[" (entry.display) "]"
+                        }
+                        " "
+                        span class=(classes::FOOTNOTE_CONTENT) {
+                            (render_elements(&entry.content, &mut inner_ctx))
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::test_support::{parse_fragment, render_html, selector};
@@ -220,36 +253,3 @@ D{{{#fn Second unnamed. }}}.
         );
     }
 }
-
-/// Render footnote list (used at document end and for mid-flush)
-pub fn render_list(ctx: &RenderContext) -> Markup {
-    render_footnote_entries(&ctx.footnotes, ctx)
-}
-
-/// Render a list of footnote entries
-pub fn render_footnote_entries(entries: &[FootnoteEntry], ctx: &RenderContext) -> Markup {
-    if entries.is_empty() {
-        return html! {};
-    }
-
-    let mut inner_ctx = ctx.child();
-    inner_ctx.in_footnote = true;
-
-    html! {
-        section class=(classes::FOOTNOTE_LIST) {
-            ol {
-                @for entry in entries {
-                    li id=(footnote_id(entry)) {
-                        a class=(classes::FOOTNOTE_BACK) href=(format!("#{}", footnote_ref_id(entry))) {
-                            "[" (entry.display) "]"
-                        }
-                        " "
-                        span class=(classes::FOOTNOTE_CONTENT) {
-                            (render_elements(&entry.content, &mut inner_ctx))
-                        }
-                    }
-                }
-            }
-        }
-    }
-}
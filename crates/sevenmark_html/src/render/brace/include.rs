@@ -1,13 +1,22 @@
 //! Include element rendering
 
 use maud::{Markup, html};
-use sevenmark_ast::{Element, Span};
+use sevenmark_ast::{Element, IncludeFailure, Span};
 
 use crate::classes;
 use crate::context::RenderContext;
 use crate::render::render_elements;
 
-pub fn render(span: &Span, children: &[Element], ctx: &mut RenderContext) -> Markup {
+pub fn render(
+    span: &Span,
+    children: &[Element],
+    failure: Option<IncludeFailure>,
+    ctx: &mut RenderContext,
+) -> Markup {
+    if let Some(failure) = failure {
+        return render_failed(span, children, failure, ctx);
+    }
+
     // Reset soft-break suppression so the included document renders naturally
     // (e.g. when {{{#include}}} appears inside {{{#fn}}}).
     let saved_depth = ctx.suppress_soft_breaks_depth;
@@ -25,3 +34,59 @@ pub fn render(span: &Span, children: &[Element], ctx: &mut RenderContext) -> Mar
         }
     }
 }
+
+/// Placeholder for an include the transform could not substitute;
+/// `children` still hold the target title
+fn render_failed(
+    span: &Span,
+    children: &[Element],
+    failure: IncludeFailure,
+    ctx: &mut RenderContext,
+) -> Markup {
+    let (reason, message) = match failure {
+        IncludeFailure::NotFound => ("not_found", "Include not found: "),
+        IncludeFailure::Unavailable => ("unavailable", "Include could not be loaded: "),
+    };
+    let title = render_elements(children, ctx);
+
+    html! {
+        span
+            class=(format!("{} {}", classes::INCLUDE, classes::INCLUDE_FAILED))
+            data-failure=(reason)
+            data-start=[ctx.span_start(span)]
+            data-end=[ctx.span_end(span)]
+        {
+            (message) (title)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::{parse_fragment, selector};
+    use crate::{RenderConfig, render_document};
+    use sevenmark_ast::{Element, IncludeFailure};
+    use sevenmark_parser::core::parse_document;
+
+    #[test]
+    fn failed_include_renders_placeholder_with_title() {
+        let mut ast = parse_document("{{{#include Template}}}");
+        match &mut ast[0] {
+            Element::Include(include) => include.failure = Some(IncludeFailure::NotFound),
+            other => panic!("expected include, got {other:?}"),
+        }
+
+        let html = render_document(&ast, &RenderConfig::default());
+        let doc = parse_fragment(&html);
+        let placeholder = doc
+            .select(&selector("span.sm-include-failed"))
+            .next()
+            .expect("expected failed include placeholder");
+
+        assert_eq!(placeholder.value().attr("data-failure"), Some("not_found"));
+        assert_eq!(
+            placeholder.text().collect::<String>(),
+            "Include not found: Template"
+        );
+    }
+}
@@ -45,10 +45,10 @@ fn build_embed_url(
     if utils::get_param(parameters, "loop").is_some() {
         params.push("loop=1".to_string());
         // For single video loop (no playlist), add playlist=id
-        if playlist_id.is_none() {
-            if let Some(id) = video_id {
-                params.push(format!("playlist={}", id));
-            }
+        if playlist_id.is_none()
+            && let Some(id) = video_id
+        {
+            params.push(format!("playlist={}", id));
         }
     }
     if utils::get_param(parameters, "mute").is_some() {
@@ -21,7 +21,7 @@ pub fn render_document(ast: &[Element], config: &RenderConfig) -> String {
     if contains_toc(ast) {
         ctx.set_toc_markup(prebuild_toc_markup(&tree, &ctx));
     }
-    let content = render_section_tree(&tree, config, &mut ctx);
+    let content = render_section_tree(&tree, &mut ctx);
 
     let markup = html! {
         (content)
@@ -52,7 +52,7 @@ pub fn render_document_with_spans(ast: &[Element], config: &RenderConfig, input:
     if contains_toc(ast) {
         ctx.set_toc_markup(prebuild_toc_markup(&tree, &ctx));
     }
-    let content = render_section_tree(&tree, config, &mut ctx);
+    let content = render_section_tree(&tree, &mut ctx);
 
     let markup = html! {
         (content)
@@ -96,23 +96,19 @@ fn contains_toc(elements: &[Element]) -> bool {
 }
 
 /// Render a section tree
-fn render_section_tree(
-    tree: &SectionTree<'_>,
-    config: &RenderConfig,
-    ctx: &mut RenderContext,
-) -> Markup {
+fn render_section_tree(tree: &SectionTree<'_>, ctx: &mut RenderContext) -> Markup {
     html! {
         @for el in &tree.preamble {
             (element::render_element(el, ctx))
         }
         @for section in &tree.sections {
-            (render_section(section, config, ctx))
+            (render_section(section, ctx))
         }
     }
 }
 
 /// Render a single section with nested structure
-fn render_section(section: &Section<'_>, config: &RenderConfig, ctx: &mut RenderContext) -> Markup {
+fn render_section(section: &Section<'_>, ctx: &mut RenderContext) -> Markup {
     let header_markup = markdown::header::render_with_path(
         section.header_level,
         section.header_children,
@@ -126,7 +122,7 @@ fn render_section(section: &Section<'_>, config: &RenderConfig, ctx: &mut Render
                 (element::render_element(el, ctx))
             }
             @for child in &section.children {
-                (render_section(child, config, ctx))
+                (render_section(child, ctx))
             }
         }
     };
@@ -119,7 +119,9 @@ pub fn render_element(el: &Element, ctx: &mut RenderContext) -> Markup {
         // Wiki elements (metadata, not rendered visually)
         Element::Category(_) => html! {},
         Element::Redirect(_) => html! {},
-        Element::Include(include) => brace::include::render(&include.span, &include.children, ctx),
+        Element::Include(include) => {
+            brace::include::render(&include.span, &include.children, include.failure, ctx)
+        }
         Element::Define(_) => html! {},
         Element::Variable(var) => text::variable::render(&var.span, &var.name, ctx),
         Element::If(_) => html! {},
@@ -40,7 +40,7 @@ fn render_section_list(sections: &[Section<'_>], ctx: &mut RenderContext) -> Mar
                     {
                         span class=(classes::SECTION_PATH) { (section.section_path) "." }
                         span class=(classes::HEADER_CONTENT) {
-                            (render_toc_label(&section.header_children, ctx))
+                            (render_toc_label(section.header_children, ctx))
                         }
                     }
                     @if !section.children.is_empty() {
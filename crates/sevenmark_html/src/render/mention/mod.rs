@@ -1,3 +1,4 @@
 //! Mention element renderers
 
+#[allow(clippy::module_inception)]
 pub mod mention;
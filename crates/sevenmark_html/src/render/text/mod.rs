@@ -2,5 +2,6 @@
 
 pub mod error;
 pub mod escape;
+#[allow(clippy::module_inception)]
 pub mod text;
 pub mod variable;
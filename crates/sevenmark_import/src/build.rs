@@ -0,0 +1,241 @@
+//! Element constructors for generated (span-less) AST nodes
+
+use std::collections::HashMap;
+
+use sevenmark_ast::builder::{CellBuilder, ParametersExt};
+use sevenmark_ast::{Element, ExternalMediaElement, HeaderElement, ListKind, Parameters, Span};
+
+use crate::inline::Inline;
+
+fn s() -> Span {
+    Span::synthesized()
+}
+
+/// Ordered parameter list builder
+#[derive(Debug, Default)]
+pub(crate) struct Params(Parameters);
+
+impl Params {
+    pub fn set(mut self, key: &str, value: &str) -> Self {
+        self.insert(key, value);
+        self
+    }
+
+    pub fn insert(&mut self, key: &str, value: &str) {
+        self.0.set(key, value);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn build(self) -> Parameters {
+        self.0
+    }
+}
+
+/// Parameter keys must match `[A-Za-z0-9_-]+`
+pub(crate) fn param_key(raw: &str) -> Option<String> {
+    let key: String = raw
+        .trim()
+        .chars()
+        .map(|c| if c.is_whitespace() { '_' } else { c })
+        .filter(|c| c.is_alphanumeric() || matches!(c, '_' | '-'))
+        .collect();
+    (!key.is_empty()).then_some(key)
+}
+
+/// Plain text as escaped elements; leading `#` is escaped so it cannot be
+/// mistaken for a parameter
+pub(crate) fn text(value: &str) -> Vec<Element> {
+    let mut inline = Inline::default();
+    inline.text(value);
+    inline.finish()
+}
+
+pub(crate) fn header(level: usize, is_folded: bool, children: Vec<Element>) -> Element {
+    Element::Header(HeaderElement {
+        span: s(),
+        level: level.clamp(1, 6),
+        is_folded,
+        section_index: 0,
+        children,
+    })
+}
+
+pub(crate) fn media(params: Params, children: Vec<Element>) -> Element {
+    Element::media()
+        .parameters(params.build())
+        .content(children)
+        .build()
+}
+
+pub(crate) fn external_media(provider: &str, params: Params) -> Element {
+    Element::ExternalMedia(ExternalMediaElement {
+        span: s(),
+        open_span: s(),
+        close_span: s(),
+        provider: provider.to_string(),
+        parameters: params.build(),
+    })
+}
+
+pub(crate) fn footnote(params: Params, children: Vec<Element>) -> Element {
+    Element::footnote()
+        .parameters(params.build())
+        .content(children)
+        .build()
+}
+
+/// Raw code cannot contain `}}}`, so closers are split to keep the block closed
+pub(crate) fn split_closers(value: &str) -> String {
+    let mut value = value.to_string();
+    while value.contains("}}}") {
+        value = value.replace("}}}", "}} }");
+    }
+    value
+}
+
+/// Named footnotes defined so far. SevenMark has no empty footnote, so a
+/// reuse (`<ref name="a" />`, `[*a]`) repeats the definition under the same
+/// name, which renders as a reference to the first one.
+#[derive(Debug, Default)]
+pub(crate) struct NamedFootnotes(HashMap<String, Vec<Element>>);
+
+impl NamedFootnotes {
+    /// The footnote, or `None` for a reuse of a name not defined yet
+    pub fn footnote(&mut self, name: Option<&str>, children: Vec<Element>) -> Option<Element> {
+        let Some(name) = name else {
+            return Some(footnote(Params::default(), children));
+        };
+        let params = Params::default().set("name", name);
+        if children.is_empty() {
+            let children = self.0.get(name)?.clone();
+            return Some(footnote(params, children));
+        }
+        self.0
+            .entry(name.to_string())
+            .or_insert_with(|| children.clone());
+        Some(footnote(params, children))
+    }
+}
+
+pub(crate) fn styled(params: Params, children: Vec<Element>) -> Element {
+    Element::styled()
+        .parameters(params.build())
+        .content(children)
+        .build()
+}
+
+pub(crate) fn blockquote(children: Vec<Element>) -> Element {
+    Element::blockquote().content(children).build()
+}
+
+pub(crate) fn ruby(annotation: &str, children: Vec<Element>) -> Element {
+    Element::ruby(annotation).content(children).build()
+}
+
+pub(crate) fn include(params: Params, page: &str) -> Element {
+    Element::include(page).parameters(params.build()).build()
+}
+
+pub(crate) fn redirect(params: Params, target: &str) -> Element {
+    Element::redirect(target).parameters(params.build()).build()
+}
+
+pub(crate) fn list(kind: ListKind, items: Vec<Vec<Element>>) -> Element {
+    items
+        .into_iter()
+        .fold(Element::list(kind), |list, item| list.item(item))
+        .build()
+}
+
+/// A table cell before it is placed in the AST
+#[derive(Debug, Default)]
+pub(crate) struct Cell {
+    pub params: Params,
+    pub colspan: Option<usize>,
+    pub rowspan: Option<usize>,
+    pub children: Vec<Element>,
+}
+
+pub(crate) fn table(params: Params, rows: Vec<Vec<Cell>>) -> Element {
+    let cell = |cell: Cell| {
+        let mut builder = CellBuilder::new()
+            .parameters(cell.params.build())
+            .content(cell.children);
+        if let Some(n) = cell.colspan.filter(|&n| n > 1) {
+            builder = builder.colspan(n);
+        }
+        if let Some(n) = cell.rowspan.filter(|&n| n > 1) {
+            builder = builder.rowspan(n);
+        }
+        builder
+    };
+    rows.into_iter()
+        .fold(
+            Element::table().parameters(params.build()),
+            |table, cells| table.row(cells.into_iter().map(cell)),
+        )
+        .build()
+}
+
+/// Whether the formatter already ends the line after this element
+pub(crate) fn ends_line(el: &Element) -> bool {
+    match el {
+        Element::Header(_)
+        | Element::HLine(_)
+        | Element::Define(_)
+        | Element::Include(_)
+        | Element::Category(_) => true,
+        Element::Comment(c) => !c.value.contains('\n'),
+        _ => false,
+    }
+}
+
+/// Joins block-level output, inserting the line breaks between blocks
+#[derive(Debug, Default)]
+pub(crate) struct Blocks {
+    out: Vec<Element>,
+    line_open: bool,
+    blank_lines: usize,
+}
+
+impl Blocks {
+    fn separate(&mut self) {
+        if self.out.is_empty() {
+            self.blank_lines = 0;
+            return;
+        }
+        if self.line_open {
+            self.out.push(Element::soft_break());
+        }
+        for _ in 0..std::mem::take(&mut self.blank_lines) {
+            self.out.push(Element::soft_break());
+        }
+    }
+
+    pub fn block(&mut self, el: Element) {
+        self.separate();
+        self.line_open = !ends_line(&el);
+        self.out.push(el);
+    }
+
+    /// Append one line (or paragraph) of inline content
+    pub fn line(&mut self, elements: Vec<Element>) {
+        if elements.is_empty() {
+            return;
+        }
+        self.separate();
+        self.line_open = !elements.last().is_some_and(ends_line);
+        self.out.extend(elements);
+    }
+
+    pub fn blank_line(&mut self) {
+        self.blank_lines += 1;
+    }
+
+    pub fn finish(self) -> Vec<Element> {
+        self.out
+    }
+}
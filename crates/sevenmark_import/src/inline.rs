@@ -0,0 +1,268 @@
+//! Inline content builders shared by the converters
+
+use sevenmark_ast::{Element, SoftBreakElement, Span};
+
+use crate::build::ends_line;
+use sevenmark_ast::builder::escape_text;
+
+/// Text styles both source syntaxes toggle with paired delimiters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Style {
+    Bold,
+    Italic,
+    Strikethrough,
+    Underline,
+    Superscript,
+    Subscript,
+}
+
+impl Style {
+    pub fn wrap(self, children: Vec<Element>) -> Element {
+        match self {
+            Style::Bold => Element::bold(children),
+            Style::Italic => Element::italic(children),
+            Style::Strikethrough => Element::strikethrough(children),
+            Style::Underline => Element::underline(children),
+            Style::Superscript => Element::superscript(children),
+            Style::Subscript => Element::subscript(children),
+        }
+    }
+}
+
+/// Inline element sequence that escapes text as it is appended.
+/// Consecutive text is buffered so escaping sees whole runs.
+#[derive(Debug)]
+pub(crate) struct Inline {
+    out: Vec<Element>,
+    pending: String,
+    line_start: bool,
+}
+
+impl Default for Inline {
+    fn default() -> Self {
+        Self {
+            out: Vec::new(),
+            pending: String::new(),
+            line_start: true,
+        }
+    }
+}
+
+impl Inline {
+    /// Builder for content that continues an already started line
+    pub fn continuing() -> Self {
+        Self {
+            line_start: false,
+            ..Self::default()
+        }
+    }
+
+    pub fn text(&mut self, s: &str) {
+        let mut lines = s.split('\n');
+        if let Some(first) = lines.next() {
+            self.pending.push_str(first);
+        }
+        for line in lines {
+            self.soft_break();
+            self.pending.push_str(line);
+        }
+    }
+
+    pub fn push(&mut self, el: Element) {
+        self.flush();
+        let breaks = matches!(el, Element::SoftBreak(_)) || ends_line(&el);
+        self.out.push(el);
+        self.line_start = breaks;
+    }
+
+    pub fn extend(&mut self, elements: Vec<Element>) {
+        for el in elements {
+            self.push(el);
+        }
+    }
+
+    pub fn soft_break(&mut self) {
+        self.push(Element::SoftBreak(SoftBreakElement {
+            span: Span::synthesized(),
+        }));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.out.is_empty() && self.pending.trim().is_empty()
+    }
+
+    /// Remove buffered trailing whitespace so it can be placed after a closer
+    fn take_trailing_space(&mut self) -> String {
+        let trimmed = self.pending.trim_end_matches([' ', '\t']).len();
+        self.pending.split_off(trimmed)
+    }
+
+    fn flush(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let pending = std::mem::take(&mut self.pending);
+        if self.line_start && pending.trim().is_empty() {
+            return;
+        }
+        // Source indentation is dropped rather than escaped
+        let pending = if self.line_start {
+            pending.trim_start_matches([' ', '\t'])
+        } else {
+            &pending
+        };
+        escape_text(pending, self.line_start, &mut self.out);
+        self.line_start = false;
+    }
+
+    /// Finish the run, dropping trailing whitespace and line breaks
+    pub fn finish(mut self) -> Vec<Element> {
+        let trimmed = self.pending.trim_end().len();
+        self.pending.truncate(trimmed);
+        self.flush();
+        while matches!(self.out.last(), Some(Element::SoftBreak(_))) {
+            self.out.pop();
+        }
+        self.out
+    }
+}
+
+/// Inline builder with a stack of open text styles.
+///
+/// Closing a style that is not innermost closes the inner ones and reopens
+/// them afterwards, so overlapping source markup still yields a tree.
+#[derive(Debug, Default)]
+pub(crate) struct InlineStack {
+    root: Inline,
+    frames: Vec<(Style, Inline)>,
+}
+
+impl InlineStack {
+    fn top(&mut self) -> &mut Inline {
+        match self.frames.last_mut() {
+            Some((_, inline)) => inline,
+            None => &mut self.root,
+        }
+    }
+
+    pub fn text(&mut self, s: &str) {
+        // Leading spaces stay outside a freshly opened style (`* x*` is a list item)
+        if let Some((_, inline)) = self.frames.last()
+            && inline.is_empty()
+        {
+            let content = s.trim_start_matches([' ', '\t']);
+            let spaces = &s[..s.len() - content.len()];
+            if !spaces.is_empty() {
+                let depth = self.frames.len() - 1;
+                self.parent_of(depth).text(spaces);
+            }
+            self.top().text(content);
+        } else {
+            self.top().text(s);
+        }
+    }
+
+    fn parent_of(&mut self, depth: usize) -> &mut Inline {
+        match depth.checked_sub(1) {
+            Some(i) => &mut self.frames[i].1,
+            None => &mut self.root,
+        }
+    }
+
+    pub fn push(&mut self, el: Element) {
+        self.top().push(el);
+    }
+
+    pub fn extend(&mut self, elements: Vec<Element>) {
+        self.top().extend(elements);
+    }
+
+    pub fn is_open(&self, style: Style) -> bool {
+        self.frames.iter().any(|(s, _)| *s == style)
+    }
+
+    pub fn open(&mut self, style: Style) {
+        self.frames.push((style, Inline::continuing()));
+    }
+
+    /// Close `style`, reopening any styles that were opened inside it
+    pub fn close(&mut self, style: Style) {
+        let Some(pos) = self.frames.iter().rposition(|(s, _)| *s == style) else {
+            return;
+        };
+        let mut reopen = Vec::new();
+        while self.frames.len() > pos {
+            let (s, mut inline) = self.frames.pop().expect("frame exists");
+            let trailing = inline.take_trailing_space();
+            let children = inline.finish();
+            if !children.is_empty() {
+                self.top().push(s.wrap(children));
+            }
+            self.top().text(&trailing);
+            if s != style {
+                reopen.push(s);
+            }
+        }
+        for s in reopen.into_iter().rev() {
+            self.open(s);
+        }
+    }
+
+    pub fn toggle(&mut self, style: Style) {
+        if self.is_open(style) {
+            self.close(style);
+        } else {
+            self.open(style);
+        }
+    }
+
+    pub fn close_all(&mut self) {
+        if let Some(&(outer, _)) = self.frames.first() {
+            self.close(outer);
+            self.frames.clear();
+        }
+    }
+
+    pub fn soft_break(&mut self) {
+        self.top().soft_break();
+    }
+
+    pub fn finish(mut self) -> Vec<Element> {
+        self.close_all();
+        self.root.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlapping_styles_are_split() {
+        let mut stack = InlineStack::default();
+        stack.open(Style::Bold);
+        stack.text("a");
+        stack.open(Style::Italic);
+        stack.text("b");
+        stack.close(Style::Bold);
+        stack.text("c");
+        let out = stack.finish();
+        assert_eq!(out.len(), 2);
+        assert!(matches!(out[0], Element::Bold(_)));
+        assert!(matches!(out[1], Element::Italic(_)));
+    }
+
+    #[test]
+    fn leading_space_moves_out_of_style() {
+        let mut stack = InlineStack::default();
+        stack.text("a");
+        stack.open(Style::Italic);
+        stack.text(" b");
+        stack.close(Style::Italic);
+        let out = stack.finish();
+        let Element::Text(text) = &out[0] else {
+            panic!("expected text");
+        };
+        assert_eq!(text.value, "a ");
+    }
+}
@@ -0,0 +1,37 @@
+//! sevenmark-import - MediaWiki and Namumark to SevenMark converters
+//!
+//! Parses foreign wiki markup into a SevenMark AST and serialises it with
+//! `sevenmark_formatter`, so the generated source always reparses to the same
+//! document. Constructs that cannot be converted (parser functions, raw HTML,
+//! engine-specific macros) are reported as [`ImportWarning`]s with source
+//! positions instead of failing the import.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sevenmark_formatter::FormatConfig;
+//! use sevenmark_import::{ImportFormat, import};
+//!
+//! let output = import(
+//!     ImportFormat::MediaWiki,
+//!     "== Intro ==\n'''Hello''' [[World]]",
+//!     &FormatConfig::default(),
+//! );
+//! assert!(output.text.starts_with("## Intro"));
+//! for warning in &output.warnings {
+//!     eprintln!("{}:{} {}", warning.line, warning.column, warning.message);
+//! }
+//! ```
+
+mod build;
+mod inline;
+mod mediawiki;
+mod namumark;
+mod output;
+mod scan;
+mod warning;
+
+pub use mediawiki::import_mediawiki;
+pub use namumark::import_namumark;
+pub use output::{ImportFormat, ImportOutput, import};
+pub use warning::ImportWarning;
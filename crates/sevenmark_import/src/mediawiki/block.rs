@@ -0,0 +1,341 @@
+//! Line-level structure: headings, rules, lists, tables, preformatted text
+//! and paragraphs
+
+use sevenmark_ast::{Element, ListKind};
+
+use super::MediaWiki;
+use crate::build::{self, Blocks};
+use crate::inline::Style;
+use crate::scan::{self, Line};
+
+/// Tags whose content may span several lines of a paragraph
+const MULTILINE_TAGS: &[&str] = &[
+    "ref",
+    "pre",
+    "nowiki",
+    "syntaxhighlight",
+    "source",
+    "math",
+    "blockquote",
+    "poem",
+    "gallery",
+    "references",
+    "div",
+    "includeonly",
+];
+
+struct ListLine<'a> {
+    prefix: &'a str,
+    content: &'a str,
+    content_start: usize,
+}
+
+impl MediaWiki<'_> {
+    pub(super) fn blocks(&mut self, text: &str, base: usize) -> Vec<Element> {
+        let lines = scan::lines(text);
+        let mut out = Blocks::default();
+        let mut i = 0;
+
+        while i < lines.len() {
+            let line = lines[i];
+            let t = line.text;
+            let trimmed = t.trim_start();
+
+            if trimmed.is_empty() {
+                out.blank_line();
+                i += 1;
+            } else if let Some(header) = self.heading(t, base + line.start) {
+                out.block(header);
+                i += 1;
+            } else if t.starts_with("----") {
+                out.block(Element::hline());
+                let rest = t.trim_start_matches('-');
+                if !rest.trim().is_empty() {
+                    let offset = line.end - rest.len();
+                    out.line(self.inline(rest, base + offset));
+                }
+                i += 1;
+            } else if trimmed.starts_with("{|") {
+                let end = table_end(&lines, i);
+                let start = line.start + t.len() - trimmed.len();
+                let end_offset = lines[end.min(lines.len() - 1)].end;
+                if end >= lines.len() {
+                    self.warnings.warn(
+                        base + start,
+                        base + line.end,
+                        "table is missing its closing `|}`",
+                    );
+                }
+                if let Some(table) = self.table(&text[start..end_offset], base + start) {
+                    out.block(table);
+                }
+                i = end + 1;
+            } else if t.starts_with(['*', '#', ':', ';']) {
+                let mut list_lines = Vec::new();
+                while let Some(line) = lines
+                    .get(i)
+                    .filter(|l| l.text.starts_with(['*', '#', ':', ';']))
+                {
+                    let prefix_len = line
+                        .text
+                        .find(|c| !matches!(c, '*' | '#' | ':' | ';'))
+                        .unwrap_or(line.text.len());
+                    let content = &line.text[prefix_len..];
+                    list_lines.push(ListLine {
+                        prefix: &line.text[..prefix_len],
+                        content,
+                        content_start: base + line.start + prefix_len,
+                    });
+                    i += 1;
+                }
+                for el in self.list(&list_lines, 0) {
+                    out.block(el);
+                }
+            } else if t.starts_with(' ') {
+                let mut code_lines = Vec::new();
+                while let Some(line) = lines
+                    .get(i)
+                    .filter(|l| l.text.starts_with(' ') && !l.text.trim().is_empty())
+                {
+                    if let Some(at) = line.text.find("}}}") {
+                        let at = base + line.start + at;
+                        self.warnings.warn(
+                            at,
+                            at + 3,
+                            "`}}}` inside code was split to keep the block closed",
+                        );
+                    }
+                    code_lines.push(&line.text[1..]);
+                    i += 1;
+                }
+                let code = build::split_closers(&code_lines.join("\n"));
+                out.block(Element::code(None, &code));
+            } else {
+                // Paragraph: consecutive plain lines, joined by the inline pass
+                let start = line.start;
+                let mut end = line.end;
+                loop {
+                    end = logical_line_end(text, end);
+                    while i < lines.len() && lines[i].start <= end {
+                        i += 1;
+                    }
+                    match lines.get(i) {
+                        Some(next) if starts_paragraph_line(next.text) => end = next.end,
+                        _ => break,
+                    }
+                }
+                out.line(self.inline(&text[start..end], base + start));
+            }
+        }
+
+        out.finish()
+    }
+
+    fn heading(&mut self, line: &str, base: usize) -> Option<Element> {
+        let t = line.trim_end();
+        if !t.starts_with('=') || !t.ends_with('=') {
+            return None;
+        }
+        let lead = t.len() - t.trim_start_matches('=').len();
+        let trail = t.len() - t.trim_end_matches('=').len();
+        let level = lead.min(trail).min(6);
+        if t.len() <= level * 2 {
+            return None;
+        }
+        let content = &t[level..t.len() - level];
+        let offset = level + content.len() - content.trim_start().len();
+        Some(build::header(
+            level,
+            false,
+            self.inline(content.trim(), base + offset),
+        ))
+    }
+
+    fn list(&mut self, lines: &[ListLine], depth: usize) -> Vec<Element> {
+        let mut out = Vec::new();
+        let mut i = 0;
+        while i < lines.len() {
+            let Some(marker) = lines[i].prefix.as_bytes().get(depth).copied() else {
+                // Shallower than expected; treat as plain continuation text
+                out.push(self.list_item(&lines[i], &[], depth));
+                i += 1;
+                continue;
+            };
+            let group_end = lines[i..]
+                .iter()
+                .position(|l| l.prefix.as_bytes().get(depth) != Some(&marker))
+                .map_or(lines.len(), |n| i + n);
+            let group = &lines[i..group_end];
+            i = group_end;
+
+            // Split the group into items: one top-level line plus deeper lines
+            let mut items = Vec::new();
+            let mut j = 0;
+            while j < group.len() {
+                let (head, sub_start) = if group[j].prefix.len() == depth + 1 {
+                    (Some(&group[j]), j + 1)
+                } else {
+                    (None, j)
+                };
+                let sub_end = group[sub_start..]
+                    .iter()
+                    .position(|l| l.prefix.len() == depth + 1)
+                    .map_or(group.len(), |n| sub_start + n);
+                items.push((head, &group[sub_start..sub_end]));
+                j = sub_end;
+            }
+
+            match marker {
+                b'*' | b'#' => {
+                    let kind = if marker == b'*' {
+                        ListKind::Unordered
+                    } else {
+                        ListKind::OrderedNumeric
+                    };
+                    let items = items
+                        .into_iter()
+                        .map(|(head, sub)| self.list_item_children(head, sub, depth))
+                        .collect();
+                    out.push(build::list(kind, items));
+                }
+                b':' => {
+                    let mut quote = Blocks::default();
+                    for (head, sub) in items {
+                        quote.line(self.list_item_children(head, sub, depth));
+                    }
+                    out.push(build::blockquote(quote.finish()));
+                }
+                _ => {
+                    // `;term : definition`
+                    for (head, sub) in items {
+                        if let Some(head) = head {
+                            let (term, definition) = split_definition(head.content);
+                            let term_children = self.inline(term, head.content_start);
+                            if !term_children.is_empty() {
+                                out.push(Style::Bold.wrap(term_children));
+                            }
+                            if let Some(definition) = definition {
+                                let offset =
+                                    head.content_start + head.content.len() - definition.len();
+                                out.push(build::blockquote(self.inline(definition, offset)));
+                            }
+                        }
+                        out.extend(self.list(sub, depth + 1));
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    fn list_item_children(
+        &mut self,
+        head: Option<&ListLine>,
+        sub: &[ListLine],
+        depth: usize,
+    ) -> Vec<Element> {
+        let mut item = Blocks::default();
+        if let Some(head) = head {
+            item.line(self.inline(head.content, head.content_start));
+        }
+        for el in self.list(sub, depth + 1) {
+            item.block(el);
+        }
+        item.finish()
+    }
+
+    fn list_item(&mut self, line: &ListLine, sub: &[ListLine], depth: usize) -> Element {
+        let children = self.list_item_children(Some(line), sub, depth);
+        build::blockquote(children)
+    }
+}
+
+/// Index of the line closing the table that starts at `start`, or
+/// `lines.len()` when it is never closed
+fn table_end(lines: &[Line], start: usize) -> usize {
+    let mut depth = 0usize;
+    for (i, line) in lines.iter().enumerate().skip(start) {
+        let t = line.text.trim_start();
+        if t.starts_with("{|") {
+            depth += 1;
+        } else if t.starts_with("|}") {
+            depth -= 1;
+            if depth == 0 {
+                return i;
+            }
+        }
+    }
+    lines.len()
+}
+
+/// Whether a line continues a paragraph rather than starting a block
+fn starts_paragraph_line(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    !(trimmed.is_empty()
+        || line.starts_with(['*', '#', ':', ';', ' '])
+        || line.starts_with("----")
+        || trimmed.starts_with("{|")
+        || (line.trim_end().starts_with('=') && line.trim_end().ends_with('=')))
+}
+
+/// Extend a line past constructs that close on a later line
+/// (`<ref>…</ref>`, `{{…}}`, `<!-- … -->`)
+fn logical_line_end(text: &str, line_end: usize) -> usize {
+    let line_start = text[..line_end].rfind('\n').map_or(0, |n| n + 1);
+    let mut end = line_end;
+    let mut pos = line_start;
+    while pos < end {
+        let Some(rel) = text[pos..end].find(['<', '{']) else {
+            break;
+        };
+        let at = pos + rel;
+        let close = construct_close(&text[at..]).map(|n| at + n);
+        match close {
+            Some(close) => {
+                if close > end {
+                    end = text[close..].find('\n').map_or(text.len(), |n| close + n);
+                }
+                pos = close;
+            }
+            None => pos = at + 1,
+        }
+    }
+    end
+}
+
+/// Length of a multi-line capable construct at the start of `text`
+fn construct_close(text: &str) -> Option<usize> {
+    if text.starts_with("<!--") {
+        return text.find("-->").map(|n| n + 3);
+    }
+    if text.starts_with("{{") {
+        return scan::find_balanced(text, "{{", "}}");
+    }
+    if !text.starts_with('<') {
+        return None;
+    }
+    let name_end = text[1..]
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .map_or(text.len(), |n| n + 1);
+    let name = text[1..name_end].to_ascii_lowercase();
+    if !MULTILINE_TAGS.contains(&name.as_str()) {
+        return None;
+    }
+    let open_end = text.find('>')? + 1;
+    if text[..open_end].ends_with("/>") {
+        return Some(open_end);
+    }
+    let close = format!("</{name}");
+    let close_at = scan::find_ci(&text[open_end..], &close)? + open_end;
+    text[close_at..].find('>').map(|n| close_at + n + 1)
+}
+
+/// Split `;term : definition`, ignoring the colon of a URL scheme
+fn split_definition(content: &str) -> (&str, Option<&str>) {
+    for (offset, part) in scan::split_top_level(content, ":").into_iter().skip(1) {
+        if !part.starts_with("//") {
+            return (&content[..offset - 1], Some(part));
+        }
+    }
+    (content, None)
+}
@@ -0,0 +1,562 @@
+//! Inline markup: apostrophe emphasis, HTML/extension tags, entities,
+//! behaviour switches and bare URLs
+
+use sevenmark_ast::Element;
+
+use super::{MediaWiki, link, parse_attrs, style_params};
+use crate::build::{self, Params};
+use crate::inline::{InlineStack, Style};
+use crate::scan;
+
+/// A parsed `<tag attrs>` / `</tag>` / `<tag/>`
+struct Tag<'a> {
+    name: String,
+    closing: bool,
+    self_closing: bool,
+    attrs: &'a str,
+    len: usize,
+}
+
+fn parse_tag(rest: &str) -> Option<Tag<'_>> {
+    let body = rest.strip_prefix('<')?;
+    let (closing, body) = match body.strip_prefix('/') {
+        Some(body) => (true, body),
+        None => (false, body),
+    };
+    if !body.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let name_len = body
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(body.len());
+    let header = rest.len() - body.len();
+    let gt = rest.find('>')?;
+    let inner = &rest[header + name_len..gt];
+    if !inner.is_empty() && !inner.starts_with([' ', '\t', '\n', '/']) {
+        return None;
+    }
+    let self_closing = inner.ends_with('/');
+    Some(Tag {
+        name: body[..name_len].to_ascii_lowercase(),
+        closing,
+        self_closing,
+        attrs: inner.trim_end_matches('/'),
+        len: gt + 1,
+    })
+}
+
+fn tag_style(name: &str) -> Option<Style> {
+    Some(match name {
+        "b" | "strong" => Style::Bold,
+        "i" | "em" | "cite" | "dfn" => Style::Italic,
+        "s" | "strike" | "del" => Style::Strikethrough,
+        "u" | "ins" => Style::Underline,
+        "sup" => Style::Superscript,
+        "sub" => Style::Subscript,
+        _ => return None,
+    })
+}
+
+/// Tags converted from their content; stray closers of these are dropped
+const CONTAINER_TAGS: &[&str] = &[
+    "ref",
+    "references",
+    "nowiki",
+    "pre",
+    "syntaxhighlight",
+    "source",
+    "math",
+    "chem",
+    "ce",
+    "code",
+    "tt",
+    "kbd",
+    "samp",
+    "blockquote",
+    "poem",
+    "gallery",
+    "center",
+    "small",
+    "big",
+    "span",
+    "font",
+    "div",
+    "includeonly",
+    "noinclude",
+    "onlyinclude",
+];
+
+/// Find `</name>` matching an opening tag, skipping nested same-name tags.
+/// Returns the offsets of the closer's start and end.
+fn find_close(rest: &str, name: &str, from: usize) -> Option<(usize, usize)> {
+    let open = format!("<{name}");
+    let close = format!("</{name}");
+    let mut depth = 1usize;
+    let mut pos = from;
+    loop {
+        let close_at = scan::find_ci(&rest[pos..], &close)? + pos;
+        let mut search = pos;
+        while let Some(at) = scan::find_ci(&rest[search..close_at], &open) {
+            let after = search + at + open.len();
+            if !rest[after..].starts_with(|c: char| c.is_ascii_alphanumeric()) {
+                depth += 1;
+            }
+            search = after;
+        }
+        let close_end = rest[close_at..].find('>')? + close_at + 1;
+        depth -= 1;
+        if depth == 0 {
+            return Some((close_at, close_end));
+        }
+        pos = close_end;
+    }
+}
+
+fn attr<'a>(attrs: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.as_str())
+}
+
+impl MediaWiki<'_> {
+    pub(super) fn inline(&mut self, text: &str, base: usize) -> Vec<Element> {
+        let mut stack = InlineStack::default();
+        self.inline_into(text, base, &mut stack);
+        stack.finish()
+    }
+
+    pub(super) fn inline_into(&mut self, text: &str, base: usize, stack: &mut InlineStack) {
+        let mut i = 0;
+        let mut plain = 0;
+        while i < text.len() {
+            let rest = &text[i..];
+            let c = rest.chars().next().expect("non-empty");
+            if !matches!(
+                c,
+                '\'' | '[' | '{' | '<' | '_' | '&' | '~' | '\n' | 'h' | 'H'
+            ) {
+                i += c.len_utf8();
+                continue;
+            }
+            stack.text(&text[plain..i]);
+            let consumed = match c {
+                '\'' => apostrophes(rest, stack),
+                '[' => self.bracket(text, i, base, stack),
+                '{' => self.brace(rest, base + i, stack),
+                '<' => self.tag(rest, base + i, stack),
+                '_' => self.behavior_switch(rest, base + i, stack),
+                '&' => scan::decode_entity(rest).map(|(ch, len)| {
+                    stack.text(ch.encode_utf8(&mut [0; 4]));
+                    len
+                }),
+                '~' => self.signature(rest, base + i, stack),
+                '\n' => {
+                    // Apostrophe emphasis never spans lines; the newline joins the paragraph
+                    stack.close_all();
+                    stack.text(" ");
+                    Some(1)
+                }
+                _ => bare_url(text, i, stack),
+            };
+            match consumed {
+                Some(n) => {
+                    i += n;
+                    plain = i;
+                }
+                None => {
+                    plain = i;
+                    i += c.len_utf8();
+                }
+            }
+        }
+        stack.text(&text[plain..]);
+    }
+
+    fn bracket(
+        &mut self,
+        text: &str,
+        i: usize,
+        base: usize,
+        stack: &mut InlineStack,
+    ) -> Option<usize> {
+        let rest = &text[i..];
+        if rest.starts_with("[[") {
+            let end = scan::find_balanced(rest, "[[", "]]")?;
+            let trail_len = rest[end..]
+                .bytes()
+                .take_while(u8::is_ascii_lowercase)
+                .count();
+            let trail = &rest[end..end + trail_len];
+            let used_trail = self.link(&rest[2..end - 2], base + i + 2, trail, stack)?;
+            return Some(if used_trail { end + trail_len } else { end });
+        }
+
+        // External link: [url label]
+        let url_part = &rest[1..];
+        if !["http://", "https://", "ftp://", "mailto:", "//"]
+            .iter()
+            .any(|scheme| scan::starts_with_ci(url_part, scheme))
+        {
+            return None;
+        }
+        let close = rest.find(']')?;
+        if rest[..close].contains('\n') {
+            return None;
+        }
+        let inner = &rest[1..close];
+        let (url, label) = inner.split_once([' ', '\t']).unwrap_or((inner, ""));
+        let label_offset = base + i + 1 + inner.len() - label.len();
+        let label = self.inline(label.trim(), label_offset);
+        let url = match url.strip_prefix("//") {
+            Some(rest) => format!("https://{rest}"),
+            None => url.to_string(),
+        };
+        if scan::starts_with_ci(&url, "http") {
+            stack.push(build::media(Params::default().set("url", &url), label));
+        } else {
+            self.warnings.warn(
+                base + i,
+                base + i + close + 1,
+                "only http(s) links are supported; kept the link text",
+            );
+            if label.is_empty() {
+                stack.text(&url);
+            } else {
+                stack.extend(label);
+            }
+        }
+        Some(close + 1)
+    }
+
+    fn brace(&mut self, rest: &str, start: usize, stack: &mut InlineStack) -> Option<usize> {
+        if rest.starts_with("{{{")
+            && let Some(end) = scan::find_balanced(rest, "{{{", "}}}")
+        {
+            // Template parameter placeholder (template source pages)
+            let inner = &rest[3..end - 3];
+            let (name, default) = match inner.split_once('|') {
+                Some((name, default)) => (name.trim(), Some(default)),
+                None => (inner.trim(), None),
+            };
+            match build::param_key(name).filter(|key| key == name) {
+                Some(name) => {
+                    if default.is_some() {
+                        self.warnings.warn(
+                            start,
+                            start + end,
+                            "template parameter defaults are not supported and were dropped",
+                        );
+                    }
+                    stack.push(Element::variable(&name));
+                }
+                None => {
+                    self.warnings.warn(
+                        start,
+                        start + end,
+                        "template parameter name cannot be used as a variable",
+                    );
+                    stack.text(&rest[..end]);
+                }
+            }
+            return Some(end);
+        }
+        if rest.starts_with("{{") {
+            let end = scan::find_balanced(rest, "{{", "}}")?;
+            self.template(&rest[2..end - 2], start, end, stack);
+            return Some(end);
+        }
+        None
+    }
+
+    fn tag(&mut self, rest: &str, start: usize, stack: &mut InlineStack) -> Option<usize> {
+        if let Some(body) = rest.strip_prefix("<!--") {
+            let (value, len) = match body.find("-->") {
+                Some(n) => (&body[..n], n + 7),
+                None => (body, rest.len()),
+            };
+            if !value.trim().is_empty() {
+                stack.push(Element::comment(value.trim()));
+            }
+            return Some(len);
+        }
+
+        let tag = parse_tag(rest)?;
+        let name = tag.name.as_str();
+        if let Some(style) = tag_style(name) {
+            if tag.closing {
+                stack.close(style);
+            } else if !tag.self_closing {
+                stack.open(style);
+            }
+            return Some(tag.len);
+        }
+        if name == "br" {
+            stack.push(Element::hard_break());
+            return Some(tag.len);
+        }
+        let known = CONTAINER_TAGS.contains(&name);
+        if tag.closing {
+            return known.then_some(tag.len);
+        }
+        if !known {
+            self.warnings.warn(
+                start,
+                start + tag.len,
+                format!("unsupported tag <{name}> was kept as text"),
+            );
+            return None;
+        }
+
+        let attrs = parse_attrs(tag.attrs);
+        if tag.self_closing {
+            match name {
+                "ref" => self.footnote(
+                    attr(&attrs, "name"),
+                    Vec::new(),
+                    start,
+                    start + tag.len,
+                    stack,
+                ),
+                "references" => stack.push(Element::footnote_ref()),
+                _ => {}
+            }
+            return Some(tag.len);
+        }
+
+        let Some((close_start, end)) = find_close(rest, name, tag.len) else {
+            self.warnings
+                .warn(start, start + tag.len, format!("<{name}> is never closed"));
+            return None;
+        };
+        let content = &rest[tag.len..close_start];
+        let content_base = start + tag.len;
+
+        match name {
+            "ref" => {
+                if attr(&attrs, "group").is_some() {
+                    self.warnings.warn(
+                        start,
+                        start + tag.len,
+                        "footnote groups are not supported; merged into the main list",
+                    );
+                }
+                let lead = content.len() - content.trim_start().len();
+                let children = self.inline(content.trim(), content_base + lead);
+                self.footnote(attr(&attrs, "name"), children, start, start + end, stack);
+            }
+            "references" => {
+                if !content.trim().is_empty() {
+                    self.warnings.warn(
+                        start,
+                        start + end,
+                        "list-defined references are not supported and were dropped",
+                    );
+                }
+                stack.push(Element::footnote_ref());
+            }
+            "nowiki" => stack.text(&scan::decode_entities(content)),
+            "pre" => stack.push(self.raw_code(None, content, content_base)),
+            "syntaxhighlight" | "source" => {
+                let lang = attr(&attrs, "lang");
+                stack.push(self.raw_code(lang, content, content_base));
+            }
+            "math" | "chem" | "ce" => {
+                let block = attr(&attrs, "display") == Some("block");
+                stack.push(Element::tex(block, content.trim()));
+            }
+            "code" | "tt" | "kbd" | "samp" => {
+                let params = Params::default().set("style", "font-family:monospace");
+                stack.push(build::styled(params, self.inline(content, content_base)));
+            }
+            "blockquote" => stack.push(build::blockquote(self.blocks(content, content_base))),
+            "poem" => {
+                let mut first = true;
+                let mut offset = content_base;
+                for line in content.trim_matches('\n').split('\n') {
+                    if !first {
+                        stack.push(Element::hard_break());
+                    }
+                    first = false;
+                    stack.extend(self.inline(line, offset));
+                    offset += line.len() + 1;
+                }
+            }
+            "gallery" => {
+                let mut offset = content_base;
+                for line in content.split('\n') {
+                    let entry = line.trim();
+                    if !entry.is_empty() {
+                        let has_namespace = entry
+                            .split_once(':')
+                            .is_some_and(|(ns, _)| link::namespace(ns).is_some());
+                        let entry = if has_namespace {
+                            entry.to_string()
+                        } else {
+                            format!("File:{entry}")
+                        };
+                        self.link(&entry, offset, "", stack);
+                    }
+                    offset += line.len() + 1;
+                }
+            }
+            "center" => {
+                let params = Params::default().set("style", "text-align:center");
+                stack.push(build::styled(params, self.blocks(content, content_base)));
+            }
+            "small" | "big" => {
+                let size = if name == "small" { "0.85em" } else { "1.2em" };
+                let params = Params::default().set("size", size);
+                stack.push(build::styled(params, self.inline(content, content_base)));
+            }
+            "span" | "font" | "div" => {
+                let params = style_params(&attrs);
+                let children = if name == "div" {
+                    self.blocks(content, content_base)
+                } else {
+                    self.inline(content, content_base)
+                };
+                if params.is_empty() {
+                    stack.extend(children);
+                } else {
+                    stack.push(build::styled(params, children));
+                }
+            }
+            "includeonly" => self.warnings.warn(
+                start,
+                start + end,
+                "<includeonly> content only applies when transcluded and was dropped",
+            ),
+            // noinclude / onlyinclude: keep the content, drop the wrapper
+            _ => self.inline_into(content, content_base, stack),
+        }
+        Some(end)
+    }
+
+    /// `<ref>`; reusing a name before its definition cannot be expressed
+    fn footnote(
+        &mut self,
+        name: Option<&str>,
+        children: Vec<Element>,
+        start: usize,
+        end: usize,
+        stack: &mut InlineStack,
+    ) {
+        match self.footnotes.footnote(name, children) {
+            Some(footnote) => stack.push(footnote),
+            None => self.warnings.warn(
+                start,
+                end,
+                format!(
+                    "footnote \"{}\" is used before it is defined and was dropped",
+                    name.unwrap_or_default()
+                ),
+            ),
+        }
+    }
+
+    /// Raw code/pre content; `}}}` cannot be represented inside SevenMark raw blocks
+    fn raw_code(&mut self, lang: Option<&str>, content: &str, base: usize) -> Element {
+        let content = content.trim_matches('\n');
+        if let Some(at) = content.find("}}}") {
+            self.warnings.warn(
+                base + at,
+                base + at + 3,
+                "`}}}` inside code was split to keep the block closed",
+            );
+        }
+        Element::code(lang, &build::split_closers(content))
+    }
+
+    /// `__TOC__` and friends
+    fn behavior_switch(
+        &mut self,
+        rest: &str,
+        start: usize,
+        stack: &mut InlineStack,
+    ) -> Option<usize> {
+        let word_len = rest
+            .strip_prefix("__")?
+            .find(|c: char| !c.is_ascii_uppercase())?;
+        if word_len == 0 || !rest[2 + word_len..].starts_with("__") {
+            return None;
+        }
+        let word = &rest[2..2 + word_len];
+        let len = word_len + 4;
+        if word == "TOC" || word == "FORCETOC" {
+            stack.push(Element::toc());
+        } else {
+            self.warnings.warn(
+                start,
+                start + len,
+                format!("behaviour switch __{word}__ has no SevenMark equivalent and was dropped"),
+            );
+        }
+        Some(len)
+    }
+
+    fn signature(&mut self, rest: &str, start: usize, stack: &mut InlineStack) -> Option<usize> {
+        let n = rest.bytes().take_while(|&b| b == b'~').count();
+        if n < 3 {
+            return None;
+        }
+        self.warnings.warn(
+            start,
+            start + n,
+            "signatures are not expanded and were kept as text",
+        );
+        stack.text(&rest[..n]);
+        Some(n)
+    }
+}
+
+/// `''`, `'''` and `'''''` toggles, following MediaWiki's run-length rules
+fn apostrophes(rest: &str, stack: &mut InlineStack) -> Option<usize> {
+    let n = rest.bytes().take_while(|&b| b == b'\'').count();
+    if n < 2 {
+        return None;
+    }
+    let (literal, run) = match n {
+        2 | 3 | 5 => (0, n),
+        4 => (1, 3),
+        _ => (n - 5, 5),
+    };
+    stack.text(&rest[..literal]);
+    match run {
+        2 => stack.toggle(Style::Italic),
+        3 => stack.toggle(Style::Bold),
+        _ => match (stack.is_open(Style::Bold), stack.is_open(Style::Italic)) {
+            (true, true) => {
+                stack.close(Style::Italic);
+                stack.close(Style::Bold);
+            }
+            (true, false) => {
+                stack.close(Style::Bold);
+                stack.open(Style::Italic);
+            }
+            (false, true) => {
+                stack.close(Style::Italic);
+                stack.open(Style::Bold);
+            }
+            (false, false) => {
+                stack.open(Style::Bold);
+                stack.open(Style::Italic);
+            }
+        },
+    }
+    Some(n)
+}
+
+fn bare_url(text: &str, i: usize, stack: &mut InlineStack) -> Option<usize> {
+    if text[..i]
+        .chars()
+        .next_back()
+        .is_some_and(|c| c.is_alphanumeric())
+    {
+        return None;
+    }
+    let len = scan::bare_url_len(&text[i..])?;
+    let url = &text[i..i + len];
+    stack.push(build::media(Params::default().set("url", url), Vec::new()));
+    Some(len)
+}
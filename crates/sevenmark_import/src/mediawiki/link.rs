@@ -0,0 +1,341 @@
+//! `[[...]]` links (pages, files, categories) and `{{...}}` templates
+
+use super::MediaWiki;
+use sevenmark_ast::Element;
+
+use crate::build::{self, Params};
+use crate::inline::InlineStack;
+use crate::scan;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Namespace {
+    Category,
+    File,
+}
+
+pub(super) fn namespace(ns: &str) -> Option<Namespace> {
+    match ns.trim().to_lowercase().as_str() {
+        "category" | "분류" => Some(Namespace::Category),
+        "file" | "image" | "media" | "파일" | "그림" => Some(Namespace::File),
+        _ => None,
+    }
+}
+
+/// Image options that only affect MediaWiki's frame layout
+const IMAGE_KEYWORDS: &[&str] = &[
+    "thumb",
+    "thumbnail",
+    "frame",
+    "framed",
+    "frameless",
+    "border",
+    "left",
+    "right",
+    "center",
+    "centre",
+    "none",
+    "baseline",
+    "middle",
+    "sub",
+    "super",
+    "top",
+    "text-top",
+    "bottom",
+    "text-bottom",
+];
+
+/// `key=value` image options; anything else containing `=` is a caption
+const IMAGE_OPTIONS: &[&str] = &[
+    "alt",
+    "link",
+    "page",
+    "class",
+    "lang",
+    "upright",
+    "thumbtime",
+    "start",
+    "end",
+    "loop",
+];
+
+/// Templates standing for the footnote list
+const REFLIST_TEMPLATES: &[&str] = &["reflist", "references", "refs", "각주", "주석"];
+
+/// Parser-function style `NAME:` prefixes that are not transclusions
+fn is_magic_prefix(prefix: &str) -> bool {
+    prefix.chars().all(|c| c.is_ascii_uppercase())
+        || matches!(
+            prefix.to_ascii_lowercase().as_str(),
+            "lc" | "uc"
+                | "lcfirst"
+                | "ucfirst"
+                | "urlencode"
+                | "anchorencode"
+                | "fullurl"
+                | "localurl"
+                | "canonicalurl"
+                | "formatnum"
+                | "padleft"
+                | "padright"
+                | "plural"
+                | "grammar"
+                | "gender"
+                | "int"
+                | "ns"
+                | "filepath"
+        )
+}
+
+/// `en`, `ko`, `zh-yue`: interlanguage link prefixes
+fn is_language_code(prefix: &str) -> bool {
+    let (lang, variant) = prefix.split_once('-').unwrap_or((prefix, "xx"));
+    (2..=3).contains(&lang.len())
+        && lang.bytes().all(|b| b.is_ascii_lowercase())
+        && (2..=8).contains(&variant.len())
+        && variant.bytes().all(|b| b.is_ascii_lowercase())
+}
+
+impl MediaWiki<'_> {
+    /// Convert the inside of `[[...]]` starting at absolute offset `start`.
+    /// Returns whether the link trail was consumed, or `None` to keep the
+    /// brackets as text.
+    pub(super) fn link(
+        &mut self,
+        inner: &str,
+        start: usize,
+        trail: &str,
+        stack: &mut InlineStack,
+    ) -> Option<bool> {
+        let parts = scan::split_top_level(inner, "|");
+        let target = parts[0].1.trim();
+        if target.is_empty() || target.contains(['{', '<', '[']) {
+            return None;
+        }
+        let (colon, target) = match target.strip_prefix(':') {
+            Some(target) => (true, target.trim_start()),
+            None => (false, target),
+        };
+        let label = parts
+            .get(1)
+            .map(|&(offset, _)| (start + offset, &inner[offset..]))
+            .filter(|(_, label)| !label.trim().is_empty());
+
+        if let Some((ns, rest)) = target.split_once(':') {
+            match (namespace(ns), colon) {
+                (Some(Namespace::Category), false) => {
+                    // The sort key has no SevenMark equivalent
+                    stack.push(Element::category(rest.trim()));
+                    return Some(false);
+                }
+                (Some(Namespace::File), false) => {
+                    self.image(rest.trim(), &parts[1..], start, stack);
+                    return Some(false);
+                }
+                (Some(Namespace::Category), true) => {
+                    let children = match label {
+                        Some((offset, label)) => self.inline(label.trim(), offset),
+                        None => build::text(target),
+                    };
+                    let params = Params::default().set("category", rest.trim());
+                    stack.push(build::media(params, children));
+                    return Some(false);
+                }
+                (None, false) if is_language_code(ns) => {
+                    self.warnings.warn(
+                        start - 2,
+                        start + inner.len() + 2,
+                        "interlanguage links are not supported and were dropped",
+                    );
+                    return Some(false);
+                }
+                _ => {}
+            }
+        }
+
+        let (page, fragment) = target.split_once('#').unwrap_or((target, ""));
+        let mut children = match label {
+            Some((offset, label)) => self.inline(label.trim(), offset),
+            None => build::text(target),
+        };
+        if !trail.is_empty() {
+            children.extend(build::text(trail));
+        }
+        if page.trim().is_empty() {
+            self.warnings.warn(
+                start - 2,
+                start + inner.len() + 2,
+                "same-page section links are not supported; kept the link text",
+            );
+            stack.extend(children);
+            return Some(true);
+        }
+        let mut params = Params::default().set("document", page.trim());
+        if !fragment.trim().is_empty() {
+            params.insert("anchor", fragment.trim());
+        }
+        stack.push(build::media(params, children));
+        Some(true)
+    }
+
+    fn image(
+        &mut self,
+        file: &str,
+        options: &[(usize, &str)],
+        start: usize,
+        stack: &mut InlineStack,
+    ) {
+        let mut params = Params::default().set("file", file);
+        let mut caption = None;
+        let mut alt = None;
+        let mut link = None;
+        for &(offset, part) in options {
+            let option = part.trim();
+            let lower = option.to_ascii_lowercase();
+            if IMAGE_KEYWORDS.contains(&lower.as_str()) {
+                continue;
+            }
+            if let Some(size) = lower.strip_suffix("px") {
+                let (width, height) = size.split_once('x').unwrap_or((size, ""));
+                let numeric = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+                if numeric(width) && numeric(height) {
+                    if !width.is_empty() {
+                        params.insert("width", &format!("{width}px"));
+                    }
+                    if !height.is_empty() {
+                        params.insert("height", &format!("{height}px"));
+                    }
+                    continue;
+                }
+            }
+            if let Some((key, value)) = option.split_once('=') {
+                let key = key.trim().to_ascii_lowercase();
+                if IMAGE_OPTIONS.contains(&key.as_str()) {
+                    match key.as_str() {
+                        "alt" => alt = Some(value.trim()),
+                        "link" => link = Some(value.trim()),
+                        _ => {}
+                    }
+                    continue;
+                }
+            }
+            caption = Some((start + offset, part));
+        }
+
+        match link {
+            Some(url) if url.contains("://") => params.insert("url", url),
+            Some(page) if !page.is_empty() => params.insert("document", page),
+            _ => {}
+        }
+        let children = match (caption, alt) {
+            (Some((offset, caption)), _) if !caption.trim().is_empty() => {
+                let lead = caption.len() - caption.trim_start().len();
+                self.inline(caption.trim(), offset + lead)
+            }
+            (_, Some(alt)) => build::text(alt),
+            _ => Vec::new(),
+        };
+        stack.push(build::media(params, children));
+    }
+
+    /// Convert `{{inner}}`; `start`/`len` locate the whole construct
+    pub(super) fn template(
+        &mut self,
+        inner: &str,
+        start: usize,
+        len: usize,
+        stack: &mut InlineStack,
+    ) {
+        let end = start + len;
+        let parts = scan::split_top_level(inner, "|");
+        let name = parts[0].1.trim();
+
+        if let Some(function) = name.strip_prefix('#') {
+            let function = function.split(':').next().unwrap_or_default().trim();
+            self.warnings.warn(
+                start,
+                end,
+                format!("parser function #{function} is not supported and was kept as text"),
+            );
+            stack.text(&format!("{{{{{inner}}}}}"));
+            return;
+        }
+
+        let name = ["subst:", "safesubst:"]
+            .iter()
+            .find_map(|prefix| {
+                scan::starts_with_ci(name, prefix).then(|| name[prefix.len()..].trim_start())
+            })
+            .unwrap_or(name);
+
+        let mut page = name.trim_start_matches(':');
+        if let Some((prefix, rest)) = page.split_once(':') {
+            let prefix = prefix.trim();
+            if prefix.eq_ignore_ascii_case("template") || prefix == "틀" {
+                page = rest.trim();
+            } else if is_magic_prefix(prefix) {
+                if matches!(prefix, "DEFAULTSORT" | "DISPLAYTITLE") {
+                    self.warnings.warn(
+                        start,
+                        end,
+                        format!("{prefix} has no SevenMark equivalent and was dropped"),
+                    );
+                } else {
+                    self.warnings.warn(
+                        start,
+                        end,
+                        format!("magic word {prefix} is not supported and was kept as text"),
+                    );
+                    stack.text(&format!("{{{{{inner}}}}}"));
+                }
+                return;
+            }
+        }
+
+        if page.trim().is_empty() {
+            self.warnings
+                .warn(start, end, "template without a name was kept as text");
+            stack.text(&format!("{{{{{inner}}}}}"));
+            return;
+        }
+
+        if parts.len() == 1
+            && page.len() > 1
+            && page
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        {
+            self.warnings.warn(
+                start,
+                end,
+                format!("magic word {page} is not supported and was kept as text"),
+            );
+            stack.text(&format!("{{{{{inner}}}}}"));
+            return;
+        }
+
+        if REFLIST_TEMPLATES
+            .iter()
+            .any(|reflist| page.to_lowercase() == *reflist)
+        {
+            stack.push(Element::footnote_ref());
+            return;
+        }
+
+        let mut params = Params::default();
+        let mut position = 0;
+        for &(_, part) in &parts[1..] {
+            let named = part
+                .split_once('=')
+                .filter(|(key, _)| !key.contains("[[") && !key.contains("{{"))
+                .and_then(|(key, value)| Some((build::param_key(key)?, value)));
+            match named {
+                Some((key, value)) => params.insert(&key, value.trim()),
+                None => {
+                    position += 1;
+                    params.insert(&position.to_string(), part.trim());
+                }
+            }
+        }
+        stack.push(build::include(params, page.trim()));
+    }
+}
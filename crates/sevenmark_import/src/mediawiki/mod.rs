@@ -0,0 +1,289 @@
+//! MediaWiki wikitext converter
+//!
+//! Covers the constructs that make up most articles: headings, apostrophe
+//! emphasis, internal/external links, files and categories, `*`/`#`/`:`/`;`
+//! lists, `{| |}` tables, `<ref>` footnotes, templates (as `#include`) and
+//! the common inline HTML tags. Parser functions, magic words and extension
+//! tags without a SevenMark equivalent are kept as text and reported.
+
+mod block;
+mod inline;
+mod link;
+mod table;
+
+use sevenmark_ast::Element;
+use sevenmark_formatter::FormatConfig;
+
+use crate::build::{self, NamedFootnotes, Params};
+use crate::output::ImportOutput;
+use crate::scan;
+use crate::warning::Warnings;
+
+pub(crate) struct MediaWiki<'a> {
+    warnings: Warnings<'a>,
+    footnotes: NamedFootnotes,
+}
+
+/// Convert MediaWiki wikitext into SevenMark
+///
+/// # Arguments
+/// * `source` - Wikitext of a single page
+/// * `config` - Formatter settings for the generated source
+pub fn import_mediawiki(source: &str, config: &FormatConfig) -> ImportOutput {
+    let mut converter = MediaWiki {
+        warnings: Warnings::new(source),
+        footnotes: NamedFootnotes::default(),
+    };
+
+    let (redirect, body_start) = converter.redirect(source);
+    let ast = match redirect {
+        // A SevenMark redirect is the whole document
+        Some(redirect) => {
+            if !source[body_start..].trim().is_empty() {
+                converter.warnings.warn(
+                    body_start,
+                    source.len(),
+                    "content after a redirect is not supported and was dropped",
+                );
+            }
+            vec![redirect]
+        }
+        None => converter.blocks(&source[body_start..], body_start),
+    };
+
+    ImportOutput::new(ast, converter.warnings.into_vec(), config)
+}
+
+/// Parse HTML attributes (`key="v" key='v' key=v flag`), lowercasing keys
+pub(super) fn parse_attrs(text: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    let mut rest = text.trim_start();
+    while !rest.is_empty() {
+        let key_len = rest
+            .find(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or(rest.len());
+        if key_len == 0 {
+            rest = rest[1..].trim_start();
+            continue;
+        }
+        let key = rest[..key_len].to_ascii_lowercase();
+        rest = rest[key_len..].trim_start();
+        let value = match rest.strip_prefix('=') {
+            Some(after) => {
+                let after = after.trim_start();
+                let (value, remaining) = match after.chars().next() {
+                    Some(q @ ('"' | '\'')) => match after[1..].find(q) {
+                        Some(n) => (&after[1..n + 1], &after[n + 2..]),
+                        None => (&after[1..], ""),
+                    },
+                    _ => {
+                        let n = after.find(char::is_whitespace).unwrap_or(after.len());
+                        (&after[..n], &after[n..])
+                    }
+                };
+                rest = remaining.trim_start();
+                value.trim().to_string()
+            }
+            None => String::new(),
+        };
+        attrs.push((key, value));
+    }
+    attrs
+}
+
+/// Map presentational HTML attributes onto SevenMark style parameters
+pub(super) fn style_params(attrs: &[(String, String)]) -> Params {
+    let mut style = Vec::new();
+    let mut params = Params::default();
+    for (key, value) in attrs {
+        if value.is_empty() {
+            continue;
+        }
+        match key.as_str() {
+            "style" => style.push(value.trim().trim_end_matches(';').to_string()),
+            "align" => style.push(format!("text-align:{value}")),
+            "valign" => style.push(format!("vertical-align:{value}")),
+            "bgcolor" | "color" | "width" | "height" => params.insert(key, value),
+            _ => {}
+        }
+    }
+    if !style.is_empty() {
+        params.insert("style", &style.join(";"));
+    }
+    params
+}
+
+impl MediaWiki<'_> {
+    /// `#REDIRECT [[Target]]` on the first line
+    fn redirect(&mut self, source: &str) -> (Option<Element>, usize) {
+        let Some(first) = scan::lines(source)
+            .into_iter()
+            .find(|l| !l.text.trim().is_empty())
+        else {
+            return (None, 0);
+        };
+        let trimmed = first.text.trim_start();
+        let keyword = ["#REDIRECT", "#넘겨주기"]
+            .into_iter()
+            .find(|k| scan::starts_with_ci(trimmed, k));
+        let (Some(keyword), Some(open)) = (keyword, trimmed.find("[[")) else {
+            return (None, 0);
+        };
+        let Some(close) = trimmed[open..].find("]]") else {
+            return (None, 0);
+        };
+        if !trimmed[keyword.len()..open]
+            .trim()
+            .trim_start_matches(':')
+            .is_empty()
+        {
+            return (None, 0);
+        }
+
+        let target = trimmed[open + 2..open + close].trim();
+        let target = target.split('|').next().unwrap_or_default();
+        let (page, fragment) = target.split_once('#').unwrap_or((target, ""));
+        if !fragment.is_empty() {
+            let start = first.start + first.text.len() - trimmed.len();
+            self.warnings.warn(
+                start,
+                first.end,
+                "redirect section fragments are not supported; redirecting to the page",
+            );
+        }
+        let redirect = match page.split_once(':') {
+            Some((ns, title)) => match link::namespace(ns) {
+                Some(link::Namespace::Category) => {
+                    build::redirect(Params::default().set("namespace", "Category"), title)
+                }
+                Some(link::Namespace::File) => {
+                    build::redirect(Params::default().set("namespace", "File"), title)
+                }
+                _ => build::redirect(Params::default(), page),
+            },
+            None => build::redirect(Params::default(), page),
+        };
+        let body_start = source[first.end..]
+            .find('\n')
+            .map_or(source.len(), |n| first.end + n + 1);
+        (Some(redirect), body_start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::assert_reparses;
+    use sevenmark_parser::core::parse_document;
+
+    fn import(source: &str) -> ImportOutput {
+        let output = import_mediawiki(source, &FormatConfig::default());
+        assert_reparses(&output);
+        output
+    }
+
+    #[test]
+    fn headings_and_emphasis() {
+        let out = import("== Intro ==\n'''Bold''' and ''italic'' and '''''both'''''.");
+        assert_eq!(out.text, "## Intro\n**Bold** and *italic* and ***both***.");
+    }
+
+    #[test]
+    fn links_files_and_categories() {
+        let out = import(
+            "See [[Main Page|home]], [[Rust#History]]s and [https://example.com site].\n[[File:Cat.png|thumb|200px|A cat]]\n[[Category:Animals]]",
+        );
+        assert!(
+            out.text.contains("[[#document=\"Main Page\" home]]"),
+            "{}",
+            out.text
+        );
+        assert!(
+            out.text
+                .contains("[[#document=\"Rust\" #anchor=\"History\" Rust#Historys]]"),
+            "{}",
+            out.text
+        );
+        assert!(
+            out.text.contains("[[#url=\"https://example.com\" site]]"),
+            "{}",
+            out.text
+        );
+        assert!(
+            out.text
+                .contains("[[#file=\"Cat.png\" #width=\"200px\" A cat]]"),
+            "{}",
+            out.text
+        );
+        assert!(out.text.contains("{{{#category Animals}}}"), "{}", out.text);
+        assert!(out.warnings.is_empty(), "{:?}", out.warnings);
+    }
+
+    #[test]
+    fn lists() {
+        let out = import("* one\n** nested\n* two\n# first\n#: continued");
+        assert!(out.text.starts_with("{{{#list"), "{}", out.text);
+        assert!(out.text.contains("{{{#list #1"), "{}", out.text);
+    }
+
+    #[test]
+    fn refs_and_references() {
+        let out = import("Fact<ref name=\"a\">Source ''one''</ref>.\n\n<references />");
+        assert!(
+            out.text
+                .contains("Fact{{{#fn #name=\"a\" || Source *one*}}}."),
+            "{}",
+            out.text
+        );
+        assert!(out.text.ends_with("[fn]"), "{}", out.text);
+    }
+
+    #[test]
+    fn templates_become_includes() {
+        let out = import("{{Infobox person\n| name = Ada\n| born = 1815\n}}\nText");
+        assert!(
+            out.text
+                .starts_with("{{{#include #name=\"Ada\" #born=\"1815\" || Infobox person}}}\n"),
+            "{}",
+            out.text
+        );
+    }
+
+    #[test]
+    fn unsupported_constructs_are_reported() {
+        let source = "a\n{{#if: x | y }} and {{PAGENAME}}";
+        let out = import(source);
+        assert_eq!(out.warnings.len(), 2, "{:?}", out.warnings);
+        assert_eq!((out.warnings[0].line, out.warnings[0].column), (2, 1));
+        assert_eq!(
+            &source[out.warnings[1].span.start..out.warnings[1].span.end],
+            "{{PAGENAME}}"
+        );
+    }
+
+    #[test]
+    fn markup_like_text_is_escaped() {
+        let out = import("1. not a list, a*b, [x] and <nowiki>'''raw'''</nowiki>");
+        let reparsed = parse_document(&out.text);
+        assert!(
+            reparsed.iter().all(|el| matches!(
+                el,
+                sevenmark_ast::Element::Text(_) | sevenmark_ast::Element::Escape(_)
+            )),
+            "{}",
+            out.text
+        );
+    }
+
+    #[test]
+    fn redirect() {
+        let source = "#REDIRECT [[Category:Foo]]\n[[Category:Bar]]";
+        let out = import(source);
+        assert_eq!(out.text, "{{{#redirect #namespace=\"Category\" Foo}}}");
+        assert_eq!(out.warnings.len(), 1, "{:?}", out.warnings);
+        assert_eq!(
+            &source[out.warnings[0].span.start..out.warnings[0].span.end],
+            "[[Category:Bar]]"
+        );
+    }
+}
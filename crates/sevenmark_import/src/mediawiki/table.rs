@@ -0,0 +1,183 @@
+//! `{| ... |}` tables
+
+use sevenmark_ast::Element;
+
+use super::{MediaWiki, parse_attrs, style_params};
+use crate::build::{self, Cell};
+use crate::scan;
+
+/// Cell location inside the table source, before conversion
+struct CellSource<'a> {
+    header: bool,
+    attrs: &'a str,
+    start: usize,
+    end: usize,
+}
+
+fn cell_source(header: bool, part: &str, start: usize) -> CellSource<'_> {
+    let pieces = scan::split_top_level(part, "|");
+    match pieces.get(1) {
+        Some(&(content_offset, _)) => CellSource {
+            header,
+            attrs: pieces[0].1,
+            start: start + content_offset,
+            end: start + part.len(),
+        },
+        None => CellSource {
+            header,
+            attrs: "",
+            start,
+            end: start + part.len(),
+        },
+    }
+}
+
+/// Caption text without links or emphasis (captions are plain parameters)
+fn plain_caption(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(at) = rest.find("[[") {
+        out.push_str(&rest[..at]);
+        let Some(end) = scan::find_balanced(&rest[at..], "[[", "]]") else {
+            out.push_str(&rest[at..]);
+            rest = "";
+            break;
+        };
+        let inner = &rest[at + 2..at + end - 2];
+        out.push_str(inner.rsplit('|').next().unwrap_or(inner));
+        rest = &rest[at + end..];
+    }
+    out.push_str(rest);
+    scan::decode_entities(&out.replace("'''", "").replace("''", ""))
+        .trim()
+        .to_string()
+}
+
+impl MediaWiki<'_> {
+    /// The table, or `None` with a warning when it has no cells
+    pub(super) fn table(&mut self, text: &str, base: usize) -> Option<Element> {
+        let lines = scan::lines(text);
+        let first = lines[0].text.trim_start();
+        let mut params = style_params(&parse_attrs(&first[2..]));
+
+        let mut rows: Vec<Vec<CellSource>> = Vec::new();
+        let mut row: Vec<CellSource> = Vec::new();
+        let mut nested = 0usize;
+        for line in &lines[1..] {
+            let t = line.text.trim_start();
+            let lead = line.start + line.text.len() - t.len();
+
+            if nested > 0 {
+                if t.starts_with("{|") {
+                    nested += 1;
+                } else if t.starts_with("|}") {
+                    nested -= 1;
+                }
+                if let Some(cell) = row.last_mut() {
+                    cell.end = line.end;
+                }
+                continue;
+            }
+            if t.starts_with("|}") {
+                break;
+            }
+            if t.starts_with("{|") {
+                match row.last_mut() {
+                    Some(cell) => {
+                        cell.end = line.end;
+                        nested = 1;
+                    }
+                    None => self.warnings.warn(
+                        base + lead,
+                        base + line.end,
+                        "nested table outside of a cell was dropped",
+                    ),
+                }
+                continue;
+            }
+            if let Some(caption) = t.strip_prefix("|+") {
+                let caption = scan::split_top_level(caption, "|")
+                    .last()
+                    .map_or(caption, |&(_, c)| c);
+                params.insert("caption", &plain_caption(caption));
+                continue;
+            }
+            if t.starts_with("|-") {
+                if !row.is_empty() {
+                    rows.push(std::mem::take(&mut row));
+                }
+                continue;
+            }
+            let (header, body) = match (t.strip_prefix('!'), t.strip_prefix('|')) {
+                (Some(body), _) => (true, body),
+                (None, Some(body)) => (false, body),
+                (None, None) => {
+                    match row.last_mut() {
+                        Some(cell) => cell.end = line.end,
+                        None if !t.is_empty() => self.warnings.warn(
+                            base + lead,
+                            base + line.end,
+                            "text between table rows was dropped",
+                        ),
+                        None => {}
+                    }
+                    continue;
+                }
+            };
+
+            let body_start = lead + 1;
+            for (offset, part) in scan::split_top_level(body, "||") {
+                if header {
+                    for (inner, part) in scan::split_top_level(part, "!!") {
+                        row.push(cell_source(true, part, body_start + offset + inner));
+                    }
+                } else {
+                    row.push(cell_source(false, part, body_start + offset));
+                }
+            }
+        }
+        if !row.is_empty() {
+            rows.push(row);
+        }
+        if rows.is_empty() {
+            self.warnings
+                .warn(base, base + text.len(), "table without cells was dropped");
+            return None;
+        }
+
+        let rows = rows
+            .into_iter()
+            .map(|cells| {
+                cells
+                    .into_iter()
+                    .map(|cell| self.cell(text, base, cell))
+                    .collect()
+            })
+            .collect();
+        Some(build::table(params, rows))
+    }
+
+    fn cell(&mut self, text: &str, base: usize, source: CellSource) -> Cell {
+        let mut attrs = parse_attrs(source.attrs);
+        let span = |key: &str| {
+            attrs
+                .iter()
+                .find(|(k, _)| k == key)
+                .and_then(|(_, v)| v.parse::<usize>().ok())
+        };
+        let colspan = span("colspan");
+        let rowspan = span("rowspan");
+        if source.header {
+            attrs.insert(0, ("style".to_string(), "font-weight:bold".to_string()));
+        }
+
+        let content = &text[source.start..source.end];
+        let lead = content.len() - content.trim_start().len();
+        Cell {
+            params: style_params(&attrs),
+            colspan,
+            rowspan,
+            children: self.blocks(content.trim(), base + source.start + lead),
+        }
+    }
+}
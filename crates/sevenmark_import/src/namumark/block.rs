@@ -0,0 +1,217 @@
+//! Line-level structure: headings, rules, comments, quotes, indented lists
+//! and tables
+
+use sevenmark_ast::{Element, ListKind};
+
+use super::Namumark;
+use super::table::{is_table_row, table_end};
+use crate::build::{self, Blocks, Params};
+use crate::scan::{self, Line};
+
+struct ListLine<'a> {
+    indent: usize,
+    kind: Option<ListKind>,
+    content: &'a str,
+    content_start: usize,
+}
+
+/// `*`, `1.`, `a.`, `A.`, `i.`, `I.` (optionally followed by `#n`)
+fn list_marker(text: &str) -> Option<(ListKind, usize)> {
+    let (kind, len) = if text.starts_with('*') {
+        (ListKind::Unordered, 1)
+    } else {
+        let kind = match text.get(..2)? {
+            "1." => ListKind::OrderedNumeric,
+            "a." => ListKind::OrderedAlphaLower,
+            "A." => ListKind::OrderedAlphaUpper,
+            "i." => ListKind::OrderedRomanLower,
+            "I." => ListKind::OrderedRomanUpper,
+            _ => return None,
+        };
+        (kind, 2)
+    };
+    let rest = &text[len..];
+    let start_len = match rest.strip_prefix('#') {
+        Some(n) => 1 + n.bytes().take_while(u8::is_ascii_digit).count(),
+        None => 0,
+    };
+    Some((kind, len + start_len))
+}
+
+fn heading(line: &str) -> Option<(usize, bool, &str, usize)> {
+    let t = line.trim_end();
+    let level = t.bytes().take_while(|&b| b == b'=').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let trail = t.len() - t.trim_end_matches('=').len();
+    if trail != level || t.len() <= level * 2 {
+        return None;
+    }
+    let inner = &t[level..t.len() - level];
+    let (folded, inner, lead) = match inner.strip_prefix('#').and_then(|i| i.strip_suffix('#')) {
+        Some(inner) => (true, inner, level + 1),
+        None => (false, inner, level),
+    };
+    let content = inner.strip_prefix(' ')?.strip_suffix(' ')?;
+    Some((level, folded, content.trim(), lead + 1))
+}
+
+fn is_hline(line: &str) -> bool {
+    let t = line.trim();
+    (4..=9).contains(&t.len()) && t.bytes().all(|b| b == b'-')
+}
+
+impl Namumark<'_> {
+    pub(super) fn blocks(&mut self, text: &str, base: usize) -> Vec<Element> {
+        let lines = scan::lines(text);
+        let mut out = Blocks::default();
+        let mut i = 0;
+
+        while i < lines.len() {
+            let line = lines[i];
+            let t = line.text;
+
+            if t.trim().is_empty() {
+                out.blank_line();
+                i += 1;
+            } else if let Some(comment) = t.strip_prefix("##") {
+                if !comment.trim().is_empty() {
+                    out.block(Element::comment(comment.trim()));
+                }
+                i += 1;
+            } else if let Some((level, folded, content, offset)) = heading(t) {
+                let children = self.inline(content, base + line.start + offset);
+                out.block(build::header(level, folded, children));
+                i += 1;
+            } else if is_hline(t) {
+                out.block(Element::hline());
+                i += 1;
+            } else if is_table_row(t) {
+                let end = table_end(&lines, i);
+                let start = lines[i].start;
+                if let Some(table) = self.table(&text[start..lines[end - 1].end], base + start) {
+                    out.block(table);
+                }
+                i = end;
+            } else if t.starts_with('>') {
+                let start = i;
+                while i < lines.len() && lines[i].text.starts_with('>') {
+                    i += 1;
+                }
+                let inner: Vec<&str> = lines[start..i]
+                    .iter()
+                    .map(|l| {
+                        let rest = &l.text[1..];
+                        rest.strip_prefix(' ').unwrap_or(rest)
+                    })
+                    .collect();
+                let children = self.blocks(&inner.join("\n"), base + line.start + 1);
+                out.block(build::blockquote(children));
+            } else if t.starts_with(' ') {
+                let mut list_lines = Vec::new();
+                while let Some(line) = lines
+                    .get(i)
+                    .filter(|l| l.text.starts_with(' ') && !l.text.trim().is_empty())
+                {
+                    list_lines.push(list_line(line, base));
+                    i += 1;
+                }
+                for el in self.list(&list_lines) {
+                    out.block(el);
+                }
+            } else {
+                let end = logical_line_end(text, line.end);
+                while i < lines.len() && lines[i].start <= end {
+                    i += 1;
+                }
+                out.line(self.inline(&text[line.start..end], base + line.start));
+            }
+        }
+
+        out.finish()
+    }
+
+    fn list(&mut self, lines: &[ListLine]) -> Vec<Element> {
+        let mut out = Vec::new();
+        let mut i = 0;
+        while i < lines.len() {
+            let head = &lines[i];
+            let Some(kind) = head.kind else {
+                // Indented text without a marker
+                let style = format!("margin-left:{}em", head.indent);
+                let children = self.inline(head.content, head.content_start);
+                out.push(build::styled(
+                    Params::default().set("style", &style),
+                    children,
+                ));
+                i += 1;
+                continue;
+            };
+
+            let mut items = Vec::new();
+            while let Some(item) = lines
+                .get(i)
+                .filter(|l| l.indent == head.indent && l.kind == Some(kind))
+            {
+                let sub_end = lines[i + 1..]
+                    .iter()
+                    .position(|l| l.indent <= head.indent)
+                    .map_or(lines.len(), |n| i + 1 + n);
+                let mut children = Blocks::default();
+                children.line(self.inline(item.content, item.content_start));
+                for el in self.list(&lines[i + 1..sub_end]) {
+                    children.block(el);
+                }
+                items.push(children.finish());
+                i = sub_end;
+            }
+            out.push(build::list(kind, items));
+        }
+        out
+    }
+}
+
+fn list_line<'a>(line: &Line<'a>, base: usize) -> ListLine<'a> {
+    let body = line.text.trim_start_matches(' ');
+    let indent = line.text.len() - body.len();
+    let (kind, content, offset) = match list_marker(body) {
+        Some((kind, len)) => {
+            let content = &body[len..];
+            let trimmed = content.trim_start();
+            (
+                Some(kind),
+                trimmed,
+                indent + len + content.len() - trimmed.len(),
+            )
+        }
+        None => (None, body, indent),
+    };
+    ListLine {
+        indent,
+        kind,
+        content,
+        content_start: base + line.start + offset,
+    }
+}
+
+/// Extend a line until every `{{{` opened on it is closed
+pub(super) fn logical_line_end(text: &str, line_end: usize) -> usize {
+    let line_start = text[..line_end].rfind('\n').map_or(0, |n| n + 1);
+    let mut end = line_end;
+    let mut pos = line_start;
+    while let Some(rel) = text[pos..end].find("{{{") {
+        let at = pos + rel;
+        match scan::find_balanced(&text[at..], "{{{", "}}}") {
+            Some(len) => {
+                let close = at + len;
+                if close > end {
+                    end = text[close..].find('\n').map_or(text.len(), |n| close + n);
+                }
+                pos = close;
+            }
+            None => break,
+        }
+    }
+    end
+}
@@ -0,0 +1,325 @@
+//! Inline markup: text styles, links, footnotes and `{{{ }}}` blocks
+
+use sevenmark_ast::Element;
+
+use super::Namumark;
+use crate::build::{self, Params};
+use crate::inline::{InlineStack, Style};
+use crate::scan;
+
+/// Paired style delimiters, longest first so `'''` wins over `''`
+const STYLES: &[(&str, Style)] = &[
+    ("'''", Style::Bold),
+    ("''", Style::Italic),
+    ("__", Style::Underline),
+    ("~~", Style::Strikethrough),
+    ("--", Style::Strikethrough),
+    ("^^", Style::Superscript),
+    (",,", Style::Subscript),
+];
+
+/// `{{{+n}}}` / `{{{-n}}}` font sizes in em
+const SIZES_UP: [&str; 5] = [
+    "1.28889em",
+    "1.55556em",
+    "1.88889em",
+    "2.44444em",
+    "2.88889em",
+];
+const SIZES_DOWN: [&str; 5] = ["0.8889em", "0.7778em", "0.6667em", "0.5556em", "0.4444em"];
+
+impl Namumark<'_> {
+    pub(super) fn inline(&mut self, text: &str, base: usize) -> Vec<Element> {
+        let mut stack = InlineStack::default();
+        self.inline_into(text, base, &mut stack);
+        stack.finish()
+    }
+
+    fn inline_into(&mut self, text: &str, base: usize, stack: &mut InlineStack) {
+        let mut i = 0;
+        let mut plain = 0;
+        while i < text.len() {
+            let rest = &text[i..];
+            let c = rest.chars().next().expect("non-empty");
+            if !matches!(
+                c,
+                '\'' | '_' | '~' | '-' | '^' | ',' | '[' | '{' | '\\' | '\n'
+            ) {
+                i += c.len_utf8();
+                continue;
+            }
+            stack.text(&text[plain..i]);
+            let consumed = match c {
+                '\\' => rest[1..].chars().next().map(|escaped| {
+                    stack.text(escaped.encode_utf8(&mut [0; 4]));
+                    1 + escaped.len_utf8()
+                }),
+                '[' => self.bracket(rest, base + i, stack),
+                '{' => self.triple_brace(rest, base + i, stack),
+                '\n' => {
+                    stack.soft_break();
+                    Some(1)
+                }
+                _ => style(rest, stack),
+            };
+            match consumed {
+                Some(n) => {
+                    i += n;
+                    plain = i;
+                }
+                None => {
+                    plain = i;
+                    i += c.len_utf8();
+                }
+            }
+        }
+        stack.text(&text[plain..]);
+    }
+
+    fn bracket(&mut self, rest: &str, start: usize, stack: &mut InlineStack) -> Option<usize> {
+        if rest.starts_with("[[") {
+            let end = scan::find_balanced(rest, "[[", "]]")?;
+            self.link(&rest[2..end - 2], start + 2, stack)?;
+            return Some(end);
+        }
+        let end = scan::find_balanced(rest, "[", "]")?;
+        let inner = &rest[1..end - 1];
+        if let Some(footnote) = inner.strip_prefix('*') {
+            // `[*label content]`
+            let (label, content) = footnote.split_once(' ').unwrap_or((footnote, ""));
+            let name = (!label.is_empty()).then_some(label);
+            let offset = start + 1 + inner.len() - content.len();
+            let children = self.inline(content.trim(), offset);
+            match self.footnotes.footnote(name, children) {
+                Some(footnote) => stack.push(footnote),
+                None => self.warnings.warn(
+                    start,
+                    start + end,
+                    format!("footnote \"{label}\" is used before it is defined and was dropped"),
+                ),
+            }
+            return Some(end);
+        }
+        if inner.contains('\n') {
+            return None;
+        }
+        self.macro_call(inner, start, start + end, stack)?;
+        Some(end)
+    }
+
+    /// `[[target|label]]`; `None` keeps the brackets as text
+    fn link(&mut self, inner: &str, start: usize, stack: &mut InlineStack) -> Option<()> {
+        let parts = scan::split_top_level(inner, "|");
+        let target = parts[0].1.trim();
+        if target.is_empty() {
+            return None;
+        }
+        let label = parts
+            .get(1)
+            .map(|&(offset, _)| (start + offset, &inner[offset..]))
+            .filter(|(_, label)| !label.trim().is_empty());
+        let label_children = |this: &mut Self, fallback: &str| match label {
+            Some((offset, label)) => {
+                let lead = label.len() - label.trim_start().len();
+                this.inline(label.trim(), offset + lead)
+            }
+            None => build::text(fallback),
+        };
+
+        if let Some(name) = target.strip_prefix("분류:") {
+            let name = name.strip_suffix("#blur").unwrap_or(name);
+            stack.push(Element::category(name.trim()));
+            return Some(());
+        }
+        if let Some(file) = target.strip_prefix("파일:") {
+            let mut params = Params::default().set("file", file.trim());
+            for option in label.map_or("", |(_, l)| l).split('&') {
+                if let Some((key, value)) = option.split_once('=')
+                    && matches!(key.trim(), "width" | "height")
+                {
+                    let value = value.trim();
+                    let value = if value.bytes().all(|b| b.is_ascii_digit()) {
+                        format!("{value}px")
+                    } else {
+                        value.to_string()
+                    };
+                    params.insert(key.trim(), &value);
+                }
+            }
+            stack.push(build::media(params, Vec::new()));
+            return Some(());
+        }
+        if scan::starts_with_ci(target, "http://") || scan::starts_with_ci(target, "https://") {
+            let children = label_children(self, target);
+            stack.push(build::media(Params::default().set("url", target), children));
+            return Some(());
+        }
+
+        let (page, anchor) = target.split_once('#').unwrap_or((target, ""));
+        let children = label_children(self, target);
+        if page.trim().is_empty() {
+            self.warnings.warn(
+                start - 2,
+                start + inner.len() + 2,
+                "same-page anchor links are not supported; kept the link text",
+            );
+            stack.extend(children);
+            return Some(());
+        }
+        let mut params = Params::default().set("document", page.trim());
+        if !anchor.trim().is_empty() {
+            params.insert("anchor", anchor.trim());
+        }
+        stack.push(build::media(params, children));
+        Some(())
+    }
+
+    fn triple_brace(&mut self, rest: &str, start: usize, stack: &mut InlineStack) -> Option<usize> {
+        if !rest.starts_with("{{{") {
+            return None;
+        }
+        let end = scan::find_balanced(rest, "{{{", "}}}")?;
+        let inner = &rest[3..end - 3];
+        let inner_base = start + 3;
+
+        if let Some(directive) = inner.strip_prefix("#!") {
+            let (head, body) = directive.split_once('\n').unwrap_or((directive, ""));
+            let body_base = inner_base + inner.len() - body.len();
+            let (name, args) = head.split_once(' ').unwrap_or((head, ""));
+            match name.trim() {
+                "wiki" => {
+                    let params = wiki_params(args);
+                    let children = self.blocks(body, body_base);
+                    stack.push(build::styled(params, children));
+                }
+                "folding" => {
+                    let summary = self.inline(args.trim(), inner_base + 2 + name.len() + 1);
+                    let details = self.blocks(body, body_base);
+                    stack.push(Element::fold(summary, details));
+                }
+                "syntax" => {
+                    stack.push(self.raw_code(Some(args.trim()), body, body_base));
+                }
+                "html" => self.warnings.warn(
+                    start,
+                    start + end,
+                    "#!html blocks are not supported and were dropped",
+                ),
+                other => {
+                    self.warnings.warn(
+                        start,
+                        start + end,
+                        format!("#!{other} blocks are not supported; kept the content as code"),
+                    );
+                    stack.push(self.raw_code(None, body, body_base));
+                }
+            }
+            return Some(end);
+        }
+
+        // {{{+n text}}} / {{{-n text}}}
+        if let Some(sign @ ('+' | '-')) = inner.chars().next()
+            && let Some(level) = inner.get(1..2).and_then(|n| n.parse::<usize>().ok())
+            && (1..=5).contains(&level)
+            && inner[2..].starts_with([' ', '\n'])
+        {
+            let size = if sign == '+' {
+                SIZES_UP[level - 1]
+            } else {
+                SIZES_DOWN[level - 1]
+            };
+            let children = self.inline(inner[3..].trim(), inner_base + 3);
+            stack.push(build::styled(Params::default().set("size", size), children));
+            return Some(end);
+        }
+
+        // {{{#color text}}} / {{{#light,#dark text}}}
+        if inner.starts_with('#')
+            && let Some((colors, content)) = inner.split_once([' ', '\n'])
+            && let Some(params) = color_params(colors)
+        {
+            let offset = inner_base + colors.len() + 1;
+            let children = self.inline(content.trim(), offset);
+            stack.push(build::styled(params, children));
+            return Some(end);
+        }
+
+        stack.push(self.raw_code(None, inner, inner_base));
+        Some(end)
+    }
+
+    /// Raw code; a nested `}}}` cannot be written inside SevenMark raw blocks
+    fn raw_code(&mut self, lang: Option<&str>, content: &str, base: usize) -> Element {
+        let content = content.trim_matches('\n');
+        if let Some(at) = content.find("}}}") {
+            self.warnings.warn(
+                base + at,
+                base + at + 3,
+                "`}}}` inside code was split to keep the block closed",
+            );
+        }
+        Element::code(lang, &build::split_closers(content))
+    }
+}
+
+/// Toggle a paired style delimiter. A style only opens when its closer
+/// appears later in the text; otherwise the characters stay literal.
+fn style(rest: &str, stack: &mut InlineStack) -> Option<usize> {
+    if rest.starts_with("'''''") && stack.is_open(Style::Bold) && stack.is_open(Style::Italic) {
+        stack.close(Style::Italic);
+        stack.close(Style::Bold);
+        return Some(5);
+    }
+    let &(delimiter, style) = STYLES.iter().find(|(d, _)| rest.starts_with(d))?;
+    if stack.is_open(style) {
+        stack.close(style);
+    } else if rest[delimiter.len()..].contains(delimiter) {
+        stack.open(style);
+    } else {
+        return None;
+    }
+    Some(delimiter.len())
+}
+
+/// `style="..."` / `dark-style="..."` arguments of `{{{#!wiki}}}`
+fn wiki_params(args: &str) -> Params {
+    let mut params = Params::default();
+    let mut rest = args.trim();
+    while let Some(eq) = rest.find("=\"") {
+        let key = rest[..eq].trim();
+        let Some(close) = rest[eq + 2..].find('"') else {
+            break;
+        };
+        let value = &rest[eq + 2..eq + 2 + close];
+        if matches!(key, "style" | "dark-style") {
+            params.insert(key, value);
+        }
+        rest = rest[eq + 2 + close + 1..].trim_start();
+    }
+    params
+}
+
+/// `#red`, `#ff0000`, `#red,#blue` (light/dark)
+fn color_params(colors: &str) -> Option<Params> {
+    let color = |c: &str| {
+        let name = c.trim().strip_prefix('#')?;
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return None;
+        }
+        let hex = matches!(name.len(), 3 | 6) && name.chars().all(|c| c.is_ascii_hexdigit());
+        Some(if hex {
+            format!("#{name}")
+        } else {
+            name.to_string()
+        })
+    };
+    let (light, dark) = match colors.split_once(',') {
+        Some((light, dark)) => (color(light)?, Some(color(dark)?)),
+        None => (color(colors)?, None),
+    };
+    let mut params = Params::default().set("color", &light);
+    if let Some(dark) = dark {
+        params.insert("dark-color", &dark);
+    }
+    Some(params)
+}
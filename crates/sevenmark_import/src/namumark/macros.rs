@@ -0,0 +1,107 @@
+//! `[name]` / `[name(args)]` macros
+
+use super::Namumark;
+use sevenmark_ast::Element;
+
+use crate::build::{self, Params};
+use crate::inline::InlineStack;
+
+/// Macros whose output depends on the wiki engine at render time
+const DROPPED_MACROS: &[&str] = &["date", "datetime", "pagecount", "version"];
+
+/// Split macro arguments on commas, trimming each one
+fn arguments(args: &str) -> Vec<&str> {
+    args.split(',')
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .collect()
+}
+
+impl Namumark<'_> {
+    /// Convert the inside of `[...]`; `start`/`end` locate the whole macro.
+    /// `None` keeps the brackets as text.
+    pub(super) fn macro_call(
+        &mut self,
+        inner: &str,
+        start: usize,
+        end: usize,
+        stack: &mut InlineStack,
+    ) -> Option<()> {
+        if inner.trim() != inner {
+            return None;
+        }
+        let (name, args) = match inner.split_once('(') {
+            Some((name, args)) => (name.trim(), Some(args.strip_suffix(')')?)),
+            None => (inner.trim(), None),
+        };
+        if name.is_empty() || !name.chars().all(char::is_alphanumeric) {
+            return None;
+        }
+        let lower = name.to_lowercase();
+        let first = args
+            .and_then(|a| arguments(a).first().copied())
+            .unwrap_or("");
+
+        match (lower.as_str(), args) {
+            ("br", None) => stack.push(Element::hard_break()),
+            ("clearfix", None) => stack.push(Element::clear()),
+            ("목차" | "tableofcontents", None) => stack.push(Element::toc()),
+            ("각주" | "footnote", None) => stack.push(Element::footnote_ref()),
+            ("age", Some(_)) => stack.push(Element::age(first)),
+            ("dday", Some(_)) => stack.push(Element::dday(first)),
+            ("anchor", Some(_)) => stack.push(Element::anchor(first)),
+            ("math", Some(args)) => stack.push(Element::tex(false, args.trim())),
+            ("include", Some(args)) => {
+                let mut parts = arguments(args).into_iter();
+                let page = parts.next().unwrap_or_default();
+                let page = page.strip_prefix("틀:").unwrap_or(page);
+                if page.is_empty() {
+                    return None;
+                }
+                let mut params = Params::default();
+                for part in parts {
+                    if let Some((key, value)) = part.split_once('=')
+                        && let Some(key) = build::param_key(key)
+                    {
+                        params.insert(&key, value.trim());
+                    }
+                }
+                stack.push(build::include(params, page));
+            }
+            ("youtube", Some(args)) => {
+                let mut parts = arguments(args).into_iter();
+                let mut params = Params::default().set("id", parts.next().unwrap_or_default());
+                for part in parts {
+                    if let Some((key, value)) = part.split_once('=')
+                        && matches!(key.trim(), "width" | "height" | "start" | "end")
+                    {
+                        params.insert(key.trim(), value.trim());
+                    }
+                }
+                stack.push(build::external_media("youtube", params));
+            }
+            ("ruby", Some(args)) => {
+                let mut parts = arguments(args).into_iter();
+                let base = parts.next().unwrap_or_default();
+                let annotation = parts
+                    .find_map(|p| p.strip_prefix("ruby="))
+                    .unwrap_or_default();
+                stack.push(build::ruby(annotation.trim(), build::text(base)));
+            }
+            _ if DROPPED_MACROS.contains(&lower.as_str()) => self.warnings.warn(
+                start,
+                end,
+                format!("[{name}] depends on the wiki engine and was dropped"),
+            ),
+            _ => {
+                self.warnings.warn(
+                    start,
+                    end,
+                    format!("unsupported macro [{name}] was kept as text"),
+                );
+                return None;
+            }
+        }
+        Some(())
+    }
+}
@@ -0,0 +1,196 @@
+//! Namumark (namu.wiki / the seed) converter
+//!
+//! Namumark is line oriented like SevenMark, so every source line maps to a
+//! line of output. Headings, text styles, links, footnotes, macros, `{{{ }}}`
+//! blocks, `||` tables, indented lists and quotes are converted; `#!html`
+//! blocks and unknown macros are reported.
+
+mod block;
+mod inline;
+mod macros;
+mod table;
+
+use sevenmark_ast::Element;
+use sevenmark_formatter::FormatConfig;
+
+use crate::build::{self, NamedFootnotes, Params};
+use crate::output::ImportOutput;
+use crate::scan;
+use crate::warning::Warnings;
+
+pub(crate) struct Namumark<'a> {
+    warnings: Warnings<'a>,
+    footnotes: NamedFootnotes,
+}
+
+/// Convert Namumark into SevenMark
+///
+/// # Arguments
+/// * `source` - Namumark source of a single page
+/// * `config` - Formatter settings for the generated source
+pub fn import_namumark(source: &str, config: &FormatConfig) -> ImportOutput {
+    let mut converter = Namumark {
+        warnings: Warnings::new(source),
+        footnotes: NamedFootnotes::default(),
+    };
+
+    let (redirect, body_start) = redirect(source);
+    let ast = match redirect {
+        // A SevenMark redirect is the whole document
+        Some(redirect) => {
+            if !source[body_start..].trim().is_empty() {
+                converter.warnings.warn(
+                    body_start,
+                    source.len(),
+                    "content after a redirect is not supported and was dropped",
+                );
+            }
+            vec![redirect]
+        }
+        None => converter.blocks(&source[body_start..], body_start),
+    };
+
+    ImportOutput::new(ast, converter.warnings.into_vec(), config)
+}
+
+/// `#redirect 문서` / `#넘겨주기 문서` on the first line
+fn redirect(source: &str) -> (Option<Element>, usize) {
+    let Some(first) = scan::lines(source).into_iter().next() else {
+        return (None, 0);
+    };
+    let target = ["#redirect ", "#넘겨주기 "]
+        .iter()
+        .find(|keyword| scan::starts_with_ci(first.text, keyword))
+        .map(|keyword| first.text[keyword.len()..].trim());
+    let Some(target) = target.filter(|t| !t.is_empty()) else {
+        return (None, 0);
+    };
+
+    let page = target.split('#').next().unwrap_or(target).trim();
+    let redirect = match page.split_once(':') {
+        Some(("분류", title)) => {
+            build::redirect(Params::default().set("namespace", "Category"), title.trim())
+        }
+        Some(("파일", title)) => {
+            build::redirect(Params::default().set("namespace", "File"), title.trim())
+        }
+        _ => build::redirect(Params::default(), page),
+    };
+    let body_start = source[first.end..]
+        .find('\n')
+        .map_or(source.len(), |n| first.end + n + 1);
+    (Some(redirect), body_start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::assert_reparses;
+    use sevenmark_parser::core::parse_document;
+
+    fn import(source: &str) -> ImportOutput {
+        let output = import_namumark(source, &FormatConfig::default());
+        assert_reparses(&output);
+        output
+    }
+
+    #[test]
+    fn headings_and_styles() {
+        let out = import("== 개요 ==\n'''굵게''' ''기울임'' __밑줄__ ~~취소~~ ^^위^^ ,,아래,,");
+        assert_eq!(
+            out.text,
+            "## 개요\n**굵게** *기울임* __밑줄__ ~~취소~~ ^^위^^ ,,아래,,"
+        );
+    }
+
+    #[test]
+    fn folded_heading() {
+        let out = import("==# 접힌 문단 #==\n내용");
+        assert!(out.text.starts_with("##! 접힌 문단\n"), "{}", out.text);
+    }
+
+    #[test]
+    fn links_and_categories() {
+        let out = import(
+            "[[나무위키|위키]] [[문서#s-2]] [[https://example.com|예시]]\n[[분류:동물#blur]]",
+        );
+        assert!(
+            out.text.contains("[[#document=\"나무위키\" 위키]]"),
+            "{}",
+            out.text
+        );
+        assert!(
+            out.text
+                .contains("[[#document=\"문서\" #anchor=\"s-2\" 문서#s-2]]"),
+            "{}",
+            out.text
+        );
+        assert!(
+            out.text.contains("[[#url=\"https://example.com\" 예시]]"),
+            "{}",
+            out.text
+        );
+        assert!(out.text.contains("{{{#category 동물}}}"), "{}", out.text);
+    }
+
+    #[test]
+    fn footnotes_and_macros() {
+        let out =
+            import("본문[* 각주 내용] 다시[*A 이름]\n[include(틀:상위 문서, 문서명=예시)]\n[각주]");
+        assert!(out.text.contains("{{{#fn 각주 내용}}}"), "{}", out.text);
+        assert!(
+            out.text.contains("{{{#fn #name=\"A\" || 이름}}}"),
+            "{}",
+            out.text
+        );
+        assert!(
+            out.text
+                .contains("{{{#include #문서명=\"예시\" || 상위 문서}}}"),
+            "{}",
+            out.text
+        );
+        assert!(out.text.ends_with("[fn]"), "{}", out.text);
+    }
+
+    #[test]
+    fn tables() {
+        let out = import("||<-2> 제목 ||\n|| a || b ||");
+        assert!(out.text.contains("#x=\"2\""), "{}", out.text);
+        let reparsed = parse_document(&out.text);
+        assert!(matches!(reparsed[0], Element::Table(_)), "{}", out.text);
+    }
+
+    #[test]
+    fn brace_blocks() {
+        let out = import(
+            "{{{+1 큰 글씨}}} {{{#red 빨강}}}\n{{{#!folding 더 보기\n숨김}}}\n{{{#!syntax rust\nfn main() {}\n}}}",
+        );
+        assert!(out.text.contains("#size=\"1.28889em\""), "{}", out.text);
+        assert!(out.text.contains("#color=\"red\""), "{}", out.text);
+        assert!(out.text.contains("{{{#fold"), "{}", out.text);
+        assert!(out.text.contains("{{{#code #lang=\"rust\""), "{}", out.text);
+    }
+
+    #[test]
+    fn lists_and_quotes() {
+        let out = import(" * 하나\n  * 둘\n 1. 첫째\n> 인용");
+        let reparsed = parse_document(&out.text);
+        assert!(matches!(reparsed[0], Element::List(_)), "{}", out.text);
+        assert!(out.text.contains("{{{#list #1"), "{}", out.text);
+        assert!(out.text.contains("{{{#quote"), "{}", out.text);
+    }
+
+    #[test]
+    fn unsupported_macros_are_reported() {
+        let source = "a\n[pagecount] {{{#!html <b>x</b>}}}";
+        let out = import(source);
+        assert_eq!(out.warnings.len(), 2, "{:?}", out.warnings);
+        assert_eq!((out.warnings[0].line, out.warnings[0].column), (2, 1));
+    }
+
+    #[test]
+    fn redirect_line() {
+        let out = import("#redirect 분류:동물");
+        assert_eq!(out.text, "{{{#redirect #namespace=\"Category\" 동물}}}");
+    }
+}
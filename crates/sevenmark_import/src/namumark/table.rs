@@ -0,0 +1,171 @@
+//! `||` tables with `<...>` cell attributes
+
+use sevenmark_ast::Element;
+
+use super::Namumark;
+use crate::build::{self, Cell, Params};
+use crate::scan::{self, Line};
+
+/// Table-level attributes that may appear in any cell
+#[derive(Default)]
+struct TableAttrs {
+    width: Option<String>,
+    bgcolor: Option<String>,
+}
+
+/// `#fff` / `red` / `#fff,#000` (dark variant dropped into `dark-bgcolor`)
+fn color_pair(value: &str) -> (String, Option<String>) {
+    match value.split_once(',') {
+        Some((light, dark)) => (light.trim().to_string(), Some(dark.trim().to_string())),
+        None => (value.trim().to_string(), None),
+    }
+}
+
+/// Whether a table row starts on this line
+pub(super) fn is_table_row(line: &str) -> bool {
+    let t = line.trim_start();
+    t.starts_with("||")
+        || (t.starts_with('|')
+            && t[1..]
+                .find('|')
+                .is_some_and(|n| t[1 + n + 1..].starts_with('|')))
+}
+
+fn row_closed(line: &Line) -> bool {
+    let t = line.text.trim();
+    t.len() > 2 && t.ends_with("||")
+}
+
+/// Index just past the last line of the table starting at `start`.
+/// A row continues over following lines until one ends with `||`.
+pub(super) fn table_end(lines: &[Line], start: usize) -> usize {
+    let mut i = start;
+    while i < lines.len() && is_table_row(lines[i].text) {
+        i = lines[i..]
+            .iter()
+            .position(row_closed)
+            .map_or(i + 1, |n| i + n + 1);
+    }
+    i.max(start + 1)
+}
+
+impl Namumark<'_> {
+    /// The table, or `None` with a warning when it has no cells
+    pub(super) fn table(&mut self, text: &str, base: usize) -> Option<Element> {
+        let mut params = Params::default();
+        let mut table = TableAttrs::default();
+        let mut rows = Vec::new();
+
+        let lines = scan::lines(text);
+        let mut row_start = None;
+        for (i, line) in lines.iter().enumerate() {
+            let start = *row_start.get_or_insert(line.start);
+            if !row_closed(line) && i + 1 < lines.len() {
+                continue;
+            }
+            row_start = None;
+
+            let raw = &text[start..line.end];
+            let mut row = raw.trim_start();
+            let mut row_base = base + start + raw.len() - row.len();
+            if rows.is_empty() && !row.starts_with("||") {
+                // `|caption|` before the first row
+                if let Some(close) = row[1..].find('|') {
+                    params.insert("caption", row[1..1 + close].trim());
+                    row = &row[1 + close..];
+                    row_base += 1 + close;
+                }
+            }
+
+            let body = row.strip_prefix("||").unwrap_or(row);
+            let body = body.trim_end().strip_suffix("||").unwrap_or(body);
+            let mut cells = Vec::new();
+            let mut colspan = 1;
+            for (offset, part) in scan::split_top_level(body, "||") {
+                if part.is_empty() {
+                    // `||||` merges with the next cell
+                    colspan += 1;
+                    continue;
+                }
+                let mut cell = self.cell(part, row_base + 2 + offset, &mut table);
+                if colspan > 1 && cell.colspan.is_none() {
+                    cell.colspan = Some(colspan);
+                }
+                colspan = 1;
+                cells.push(cell);
+            }
+            if !cells.is_empty() {
+                rows.push(cells);
+            }
+        }
+
+        if rows.is_empty() {
+            self.warnings
+                .warn(base, base + text.len(), "table without cells was dropped");
+            return None;
+        }
+        if let Some(width) = table.width {
+            params.insert("width", &width);
+        }
+        if let Some(bgcolor) = table.bgcolor {
+            params.insert("bgcolor", &bgcolor);
+        }
+        Some(build::table(params, rows))
+    }
+
+    fn cell(&mut self, part: &str, base: usize, table: &mut TableAttrs) -> Cell {
+        let mut cell = Cell::default();
+        let mut style = Vec::new();
+        let mut rest = part.trim_start();
+        let mut offset = part.len() - rest.len();
+
+        while rest.starts_with('<') {
+            let Some(close) = rest.find('>') else {
+                break;
+            };
+            let attr = &rest[1..close];
+            let (key, value) = attr.split_once('=').unwrap_or((attr, ""));
+            let span = |n: &str| n.parse::<usize>().ok();
+            match (key.trim(), value) {
+                (k, "") if k.starts_with('-') => cell.colspan = span(&k[1..]),
+                (k, "") if k.starts_with('|') || k.starts_with("^|") || k.starts_with("v|") => {
+                    cell.rowspan = span(k.trim_start_matches(['^', 'v']).trim_start_matches('|'))
+                }
+                (":", "") => style.push("text-align:center".to_string()),
+                ("(", "") => style.push("text-align:left".to_string()),
+                (")", "") => style.push("text-align:right".to_string()),
+                (k, "") if k.starts_with('#') => {
+                    let (light, dark) = color_pair(k);
+                    cell.params.insert("bgcolor", &light);
+                    if let Some(dark) = dark {
+                        cell.params.insert("dark-bgcolor", &dark);
+                    }
+                }
+                ("bgcolor" | "color", value) => {
+                    let (light, dark) = color_pair(value);
+                    cell.params.insert(key.trim(), &light);
+                    if let Some(dark) = dark {
+                        cell.params.insert(&format!("dark-{}", key.trim()), &dark);
+                    }
+                }
+                ("width" | "height", value) => cell.params.insert(key.trim(), value.trim()),
+                ("tablewidth", value) => table.width = Some(value.trim().to_string()),
+                ("tablebgcolor", value) => table.bgcolor = Some(color_pair(value).0),
+                _ => self.warnings.warn(
+                    base + offset,
+                    base + offset + close + 1,
+                    format!("table attribute <{attr}> is not supported and was dropped"),
+                ),
+            }
+            rest = &rest[close + 1..];
+            offset += close + 1;
+        }
+
+        if !style.is_empty() {
+            cell.params.insert("style", &style.join(";"));
+        }
+        let lead = rest.len() - rest.trim_start().len();
+        cell.children = self.blocks(rest.trim(), base + offset + lead);
+        cell
+    }
+}
@@ -0,0 +1,119 @@
+//! Conversion result and format dispatch
+
+use std::str::FromStr;
+
+use serde::Serialize;
+use sevenmark_ast::Element;
+use sevenmark_formatter::{FormatConfig, format_document};
+
+use crate::mediawiki::import_mediawiki;
+use crate::namumark::import_namumark;
+use crate::warning::ImportWarning;
+
+/// Supported source markup languages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    MediaWiki,
+    Namumark,
+}
+
+impl FromStr for ImportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "mediawiki" | "wikitext" => Ok(Self::MediaWiki),
+            "namumark" | "namu" => Ok(Self::Namumark),
+            other => Err(format!("unknown import format: {other}")),
+        }
+    }
+}
+
+/// Converted document
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportOutput {
+    /// Generated AST (all spans synthesized)
+    pub ast: Vec<Element>,
+    /// `ast` serialised with `sevenmark_formatter`
+    pub text: String,
+    /// Constructs that were dropped or only approximated
+    pub warnings: Vec<ImportWarning>,
+}
+
+impl ImportOutput {
+    pub(crate) fn new(
+        ast: Vec<Element>,
+        warnings: Vec<ImportWarning>,
+        config: &FormatConfig,
+    ) -> Self {
+        let text = format_document(&ast, config);
+        Self {
+            ast,
+            text,
+            warnings,
+        }
+    }
+}
+
+/// Convert `source` written in `format` into SevenMark
+pub fn import(format: ImportFormat, source: &str, config: &FormatConfig) -> ImportOutput {
+    match format {
+        ImportFormat::MediaWiki => import_mediawiki(source, config),
+        ImportFormat::Namumark => import_namumark(source, config),
+    }
+}
+
+/// Assert that `output.text` reparses to `output.ast`
+#[cfg(test)]
+pub(crate) fn assert_reparses(output: &ImportOutput) {
+    use serde_json::Value;
+    use sevenmark_parser::core::parse_document;
+
+    fn text(value: &Value) -> Option<&str> {
+        value.get("Text")?.get("value")?.as_str()
+    }
+
+    /// JSON without spans and parser-assigned numbering, with adjacent
+    /// `Text` runs merged as the parser may split them
+    fn strip(value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                map.retain(|key, _| {
+                    !key.ends_with("span")
+                        && !key.ends_with("spans")
+                        && key != "footnote_index"
+                        && key != "section_index"
+                });
+                map.values_mut().for_each(strip);
+            }
+            Value::Array(items) => {
+                let mut merged: Vec<Value> = Vec::new();
+                for mut item in std::mem::take(items) {
+                    strip(&mut item);
+                    match (merged.last().and_then(text), text(&item)) {
+                        (Some(prev), Some(next)) => {
+                            let joined = format!("{prev}{next}");
+                            merged.pop();
+                            merged.push(serde_json::json!({ "Text": { "value": joined } }));
+                        }
+                        _ => merged.push(item),
+                    }
+                }
+                *items = merged;
+            }
+            _ => {}
+        }
+    }
+
+    let normalized = |elements: &[Element]| {
+        let mut value = serde_json::to_value(elements).unwrap();
+        strip(&mut value);
+        value
+    };
+    assert_eq!(
+        normalized(&parse_document(&output.text)),
+        normalized(&output.ast),
+        "output does not reparse to the generated AST:\n{}",
+        output.text
+    );
+}
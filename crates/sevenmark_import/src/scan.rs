@@ -0,0 +1,192 @@
+//! Source scanning helpers shared by the converters
+
+/// A physical source line; `start`/`end` are byte offsets excluding the newline
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Line<'a> {
+    pub start: usize,
+    pub end: usize,
+    pub text: &'a str,
+}
+
+pub(crate) fn lines(text: &str) -> Vec<Line<'_>> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    for raw in text.split_inclusive('\n') {
+        let body = raw.trim_end_matches('\n').trim_end_matches('\r');
+        out.push(Line {
+            start,
+            end: start + body.len(),
+            text: body,
+        });
+        start += raw.len();
+    }
+    out
+}
+
+/// Given `text` starting with `open`, return the offset just past the
+/// matching `close`, honouring nesting
+pub(crate) fn find_balanced(text: &str, open: &str, close: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        if rest.starts_with(open) {
+            depth += 1;
+            i += open.len();
+        } else if rest.starts_with(close) {
+            depth -= 1;
+            i += close.len();
+            if depth == 0 {
+                return Some(i);
+            }
+        } else {
+            i += rest.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    None
+}
+
+/// Split on `sep` outside of `[[...]]` and `{{...}}`, returning each part
+/// with its offset in `text`
+pub(crate) fn split_top_level<'a>(text: &'a str, sep: &str) -> Vec<(usize, &'a str)> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut part_start = 0;
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        if rest.starts_with("[[") || rest.starts_with("{{") {
+            depth += 1;
+            i += 2;
+        } else if (rest.starts_with("]]") || rest.starts_with("}}")) && depth > 0 {
+            depth -= 1;
+            i += 2;
+        } else if depth == 0 && rest.starts_with(sep) {
+            parts.push((part_start, &text[part_start..i]));
+            i += sep.len();
+            part_start = i;
+        } else {
+            i += rest.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    parts.push((part_start, &text[part_start..]));
+    parts
+}
+
+/// ASCII case-insensitive substring search
+pub(crate) fn find_ci(haystack: &str, needle: &str) -> Option<usize> {
+    let needle = needle.as_bytes();
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|w| w.eq_ignore_ascii_case(needle))
+}
+
+pub(crate) fn starts_with_ci(text: &str, prefix: &str) -> bool {
+    text.len() >= prefix.len()
+        && text.as_bytes()[..prefix.len()].eq_ignore_ascii_case(prefix.as_bytes())
+}
+
+/// Length of a bare `http(s)://` URL at the start of `text`, without
+/// trailing punctuation
+pub(crate) fn bare_url_len(text: &str) -> Option<usize> {
+    if !starts_with_ci(text, "http://") && !starts_with_ci(text, "https://") {
+        return None;
+    }
+    let end = text
+        .find(|c: char| {
+            c.is_whitespace() || matches!(c, '<' | '>' | '[' | ']' | '"' | '|' | '{' | '}' | '\'')
+        })
+        .unwrap_or(text.len());
+    let url = text[..end].trim_end_matches(['.', ',', ';', ':', '!', '?', ')']);
+    (!url.ends_with("//")).then_some(url.len())
+}
+
+/// Decode an HTML character reference at the start of `text`
+/// (`&amp;`, `&#39;`, `&#x27;`), returning the character and its length
+pub(crate) fn decode_entity(text: &str) -> Option<(char, usize)> {
+    let end = text.get(..12).unwrap_or(text).find(';')?;
+    let name = &text[1..end];
+    let c = if let Some(hex) = name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+        char::from_u32(u32::from_str_radix(hex, 16).ok()?)?
+    } else if let Some(dec) = name.strip_prefix('#') {
+        char::from_u32(dec.parse().ok()?)?
+    } else {
+        match name {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            "nbsp" => '\u{a0}',
+            "ndash" => '–',
+            "mdash" => '—',
+            "hellip" => '…',
+            "middot" => '·',
+            "copy" => '©',
+            "reg" => '®',
+            "trade" => '™',
+            "times" => '×',
+            "larr" => '←',
+            "rarr" => '→',
+            "laquo" => '«',
+            "raquo" => '»',
+            "deg" => '°',
+            "shy" => '\u{ad}',
+            "zwj" => '\u{200d}',
+            "zwnj" => '\u{200c}',
+            _ => return None,
+        }
+    };
+    Some((c, end + 1))
+}
+
+/// Decode every character reference in `text`, leaving unknown ones as-is
+pub(crate) fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(at) = rest.find('&') {
+        out.push_str(&rest[..at]);
+        rest = &rest[at..];
+        match decode_entity(rest) {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn balanced_and_split() {
+        assert_eq!(find_balanced("{{a|{{b}}}} x", "{{", "}}"), Some(11));
+        let parts: Vec<&str> = split_top_level("a|[[b|c]]|{{d|e}}", "|")
+            .into_iter()
+            .map(|(_, p)| p)
+            .collect();
+        assert_eq!(parts, ["a", "[[b|c]]", "{{d|e}}"]);
+    }
+
+    #[test]
+    fn bare_urls_drop_trailing_punctuation() {
+        assert_eq!(bare_url_len("https://example.com/a. next"), Some(21));
+        assert_eq!(bare_url_len("http://"), None);
+    }
+
+    #[test]
+    fn entities() {
+        assert_eq!(decode_entity("&amp; x"), Some(('&', 5)));
+        assert_eq!(decode_entity("&#x41;"), Some(('A', 6)));
+        assert_eq!(decode_entity("& x;"), None);
+    }
+}
@@ -0,0 +1,65 @@
+//! Import warnings with source positions
+
+use serde::Serialize;
+use sevenmark_ast::Span;
+
+/// A source construct that could not be converted (or only approximately)
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportWarning {
+    /// Byte range in the imported source
+    pub span: Span,
+    /// 1-based line of `span.start`
+    pub line: usize,
+    /// 1-based column (in characters) of `span.start`
+    pub column: usize,
+    pub message: String,
+}
+
+/// Collects warnings and maps byte offsets to line/column
+pub(crate) struct Warnings<'a> {
+    source: &'a str,
+    line_starts: Vec<usize>,
+    items: Vec<ImportWarning>,
+}
+
+impl<'a> Warnings<'a> {
+    pub fn new(source: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self {
+            source,
+            line_starts,
+            items: Vec::new(),
+        }
+    }
+
+    pub fn warn(&mut self, start: usize, end: usize, message: impl Into<String>) {
+        let line_index = self.line_starts.partition_point(|&s| s <= start) - 1;
+        let line_start = self.line_starts[line_index];
+        let column = self.source[line_start..start].chars().count() + 1;
+        self.items.push(ImportWarning {
+            span: Span::new(start, end),
+            line: line_index + 1,
+            column,
+            message: message.into(),
+        });
+    }
+
+    pub fn into_vec(self) -> Vec<ImportWarning> {
+        self.items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_are_one_based() {
+        let mut warnings = Warnings::new("ab\n가나다 x");
+        warnings.warn(13, 14, "x");
+        let items = warnings.into_vec();
+        assert_eq!((items[0].line, items[0].column), (2, 5));
+    }
+}
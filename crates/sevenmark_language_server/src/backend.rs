@@ -1,12 +1,28 @@
 use dashmap::DashMap;
+use sevenmark_lsp_core::code_lens::collect_code_lenses;
 use sevenmark_lsp_core::completion::get_completions;
 use sevenmark_lsp_core::definition::find_definition;
 use sevenmark_lsp_core::diagnostics::collect_diagnostics;
 use sevenmark_lsp_core::document::DocumentState;
+use sevenmark_lsp_core::document_link::collect_document_links;
 use sevenmark_lsp_core::folding::collect_folding_ranges;
 use sevenmark_lsp_core::hover::get_hover;
-use sevenmark_lsp_core::semantic_tokens::{collect_semantic_tokens, legend};
+use sevenmark_lsp_core::inlay_hints::{collect_inlay_hints, today};
+use sevenmark_lsp_core::linked_editing::linked_editing_ranges;
+use sevenmark_lsp_core::preview::{
+    Preview, PreviewConfig, PreviewParams, PreviewResult, ScrollSync, ScrollSyncParams,
+};
+use sevenmark_lsp_core::selection_range::collect_selection_ranges;
+use sevenmark_lsp_core::semantic_tokens::{
+    SemanticTokensCache, collect_semantic_tokens_range, legend,
+};
 use sevenmark_lsp_core::symbols::collect_document_symbols;
+use sevenmark_lsp_core::workspace::{
+    FolderSource, IncludeGraph, IncludeGraphParams, WorkspaceIndex, collect_workspace_diagnostics,
+    collect_workspace_symbols,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, RwLock};
 use tower_lsp_server::jsonrpc::Result;
 use tower_lsp_server::ls_types::*;
 use tower_lsp_server::{Client, LanguageServer};
@@ -15,10 +31,32 @@ pub struct Backend {
     pub client: Client,
     pub documents: DashMap<String, DocumentState>,
     pub document_versions: DashMap<String, i32>,
+    pub workspace: RwLock<WorkspaceIndex>,
+    /// Set by `initialize`, indexed in the background once initialized
+    pub workspace_folder: Mutex<Option<FolderSource>>,
+    /// The client can register `workspace/didChangeWatchedFiles` for us
+    pub watch_files: AtomicBool,
+    pub preview_config: RwLock<PreviewConfig>,
+    pub semantic_tokens: Mutex<SemanticTokensCache>,
 }
 
 impl LanguageServer for Backend {
-    async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
+    async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
+        *self.workspace_folder.lock().unwrap() = FolderSource::from_initialize_params(&params);
+        let watch_files = params
+            .capabilities
+            .workspace
+            .as_ref()
+            .and_then(|workspace| workspace.did_change_watched_files.as_ref())
+            .and_then(|watched| watched.dynamic_registration)
+            .unwrap_or(false);
+        self.watch_files.store(watch_files, Ordering::Relaxed);
+        if let Some(config) =
+            PreviewConfig::from_initialization_options(params.initialization_options.as_ref())
+        {
+            *self.preview_config.write().unwrap() = config;
+        }
+
         Ok(InitializeResult {
             capabilities: ServerCapabilities {
                 text_document_sync: Some(TextDocumentSyncCapability::Options(
@@ -33,6 +71,7 @@ impl LanguageServer for Backend {
                         "[".to_string(),
                         "#".to_string(),
                         "(".to_string(),
+                        "\"".to_string(),
                     ]),
                     ..Default::default()
                 }),
@@ -43,13 +82,26 @@ impl LanguageServer for Backend {
                     SemanticTokensServerCapabilities::SemanticTokensOptions(
                         SemanticTokensOptions {
                             legend: legend(),
-                            full: Some(SemanticTokensFullOptions::Bool(true)),
-                            range: None,
+                            full: Some(SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                            range: Some(true),
                             ..Default::default()
                         },
                     ),
                 ),
                 folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+                inlay_hint_provider: Some(OneOf::Left(true)),
+                code_lens_provider: Some(CodeLensOptions {
+                    resolve_provider: Some(false),
+                }),
+                selection_range_provider: Some(SelectionRangeProviderCapability::Simple(true)),
+                linked_editing_range_provider: Some(LinkedEditingRangeServerCapabilities::Simple(
+                    true,
+                )),
+                document_link_provider: Some(DocumentLinkOptions {
+                    resolve_provider: Some(false),
+                    work_done_progress_options: Default::default(),
+                }),
+                workspace_symbol_provider: Some(OneOf::Left(true)),
                 ..Default::default()
             },
             server_info: Some(ServerInfo {
@@ -60,7 +112,64 @@ impl LanguageServer for Backend {
         })
     }
 
-    async fn initialized(&self, _: InitializedParams) {}
+    async fn initialized(&self, _: InitializedParams) {
+        let Some(folder) = self.workspace_folder.lock().unwrap().take() else {
+            return;
+        };
+
+        if self.watch_files.load(Ordering::Relaxed) {
+            let options = DidChangeWatchedFilesRegistrationOptions {
+                watchers: vec![FileSystemWatcher {
+                    glob_pattern: GlobPattern::String(format!("**/*.{}", FolderSource::EXTENSION)),
+                    kind: None,
+                }],
+            };
+            let registration = Registration {
+                id: "sevenmark-workspace-files".to_string(),
+                method: "workspace/didChangeWatchedFiles".to_string(),
+                register_options: serde_json::to_value(options).ok(),
+            };
+            if let Err(e) = self.client.register_capability(vec![registration]).await {
+                self.client
+                    .log_message(
+                        MessageType::WARNING,
+                        format!("Could not watch workspace files: {e}"),
+                    )
+                    .await;
+            }
+        }
+
+        // Reading every page can take a while; requests are answered without
+        // the workspace until it is swapped in
+        let index =
+            match tokio::task::spawn_blocking(move || WorkspaceIndex::from_source(folder)).await {
+                Ok(index) => index,
+                Err(e) => {
+                    self.client
+                        .log_message(
+                            MessageType::ERROR,
+                            format!("Failed to index the workspace: {e}"),
+                        )
+                        .await;
+                    return;
+                }
+            };
+        *self.workspace.write().unwrap() = index;
+        self.refresh_open_documents().await;
+    }
+
+    async fn did_change_watched_files(&self, params: DidChangeWatchedFilesParams) {
+        {
+            let mut workspace = self.workspace.write().unwrap();
+            for change in &params.changes {
+                // Open documents are indexed from the editor until closed
+                if !self.documents.contains_key(change.uri.as_str()) {
+                    workspace.restore(&change.uri);
+                }
+            }
+        }
+        self.refresh_open_documents().await;
+    }
 
     async fn shutdown(&self) -> Result<()> {
         Ok(())
@@ -103,7 +212,16 @@ impl LanguageServer for Backend {
         let uri_key = uri.to_string();
         self.documents.remove(&uri_key);
         self.document_versions.remove(&uri_key);
-        self.client.publish_diagnostics(uri, Vec::new(), None).await;
+        self.semantic_tokens.lock().unwrap().remove(&uri_key);
+        self.client
+            .publish_diagnostics(uri.clone(), Vec::new(), None)
+            .await;
+
+        // Unsaved edits no longer count for the other pages
+        let restored = self.workspace.write().unwrap().restore(&uri);
+        if restored {
+            self.refresh_open_documents().await;
+        }
     }
 
     async fn goto_definition(
@@ -120,7 +238,8 @@ impl LanguageServer for Backend {
             state
                 .line_index
                 .position_to_byte_offset(&state.text, pos.line, pos.character);
-        let location = find_definition(&state, &uri, byte_offset);
+        let workspace = self.workspace.read().unwrap();
+        let location = find_definition(&state, &uri, byte_offset, &workspace);
         Ok(location.map(GotoDefinitionResponse::Scalar))
     }
 
@@ -135,7 +254,8 @@ impl LanguageServer for Backend {
             state
                 .line_index
                 .position_to_byte_offset(&state.text, pos.line, pos.character);
-        Ok(get_hover(&state, byte_offset))
+        let workspace = self.workspace.read().unwrap();
+        Ok(get_hover(&state, byte_offset, &workspace))
     }
 
     async fn completion(&self, params: CompletionParams) -> Result<Option<CompletionResponse>> {
@@ -148,7 +268,8 @@ impl LanguageServer for Backend {
             state
                 .line_index
                 .position_to_byte_offset(&state.text, pos.line, pos.character);
-        let items = get_completions(&state, pos, byte_offset);
+        let workspace = self.workspace.read().unwrap();
+        let items = get_completions(&state, pos, byte_offset, &workspace);
         if items.is_empty() {
             Ok(None)
         } else {
@@ -164,8 +285,43 @@ impl LanguageServer for Backend {
         let Some(state) = self.documents.get(&uri) else {
             return Ok(None);
         };
-        let tokens = collect_semantic_tokens(&state);
-        Ok(Some(SemanticTokensResult::Tokens(SemanticTokens {
+        let version = self.document_versions.get(&uri).map(|v| *v);
+        let tokens = self
+            .semantic_tokens
+            .lock()
+            .unwrap()
+            .full(&uri, version, &state);
+        Ok(Some(SemanticTokensResult::Tokens(tokens)))
+    }
+
+    async fn semantic_tokens_full_delta(
+        &self,
+        params: SemanticTokensDeltaParams,
+    ) -> Result<Option<SemanticTokensFullDeltaResult>> {
+        let uri = params.text_document.uri.to_string();
+        let Some(state) = self.documents.get(&uri) else {
+            return Ok(None);
+        };
+        let version = self.document_versions.get(&uri).map(|v| *v);
+        let result = self.semantic_tokens.lock().unwrap().delta(
+            &uri,
+            version,
+            &state,
+            &params.previous_result_id,
+        );
+        Ok(Some(result))
+    }
+
+    async fn semantic_tokens_range(
+        &self,
+        params: SemanticTokensRangeParams,
+    ) -> Result<Option<SemanticTokensRangeResult>> {
+        let uri = params.text_document.uri.to_string();
+        let Some(state) = self.documents.get(&uri) else {
+            return Ok(None);
+        };
+        let tokens = collect_semantic_tokens_range(&state, params.range);
+        Ok(Some(SemanticTokensRangeResult::Tokens(SemanticTokens {
             result_id: None,
             data: tokens,
         })))
@@ -199,23 +355,150 @@ impl LanguageServer for Backend {
             Ok(Some(DocumentSymbolResponse::Nested(symbols)))
         }
     }
+
+    async fn inlay_hint(&self, params: InlayHintParams) -> Result<Option<Vec<InlayHint>>> {
+        let uri = params.text_document.uri.to_string();
+        let Some(state) = self.documents.get(&uri) else {
+            return Ok(None);
+        };
+        Ok(Some(collect_inlay_hints(&state, params.range, today())))
+    }
+
+    async fn code_lens(&self, params: CodeLensParams) -> Result<Option<Vec<CodeLens>>> {
+        let uri = params.text_document.uri.to_string();
+        let Some(state) = self.documents.get(&uri) else {
+            return Ok(None);
+        };
+        Ok(Some(collect_code_lenses(&state)))
+    }
+
+    async fn selection_range(
+        &self,
+        params: SelectionRangeParams,
+    ) -> Result<Option<Vec<SelectionRange>>> {
+        let uri = params.text_document.uri.to_string();
+        let Some(state) = self.documents.get(&uri) else {
+            return Ok(None);
+        };
+        Ok(Some(collect_selection_ranges(&state, &params.positions)))
+    }
+
+    async fn linked_editing_range(
+        &self,
+        params: LinkedEditingRangeParams,
+    ) -> Result<Option<LinkedEditingRanges>> {
+        let uri = params
+            .text_document_position_params
+            .text_document
+            .uri
+            .to_string();
+        let pos = params.text_document_position_params.position;
+        let Some(state) = self.documents.get(&uri) else {
+            return Ok(None);
+        };
+        let byte_offset =
+            state
+                .line_index
+                .position_to_byte_offset(&state.text, pos.line, pos.character);
+        Ok(linked_editing_ranges(&state, byte_offset))
+    }
+
+    async fn document_link(&self, params: DocumentLinkParams) -> Result<Option<Vec<DocumentLink>>> {
+        let uri = params.text_document.uri.to_string();
+        let Some(state) = self.documents.get(&uri) else {
+            return Ok(None);
+        };
+        let config = self.preview_config.read().unwrap();
+        let workspace = self.workspace.read().unwrap();
+        Ok(Some(collect_document_links(&state, &config, &workspace)))
+    }
+
+    async fn symbol(
+        &self,
+        params: WorkspaceSymbolParams,
+    ) -> Result<Option<WorkspaceSymbolResponse>> {
+        let documents: Vec<_> = self.documents.iter().collect();
+        let open: Vec<_> = documents
+            .iter()
+            .filter_map(|entry| Some((entry.key().parse().ok()?, entry.value())))
+            .collect();
+        let workspace = self.workspace.read().unwrap();
+        let symbols = collect_workspace_symbols(&params.query, &workspace, &open);
+        Ok(Some(WorkspaceSymbolResponse::Nested(symbols)))
+    }
 }
 
 impl Backend {
+    /// `sevenmark/preview`
+    pub async fn preview(&self, params: PreviewParams) -> Result<Option<PreviewResult>> {
+        let uri = params.text_document.uri.to_string();
+        let Some(state) = self.documents.get(&uri) else {
+            return Ok(None);
+        };
+        let default_config = self.preview_config.read().unwrap();
+        let config = params.config.as_ref().unwrap_or(&default_config);
+        let workspace = self.workspace.read().unwrap();
+        let preview = Preview::render(&state, config, &workspace);
+        Ok(Some(PreviewResult {
+            html: preview.html,
+            version: self.document_versions.get(&uri).map(|v| *v),
+        }))
+    }
+
+    /// `sevenmark/scrollSync`
+    pub async fn scroll_sync(&self, params: ScrollSyncParams) -> Result<Option<ScrollSync>> {
+        let uri = params.text_document.uri.to_string();
+        let Some(state) = self.documents.get(&uri) else {
+            return Ok(None);
+        };
+        let config = self.preview_config.read().unwrap();
+        let workspace = self.workspace.read().unwrap();
+        let preview = Preview::render(&state, &config, &workspace);
+        Ok(ScrollSync::from_params(&state, &preview, &params))
+    }
+
+    /// `sevenmark/includeGraph`
+    pub async fn include_graph(&self, params: IncludeGraphParams) -> Result<IncludeGraph> {
+        let uri = params.text_document.uri;
+        let state = self.documents.get(&uri.to_string());
+        let workspace = self.workspace.read().unwrap();
+        Ok(IncludeGraph::new(&uri, state.as_deref(), &workspace))
+    }
+
     /// Parses the document, publishes diagnostics, and caches state.
+    /// Re-index and re-check the open documents after the workspace changed
+    async fn refresh_open_documents(&self) {
+        let open: Vec<_> = self
+            .documents
+            .iter()
+            .filter_map(|entry| {
+                let uri = entry.key().parse::<Uri>().ok()?;
+                let version = self.document_versions.get(entry.key()).map(|v| *v);
+                Some((uri, version, entry.value().text.clone()))
+            })
+            .collect();
+        for (uri, version, text) in open {
+            self.on_change(uri, version, text).await;
+        }
+    }
+
     async fn on_change(&self, uri: Uri, version: Option<i32>, text: String) {
         let uri_key = uri.to_string();
 
-        if let Some(version) = version {
-            if let Some(prev_version) = self.document_versions.get(&uri_key)
-                && version < *prev_version
-            {
-                return;
-            }
+        if let Some(version) = version
+            && let Some(prev_version) = self.document_versions.get(&uri_key)
+            && version < *prev_version
+        {
+            return;
         }
 
         let state = DocumentState::new(text);
-        let diagnostics = collect_diagnostics(&state);
+        let mut diagnostics = collect_diagnostics(&state);
+        {
+            let mut workspace = self.workspace.write().unwrap();
+            workspace.update(&uri, &state);
+            diagnostics.extend(collect_workspace_diagnostics(&state, &workspace));
+        }
 
         // Cache first so hover/completion/definition always see latest parse.
         self.documents.insert(uri_key.clone(), state);
@@ -1,6 +1,8 @@
 mod backend;
 
 use dashmap::DashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Mutex, RwLock};
 use tower_lsp_server::{LspService, Server};
 
 use backend::Backend;
@@ -10,11 +12,20 @@ async fn main() {
     let stdin = tokio::io::stdin();
     let stdout = tokio::io::stdout();
 
-    let (service, socket) = LspService::new(|client| Backend {
+    let (service, socket) = LspService::build(|client| Backend {
         client,
         documents: DashMap::new(),
         document_versions: DashMap::new(),
-    });
+        workspace: RwLock::default(),
+        workspace_folder: Mutex::default(),
+        watch_files: AtomicBool::default(),
+        preview_config: RwLock::default(),
+        semantic_tokens: Mutex::default(),
+    })
+    .custom_method("sevenmark/preview", Backend::preview)
+    .custom_method("sevenmark/scrollSync", Backend::scroll_sync)
+    .custom_method("sevenmark/includeGraph", Backend::include_graph)
+    .finish();
 
     Server::new(stdin, stdout, socket).serve(service).await;
 }
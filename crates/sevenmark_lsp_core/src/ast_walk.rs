@@ -1,15 +1,49 @@
-use sevenmark_ast::{Element, Traversable};
+use sevenmark_ast::Element;
+use sevenmark_ast::visit::{VisitPath, Visitor, walk_element};
 
-/// Recursively visits every element in the AST via depth-first traversal.
+/// Recursively visits every element in the AST via depth-first traversal,
+/// including parameter values and `{{{#if}}}` conditions.
 pub fn visit_elements(elements: &[Element], visitor: &mut dyn FnMut(&Element)) {
-    for element in elements {
-        visit_element(element, visitor);
+    Callback(visitor).visit_document(elements);
+}
+
+struct Callback<'a>(&'a mut dyn FnMut(&Element));
+
+impl Visitor for Callback<'_> {
+    fn visit_element(&mut self, element: &Element, path: &mut VisitPath) {
+        (self.0)(element);
+        walk_element(self, element, path);
     }
 }
 
-fn visit_element(element: &Element, visitor: &mut dyn FnMut(&Element)) {
-    visitor(element);
-    element.traverse_children_ref(&mut |child| {
-        visit_element(child, visitor);
-    });
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sevenmark_parser::core::parse_document;
+
+    fn variables(input: &str) -> Vec<String> {
+        let mut names = Vec::new();
+        visit_elements(&parse_document(input), &mut |element| {
+            if let Element::Variable(variable) = element {
+                names.push(variable.name.clone());
+            }
+        });
+        names
+    }
+
+    #[test]
+    fn visits_parameter_values() {
+        assert_eq!(
+            variables("{{{#style #style=\"color: [var(color)]\" text}}}"),
+            ["color"]
+        );
+    }
+
+    #[test]
+    fn visits_if_conditions() {
+        assert_eq!(
+            variables("{{{#if [var(mode)] == \"dark\" :: [var(body)]}}}"),
+            ["mode", "body"]
+        );
+    }
 }
@@ -0,0 +1,113 @@
+use sevenmark_ast::visit::{
+    VisitPath, Visitor, walk_element, walk_list_content_item, walk_list_item, walk_table_cell,
+    walk_table_cell_item, walk_table_row, walk_table_row_item,
+};
+use sevenmark_ast::{
+    Element, ListContentItem, ListItemElement, Span, TableCellElement, TableCellItem,
+    TableRowElement, TableRowItem,
+};
+
+/// A delimited construct: a `{{{...}}}`/`[[...]]` element, a table row or
+/// cell, a list item, a fold part or a conditional
+#[derive(Debug, Clone)]
+pub(crate) struct Block {
+    /// Opening delimiter with the keyword and parameters, e.g. `{{{#table #style="..."`
+    pub open: Span,
+    pub close: Span,
+}
+
+impl Block {
+    /// From the opening delimiter to the end of the closing one; row and
+    /// conditional spans also cover the surrounding whitespace
+    pub fn span(&self) -> Span {
+        Span::new(self.open.start, self.close.end)
+    }
+}
+
+/// Every block in the document, outer blocks before the blocks they contain
+pub(crate) fn collect_blocks(elements: &[Element]) -> Vec<Block> {
+    let mut collector = Collector::default();
+    collector.visit_document(elements);
+    collector.blocks
+}
+
+#[derive(Default)]
+struct Collector {
+    blocks: Vec<Block>,
+}
+
+impl Collector {
+    fn push(&mut self, open: &Span, close: &Span) {
+        self.blocks.push(Block {
+            open: open.clone(),
+            close: close.clone(),
+        });
+    }
+}
+
+impl Visitor for Collector {
+    fn visit_element(&mut self, element: &Element, path: &mut VisitPath) {
+        match element {
+            Element::Literal(e) => self.push(&e.open_span, &e.close_span),
+            Element::Define(e) => self.push(&e.open_span, &e.close_span),
+            Element::Styled(e) => self.push(&e.open_span, &e.close_span),
+            Element::Table(e) => self.push(&e.open_span, &e.close_span),
+            Element::List(e) => self.push(&e.open_span, &e.close_span),
+            Element::Fold(e) => {
+                self.push(&e.open_span, &e.close_span);
+                self.push(&e.summary.open_span, &e.summary.close_span);
+                self.push(&e.details.open_span, &e.details.close_span);
+            }
+            Element::BlockQuote(e) => self.push(&e.open_span, &e.close_span),
+            Element::Ruby(e) => self.push(&e.open_span, &e.close_span),
+            Element::Footnote(e) => self.push(&e.open_span, &e.close_span),
+            Element::Code(e) => self.push(&e.open_span, &e.close_span),
+            Element::TeX(e) => self.push(&e.open_span, &e.close_span),
+            Element::Css(e) => self.push(&e.open_span, &e.close_span),
+            Element::Include(e) => self.push(&e.open_span, &e.close_span),
+            Element::Category(e) => self.push(&e.open_span, &e.close_span),
+            Element::Redirect(e) => self.push(&e.open_span, &e.close_span),
+            Element::Media(e) => self.push(&e.open_span, &e.close_span),
+            Element::ExternalMedia(e) => self.push(&e.open_span, &e.close_span),
+            Element::If(e) => self.push(&e.open_span, &e.close_span),
+            _ => {}
+        }
+        walk_element(self, element, path);
+    }
+
+    fn visit_table_row_item(&mut self, item: &TableRowItem, path: &mut VisitPath) {
+        if let TableRowItem::Conditional(cond) = item {
+            self.push(&cond.open_span, &cond.close_span);
+        }
+        walk_table_row_item(self, item, path);
+    }
+
+    fn visit_table_row(&mut self, row: &TableRowElement, path: &mut VisitPath) {
+        self.push(&row.open_span, &row.close_span);
+        walk_table_row(self, row, path);
+    }
+
+    fn visit_table_cell_item(&mut self, item: &TableCellItem, path: &mut VisitPath) {
+        if let TableCellItem::Conditional(cond) = item {
+            self.push(&cond.open_span, &cond.close_span);
+        }
+        walk_table_cell_item(self, item, path);
+    }
+
+    fn visit_table_cell(&mut self, cell: &TableCellElement, path: &mut VisitPath) {
+        self.push(&cell.open_span, &cell.close_span);
+        walk_table_cell(self, cell, path);
+    }
+
+    fn visit_list_content_item(&mut self, item: &ListContentItem, path: &mut VisitPath) {
+        if let ListContentItem::Conditional(cond) = item {
+            self.push(&cond.open_span, &cond.close_span);
+        }
+        walk_list_content_item(self, item, path);
+    }
+
+    fn visit_list_item(&mut self, item: &ListItemElement, path: &mut VisitPath) {
+        self.push(&item.open_span, &item.close_span);
+        walk_list_item(self, item, path);
+    }
+}
@@ -0,0 +1,77 @@
+use ls_types::{CodeLens, Command, Position, Range};
+
+use crate::document::DocumentState;
+use crate::evaluation::evaluate;
+
+/// A lens above every `{{{#if}}}` (and conditional table rows/cells and
+/// list items) showing whether its condition holds with this page's own
+/// `{{{#define}}}`s, and the values it reads.
+pub fn collect_code_lenses(state: &DocumentState) -> Vec<CodeLens> {
+    evaluate(&state.elements)
+        .conditions
+        .into_iter()
+        .map(|condition| {
+            let (start, end) = state
+                .line_index
+                .span_to_range(&state.text, &condition.open_span);
+            let mut title = format!("condition is {}", condition.result);
+            if !condition.reads.is_empty() {
+                let reads: Vec<_> = condition
+                    .reads
+                    .iter()
+                    .map(|(name, value)| match value {
+                        Some(value) => format!("{name} = \"{value}\""),
+                        None => format!("{name} undefined"),
+                    })
+                    .collect();
+                title = format!("{title} ({})", reads.join(", "));
+            }
+            CodeLens {
+                range: Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1)),
+                // Informational only: no client command behind it
+                command: Some(Command {
+                    title,
+                    command: String::new(),
+                    arguments: None,
+                }),
+                data: None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn titles(text: &str) -> Vec<String> {
+        collect_code_lenses(&DocumentState::new(text.to_string()))
+            .into_iter()
+            .map(|lens| lens.command.unwrap().title)
+            .collect()
+    }
+
+    #[test]
+    fn lens_shows_result_and_read_values() {
+        let text = concat!(
+            "{{{#define #lang=\"ko\"}}}\n",
+            "{{{#if [var(lang)] == \"ko\" :: 한국어}}}\n",
+            "{{{#if [var(missing)] :: hidden}}}\n",
+            "{{{#if true :: always}}}",
+        );
+        assert_eq!(
+            titles(text),
+            [
+                "condition is true (lang = \"ko\")",
+                "condition is false (missing undefined)",
+                "condition is true",
+            ]
+        );
+    }
+
+    #[test]
+    fn conditional_table_rows_get_lenses() {
+        let text = "{{{#table\n{{{#if false :: [[ [[a]] ]]}}}\n}}}";
+        assert_eq!(titles(text), ["condition is false"]);
+    }
+}
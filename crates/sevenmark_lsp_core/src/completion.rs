@@ -1,11 +1,13 @@
 mod context;
 mod items;
 mod params;
+mod titles;
 mod variables;
 
 use ls_types::{CompletionItem, Position};
 
 use crate::document::DocumentState;
+use crate::workspace::WorkspaceIndex;
 
 use context::context_and_bracket_depth;
 use items::{
@@ -13,6 +15,7 @@ use items::{
     macro_completions, markdown_line_start_completions,
 };
 use params::parameter_completions;
+use titles::title_completions;
 use variables::variable_completions;
 
 /// Returns completion items based on the cursor context.
@@ -20,6 +23,7 @@ pub fn get_completions(
     state: &DocumentState,
     position: Position,
     byte_offset: usize,
+    workspace: &WorkspaceIndex,
 ) -> Vec<CompletionItem> {
     let prefix = &state.text[..byte_offset];
 
@@ -27,6 +31,10 @@ pub fn get_completions(
         return variable_completions(state);
     }
 
+    if let Some(items) = title_completions(state, byte_offset, position, workspace) {
+        return items;
+    }
+
     let ctx = context_and_bracket_depth(prefix);
 
     if prefix.ends_with("{{{#") {
@@ -41,10 +49,10 @@ pub fn get_completions(
         return bracket_completions_ctx(ctx, position);
     }
 
-    if prefix.ends_with('#') {
-        if let Some(items) = parameter_completions(prefix, ctx) {
-            return items;
-        }
+    if prefix.ends_with('#')
+        && let Some(items) = parameter_completions(prefix, ctx)
+    {
+        return items;
     }
 
     if prefix.ends_with('[') {
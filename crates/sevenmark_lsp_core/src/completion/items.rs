@@ -1,4 +1,5 @@
 use ls_types::{CompletionItem, CompletionItemKind, InsertTextFormat, Position};
+use sevenmark_registry::{Syntax, element, elements};
 
 use super::context::CompletionContext;
 use super::params::{
@@ -61,30 +62,21 @@ pub(super) fn bracket_hash_completions_ctx(
 }
 
 pub(super) fn macro_completions(_pos: Position) -> Vec<CompletionItem> {
-    let macros = [
-        ("var", "var($1)]", "Variable reference"),
-        ("br", "br]", "Line break"),
-        ("clear", "clear]", "Float clear"),
-        ("null", "null]", "Null (no output)"),
-        ("fn", "fn]", "Render collected footnotes"),
-        ("now", "now]", "Current time"),
-        ("age", "age($1)]", "Age calculation"),
-        ("anchor", "anchor($1)]", "Named anchor"),
-        ("date", "date]", "Current date"),
-        ("datetime", "datetime]", "Current date and time"),
-        ("dday", "dday($1)]", "D-day counter"),
-        ("pagecount", "pagecount]", "Total page count"),
-        ("toc", "toc]", "Table of contents"),
-    ];
-    macros
-        .into_iter()
-        .map(|(label, snippet, detail)| CompletionItem {
-            label: label.to_string(),
-            kind: Some(CompletionItemKind::FUNCTION),
-            detail: Some(detail.to_string()),
-            insert_text_format: Some(InsertTextFormat::SNIPPET),
-            insert_text: Some(snippet.to_string()),
-            ..Default::default()
+    elements(Syntax::Macro)
+        .map(|spec| {
+            let snippet = if spec.argument.is_some() && !spec.optional_argument {
+                format!("{}($1)]", spec.name)
+            } else {
+                format!("{}]", spec.name)
+            };
+            CompletionItem {
+                label: spec.name.to_string(),
+                kind: Some(CompletionItemKind::FUNCTION),
+                detail: Some(spec.summary.to_string()),
+                insert_text_format: Some(InsertTextFormat::SNIPPET),
+                insert_text: Some(snippet),
+                ..Default::default()
+            }
         })
         .collect()
 }
@@ -130,27 +122,27 @@ pub(super) fn markdown_line_start_completions() -> Vec<CompletionItem> {
 
 fn brace_keyword_completions() -> Vec<CompletionItem> {
     let keywords = [
-        ("code", "code #lang=\"$1\"\n$0\n}}}", "Code block"),
-        ("tex", "tex\n$0\n}}}", "TeX block"),
-        ("css", "css\n$0\n}}}", "CSS block"),
-        ("table", "table\n$0\n}}}", "Table"),
-        ("list", "list\n$0\n}}}", "List"),
-        ("fold", "fold\n$0\n}}}", "Fold (collapsible)"),
-        ("quote", "quote\n$0\n}}}", "Block quote"),
-        ("define", "define #$1=\"$2\"}}}", "Variable definition"),
-        ("if", "if $1 ::\n$0\n}}}", "Conditional block"),
-        ("include", "include $0}}}", "Document inclusion"),
-        ("category", "category $0}}}", "Category"),
-        ("redirect", "redirect $0}}}", "Redirect"),
-        ("ruby", "ruby #ruby=\"$1\" $0}}}", "Ruby annotation"),
-        ("fn", "fn $0}}}", "Footnote"),
+        ("code", "code #lang=\"$1\"\n$0\n}}}"),
+        ("tex", "tex\n$0\n}}}"),
+        ("css", "css\n$0\n}}}"),
+        ("table", "table\n$0\n}}}"),
+        ("list", "list\n$0\n}}}"),
+        ("fold", "fold\n$0\n}}}"),
+        ("quote", "quote\n$0\n}}}"),
+        ("define", "define #$1=\"$2\"}}}"),
+        ("if", "if $1 ::\n$0\n}}}"),
+        ("include", "include $0}}}"),
+        ("category", "category $0}}}"),
+        ("redirect", "redirect $0}}}"),
+        ("ruby", "ruby #ruby=\"$1\" $0}}}"),
+        ("fn", "fn $0}}}"),
     ];
     keywords
         .into_iter()
-        .map(|(label, snippet, detail)| CompletionItem {
+        .map(|(label, snippet)| CompletionItem {
             label: label.to_string(),
             kind: Some(CompletionItemKind::KEYWORD),
-            detail: Some(detail.to_string()),
+            detail: element(Syntax::Brace, label).map(|spec| spec.summary.to_string()),
             insert_text_format: Some(InsertTextFormat::SNIPPET),
             insert_text: Some(snippet.to_string()),
             ..Default::default()
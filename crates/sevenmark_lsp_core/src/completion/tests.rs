@@ -3,6 +3,7 @@ use ls_types::{CompletionItem, Position};
 use super::context::context_and_bracket_depth;
 use super::get_completions;
 use crate::document::DocumentState;
+use crate::workspace::{Namespace, WorkspaceIndex, test_index};
 
 fn pos() -> Position {
     Position::new(0, 0)
@@ -15,7 +16,7 @@ fn make_state(text: &str) -> DocumentState {
 fn completions(text: &str) -> Vec<CompletionItem> {
     let state = make_state(text);
     let byte_offset = text.len();
-    get_completions(&state, pos(), byte_offset)
+    get_completions(&state, pos(), byte_offset, &WorkspaceIndex::new())
 }
 
 fn labels(items: &[CompletionItem]) -> Vec<&str> {
@@ -429,7 +430,8 @@ fn footnote_params_use_fn_keyword() {
     let l = labels(&c);
     assert!(l.contains(&"display"));
     assert!(l.contains(&"name"));
-    assert!(l.contains(&"style"));
+    // The footnote renderer ignores styling parameters
+    assert!(!l.contains(&"style"));
 }
 
 #[test]
@@ -525,3 +527,60 @@ fn line_start_markdown_completions_include_list_and_blockquote() {
 fn no_completions_for_plain_text() {
     assert!(completions("hello world").is_empty());
 }
+
+fn workspace_completions(text: &str) -> Vec<CompletionItem> {
+    let workspace = test_index(&[
+        (Namespace::Document, "Rust", "# Intro\n[anchor(traits)]"),
+        (
+            Namespace::Document,
+            "Rust/Traits",
+            "{{{#category Languages}}}",
+        ),
+        (Namespace::Category, "Programming", ""),
+        (Namespace::File, "logo.png", ""),
+    ]);
+    let state = make_state(text);
+    get_completions(&state, pos(), text.len(), &workspace)
+}
+
+#[test]
+fn media_values_complete_workspace_titles() {
+    let c = workspace_completions("[[#document=\"Ru");
+    assert_eq!(labels(&c), ["Rust", "Rust/Traits"]);
+    let Some(ls_types::CompletionTextEdit::Edit(edit)) = &c[0].text_edit else {
+        panic!("expected a text edit");
+    };
+    assert_eq!(edit.range.start, Position::new(0, 13));
+
+    assert_eq!(labels(&workspace_completions("[[#file=\"")), ["logo.png"]);
+    assert_eq!(
+        labels(&workspace_completions("[[#category=\"")),
+        ["Languages", "Programming"]
+    );
+    assert!(labels(&workspace_completions("[[#url=\"")).is_empty());
+}
+
+#[test]
+fn anchor_values_complete_linked_page_anchors() {
+    let c = workspace_completions("[[#document=\"Rust\" #anchor=\"");
+    assert_eq!(labels(&c), ["s-1", "traits"]);
+    assert_eq!(c[0].detail.as_deref(), Some("Section: Intro"));
+}
+
+#[test]
+fn include_and_category_titles_complete_after_parameters() {
+    assert_eq!(
+        labels(&workspace_completions("{{{#include #x=\"1\" Rust/")),
+        ["Rust", "Rust/Traits"]
+    );
+    assert_eq!(
+        labels(&workspace_completions("{{{#include #namespace=\"File\" ")),
+        ["logo.png"]
+    );
+    assert_eq!(
+        labels(&workspace_completions("{{{#category ")),
+        ["Languages", "Programming"]
+    );
+    // Parameter names still complete
+    assert!(labels(&workspace_completions("{{{#include #")).contains(&"namespace"));
+}
@@ -0,0 +1,136 @@
+use ls_types::{CompletionItem, CompletionItemKind, CompletionTextEdit, Position, Range, TextEdit};
+
+use super::context::detect_brace_element;
+use crate::document::DocumentState;
+use crate::workspace::{DocumentKey, Namespace, WorkspaceIndex};
+
+/// Workspace page titles for media `#document="`/`#file="`/`#category="`/`#user="`
+/// values and `{{{#include`/`{{{#redirect`/`{{{#category` titles, and the
+/// anchors of the linked page for `#anchor="`.
+pub(super) fn title_completions(
+    state: &DocumentState,
+    byte_offset: usize,
+    position: Position,
+    workspace: &WorkspaceIndex,
+) -> Option<Vec<CompletionItem>> {
+    if workspace.is_empty() {
+        return None;
+    }
+    let prefix = &state.text[..byte_offset];
+    let (partial_start, target) =
+        param_value_target(prefix).or_else(|| brace_title_target(prefix))?;
+
+    let start = state
+        .line_index
+        .byte_offset_to_position(&state.text, partial_start);
+    let range = Range::new(Position::new(start.0, start.1), position);
+    let item = |label: &str, kind, detail: String| CompletionItem {
+        label: label.to_string(),
+        kind: Some(kind),
+        detail: Some(detail),
+        text_edit: Some(CompletionTextEdit::Edit(TextEdit::new(
+            range,
+            label.to_string(),
+        ))),
+        ..Default::default()
+    };
+
+    let items: Vec<_> = match target {
+        Target::Titles(Namespace::Category) => workspace
+            .categories()
+            .into_iter()
+            .map(|name| item(name, CompletionItemKind::FILE, "Category".to_string()))
+            .collect(),
+        Target::Titles(namespace) => workspace
+            .titles(namespace)
+            .map(|title| item(title, CompletionItemKind::FILE, namespace.to_string()))
+            .collect(),
+        Target::Anchors(key) => workspace
+            .get(&key)?
+            .anchors
+            .iter()
+            .map(|anchor| {
+                let detail = match &anchor.heading {
+                    Some(heading) => format!("Section: {heading}"),
+                    None => "Anchor".to_string(),
+                };
+                item(&anchor.name, CompletionItemKind::REFERENCE, detail)
+            })
+            .collect(),
+    };
+    (!items.is_empty()).then_some(items)
+}
+
+enum Target {
+    Titles(Namespace),
+    Anchors(DocumentKey),
+}
+
+/// Cursor inside `#key="...` of an unclosed `[[...`: `(value start, target)`
+fn param_value_target(prefix: &str) -> Option<(usize, Target)> {
+    let quote = prefix.rfind('"')?;
+    let partial = &prefix[quote + 1..];
+    if partial.contains(['\n', ']']) {
+        return None;
+    }
+    let bracket = prefix.rfind("[[")?;
+    let element = &prefix[bracket..quote];
+    if element.contains("]]") {
+        return None;
+    }
+
+    let key = element.strip_suffix('=')?;
+    let key = &key[key.rfind('#')? + 1..];
+    let target = match key {
+        "document" => Target::Titles(Namespace::Document),
+        "file" => Target::Titles(Namespace::File),
+        "category" => Target::Titles(Namespace::Category),
+        "user" => Target::Titles(Namespace::User),
+        "anchor" => {
+            let document = param_value(&prefix[bracket..], "document")?;
+            Target::Anchors(DocumentKey::new(Namespace::Document, document.trim()))
+        }
+        _ => return None,
+    };
+    Some((quote + 1, target))
+}
+
+/// Cursor in the title of `{{{#include`, `{{{#redirect` or `{{{#category`,
+/// after any parameters: `(title start, target)`
+fn brace_title_target(prefix: &str) -> Option<(usize, Target)> {
+    let keyword = detect_brace_element(prefix)?;
+    if !matches!(keyword, "include" | "redirect" | "category") {
+        return None;
+    }
+    let brace = prefix.rfind("{{{#")?;
+    let body_start = brace + 4 + keyword.len();
+    let body = &prefix[body_start..];
+    if !body.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    // Skip `#key="value"` parameters
+    let mut rest = body.trim_start();
+    while let Some(param) = rest.strip_prefix('#') {
+        let value_end = param
+            .find('"')
+            .and_then(|open| param[open + 1..].find('"').map(|close| open + close + 2))?;
+        rest = param[value_end..].trim_start();
+    }
+    if rest.contains(['#', '"', '[', '{', '}', '\n']) {
+        return None;
+    }
+
+    let namespace = match keyword {
+        "category" => Namespace::Category,
+        _ => Namespace::from_param(param_value(body, "namespace")),
+    };
+    Some((prefix.len() - rest.len(), Target::Titles(namespace)))
+}
+
+/// Value of a closed `#key="value"` in `text`
+fn param_value<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+    let start = text.find(&format!("#{key}=\""))? + key.len() + 3;
+    let len = text[start..].find('"')?;
+    Some(&text[start..start + len])
+}
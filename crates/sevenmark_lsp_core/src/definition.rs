@@ -3,14 +3,37 @@ use sevenmark_ast::Element;
 
 use crate::ast_walk::visit_elements;
 use crate::document::DocumentState;
+use crate::workspace::{WorkspaceIndex, link_at};
 
 /// Finds the definition site for the element at the given byte offset.
 ///
 /// Currently supports:
 /// - `[var(name)]` → jumps to the first `{{{#define #name="name" ...}}}` in the document
-pub fn find_definition(state: &DocumentState, uri: &Uri, byte_offset: usize) -> Option<Location> {
-    let var_name = find_variable_at(state, byte_offset)?;
-    find_define_location(state, uri, &var_name)
+/// - `{{{#include}}}`, `{{{#redirect}}}`, `{{{#category}}}` and media
+///   `#document`/`#file`/`#category`/`#user` → the target page in the
+///   workspace, at its `#anchor` when one is given
+pub fn find_definition(
+    state: &DocumentState,
+    uri: &Uri,
+    byte_offset: usize,
+    workspace: &WorkspaceIndex,
+) -> Option<Location> {
+    if let Some(var_name) = find_variable_at(state, byte_offset) {
+        return find_define_location(state, uri, &var_name);
+    }
+
+    let link = link_at(&state.elements, byte_offset)?;
+    let page = workspace.get(&link.target)?;
+    let range = link
+        .anchor
+        .as_deref()
+        .and_then(|anchor| page.anchor(anchor))
+        .map(|anchor| anchor.range)
+        .unwrap_or_default();
+    Some(Location {
+        uri: page.uri.clone(),
+        range,
+    })
 }
 
 /// Returns the variable name if a `VariableElement` spans the given byte offset.
@@ -20,10 +43,11 @@ fn find_variable_at(state: &DocumentState, byte_offset: usize) -> Option<String>
         if result.is_some() {
             return;
         }
-        if let Element::Variable(v) = element {
-            if v.span.start <= byte_offset && byte_offset < v.span.end {
-                result = Some(v.name.clone());
-            }
+        if let Element::Variable(v) = element
+            && v.span.start <= byte_offset
+            && byte_offset < v.span.end
+        {
+            result = Some(v.name.clone());
         }
     });
     result
@@ -36,14 +60,14 @@ fn find_define_location(state: &DocumentState, uri: &Uri, target: &str) -> Optio
         if result.is_some() {
             return;
         }
-        if let Element::Define(d) = element {
-            if d.parameters.contains_key(target) {
-                let (start, end) = state.line_index.span_to_range(&state.text, &d.span);
-                result = Some(Location {
-                    uri: uri.clone(),
-                    range: Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1)),
-                });
-            }
+        if let Element::Define(d) = element
+            && d.parameters.contains_key(target)
+        {
+            let (start, end) = state.line_index.span_to_range(&state.text, &d.span);
+            result = Some(Location {
+                uri: uri.clone(),
+                range: Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1)),
+            });
         }
     });
     result
@@ -69,7 +93,7 @@ mod tests {
         // Byte offset inside [var(x)]
         let var_start = text.find("[var(x)]").unwrap();
         let byte_offset = var_start + 5; // points to 'x'
-        let loc = find_definition(&state, &uri, byte_offset);
+        let loc = find_definition(&state, &uri, byte_offset, &WorkspaceIndex::new());
         assert!(loc.is_some(), "expected definition location");
         assert_eq!(loc.unwrap().uri, uri);
     }
@@ -80,7 +104,7 @@ mod tests {
         let state = make_state(text);
         let uri = test_uri();
         let byte_offset = 5; // inside [var(x)]
-        let loc = find_definition(&state, &uri, byte_offset);
+        let loc = find_definition(&state, &uri, byte_offset, &WorkspaceIndex::new());
         assert!(loc.is_none());
     }
 
@@ -89,7 +113,37 @@ mod tests {
         let text = "hello world";
         let state = make_state(text);
         let uri = test_uri();
-        let loc = find_definition(&state, &uri, 3);
+        let loc = find_definition(&state, &uri, 3, &WorkspaceIndex::new());
         assert!(loc.is_none());
     }
+
+    #[test]
+    fn links_jump_to_workspace_pages_and_anchors() {
+        use crate::workspace::{Namespace, test_index};
+
+        let workspace = test_index(&[
+            (
+                Namespace::Document,
+                "Rust",
+                "# Intro\ntext\n[anchor(traits)]",
+            ),
+            (Namespace::Document, "Infobox", "[var(name)]"),
+        ]);
+        let text =
+            "{{{#include Infobox}}} [[#document=\"Rust\" #anchor=\"traits\"]] [[#document=\"Go\"]]";
+        let state = make_state(text);
+        let at = |needle: &str| {
+            find_definition(&state, &test_uri(), text.find(needle).unwrap(), &workspace)
+        };
+
+        let include = at("Infobox").unwrap();
+        assert_eq!(include.uri.as_str(), "file:///wiki/Document/Infobox.sm");
+        assert_eq!(include.range, Range::default());
+
+        let anchored = at("document=\"Rust").unwrap();
+        assert_eq!(anchored.uri.as_str(), "file:///wiki/Document/Rust.sm");
+        assert_eq!(anchored.range.start, Position::new(2, 0));
+
+        assert!(at("Go").is_none());
+    }
 }
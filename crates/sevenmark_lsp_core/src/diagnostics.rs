@@ -1,16 +1,20 @@
 use std::collections::HashSet;
 
-use ls_types::{Diagnostic, DiagnosticSeverity, Position, Range};
-use sevenmark_ast::Element;
+use ls_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
+use sevenmark_ast::{Element, Span};
+use sevenmark_registry::{ElementSpec, ParamSpec, Syntax, ValueError};
 
 use crate::ast_walk::visit_elements;
 use crate::document::DocumentState;
+use crate::element_params::parameter_sets;
 
 /// Collects LSP diagnostics from parsed AST.
 ///
-/// Two kinds of diagnostics:
+/// Diagnostics produced:
 /// - **Error**: `ErrorElement` nodes (parser failures)
 /// - **Warning**: `VariableElement` referencing an undefined variable
+/// - **Warning**: parameters (and macro arguments) the registry does not know,
+///   or whose plain-text value does not match the parameter's value type
 pub fn collect_diagnostics(state: &DocumentState) -> Vec<Diagnostic> {
     let mut diagnostics = Vec::new();
     let mut defined_vars = HashSet::new();
@@ -51,9 +55,100 @@ pub fn collect_diagnostics(state: &DocumentState) -> Vec<Diagnostic> {
         }
     });
 
+    // Pass 3: check parameters and macro arguments against the registry
+    let mut warn = |span: &Span, code: &str, message: String| {
+        let (start, end) = state.line_index.span_to_range(&state.text, span);
+        diagnostics.push(Diagnostic {
+            range: Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1)),
+            severity: Some(DiagnosticSeverity::WARNING),
+            code: Some(NumberOrString::String(code.to_string())),
+            source: Some("sevenmark".to_string()),
+            message,
+            ..Default::default()
+        });
+    };
+    visit_elements(&state.elements, &mut |element| {
+        for set in parameter_sets(element) {
+            for parameter in set.parameters.values() {
+                match set.spec.param(&parameter.key) {
+                    Some(param) => {
+                        if let Some(value) = plain_value(&parameter.value)
+                            && let Some(message) = check_value(param, &value)
+                        {
+                            warn(&parameter.span, "invalid-value", message);
+                        }
+                    }
+                    None if set.spec.open_params => {}
+                    None => warn(
+                        &parameter.span,
+                        "unknown-parameter",
+                        unknown_parameter_message(set.spec, &parameter.key),
+                    ),
+                }
+            }
+        }
+
+        let argument = match element {
+            Element::Age(e) => Some(("age", &e.span, e.date.as_str())),
+            Element::Dday(e) => Some(("dday", &e.span, e.date.as_str())),
+            Element::PageCount(e) => e
+                .namespace
+                .as_deref()
+                .map(|namespace| ("pagecount", &e.span, namespace)),
+            _ => None,
+        };
+        if let Some((name, span, value)) = argument
+            && let Some(param) =
+                sevenmark_registry::element(Syntax::Macro, name).and_then(|spec| spec.argument)
+            && let Some(message) = check_value(&param, value)
+        {
+            warn(span, "invalid-value", message);
+        }
+    });
+
     diagnostics
 }
 
+fn unknown_parameter_message(spec: &ElementSpec, key: &str) -> String {
+    let target = match spec.syntax {
+        Syntax::Part => spec.summary.to_lowercase(),
+        _ => format!("#{}", spec.name),
+    };
+    match spec.suggest(key) {
+        Some(suggestion) => {
+            format!("Unknown parameter `#{key}` on {target}; did you mean `#{suggestion}`?")
+        }
+        None => format!("Unknown parameter `#{key}` on {target}"),
+    }
+}
+
+fn check_value(param: &ParamSpec, value: &str) -> Option<String> {
+    match param.value.validate(value) {
+        Ok(()) => None,
+        Err(ValueError::FlagWithValue) => {
+            Some(format!("`#{}` is a flag; its value is ignored", param.name))
+        }
+        Err(ValueError::Expected(expected)) => Some(format!(
+            "Invalid value `{}` for `#{}`: expected {expected}",
+            truncate(value, 40),
+            param.name
+        )),
+    }
+}
+
+/// Parameter value as written, when it contains no markup or variables
+fn plain_value(value: &[Element]) -> Option<String> {
+    let mut text = String::new();
+    for element in value {
+        match element {
+            Element::Text(t) => text.push_str(&t.value),
+            Element::Escape(e) => text.push_str(&e.value),
+            _ => return None,
+        }
+    }
+    Some(text)
+}
+
 /// Truncates a string for diagnostic messages.
 fn truncate(s: &str, max_len: usize) -> String {
     let clean = s.replace('\n', "\\n");
@@ -119,4 +214,63 @@ mod tests {
             .collect();
         assert!(!errors.is_empty(), "expected at least one ERROR diagnostic");
     }
+
+    fn warning_messages(text: &str) -> Vec<String> {
+        collect_diagnostics(&make_state(text))
+            .into_iter()
+            .filter(|d| d.severity == Some(DiagnosticSeverity::WARNING))
+            .map(|d| d.message)
+            .collect()
+    }
+
+    #[test]
+    fn unknown_parameter_suggests_close_name() {
+        let text = "{{{#table #wraper-align=\"center\"\n[[ [[a]] ]]\n}}}";
+        let diags = collect_diagnostics(&make_state(text));
+        let warning = diags
+            .iter()
+            .find(|d| d.code == Some(NumberOrString::String("unknown-parameter".to_string())))
+            .expect("expected an unknown-parameter warning");
+        assert!(warning.message.contains("`#wraper-align`"));
+        assert!(warning.message.contains("did you mean `#wrapper-align`?"));
+        // Range covers the parameter, not the whole table
+        assert_eq!(warning.range.start, Position::new(0, 10));
+    }
+
+    #[test]
+    fn invalid_values_are_reported() {
+        let messages = warning_messages(
+            "{{{#table #wrapper-align=\"middle\" #sortable=\"yes\"\n[[ [[a]] ]]\n}}}",
+        );
+        assert_eq!(messages.len(), 2, "{messages:?}");
+        assert!(messages[0].contains("`middle`") && messages[0].contains("left, center, right"));
+        assert!(messages[1].contains("`#sortable` is a flag"));
+    }
+
+    #[test]
+    fn nested_parts_and_macro_arguments_are_checked() {
+        let messages = warning_messages(
+            "{{{#table\n[[ [[#x=\"two\" a]] ]]\n}}}\n{{{#list\n[[#colr=\"red\" a]]\n}}}\n[pagecount(page)]",
+        );
+        assert_eq!(messages.len(), 3, "{messages:?}");
+        assert!(messages[0].contains("`#x`") && messages[0].contains("integer"));
+        assert!(messages[1].contains("on list item") && messages[1].contains("`#color`"));
+        assert!(messages[2].contains("`page`"));
+    }
+
+    #[test]
+    fn valid_and_open_parameters_are_not_reported() {
+        let messages = warning_messages(concat!(
+            "{{{#define #title=\"t\"}}}",
+            "{{{#include #namespace=\"Document\" #title=\"x\" Page}}}",
+            "{{{#table #wrapper-align=\"center\" #sortable #width=\"50%\"\n",
+            "[[#head [[#x=\"2\" #bgcolor=\"#f0f0f0\" a]] ]]\n}}}",
+            "{{{#color=\"[var(title)]\" styled}}}",
+            "[[#youtube #id=\"abc\" #autoplay]] [age(2000-01-01)]",
+        ));
+        assert!(
+            messages.is_empty(),
+            "expected no warnings but got: {messages:?}"
+        );
+    }
 }
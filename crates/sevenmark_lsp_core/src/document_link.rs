@@ -0,0 +1,141 @@
+use ls_types::{DocumentLink, Position, Range, Uri};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+use sevenmark_ast::Element;
+use sevenmark_utils::extract_plain_text;
+
+use crate::ast_walk::visit_elements;
+use crate::document::DocumentState;
+use crate::preview::PreviewConfig;
+use crate::workspace::{Link, Namespace, WorkspaceIndex, media_links};
+
+/// Same set as the HTML renderer's internal links: RFC 3986 unreserved
+/// characters are kept
+const ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Clickable links of media elements:
+/// - `#url="https://..."`
+/// - `#document`/`#file`/`#category`/`#user`, as the wiki URL built from the
+///   namespace's base URL in `config` like the preview does, or else the
+///   page's file in the workspace
+pub fn collect_document_links(
+    state: &DocumentState,
+    config: &PreviewConfig,
+    workspace: &WorkspaceIndex,
+) -> Vec<DocumentLink> {
+    let mut links = Vec::new();
+    visit_elements(&state.elements, &mut |element| {
+        let Element::Media(media) = element else {
+            return;
+        };
+
+        let external = media.parameters.get("url").and_then(|url| {
+            let target = external_url(&extract_plain_text(&url.value))?;
+            Some((url.span.clone(), target, None))
+        });
+        let internal = media_links(&media.parameters)
+            .into_iter()
+            .filter_map(|link| {
+                let target = internal_url(&link, config, workspace)?;
+                Some((link.span, target, Some(link.target.to_string())))
+            });
+
+        for (span, target, tooltip) in external.into_iter().chain(internal) {
+            let (start, end) = state.line_index.span_to_range(&state.text, &span);
+            links.push(DocumentLink {
+                range: Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1)),
+                target: Some(target),
+                tooltip,
+                data: None,
+            });
+        }
+    });
+    links
+}
+
+/// Only `http(s)` URLs, as the renderer allows
+fn external_url(raw: &str) -> Option<Uri> {
+    let url = raw.trim();
+    let lower = url.to_ascii_lowercase();
+    if !(lower.starts_with("http://") || lower.starts_with("https://")) {
+        return None;
+    }
+    url.parse().ok()
+}
+
+fn internal_url(link: &Link, config: &PreviewConfig, workspace: &WorkspaceIndex) -> Option<Uri> {
+    let base = match link.target.namespace {
+        Namespace::Document => &config.document_base_url,
+        Namespace::File => &config.file_base_url,
+        Namespace::Category => &config.category_base_url,
+        Namespace::User => &config.user_base_url,
+    };
+    // A relative base URL is no use to an editor
+    let from_base = base.as_deref().and_then(|base| {
+        let mut url = format!(
+            "{base}{}",
+            utf8_percent_encode(&link.target.title, ENCODE_SET)
+        );
+        if let Some(anchor) = &link.anchor {
+            url = format!("{url}#{}", utf8_percent_encode(anchor, ENCODE_SET));
+        }
+        url.parse().ok()
+    });
+    from_base.or_else(|| workspace.get(&link.target).map(|page| page.uri.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workspace::{DocumentKey, test_index};
+
+    fn targets(text: &str, config: &PreviewConfig, workspace: &WorkspaceIndex) -> Vec<String> {
+        let state = DocumentState::new(text.to_string());
+        collect_document_links(&state, config, workspace)
+            .into_iter()
+            .map(|link| link.target.unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn urls_and_base_url_links() {
+        let config = PreviewConfig {
+            document_base_url: Some("https://wiki.example/Document/".to_string()),
+            ..Default::default()
+        };
+        let text = concat!(
+            "[[#url=\"https://example.com/a?b=1\" x]] [[#url=\"javascript:alert(1)\" y]]\n",
+            "[[#document=\"Rust Lang\" #anchor=\"1.2\" z]] [[#user=\"kim\" w]]",
+        );
+        assert_eq!(
+            targets(text, &config, &WorkspaceIndex::new()),
+            [
+                "https://example.com/a?b=1",
+                "https://wiki.example/Document/Rust%20Lang#1.2",
+            ]
+        );
+    }
+
+    #[test]
+    fn without_base_url_links_open_workspace_pages() {
+        let workspace = test_index(&[(Namespace::Document, "Rust", "")]);
+        let config = PreviewConfig {
+            document_base_url: Some("/Document/".to_string()),
+            ..Default::default()
+        };
+        let text = "[[#document=\"Rust\" a]] [[#document=\"Go\" b]]";
+        let state = DocumentState::new(text.to_string());
+        let links = collect_document_links(&state, &config, &workspace);
+
+        assert_eq!(links.len(), 1);
+        let rust = DocumentKey::new(Namespace::Document, "Rust");
+        assert_eq!(
+            links[0].target.as_ref(),
+            Some(&workspace.get(&rust).unwrap().uri)
+        );
+        assert_eq!(links[0].tooltip.as_deref(), Some("Document:Rust"));
+    }
+}
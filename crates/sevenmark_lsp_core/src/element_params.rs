@@ -0,0 +1,72 @@
+use sevenmark_ast::{Element, ListContentItem, Parameters, TableCellItem, TableRowItem};
+use sevenmark_registry::{ElementSpec, Syntax};
+
+/// Parameters written on an element, with the registry entry describing them
+pub(crate) struct ParameterSet<'a> {
+    pub spec: &'static ElementSpec,
+    pub parameters: &'a Parameters,
+}
+
+/// Every parameter map an element owns directly, including table rows/cells,
+/// list items and fold sections (which are not separate `Element`s).
+pub(crate) fn parameter_sets(element: &Element) -> Vec<ParameterSet<'_>> {
+    let mut sets = Vec::new();
+    let mut push = |syntax, name, parameters| {
+        if let Some(spec) = sevenmark_registry::element(syntax, name) {
+            sets.push(ParameterSet { spec, parameters });
+        }
+    };
+
+    match element {
+        Element::Code(e) => push(Syntax::Brace, "code", &e.parameters),
+        Element::Styled(e) => push(Syntax::Brace, "styled", &e.parameters),
+        Element::BlockQuote(e) => push(Syntax::Brace, "quote", &e.parameters),
+        Element::Ruby(e) => push(Syntax::Brace, "ruby", &e.parameters),
+        Element::Footnote(e) => push(Syntax::Brace, "fn", &e.parameters),
+        Element::Include(e) => push(Syntax::Brace, "include", &e.parameters),
+        Element::Redirect(e) => push(Syntax::Brace, "redirect", &e.parameters),
+        Element::Fold(e) => {
+            push(Syntax::Brace, "fold", &e.parameters);
+            push(Syntax::Part, "fold-section", &e.summary.parameters);
+            push(Syntax::Part, "fold-section", &e.details.parameters);
+        }
+        Element::Media(e) => push(Syntax::Bracket, "media", &e.parameters),
+        Element::ExternalMedia(e) => push(Syntax::Bracket, &e.provider, &e.parameters),
+        Element::Table(e) => {
+            push(Syntax::Brace, "table", &e.parameters);
+            for item in &e.children {
+                let rows = match item {
+                    TableRowItem::Row(row) => std::slice::from_ref(row),
+                    TableRowItem::Conditional(c) => c.rows.as_slice(),
+                };
+                for row in rows {
+                    push(Syntax::Part, "table-row", &row.parameters);
+                    for item in &row.children {
+                        let cells = match item {
+                            TableCellItem::Cell(cell) => std::slice::from_ref(cell),
+                            TableCellItem::Conditional(c) => c.cells.as_slice(),
+                        };
+                        for cell in cells {
+                            push(Syntax::Part, "table-cell", &cell.parameters);
+                        }
+                    }
+                }
+            }
+        }
+        Element::List(e) => {
+            push(Syntax::Brace, "list", &e.parameters);
+            for item in &e.children {
+                let items = match item {
+                    ListContentItem::Item(item) => std::slice::from_ref(item),
+                    ListContentItem::Conditional(c) => c.items.as_slice(),
+                };
+                for item in items {
+                    push(Syntax::Part, "list-item", &item.parameters);
+                }
+            }
+        }
+        _ => {}
+    }
+
+    sets
+}
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use sevenmark_ast::visit::{
+    VisitPath, Visitor, walk_element, walk_list_content_item, walk_table_cell_item,
+    walk_table_row_item,
+};
+use sevenmark_ast::{Element, Expression, ListContentItem, Span, TableCellItem, TableRowItem};
+use sevenmark_utils::expression_evaluator::evaluate_condition;
+use sevenmark_utils::extract_plain_text;
+
+/// Variable values and `{{{#if}}}` results as the preprocessor sees them:
+/// this page's `{{{#define}}}`s applied in document order, and the defines
+/// inside a false condition dropped.
+///
+/// Values passed in by an including page are unknown here.
+#[derive(Debug, Default)]
+pub(crate) struct Evaluation {
+    /// `[var(name)]` span and its value, for defined variables only
+    pub variables: Vec<(Span, String)>,
+    pub conditions: Vec<Condition>,
+}
+
+/// A `{{{#if}}}` block, or a conditional table row/cell or list item group
+#[derive(Debug)]
+pub(crate) struct Condition {
+    pub open_span: Span,
+    pub result: bool,
+    /// Variables the condition reads, with their value there
+    pub reads: Vec<(String, Option<String>)>,
+}
+
+pub(crate) fn evaluate(elements: &[Element]) -> Evaluation {
+    let mut evaluator = Evaluator::default();
+    evaluator.visit_document(elements);
+    evaluator.evaluation
+}
+
+#[derive(Default)]
+struct Evaluator {
+    variables: HashMap<String, String>,
+    evaluation: Evaluation,
+}
+
+impl Evaluator {
+    /// Records the condition and walks its block with `walk`; a false
+    /// block's defines do not leak out
+    fn conditional(
+        &mut self,
+        condition: &Expression,
+        open_span: &Span,
+        walk: impl FnOnce(&mut Self),
+    ) {
+        let result = evaluate_condition(condition, &self.variables);
+        let mut reads = Vec::new();
+        variable_names(condition, &mut reads);
+        self.evaluation.conditions.push(Condition {
+            open_span: open_span.clone(),
+            result,
+            reads: reads
+                .into_iter()
+                .map(|name| {
+                    let value = self.variables.get(&name).cloned();
+                    (name, value)
+                })
+                .collect(),
+        });
+
+        if result {
+            walk(self);
+        } else {
+            let saved = self.variables.clone();
+            walk(self);
+            self.variables = saved;
+        }
+    }
+}
+
+impl Visitor for Evaluator {
+    fn visit_element(&mut self, element: &Element, path: &mut VisitPath) {
+        match element {
+            Element::Define(define) => {
+                walk_element(self, element, path);
+                for (name, parameter) in &define.parameters {
+                    let value = substituted_text(&parameter.value, &self.variables);
+                    if !value.is_empty() {
+                        self.variables.insert(name.clone(), value);
+                    }
+                }
+            }
+            Element::Variable(variable) => {
+                if let Some(value) = self.variables.get(&variable.name) {
+                    self.evaluation
+                        .variables
+                        .push((variable.span.clone(), value.clone()));
+                }
+            }
+            Element::If(e) => self.conditional(&e.condition, &e.open_span, |this| {
+                walk_element(this, element, path)
+            }),
+            _ => walk_element(self, element, path),
+        }
+    }
+
+    fn visit_table_row_item(&mut self, item: &TableRowItem, path: &mut VisitPath) {
+        match item {
+            TableRowItem::Conditional(cond) => {
+                self.conditional(&cond.condition, &cond.open_span, |this| {
+                    walk_table_row_item(this, item, path)
+                })
+            }
+            TableRowItem::Row(_) => walk_table_row_item(self, item, path),
+        }
+    }
+
+    fn visit_table_cell_item(&mut self, item: &TableCellItem, path: &mut VisitPath) {
+        match item {
+            TableCellItem::Conditional(cond) => {
+                self.conditional(&cond.condition, &cond.open_span, |this| {
+                    walk_table_cell_item(this, item, path)
+                })
+            }
+            TableCellItem::Cell(_) => walk_table_cell_item(self, item, path),
+        }
+    }
+
+    fn visit_list_content_item(&mut self, item: &ListContentItem, path: &mut VisitPath) {
+        match item {
+            ListContentItem::Conditional(cond) => {
+                self.conditional(&cond.condition, &cond.open_span, |this| {
+                    walk_list_content_item(this, item, path)
+                })
+            }
+            ListContentItem::Item(_) => walk_list_content_item(self, item, path),
+        }
+    }
+}
+
+/// Plain text of a define value after substituting known variables
+fn substituted_text(elements: &[Element], variables: &HashMap<String, String>) -> String {
+    elements
+        .iter()
+        .map(|element| match element {
+            Element::Variable(v) => variables.get(&v.name).cloned().unwrap_or_default(),
+            other => extract_plain_text(std::slice::from_ref(other)),
+        })
+        .collect()
+}
+
+/// Names of the `[var(...)]`s in an expression, first occurrence first
+fn variable_names(expression: &Expression, names: &mut Vec<String>) {
+    struct Names<'a>(&'a mut Vec<String>);
+
+    impl Visitor for Names<'_> {
+        fn visit_element(&mut self, element: &Element, path: &mut VisitPath) {
+            if let Element::Variable(v) = element
+                && !self.0.contains(&v.name)
+            {
+                self.0.push(v.name.clone());
+            }
+            walk_element(self, element, path);
+        }
+    }
+
+    Names(names).visit_expression(expression, &mut VisitPath::new());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sevenmark_parser::core::parse_document;
+
+    fn values(text: &str) -> Vec<String> {
+        evaluate(&parse_document(text))
+            .variables
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect()
+    }
+
+    #[test]
+    fn defines_apply_in_document_order() {
+        let text =
+            "[var(x)] {{{#define #x=\"1\"}}} [var(x)] {{{#define #x=\"[var(x)]2\"}}} [var(x)]";
+        // The reference inside the second define reads the first value
+        assert_eq!(values(text), ["1", "1", "12"]);
+    }
+
+    #[test]
+    fn false_condition_keeps_its_defines() {
+        let text = concat!(
+            "{{{#define #mode=\"dark\"}}}",
+            "{{{#if [var(mode)] == \"light\" :: {{{#define #x=\"a\"}}}}}}",
+            "{{{#if [var(mode)] == \"dark\" :: {{{#define #y=\"b\"}}}}}}",
+            "[var(x)][var(y)]",
+        );
+        let evaluation = evaluate(&parse_document(text));
+
+        let results: Vec<_> = evaluation.conditions.iter().map(|c| c.result).collect();
+        assert_eq!(results, [false, true]);
+        assert_eq!(
+            evaluation.conditions[0].reads,
+            [("mode".to_string(), Some("dark".to_string()))]
+        );
+        // `mode` twice in conditions, then only `y`
+        let values: Vec<_> = evaluation
+            .variables
+            .iter()
+            .map(|(_, v)| v.as_str())
+            .collect();
+        assert_eq!(values, ["dark", "dark", "b"]);
+    }
+}
@@ -1,25 +1,46 @@
 use ls_types::{Hover, HoverContents, MarkupContent, MarkupKind, Position, Range};
 use sevenmark_ast::{Element, ListKind};
+use sevenmark_registry::{ElementSpec, Syntax};
 
 use crate::ast_walk::visit_elements;
 use crate::document::DocumentState;
+use crate::element_params::parameter_sets;
+use crate::workspace::{WorkspaceIndex, element_links};
 
-/// Returns hover information for the element at the given byte offset.
-pub fn get_hover(state: &DocumentState, byte_offset: usize) -> Option<Hover> {
-    // Find the most specific (deepest/smallest) element containing the offset.
+/// Returns hover information for the element or parameter at the given byte offset.
+pub fn get_hover(
+    state: &DocumentState,
+    byte_offset: usize,
+    workspace: &WorkspaceIndex,
+) -> Option<Hover> {
+    // Find the most specific (deepest/smallest) element or parameter containing the offset.
     // We store (content, span_start, span_end, span_len) as owned values.
     let mut best: Option<(String, usize, usize, usize)> = None;
+    let mut consider = |content: Option<String>, start: usize, end: usize| {
+        let len = end - start;
+        if start <= byte_offset
+            && byte_offset < end
+            && best
+                .as_ref()
+                .is_none_or(|(_, _, _, best_len)| len < *best_len)
+            && let Some(content) = content
+        {
+            best = Some((content, start, end, len));
+        }
+    };
 
     visit_elements(&state.elements, &mut |element| {
         let span = element.span();
         if span.start <= byte_offset && byte_offset < span.end {
-            let len = span.end - span.start;
-            if let Some(content) = hover_content(element) {
-                if best
-                    .as_ref()
-                    .is_none_or(|(_, _, _, best_len)| len < *best_len)
-                {
-                    best = Some((content, span.start, span.end, len));
+            let content = hover_content(element).map(|content| match element {
+                Element::Include(_) => content + &include_details(element, workspace),
+                _ => content,
+            });
+            consider(content, span.start, span.end);
+            for set in parameter_sets(element) {
+                for parameter in set.parameters.values() {
+                    let content = parameter_hover_content(set.spec, &parameter.key);
+                    consider(content, parameter.span.start, parameter.span.end);
                 }
             }
         }
@@ -45,6 +66,49 @@ pub fn get_hover(state: &DocumentState, byte_offset: usize) -> Option<Hover> {
     })
 }
 
+/// Target of an include and the variables it reads, from the workspace index.
+fn include_details(element: &Element, workspace: &WorkspaceIndex) -> String {
+    let Some(link) = element_links(element).pop() else {
+        return String::new();
+    };
+    let Some(page) = workspace.get(&link.target) else {
+        return if workspace.is_empty() {
+            format!("\n\n`{}`", link.target)
+        } else {
+            format!("\n\n`{}` - not found in the workspace", link.target)
+        };
+    };
+
+    let mut text = format!("\n\n`{}`", link.target);
+    if !page.variables.is_empty() {
+        text.push_str("\n\nVariables:");
+        for variable in &page.variables {
+            match &variable.default {
+                Some(default) => {
+                    text.push_str(&format!("\n- `#{}` (default: `{default}`)", variable.name))
+                }
+                None => text.push_str(&format!("\n- `#{}`", variable.name)),
+            }
+        }
+    }
+    text
+}
+
+/// Markdown hover text for a parameter the registry knows.
+fn parameter_hover_content(spec: &ElementSpec, key: &str) -> Option<String> {
+    let param = spec.param(key)?;
+    let owner = match spec.syntax {
+        Syntax::Part => spec.summary.to_string(),
+        _ => format!("#{}", spec.name),
+    };
+    Some(format!(
+        "**#{}** ({owner})\n\n{}\n\nValue: {}",
+        param.name,
+        param.doc,
+        param.value.describe()
+    ))
+}
+
 /// Generates markdown hover text for an element.
 fn hover_content(element: &Element) -> Option<String> {
     let text = match element {
@@ -141,7 +205,7 @@ mod tests {
     }
 
     fn hover_value(state: &DocumentState, byte_offset: usize) -> Option<String> {
-        get_hover(state, byte_offset).map(|h| match h.contents {
+        get_hover(state, byte_offset, &WorkspaceIndex::new()).map(|h| match h.contents {
             HoverContents::Markup(m) => m.value,
             _ => panic!("expected markup hover"),
         })
@@ -187,4 +251,49 @@ mod tests {
         let val = hover_value(&state, 3);
         assert!(val.is_none());
     }
+
+    #[test]
+    fn parameter_hover_describes_value() {
+        let text = "{{{#table #wrapper-align=\"center\"\n[[ [[a]] ]]\n}}}";
+        let state = make_state(text);
+        let val = hover_value(&state, text.find("wrapper").unwrap()).unwrap();
+        assert!(val.starts_with("**#wrapper-align** (#table)"), "{val}");
+        assert!(val.contains("`center`"), "{val}");
+
+        let text = "{{{#fold\n[[#style=\"color:red\" title]]\n[[body]]\n}}}";
+        let state = make_state(text);
+        let val = hover_value(&state, text.find("style").unwrap()).unwrap();
+        assert!(val.contains("(Fold section"), "{val}");
+        assert!(val.contains("CSS declarations"), "{val}");
+    }
+
+    #[test]
+    fn include_hover_lists_template_variables() {
+        use crate::workspace::{Namespace, test_index};
+
+        let workspace = test_index(&[(
+            Namespace::Document,
+            "Infobox",
+            "{{{#define #color=\"gray\"}}}[var(name)] [var(color)]",
+        )]);
+        let text = "{{{#include #name=\"Rust\" Infobox}}} {{{#include Navbox}}}";
+        let state = make_state(text);
+        let hover = |offset| {
+            get_hover(&state, offset, &workspace).map(|h| match h.contents {
+                HoverContents::Markup(m) => m.value,
+                _ => panic!("expected markup hover"),
+            })
+        };
+
+        let val = hover(text.find("Infobox").unwrap()).unwrap();
+        assert!(val.starts_with("**Include**"), "{val}");
+        assert!(val.contains("`Document:Infobox`"), "{val}");
+        assert!(
+            val.contains("- `#name`\n- `#color` (default: `gray`)"),
+            "{val}"
+        );
+
+        let val = hover(text.find("Navbox").unwrap()).unwrap();
+        assert!(val.contains("not found in the workspace"), "{val}");
+    }
 }
@@ -0,0 +1,238 @@
+use ls_types::{InlayHint, InlayHintLabel, Position, Range};
+use sevenmark_ast::{Element, Span};
+
+use crate::ast_walk::visit_elements;
+use crate::document::DocumentState;
+use crate::evaluation::evaluate;
+use crate::workspace::section_paths;
+
+/// Longest variable value shown in full
+const MAX_VALUE_CHARS: usize = 32;
+
+/// Inlay hints inside `range`:
+/// - the value of `[var(name)]` after the reference
+/// - the number of unnamed `{{{#fn}}}` footnotes, as rendered
+/// - section numbers (`2.1.3`) before headers
+/// - the computed age of `[age(...)]` and d-day of `[dday(...)]`, when
+///   `today` (days since 1970-01-01) is known
+pub fn collect_inlay_hints(
+    state: &DocumentState,
+    range: Range,
+    today: Option<i64>,
+) -> Vec<InlayHint> {
+    let mut hints = Vec::new();
+    let position = |offset: usize| {
+        let (line, character) = state
+            .line_index
+            .byte_offset_to_position(&state.text, offset);
+        Position::new(line, character)
+    };
+    let mut push = |offset: usize, label: String, before: bool| {
+        let position = position(offset);
+        if range.start <= position && position <= range.end {
+            hints.push(InlayHint {
+                position,
+                label: InlayHintLabel::String(label),
+                kind: None,
+                text_edits: None,
+                tooltip: None,
+                padding_left: Some(!before),
+                padding_right: Some(before),
+                data: None,
+            });
+        }
+    };
+
+    for (header, path) in section_paths(&state.elements) {
+        push(header.span.start, path, true);
+    }
+    for (span, value) in evaluate(&state.elements).variables {
+        push(span.end, format!("= {}", truncate(&value)), false);
+    }
+
+    for (number, span) in numbered_footnotes(&state.elements).iter().enumerate() {
+        push(span.start, format!("[{}]", number + 1), true);
+    }
+
+    if let Some(today) = today {
+        visit_elements(&state.elements, &mut |element| match element {
+            Element::Age(e) => {
+                if let Some(age) = parse_date(&e.date).and_then(|date| age(date, today)) {
+                    push(e.span.end, format!("= {age}"), false);
+                }
+            }
+            Element::Dday(e) => {
+                if let Some(days) = parse_date(&e.date).map(|date| days_from_civil(date) - today) {
+                    push(e.span.end, format!("= {}", dday_label(days)), false);
+                }
+            }
+            _ => {}
+        });
+    }
+
+    hints.sort_by_key(|hint| hint.position);
+    hints
+}
+
+/// Unnamed footnotes numbered like the renderer does: in document order,
+/// skipping footnotes nested in another footnote. A `#display` text still
+/// takes a number.
+fn numbered_footnotes(elements: &[Element]) -> Vec<Span> {
+    let mut outer: Vec<Span> = Vec::new();
+    let mut numbered = Vec::new();
+    visit_elements(elements, &mut |element| {
+        let Element::Footnote(e) = element else {
+            return;
+        };
+        if outer
+            .iter()
+            .any(|o| o.start < e.span.start && e.span.end <= o.end)
+        {
+            return;
+        }
+        outer.push(e.span.clone());
+        if !e.parameters.contains_key("name") {
+            numbered.push(e.span.clone());
+        }
+    });
+    numbered
+}
+
+fn truncate(value: &str) -> String {
+    let value = value.trim();
+    match value.char_indices().nth(MAX_VALUE_CHARS) {
+        Some((end, _)) => format!("\"{}…\"", &value[..end]),
+        None => format!("\"{value}\""),
+    }
+}
+
+fn dday_label(days: i64) -> String {
+    match days {
+        0 => "D-Day".to_string(),
+        d if d > 0 => format!("D-{d}"),
+        d => format!("D+{}", -d),
+    }
+}
+
+/// Whole years from `birth` to `today`; `None` for a future date
+fn age(birth: (i64, u32, u32), today: i64) -> Option<i64> {
+    let (year, month, day) = civil_from_days(today);
+    let mut age = year - birth.0;
+    if (month, day) < (birth.1, birth.2) {
+        age -= 1;
+    }
+    (age >= 0).then_some(age)
+}
+
+/// `YYYY-MM-DD`
+fn parse_date(text: &str) -> Option<(i64, u32, u32)> {
+    let mut parts = text.trim().splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse().ok()?;
+    let day = parts.next()?.parse().ok()?;
+    ((1..=12).contains(&month) && (1..=31).contains(&day)).then_some((year, month, day))
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date
+fn days_from_civil((year, month, day): (i64, u32, u32)) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Days since 1970-01-01 in UTC, from the system clock
+#[cfg(not(target_arch = "wasm32"))]
+pub fn today() -> Option<i64> {
+    let elapsed = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?;
+    Some((elapsed.as_secs() / 86_400) as i64)
+}
+
+/// No system clock on `wasm32-unknown-unknown`; the host passes the date in
+#[cfg(target_arch = "wasm32")]
+pub fn today() -> Option<i64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(text: &str, today: Option<i64>) -> Vec<String> {
+        let state = DocumentState::new(text.to_string());
+        let everything = Range::new(Position::new(0, 0), Position::new(u32::MAX, 0));
+        collect_inlay_hints(&state, everything, today)
+            .into_iter()
+            .map(|hint| match hint.label {
+                InlayHintLabel::String(label) => label,
+                InlayHintLabel::LabelParts(_) => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn variables_footnotes_and_sections() {
+        let text = concat!(
+            "# Intro\n",
+            "{{{#define #name=\"SevenMark\"}}}[var(name)] [var(other)]\n",
+            "## Usage\n",
+            "a{{{#fn first}}} b{{{#fn #name=\"n\" named}}} c{{{#fn #display=\"*\" third}}}\n",
+            "# Next\n",
+        );
+        assert_eq!(
+            labels(text, None),
+            ["1", "= \"SevenMark\"", "1.1", "[1]", "[2]", "2"]
+        );
+    }
+
+    #[test]
+    fn age_and_dday_use_today() {
+        let today = days_from_civil((2026, 10, 19));
+        let text = "[age(2000-10-20)] [age(2000-10-19)] [dday(2026-10-29)] [dday(2026-10-19)] [dday(2026-10-09)]";
+        assert_eq!(
+            labels(text, Some(today)),
+            ["= 25", "= 26", "= D-10", "= D-Day", "= D+10"]
+        );
+        assert!(labels(text, None).is_empty());
+    }
+
+    #[test]
+    fn hints_outside_range_are_skipped() {
+        let state = DocumentState::new("# A\n# B\n# C".to_string());
+        let range = Range::new(Position::new(1, 0), Position::new(1, 3));
+        let hints = collect_inlay_hints(&state, range, None);
+        assert_eq!(hints.len(), 1);
+        assert_eq!(hints[0].position, Position::new(1, 0));
+    }
+
+    #[test]
+    fn civil_dates_round_trip() {
+        for date in [(1970, 1, 1), (2000, 2, 29), (2024, 12, 31), (1969, 12, 31)] {
+            assert_eq!(civil_from_days(days_from_civil(date)), date);
+        }
+        assert_eq!(days_from_civil((1970, 1, 2)), 1);
+    }
+}
@@ -1,10 +1,20 @@
 pub mod ast_walk;
+mod blocks;
+pub mod code_lens;
 pub mod completion;
 pub mod definition;
 pub mod diagnostics;
 pub mod document;
+pub mod document_link;
+mod element_params;
+mod evaluation;
 pub mod folding;
 pub mod hover;
+pub mod inlay_hints;
+pub mod linked_editing;
+pub mod preview;
+pub mod selection_range;
 pub mod semantic_tokens;
 pub mod server_state;
 pub mod symbols;
+pub mod workspace;
@@ -0,0 +1,106 @@
+use ls_types::{LinkedEditingRanges, Position, Range};
+use sevenmark_ast::{Element, Span};
+
+use crate::ast_walk::visit_elements;
+use crate::document::DocumentState;
+
+/// Every occurrence of the variable name at `byte_offset`: its `[var(...)]`
+/// references and the `#name` keys of `{{{#define}}}`. The ranges cover the
+/// name only, so they all have the same text as linked editing requires.
+pub fn linked_editing_ranges(
+    state: &DocumentState,
+    byte_offset: usize,
+) -> Option<LinkedEditingRanges> {
+    let occurrences = variable_names(&state.text, &state.elements);
+    let (name, _) = occurrences
+        .iter()
+        .find(|(_, span)| touches(span, byte_offset))?;
+
+    let range = |span: &Span| {
+        let (start, end) = state.line_index.span_to_range(&state.text, span);
+        Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1))
+    };
+    Some(LinkedEditingRanges {
+        ranges: occurrences
+            .iter()
+            .filter(|(other, _)| other == name)
+            .map(|(_, span)| range(span))
+            .collect(),
+        word_pattern: None,
+    })
+}
+
+/// Name spans of variable references and define keys, in document order
+fn variable_names(text: &str, elements: &[Element]) -> Vec<(String, Span)> {
+    let mut names = Vec::new();
+    visit_elements(elements, &mut |element| match element {
+        Element::Variable(v) => names.extend(name_span(text, &v.span, "[var(", &v.name)),
+        Element::Define(d) => {
+            for parameter in d.parameters.values() {
+                names.extend(name_span(text, &parameter.span, "#", &parameter.key));
+            }
+        }
+        _ => {}
+    });
+    names
+}
+
+/// Span of `name` written right after `prefix` at the start of `span`.
+/// Occurrences written differently in the source are left out.
+fn name_span(text: &str, span: &Span, prefix: &str, name: &str) -> Option<(String, Span)> {
+    let start = span.start + prefix.len();
+    let end = start + name.len();
+    (text.get(span.start..start)? == prefix && text.get(start..end)? == name)
+        .then(|| (name.to_string(), Span::new(start, end)))
+}
+
+/// Inside the span or at either edge, where the cursor sits while typing
+fn touches(span: &Span, offset: usize) -> bool {
+    span.start <= offset && offset <= span.end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges(text: &str, offset: usize) -> Option<Vec<Range>> {
+        let state = DocumentState::new(text.to_string());
+        linked_editing_ranges(&state, offset).map(|r| r.ranges)
+    }
+
+    fn range(start: u32, end: u32) -> Range {
+        Range::new(Position::new(0, start), Position::new(0, end))
+    }
+
+    /// Source text of single-line ranges
+    fn texts<'a>(text: &'a str, ranges: &[Range]) -> Vec<&'a str> {
+        ranges
+            .iter()
+            .map(|r| &text[r.start.character as usize..r.end.character as usize])
+            .collect()
+    }
+
+    #[test]
+    fn variable_name_is_linked_with_its_defines() {
+        let text = "{{{#define #name=\"x\" #other=\"y\"}}}[var(name)] [var(other)] [var(name)]";
+        let expected = Some(vec![range(12, 16), range(39, 43), range(64, 68)]);
+        assert_eq!(ranges(text, 13), expected);
+        assert_eq!(ranges(text, 41), expected);
+        assert_eq!(ranges(text, 2), None);
+    }
+
+    #[test]
+    fn linked_ranges_have_identical_text() {
+        let text = "{{{#define #a=\"1\"}}}{{{#fold [[ [var(a)] ]] [[{{{#if [var(a)] == \"1\" :: [var(a)]}}}]]}}}";
+        let ranges = ranges(text, text.find("[var(a)]").unwrap() + 5).unwrap();
+        assert_eq!(ranges.len(), 4);
+        assert!(texts(text, &ranges).iter().all(|t| *t == "a"));
+    }
+
+    #[test]
+    fn delimiters_are_not_linked() {
+        let text = "{{{#fold [[a]] [[b]]}}}";
+        assert_eq!(ranges(text, 1), None);
+        assert_eq!(ranges(text, 22), None);
+    }
+}
@@ -0,0 +1,326 @@
+//! Live HTML preview and editor ↔ preview scroll synchronization.
+//!
+//! The preview is rendered with `data-start`/`data-end` attributes holding
+//! UTF-16 offsets into the source, so a client can map the cursor to a
+//! rendered element and a scrolled-to element back to the source.
+//!
+//! Media links are resolved against the [`WorkspaceIndex`] instead of the
+//! wiki database, so `#file` titles stand in for stored file URLs.
+
+use ls_types::{Position, TextDocumentIdentifier};
+use serde::{Deserialize, Serialize};
+use sevenmark_ast::{
+    Element, MediaElement, ResolvedDoc, ResolvedFile, ResolvedMediaInfo, Traversable,
+};
+use sevenmark_html::{RenderConfig, render_document_with_spans};
+use sevenmark_utils::{Utf16OffsetConverter, Utf16Position, extract_plain_text};
+
+use crate::document::DocumentState;
+use crate::workspace::{DocumentKey, Namespace, WorkspaceIndex, media_links};
+
+/// Owned [`RenderConfig`] as sent by the client (`initializationOptions.preview`
+/// or the `config` of a `sevenmark/preview` request)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewConfig {
+    pub file_base_url: Option<String>,
+    pub document_base_url: Option<String>,
+    pub category_base_url: Option<String>,
+    pub user_base_url: Option<String>,
+}
+
+impl PreviewConfig {
+    /// The `preview` object of `initializationOptions`
+    pub fn from_initialization_options(options: Option<&serde_json::Value>) -> Option<Self> {
+        Self::deserialize(options?.get("preview")?).ok()
+    }
+
+    pub fn render_config(&self) -> RenderConfig<'_> {
+        RenderConfig {
+            file_base_url: self.file_base_url.as_deref(),
+            document_base_url: self.document_base_url.as_deref(),
+            category_base_url: self.category_base_url.as_deref(),
+            user_base_url: self.user_base_url.as_deref(),
+        }
+    }
+}
+
+/// Params of `sevenmark/preview`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewParams {
+    pub text_document: TextDocumentIdentifier,
+    /// Overrides the configuration from `initializationOptions.preview`
+    pub config: Option<PreviewConfig>,
+}
+
+/// Result of `sevenmark/preview`
+#[derive(Debug, Clone, Serialize)]
+pub struct PreviewResult {
+    pub html: String,
+    /// Version of the rendered text, to drop stale responses
+    pub version: Option<i32>,
+}
+
+/// Params of `sevenmark/scrollSync`: either an editor `position` or a
+/// preview `offset` (UTF-16, as in `data-start`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrollSyncParams {
+    pub text_document: TextDocumentIdentifier,
+    pub position: Option<Position>,
+    pub offset: Option<u32>,
+}
+
+/// Rendered preview of a document
+#[derive(Debug, Clone)]
+pub struct Preview {
+    pub html: String,
+    /// `data-start`/`data-end` pairs of the rendered elements, in document order
+    pub anchors: Vec<Utf16Position>,
+}
+
+impl Preview {
+    pub fn render(
+        state: &DocumentState,
+        config: &PreviewConfig,
+        workspace: &WorkspaceIndex,
+    ) -> Self {
+        let mut elements = state.elements.clone();
+        for element in &mut elements {
+            resolve_media(element, workspace);
+        }
+        let html = render_document_with_spans(&elements, &config.render_config(), &state.text);
+        let anchors = collect_anchors(&html);
+        Self { html, anchors }
+    }
+
+    /// Innermost anchor containing `offset`, or the last one starting before it
+    pub fn anchor_at(&self, offset: u32) -> Option<&Utf16Position> {
+        self.anchors
+            .iter()
+            .filter(|a| a.start <= offset && offset < a.end)
+            .min_by_key(|a| a.end - a.start)
+            .or_else(|| {
+                self.anchors
+                    .iter()
+                    .filter(|a| a.start <= offset)
+                    .max_by_key(|a| a.start)
+            })
+    }
+}
+
+/// Result of `sevenmark/scrollSync`: the same location as a source position,
+/// a UTF-16 offset and the preview anchor to scroll to
+#[derive(Debug, Clone, Serialize)]
+pub struct ScrollSync {
+    pub position: Position,
+    pub offset: u32,
+    pub anchor: Option<Utf16Position>,
+}
+
+impl ScrollSync {
+    /// `None` when the params carry neither a position nor an offset
+    pub fn from_params(
+        state: &DocumentState,
+        preview: &Preview,
+        params: &ScrollSyncParams,
+    ) -> Option<Self> {
+        match (params.position, params.offset) {
+            (Some(position), _) => Some(Self::from_position(state, preview, position)),
+            (None, Some(offset)) => Some(Self::from_offset(state, preview, offset)),
+            (None, None) => None,
+        }
+    }
+
+    /// From an editor position
+    pub fn from_position(state: &DocumentState, preview: &Preview, position: Position) -> Self {
+        let byte_offset = state.line_index.position_to_byte_offset(
+            &state.text,
+            position.line,
+            position.character,
+        );
+        let offset = Utf16OffsetConverter::new(&state.text).convert(byte_offset);
+        Self::from_offset(state, preview, offset)
+    }
+
+    /// From a UTF-16 offset, e.g. the `data-start` of a scrolled-to element
+    pub fn from_offset(state: &DocumentState, preview: &Preview, offset: u32) -> Self {
+        let byte_offset = utf16_to_byte_offset(&state.text, offset);
+        let (line, character) = state
+            .line_index
+            .byte_offset_to_position(&state.text, byte_offset);
+        Self {
+            position: Position::new(line, character),
+            offset: Utf16OffsetConverter::new(&state.text).convert(byte_offset),
+            anchor: preview.anchor_at(offset).cloned(),
+        }
+    }
+}
+
+/// Fills `resolved_info` of media the way the server's postprocessor does
+fn resolve_media(element: &mut Element, workspace: &WorkspaceIndex) {
+    if let Element::Media(media) = element {
+        media.resolved_info = resolved_info(media, workspace);
+    }
+    element.traverse_children(&mut |child| resolve_media(child, workspace));
+}
+
+fn resolved_info(media: &MediaElement, workspace: &WorkspaceIndex) -> Option<ResolvedMediaInfo> {
+    let mut info = ResolvedMediaInfo {
+        url: media
+            .parameters
+            .get("url")
+            .map(|p| extract_plain_text(&p.value).trim().to_string())
+            .filter(|url| !url.is_empty()),
+        ..Default::default()
+    };
+    for link in media_links(&media.parameters) {
+        let is_valid = exists(workspace, &link.target);
+        let title = link.target.title;
+        match link.target.namespace {
+            Namespace::File => {
+                info.file = Some(ResolvedFile {
+                    url: title,
+                    is_valid,
+                    width: None,
+                    height: None,
+                })
+            }
+            Namespace::Document => info.document = Some(ResolvedDoc { title, is_valid }),
+            Namespace::Category => info.category = Some(ResolvedDoc { title, is_valid }),
+            Namespace::User => info.user = Some(ResolvedDoc { title, is_valid }),
+        }
+    }
+
+    let resolved = info.url.is_some()
+        || info.file.is_some()
+        || info.document.is_some()
+        || info.category.is_some()
+        || info.user.is_some();
+    resolved.then_some(info)
+}
+
+/// Same rule as the workspace diagnostics: pages in namespaces the
+/// workspace has nothing in are assumed to exist
+fn exists(workspace: &WorkspaceIndex, key: &DocumentKey) -> bool {
+    workspace.contains(key)
+        || workspace.is_empty()
+        || (key.namespace != Namespace::Category && !workspace.has_namespace(key.namespace))
+}
+
+/// Scans `data-start="N" data-end="M"` pairs. Attribute values in user
+/// content are escaped, so a literal `data-start="` only comes from the renderer.
+fn collect_anchors(html: &str) -> Vec<Utf16Position> {
+    const START: &str = "data-start=\"";
+    const END: &str = "data-end=\"";
+
+    let mut anchors = Vec::new();
+    let mut rest = html;
+    while let Some(pos) = rest.find(START) {
+        rest = &rest[pos + START.len()..];
+        let Some((start, after)) = number(rest) else {
+            continue;
+        };
+        let Some(end) = after
+            .trim_start()
+            .strip_prefix(END)
+            .and_then(number)
+            .map(|(end, _)| end)
+        else {
+            continue;
+        };
+        anchors.push(Utf16Position { start, end });
+    }
+    anchors
+}
+
+/// Leading `N"` of `text`: `(N, rest after the quote)`
+fn number(text: &str) -> Option<(u32, &str)> {
+    let (digits, rest) = text.split_once('"')?;
+    Some((digits.parse().ok()?, rest))
+}
+
+/// Byte offset of a UTF-16 offset, clamped to the text and rounded down to a
+/// char boundary
+fn utf16_to_byte_offset(text: &str, offset: u32) -> usize {
+    let mut utf16 = 0;
+    for (byte, ch) in text.char_indices() {
+        utf16 += ch.len_utf16() as u32;
+        if utf16 > offset {
+            return byte;
+        }
+    }
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workspace::test_index;
+
+    fn state(text: &str) -> DocumentState {
+        DocumentState::new(text.to_string())
+    }
+
+    #[test]
+    fn preview_anchors_match_rendered_spans() {
+        let state = state("가나 **굵게**");
+        let preview = Preview::render(&state, &PreviewConfig::default(), &WorkspaceIndex::new());
+
+        assert!(preview.html.contains("data-start=\"3\""));
+        let bold = preview.anchor_at(4).unwrap();
+        assert_eq!((bold.start, bold.end), (3, 9));
+    }
+
+    #[test]
+    fn config_sets_link_base_urls() {
+        let state = state("[[#document=\"Rust\" Rust]] [[#file=\"logo.png\"]]");
+        let config = PreviewConfig {
+            file_base_url: Some("/files/".to_string()),
+            document_base_url: Some("/wiki/".to_string()),
+            ..Default::default()
+        };
+        let preview = Preview::render(&state, &config, &WorkspaceIndex::new());
+        assert!(preview.html.contains("href=\"/wiki/Rust\""));
+        assert!(preview.html.contains("src=\"/files/logo.png\""));
+    }
+
+    #[test]
+    fn scroll_sync_round_trips_through_utf16_offsets() {
+        let state = state("😀 first\n\n**second** line");
+        let preview = Preview::render(&state, &PreviewConfig::default(), &WorkspaceIndex::new());
+
+        let from_editor = ScrollSync::from_position(&state, &preview, Position::new(2, 3));
+        assert_eq!(from_editor.offset, 13);
+        let anchor = from_editor.anchor.unwrap();
+        assert_eq!((anchor.start, anchor.end), (12, 18));
+
+        let from_preview = ScrollSync::from_offset(&state, &preview, 10);
+        assert_eq!(from_preview.position, Position::new(2, 0));
+    }
+
+    #[test]
+    fn media_missing_from_workspace_is_invalid() {
+        let workspace = test_index(&[(Namespace::Document, "Rust", "")]);
+        let state = state("[[#document=\"Rust\" a]] [[#document=\"Go\" b]]");
+        let config = PreviewConfig {
+            document_base_url: Some("/wiki/".to_string()),
+            ..Default::default()
+        };
+        let preview = Preview::render(&state, &config, &workspace);
+        assert!(preview.html.contains("class=\"sm-link\" data-start=\"0\""));
+        assert!(
+            preview
+                .html
+                .contains("class=\"sm-link-invalid\" data-start=\"23\"")
+        );
+    }
+
+    #[test]
+    fn utf16_offsets_inside_surrogate_pairs_round_down() {
+        assert_eq!(utf16_to_byte_offset("😀a", 1), 0);
+        assert_eq!(utf16_to_byte_offset("😀a", 2), 4);
+        assert_eq!(utf16_to_byte_offset("😀a", 99), 5);
+    }
+}
@@ -0,0 +1,164 @@
+use ls_types::{Position, Range, SelectionRange};
+use sevenmark_ast::visit::{VisitPath, Visitor, walk_element, walk_parameter};
+use sevenmark_ast::{Element, Parameter, Span};
+
+use crate::blocks::collect_blocks;
+use crate::document::DocumentState;
+
+/// Expanding selections for each position, innermost first: text → styled
+/// span → parameter → cell → row → table → section → document.
+pub fn collect_selection_ranges(
+    state: &DocumentState,
+    positions: &[Position],
+) -> Vec<SelectionRange> {
+    let mut spans = Spans::default();
+    spans.visit_document(&state.elements);
+    spans
+        .0
+        .extend(collect_blocks(&state.elements).iter().map(|b| b.span()));
+    spans
+        .0
+        .extend(section_spans(&state.elements, state.text.len()));
+    spans.0.push(Span::new(0, state.text.len()));
+
+    positions
+        .iter()
+        .map(|position| {
+            let offset = state.line_index.position_to_byte_offset(
+                &state.text,
+                position.line,
+                position.character,
+            );
+            selection_range(state, &spans.0, offset)
+        })
+        .collect()
+}
+
+/// Chain of the spans containing `offset`; spans that do not nest with the
+/// smaller ones are skipped
+fn selection_range(state: &DocumentState, spans: &[Span], offset: usize) -> SelectionRange {
+    let mut containing: Vec<&Span> = spans
+        .iter()
+        .filter(|s| s.start <= offset && (offset < s.end || s.end == state.text.len()))
+        .collect();
+    containing.sort_by_key(|s| (s.end - s.start, s.start));
+
+    let mut chain: Vec<&Span> = Vec::new();
+    for span in containing {
+        let nests = chain
+            .last()
+            .is_none_or(|inner| span.start <= inner.start && inner.end <= span.end);
+        let same = chain
+            .last()
+            .is_some_and(|inner| (inner.start, inner.end) == (span.start, span.end));
+        if nests && !same {
+            chain.push(span);
+        }
+    }
+
+    let mut selection: Option<SelectionRange> = None;
+    for span in chain.into_iter().rev() {
+        let (start, end) = state.line_index.span_to_range(&state.text, span);
+        selection = Some(SelectionRange {
+            range: Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1)),
+            parent: selection.map(Box::new),
+        });
+    }
+    // The document span always contains the offset
+    selection.unwrap_or(SelectionRange {
+        range: Range::default(),
+        parent: None,
+    })
+}
+
+/// A header up to the next header of the same or a higher level
+fn section_spans(elements: &[Element], text_len: usize) -> Vec<Span> {
+    let headers: Vec<_> = elements
+        .iter()
+        .filter_map(|element| match element {
+            Element::Header(h) => Some(h),
+            _ => None,
+        })
+        .collect();
+    headers
+        .iter()
+        .enumerate()
+        .map(|(i, header)| {
+            let end = headers[i + 1..]
+                .iter()
+                .find(|next| next.level <= header.level)
+                .map_or(text_len, |next| next.span.start);
+            Span::new(header.span.start, end)
+        })
+        .collect()
+}
+
+/// Element and parameter spans
+#[derive(Default)]
+struct Spans(Vec<Span>);
+
+impl Visitor for Spans {
+    fn visit_element(&mut self, element: &Element, path: &mut VisitPath) {
+        self.0.push(element.span().clone());
+        walk_element(self, element, path);
+    }
+
+    fn visit_parameter(&mut self, parameter: &Parameter, path: &mut VisitPath) {
+        self.0.push(parameter.span.clone());
+        walk_parameter(self, parameter, path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(text: &str, position: Position) -> Vec<String> {
+        let state = DocumentState::new(text.to_string());
+        let mut selection = collect_selection_ranges(&state, &[position]).pop();
+        let mut texts = Vec::new();
+        while let Some(range) = selection {
+            let start = state.line_index.position_to_byte_offset(
+                &state.text,
+                range.range.start.line,
+                range.range.start.character,
+            );
+            let end = state.line_index.position_to_byte_offset(
+                &state.text,
+                range.range.end.line,
+                range.range.end.character,
+            );
+            texts.push(state.text[start..end].to_string());
+            selection = range.parent.map(|parent| *parent);
+        }
+        texts
+    }
+
+    #[test]
+    fn expands_from_text_to_section() {
+        let text = "# Intro\n{{{#table [[ [[a **bold** b]] ]] }}}\n# Next\n";
+        let texts = chain(text, Position::new(1, 20));
+        assert_eq!(
+            texts,
+            [
+                "bold",
+                "**bold**",
+                "[[a **bold** b]]",
+                "[[ [[a **bold** b]] ]]",
+                "{{{#table [[ [[a **bold** b]] ]] }}}",
+                "# Intro\n{{{#table [[ [[a **bold** b]] ]] }}}\n",
+                text,
+            ]
+        );
+    }
+
+    #[test]
+    fn subsections_nest_in_their_section() {
+        let text = "# A\n## B\nx\n# C\n";
+        let texts = chain(text, Position::new(2, 0));
+        assert_eq!(
+            texts[texts.len() - 3..],
+            ["## B\nx\n", "# A\n## B\nx\n", text]
+        );
+    }
+}
@@ -1,4 +1,9 @@
-use ls_types::{SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokensLegend};
+use std::collections::HashMap;
+
+use ls_types::{
+    Range, SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokens,
+    SemanticTokensDelta, SemanticTokensEdit, SemanticTokensFullDeltaResult, SemanticTokensLegend,
+};
 use sevenmark_ast::{
     ConditionalListItems, ConditionalTableCells, ConditionalTableRows, Element, Expression,
     FoldInnerElement, ListContentItem, ListItemElement, Parameter, TableCellElement, TableCellItem,
@@ -171,8 +176,30 @@ pub fn legend() -> SemanticTokensLegend {
 /// LSP semantic tokens must be single-line. Multi-line spans are split into one token
 /// per line so every line that a span covers gets highlighted.
 pub fn collect_semantic_tokens(state: &DocumentState) -> Vec<SemanticToken> {
+    encode_tokens(state, 0, u32::MAX)
+}
+
+/// Tokens on the lines of `range` only, for `semanticTokens/range`.
+///
+/// Positions are still relative to the start of the document.
+pub fn collect_semantic_tokens_range(state: &DocumentState, range: Range) -> Vec<SemanticToken> {
+    encode_tokens(state, range.start.line, range.end.line)
+}
+
+/// Tokens on lines `first_line..=last_line`
+fn encode_tokens(state: &DocumentState, first_line: u32, last_line: u32) -> Vec<SemanticToken> {
     let mut raw: Vec<(usize, usize, u32)> = Vec::new();
     walk_elements(&state.elements, &mut raw);
+
+    // Spans entirely outside the lines never produce a token
+    let first_byte = state
+        .line_index
+        .position_to_byte_offset(&state.text, first_line, 0);
+    let last_byte =
+        state
+            .line_index
+            .position_to_byte_offset(&state.text, last_line.saturating_add(1), 0);
+    raw.retain(|&(start, end, _)| end > first_byte && start < last_byte);
     raw.sort_by_key(|&(start, _, _)| start);
 
     // Expand raw byte-offset spans into per-line (line, col, length, token_type) tuples.
@@ -188,13 +215,13 @@ pub fn collect_semantic_tokens(state: &DocumentState) -> Vec<SemanticToken> {
 
         if start_line == end_line {
             let length = end_col - start_col;
-            if length > 0 {
+            if length > 0 && (first_line..=last_line).contains(&start_line) {
                 per_line.push((start_line, start_col, length, token_type));
             }
         } else {
             has_multiline = true;
             // Emit one token per line covered by this span.
-            for line in start_line..=end_line {
+            for line in start_line.max(first_line)..=end_line.min(last_line) {
                 let col_start = if line == start_line { start_col } else { 0 };
                 let col_end = if line == end_line {
                     end_col
@@ -244,6 +271,125 @@ pub fn collect_semantic_tokens(state: &DocumentState) -> Vec<SemanticToken> {
     tokens
 }
 
+/// Last tokens sent per document, so `semanticTokens/full/delta` can answer
+/// with the edits since the client's `previousResultId`.
+#[derive(Default)]
+pub struct SemanticTokensCache {
+    next_id: u64,
+    documents: HashMap<String, CachedTokens>,
+}
+
+struct CachedTokens {
+    result_id: String,
+    version: Option<i32>,
+    tokens: Vec<SemanticToken>,
+}
+
+impl SemanticTokensCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `semanticTokens/full`: every token, with a fresh result id
+    pub fn full(
+        &mut self,
+        uri: &str,
+        version: Option<i32>,
+        state: &DocumentState,
+    ) -> SemanticTokens {
+        let cached = self.refresh(uri, version, state);
+        SemanticTokens {
+            result_id: Some(cached.result_id.clone()),
+            data: cached.tokens.clone(),
+        }
+    }
+
+    /// `semanticTokens/full/delta`: edits against `previous_result_id`, or
+    /// every token when that result is no longer cached
+    pub fn delta(
+        &mut self,
+        uri: &str,
+        version: Option<i32>,
+        state: &DocumentState,
+        previous_result_id: &str,
+    ) -> SemanticTokensFullDeltaResult {
+        let previous = self
+            .documents
+            .remove(uri)
+            .filter(|cached| cached.result_id == previous_result_id);
+        let Some(previous) = previous else {
+            return self.full(uri, version, state).into();
+        };
+
+        if version.is_some() && previous.version == version {
+            let result_id = previous.result_id.clone();
+            self.documents.insert(uri.to_string(), previous);
+            return SemanticTokensDelta {
+                result_id: Some(result_id),
+                edits: Vec::new(),
+            }
+            .into();
+        }
+
+        let cached = self.refresh(uri, version, state);
+        SemanticTokensDelta {
+            result_id: Some(cached.result_id.clone()),
+            edits: token_edits(&previous.tokens, &cached.tokens),
+        }
+        .into()
+    }
+
+    pub fn remove(&mut self, uri: &str) {
+        self.documents.remove(uri);
+    }
+
+    /// Tokens of `version`, recomputed only when the version changed
+    fn refresh(&mut self, uri: &str, version: Option<i32>, state: &DocumentState) -> &CachedTokens {
+        let stale = self
+            .documents
+            .get(uri)
+            .is_none_or(|cached| version.is_none() || cached.version != version);
+        if stale {
+            self.next_id += 1;
+            let cached = CachedTokens {
+                result_id: self.next_id.to_string(),
+                version,
+                tokens: collect_semantic_tokens(state),
+            };
+            self.documents.insert(uri.to_string(), cached);
+        }
+        &self.documents[uri]
+    }
+}
+
+/// A single edit replacing the tokens between the common prefix and suffix.
+///
+/// `start` and `delete_count` index the flattened array, five integers per token.
+fn token_edits(previous: &[SemanticToken], current: &[SemanticToken]) -> Vec<SemanticTokensEdit> {
+    let prefix = previous
+        .iter()
+        .zip(current)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = previous[prefix..]
+        .iter()
+        .rev()
+        .zip(current[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let deleted = previous.len() - prefix - suffix;
+    let inserted = &current[prefix..current.len() - suffix];
+    if deleted == 0 && inserted.is_empty() {
+        return Vec::new();
+    }
+    vec![SemanticTokensEdit {
+        start: (prefix * 5) as u32,
+        delete_count: (deleted * 5) as u32,
+        data: Some(inserted.to_vec()),
+    }]
+}
+
 /// Returns the column index of the last visible character on `line` (i.e. excludes `\r`/`\n`).
 fn line_content_end_col(state: &DocumentState, line: u32) -> u32 {
     let text_len = state.text.len();
@@ -773,6 +919,7 @@ fn element_token_type(element: &Element) -> u32 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ls_types::Position;
 
     fn make_state(text: &str) -> DocumentState {
         DocumentState::new(text.to_string())
@@ -995,4 +1142,86 @@ mod tests {
         assert_eq!(raw[0], (0, 3, TokenIdx::Table.as_u32()));
         assert_eq!(raw[1], (3, 6, TokenIdx::Table.as_u32()));
     }
+
+    #[test]
+    fn range_keeps_only_tokens_on_requested_lines() {
+        let state = make_state("**a**\n// note\n~~c~~");
+        let range = collect_semantic_tokens_range(
+            &state,
+            Range::new(Position::new(1, 0), Position::new(1, 5)),
+        );
+
+        // Still relative to the document start
+        assert_eq!(range.len(), 1);
+        assert_eq!(range[0].delta_line, 1);
+        assert_eq!(range[0].token_type, TokenIdx::Comment.as_u32());
+    }
+
+    #[test]
+    fn delta_replays_to_new_tokens() {
+        let before = make_state("**a**\n//b//\n~~c~~");
+        let after = make_state("**a**\n//b// **new**\n~~c~~");
+        let mut cache = SemanticTokensCache::new();
+
+        let full = cache.full("file:///a.sm", Some(1), &before);
+        let result_id = full.result_id.unwrap();
+        let SemanticTokensFullDeltaResult::TokensDelta(delta) =
+            cache.delta("file:///a.sm", Some(2), &after, &result_id)
+        else {
+            panic!("expected a delta");
+        };
+        assert_ne!(delta.result_id.as_deref(), Some(result_id.as_str()));
+        assert_eq!(delta.edits.len(), 1);
+
+        // Apply the edit to the flattened previous array
+        let flatten = |tokens: &[SemanticToken]| -> Vec<u32> {
+            tokens
+                .iter()
+                .flat_map(|t| {
+                    [
+                        t.delta_line,
+                        t.delta_start,
+                        t.length,
+                        t.token_type,
+                        t.token_modifiers_bitset,
+                    ]
+                })
+                .collect()
+        };
+        let mut data = flatten(&full.data);
+        let edit = &delta.edits[0];
+        let start = edit.start as usize;
+        data.splice(
+            start..start + edit.delete_count as usize,
+            flatten(edit.data.as_deref().unwrap()),
+        );
+        assert_eq!(data, flatten(&collect_semantic_tokens(&after)));
+    }
+
+    #[test]
+    fn delta_against_unknown_result_returns_full_tokens() {
+        let state = make_state("**a**");
+        let mut cache = SemanticTokensCache::new();
+        cache.full("file:///a.sm", Some(1), &state);
+
+        assert!(matches!(
+            cache.delta("file:///a.sm", Some(2), &state, "stale"),
+            SemanticTokensFullDeltaResult::Tokens(_)
+        ));
+        // Same version again: nothing changed
+        let SemanticTokensFullDeltaResult::Tokens(tokens) =
+            cache.delta("file:///a.sm", Some(2), &state, "stale")
+        else {
+            panic!("expected full tokens");
+        };
+        let SemanticTokensFullDeltaResult::TokensDelta(delta) = cache.delta(
+            "file:///a.sm",
+            Some(2),
+            &state,
+            tokens.result_id.as_deref().unwrap(),
+        ) else {
+            panic!("expected a delta");
+        };
+        assert!(delta.edits.is_empty());
+    }
 }
@@ -3,20 +3,40 @@ use std::collections::HashMap;
 use ls_types::*;
 use serde_json::Value;
 
+use crate::code_lens::collect_code_lenses;
 use crate::completion::get_completions;
 use crate::definition::find_definition;
 use crate::diagnostics::collect_diagnostics;
 use crate::document::DocumentState;
+use crate::document_link::collect_document_links;
 use crate::folding::collect_folding_ranges;
 use crate::hover::get_hover;
-use crate::semantic_tokens::{collect_semantic_tokens, legend};
+use crate::inlay_hints::{self, collect_inlay_hints};
+use crate::linked_editing::linked_editing_ranges;
+use crate::preview::{
+    Preview, PreviewConfig, PreviewParams, PreviewResult, ScrollSync, ScrollSyncParams,
+};
+use crate::selection_range::collect_selection_ranges;
+use crate::semantic_tokens::{SemanticTokensCache, collect_semantic_tokens_range, legend};
 use crate::symbols::collect_document_symbols;
+use crate::workspace::{
+    DocumentKey, FolderSource, IncludeGraph, IncludeGraphParams, MemorySource, Namespace,
+    SourceDocument, WorkspaceIndex, collect_workspace_diagnostics, collect_workspace_symbols,
+};
 
 /// Transport-agnostic LSP state. Owns all open documents and handles
 /// JSON-RPC messages synchronously - no async runtime required.
 pub struct LspState {
     documents: HashMap<String, DocumentState>,
     document_versions: HashMap<String, i32>,
+    workspace: WorkspaceIndex,
+    /// Base URLs from `initializationOptions.preview`
+    preview_config: PreviewConfig,
+    /// Last rendered preview per document, dropped on change
+    previews: HashMap<String, Preview>,
+    semantic_tokens: SemanticTokensCache,
+    /// Date for `[age]`/`[dday]` inlay hints, when the host has no clock
+    today: Option<i64>,
 }
 
 /// The result of handling a single JSON-RPC message.
@@ -33,9 +53,20 @@ impl LspState {
         Self {
             documents: HashMap::new(),
             document_versions: HashMap::new(),
+            workspace: WorkspaceIndex::new(),
+            preview_config: PreviewConfig::default(),
+            previews: HashMap::new(),
+            semantic_tokens: SemanticTokensCache::new(),
+            today: None,
         }
     }
 
+    /// Sets today's date (days since 1970-01-01) used by `[age]`/`[dday]`
+    /// inlay hints instead of the system clock
+    pub fn set_today(&mut self, unix_days: i64) {
+        self.today = Some(unix_days);
+    }
+
     /// Processes a single JSON-RPC message (request or notification) and returns
     /// the response plus any server-initiated notifications.
     pub fn handle_message(&mut self, json: &str) -> HandleResult {
@@ -68,8 +99,22 @@ impl LspState {
             "textDocument/hover" => self.handle_hover(id, params),
             "textDocument/definition" => self.handle_definition(id, params),
             "textDocument/semanticTokens/full" => self.handle_semantic_tokens(id, params),
+            "textDocument/semanticTokens/full/delta" => {
+                self.handle_semantic_tokens_delta(id, params)
+            }
+            "textDocument/semanticTokens/range" => self.handle_semantic_tokens_range(id, params),
             "textDocument/foldingRange" => self.handle_folding_range(id, params),
             "textDocument/documentSymbol" => self.handle_document_symbol(id, params),
+            "textDocument/inlayHint" => self.handle_inlay_hint(id, params),
+            "textDocument/codeLens" => self.handle_code_lens(id, params),
+            "textDocument/selectionRange" => self.handle_selection_range(id, params),
+            "textDocument/linkedEditingRange" => self.handle_linked_editing_range(id, params),
+            "textDocument/documentLink" => self.handle_document_link(id, params),
+            "sevenmark/preview" => self.handle_preview(id, params),
+            "sevenmark/scrollSync" => self.handle_scroll_sync(id, params),
+            "sevenmark/includeGraph" => self.handle_include_graph(id, params),
+            "workspace/symbol" => self.handle_workspace_symbol(id, params),
+            "sevenmark/workspaceDocuments" => self.handle_workspace_documents(params),
             _ => {
                 if let Some(id) = id {
                     // Unknown request → method not found
@@ -91,7 +136,18 @@ impl LspState {
 
     // ── Request handlers ─────────────────────────────────────────────────
 
-    fn handle_initialize(&self, id: Option<Value>, _params: Value) -> HandleResult {
+    fn handle_initialize(&mut self, id: Option<Value>, params: Value) -> HandleResult {
+        if let Some(config) =
+            PreviewConfig::from_initialization_options(params.get("initializationOptions"))
+        {
+            self.preview_config = config;
+        }
+        if let Ok(params) = serde_json::from_value::<InitializeParams>(params)
+            && let Some(folder) = FolderSource::from_initialize_params(&params)
+        {
+            self.workspace = WorkspaceIndex::from_source(folder);
+        }
+
         let capabilities = ServerCapabilities {
             text_document_sync: Some(TextDocumentSyncCapability::Options(
                 TextDocumentSyncOptions {
@@ -101,7 +157,12 @@ impl LspState {
                 },
             )),
             completion_provider: Some(CompletionOptions {
-                trigger_characters: Some(vec!["[".to_string(), "#".to_string(), "(".to_string()]),
+                trigger_characters: Some(vec![
+                    "[".to_string(),
+                    "#".to_string(),
+                    "(".to_string(),
+                    "\"".to_string(),
+                ]),
                 ..Default::default()
             }),
             definition_provider: Some(OneOf::Left(true)),
@@ -110,12 +171,23 @@ impl LspState {
             semantic_tokens_provider: Some(
                 SemanticTokensServerCapabilities::SemanticTokensOptions(SemanticTokensOptions {
                     legend: legend(),
-                    full: Some(SemanticTokensFullOptions::Bool(true)),
-                    range: None,
+                    full: Some(SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                    range: Some(true),
                     ..Default::default()
                 }),
             ),
             folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+            inlay_hint_provider: Some(OneOf::Left(true)),
+            code_lens_provider: Some(CodeLensOptions {
+                resolve_provider: Some(false),
+            }),
+            selection_range_provider: Some(SelectionRangeProviderCapability::Simple(true)),
+            linked_editing_range_provider: Some(LinkedEditingRangeServerCapabilities::Simple(true)),
+            document_link_provider: Some(DocumentLinkOptions {
+                resolve_provider: Some(false),
+                work_done_progress_options: Default::default(),
+            }),
+            workspace_symbol_provider: Some(OneOf::Left(true)),
             ..Default::default()
         };
 
@@ -144,7 +216,7 @@ impl LspState {
             state
                 .line_index
                 .position_to_byte_offset(&state.text, pos.line, pos.character);
-        let items = get_completions(state, pos, byte_offset);
+        let items = get_completions(state, pos, byte_offset, &self.workspace);
         let result = if items.is_empty() {
             Value::Null
         } else {
@@ -170,7 +242,7 @@ impl LspState {
             state
                 .line_index
                 .position_to_byte_offset(&state.text, pos.line, pos.character);
-        let result = match get_hover(state, byte_offset) {
+        let result = match get_hover(state, byte_offset, &self.workspace) {
             Some(hover) => serde_json::to_value(hover).unwrap(),
             None => Value::Null,
         };
@@ -195,14 +267,14 @@ impl LspState {
             state
                 .line_index
                 .position_to_byte_offset(&state.text, pos.line, pos.character);
-        let result = match find_definition(state, &uri, byte_offset) {
+        let result = match find_definition(state, &uri, byte_offset, &self.workspace) {
             Some(location) => serde_json::to_value(location).unwrap(),
             None => Value::Null,
         };
         HandleResult::response_only(id, result)
     }
 
-    fn handle_semantic_tokens(&self, id: Option<Value>, params: Value) -> HandleResult {
+    fn handle_semantic_tokens(&mut self, id: Option<Value>, params: Value) -> HandleResult {
         let Ok(params) = serde_json::from_value::<SemanticTokensParams>(params) else {
             return HandleResult::response_only(id, Value::Null);
         };
@@ -210,8 +282,37 @@ impl LspState {
         let Some(state) = self.documents.get(&uri) else {
             return HandleResult::response_only(id, Value::Null);
         };
-        let tokens = collect_semantic_tokens(state);
-        let result = SemanticTokensResult::Tokens(SemanticTokens {
+        let version = self.document_versions.get(&uri).copied();
+        let tokens = self.semantic_tokens.full(&uri, version, state);
+        let result = SemanticTokensResult::Tokens(tokens);
+        HandleResult::response_only(id, serde_json::to_value(result).unwrap())
+    }
+
+    fn handle_semantic_tokens_delta(&mut self, id: Option<Value>, params: Value) -> HandleResult {
+        let Ok(params) = serde_json::from_value::<SemanticTokensDeltaParams>(params) else {
+            return HandleResult::response_only(id, Value::Null);
+        };
+        let uri = params.text_document.uri.to_string();
+        let Some(state) = self.documents.get(&uri) else {
+            return HandleResult::response_only(id, Value::Null);
+        };
+        let version = self.document_versions.get(&uri).copied();
+        let result = self
+            .semantic_tokens
+            .delta(&uri, version, state, &params.previous_result_id);
+        HandleResult::response_only(id, serde_json::to_value(result).unwrap())
+    }
+
+    fn handle_semantic_tokens_range(&self, id: Option<Value>, params: Value) -> HandleResult {
+        let Ok(params) = serde_json::from_value::<SemanticTokensRangeParams>(params) else {
+            return HandleResult::response_only(id, Value::Null);
+        };
+        let uri = params.text_document.uri.to_string();
+        let Some(state) = self.documents.get(&uri) else {
+            return HandleResult::response_only(id, Value::Null);
+        };
+        let tokens = collect_semantic_tokens_range(state, params.range);
+        let result = SemanticTokensRangeResult::Tokens(SemanticTokens {
             result_id: None,
             data: tokens,
         });
@@ -252,6 +353,146 @@ impl LspState {
         HandleResult::response_only(id, result)
     }
 
+    fn handle_inlay_hint(&self, id: Option<Value>, params: Value) -> HandleResult {
+        let Ok(params) = serde_json::from_value::<InlayHintParams>(params) else {
+            return HandleResult::response_only(id, Value::Null);
+        };
+        let uri = params.text_document.uri.to_string();
+        let Some(state) = self.documents.get(&uri) else {
+            return HandleResult::response_only(id, Value::Null);
+        };
+        let today = self.today.or_else(inlay_hints::today);
+        let hints = collect_inlay_hints(state, params.range, today);
+        HandleResult::response_only(id, serde_json::to_value(hints).unwrap())
+    }
+
+    fn handle_code_lens(&self, id: Option<Value>, params: Value) -> HandleResult {
+        let Ok(params) = serde_json::from_value::<CodeLensParams>(params) else {
+            return HandleResult::response_only(id, Value::Null);
+        };
+        let uri = params.text_document.uri.to_string();
+        let Some(state) = self.documents.get(&uri) else {
+            return HandleResult::response_only(id, Value::Null);
+        };
+        let lenses = collect_code_lenses(state);
+        HandleResult::response_only(id, serde_json::to_value(lenses).unwrap())
+    }
+
+    fn handle_selection_range(&self, id: Option<Value>, params: Value) -> HandleResult {
+        let Ok(params) = serde_json::from_value::<SelectionRangeParams>(params) else {
+            return HandleResult::response_only(id, Value::Null);
+        };
+        let uri = params.text_document.uri.to_string();
+        let Some(state) = self.documents.get(&uri) else {
+            return HandleResult::response_only(id, Value::Null);
+        };
+        let ranges = collect_selection_ranges(state, &params.positions);
+        HandleResult::response_only(id, serde_json::to_value(ranges).unwrap())
+    }
+
+    fn handle_linked_editing_range(&self, id: Option<Value>, params: Value) -> HandleResult {
+        let Ok(params) = serde_json::from_value::<LinkedEditingRangeParams>(params) else {
+            return HandleResult::response_only(id, Value::Null);
+        };
+        let uri = params
+            .text_document_position_params
+            .text_document
+            .uri
+            .to_string();
+        let pos = params.text_document_position_params.position;
+        let Some(state) = self.documents.get(&uri) else {
+            return HandleResult::response_only(id, Value::Null);
+        };
+        let byte_offset =
+            state
+                .line_index
+                .position_to_byte_offset(&state.text, pos.line, pos.character);
+        let result = match linked_editing_ranges(state, byte_offset) {
+            Some(ranges) => serde_json::to_value(ranges).unwrap(),
+            None => Value::Null,
+        };
+        HandleResult::response_only(id, result)
+    }
+
+    fn handle_document_link(&self, id: Option<Value>, params: Value) -> HandleResult {
+        let Ok(params) = serde_json::from_value::<DocumentLinkParams>(params) else {
+            return HandleResult::response_only(id, Value::Null);
+        };
+        let uri = params.text_document.uri.to_string();
+        let Some(state) = self.documents.get(&uri) else {
+            return HandleResult::response_only(id, Value::Null);
+        };
+        let links = collect_document_links(state, &self.preview_config, &self.workspace);
+        HandleResult::response_only(id, serde_json::to_value(links).unwrap())
+    }
+
+    /// `sevenmark/preview`: the document rendered to HTML with `data-start`/
+    /// `data-end` attributes
+    fn handle_preview(&mut self, id: Option<Value>, params: Value) -> HandleResult {
+        let Ok(params) = serde_json::from_value::<PreviewParams>(params) else {
+            return HandleResult::response_only(id, Value::Null);
+        };
+        let uri = params.text_document.uri.to_string();
+        let Some(state) = self.documents.get(&uri) else {
+            return HandleResult::response_only(id, Value::Null);
+        };
+        let config = params.config.as_ref().unwrap_or(&self.preview_config);
+        let preview = Preview::render(state, config, &self.workspace);
+        let result = PreviewResult {
+            html: preview.html.clone(),
+            version: self.document_versions.get(&uri).copied(),
+        };
+        self.previews.insert(uri, preview);
+        HandleResult::response_only(id, serde_json::to_value(result).unwrap())
+    }
+
+    /// `sevenmark/scrollSync`: maps an editor position or a preview offset to
+    /// both, plus the innermost preview anchor at that location
+    fn handle_scroll_sync(&mut self, id: Option<Value>, params: Value) -> HandleResult {
+        let Ok(params) = serde_json::from_value::<ScrollSyncParams>(params) else {
+            return HandleResult::response_only(id, Value::Null);
+        };
+        let uri = params.text_document.uri.to_string();
+        let Some(state) = self.documents.get(&uri) else {
+            return HandleResult::response_only(id, Value::Null);
+        };
+        // Reuse the anchors of the last `sevenmark/preview` while the text is unchanged
+        let preview = self
+            .previews
+            .entry(uri)
+            .or_insert_with(|| Preview::render(state, &self.preview_config, &self.workspace));
+        let result = match ScrollSync::from_params(state, preview, &params) {
+            Some(sync) => serde_json::to_value(sync).unwrap(),
+            None => Value::Null,
+        };
+        HandleResult::response_only(id, result)
+    }
+
+    /// `sevenmark/includeGraph`: the templates a page includes and the pages
+    /// that include it
+    fn handle_include_graph(&self, id: Option<Value>, params: Value) -> HandleResult {
+        let Ok(params) = serde_json::from_value::<IncludeGraphParams>(params) else {
+            return HandleResult::response_only(id, Value::Null);
+        };
+        let uri = params.text_document.uri;
+        let state = self.documents.get(&uri.to_string());
+        let graph = IncludeGraph::new(&uri, state, &self.workspace);
+        HandleResult::response_only(id, serde_json::to_value(graph).unwrap())
+    }
+
+    fn handle_workspace_symbol(&self, id: Option<Value>, params: Value) -> HandleResult {
+        let Ok(params) = serde_json::from_value::<WorkspaceSymbolParams>(params) else {
+            return HandleResult::response_only(id, Value::Null);
+        };
+        let open: Vec<_> = self
+            .documents
+            .iter()
+            .filter_map(|(uri, state)| Some((uri.parse().ok()?, state)))
+            .collect();
+        let symbols = collect_workspace_symbols(&params.query, &self.workspace, &open);
+        HandleResult::response_only(id, serde_json::to_value(symbols).unwrap())
+    }
+
     // ── Notification handlers ────────────────────────────────────────────
 
     fn handle_did_open(&mut self, params: Value) -> HandleResult {
@@ -287,6 +528,10 @@ impl LspState {
         let uri_key = uri.to_string();
         self.documents.remove(&uri_key);
         self.document_versions.remove(&uri_key);
+        self.previews.remove(&uri_key);
+        self.semantic_tokens.remove(&uri_key);
+        // Unsaved edits no longer count for the other pages
+        self.workspace.restore(&uri);
 
         // Push empty diagnostics to clear
         let notification = make_notification(
@@ -305,28 +550,65 @@ impl LspState {
         }
     }
 
-    // ── Internal helpers ─────────────────────────────────────────────────
+    /// `sevenmark/workspaceDocuments`: replaces the workspace with pages sent
+    /// by the client (for hosts that cannot read the workspace folder), then
+    /// re-checks every open document against it.
+    fn handle_workspace_documents(&mut self, params: Value) -> HandleResult {
+        #[derive(serde::Deserialize)]
+        struct Params {
+            documents: Vec<Page>,
+        }
+        #[derive(serde::Deserialize)]
+        struct Page {
+            namespace: String,
+            title: String,
+            uri: Uri,
+            text: String,
+        }
 
-    fn on_change(&mut self, uri: Uri, version: Option<i32>, text: String) -> HandleResult {
-        let uri_key = uri.to_string();
+        let Ok(params) = serde_json::from_value::<Params>(params) else {
+            return HandleResult::empty();
+        };
+        let documents = params
+            .documents
+            .into_iter()
+            .filter_map(|page| {
+                Some(SourceDocument {
+                    key: DocumentKey::new(Namespace::parse(&page.namespace)?, page.title),
+                    uri: page.uri,
+                    text: page.text,
+                })
+            })
+            .collect();
+        self.workspace = WorkspaceIndex::from_source(MemorySource::new(documents));
 
-        if let Some(version) = version {
-            if let Some(&prev_version) = self.document_versions.get(&uri_key) {
-                if version < prev_version {
-                    return HandleResult::empty();
-                }
-            }
+        let mut notifications = Vec::new();
+        for (uri_key, state) in &self.documents {
+            let Ok(uri) = uri_key.parse::<Uri>() else {
+                continue;
+            };
+            self.workspace.update(&uri, state);
+        }
+        for (uri_key, state) in &self.documents {
+            let Ok(uri) = uri_key.parse::<Uri>() else {
+                continue;
+            };
+            let version = self.document_versions.get(uri_key).copied();
+            notifications.push(self.publish_diagnostics(uri, state, version));
         }
 
-        let state = DocumentState::new(text);
-        let diagnostics = collect_diagnostics(&state);
-
-        self.documents.insert(uri_key.clone(), state);
-        if let Some(version) = version {
-            self.document_versions.insert(uri_key, version);
+        HandleResult {
+            response: None,
+            notifications,
         }
+    }
 
-        let notification = make_notification(
+    // ── Internal helpers ─────────────────────────────────────────────────
+
+    fn publish_diagnostics(&self, uri: Uri, state: &DocumentState, version: Option<i32>) -> String {
+        let mut diagnostics = collect_diagnostics(state);
+        diagnostics.extend(collect_workspace_diagnostics(state, &self.workspace));
+        make_notification(
             "textDocument/publishDiagnostics",
             serde_json::to_value(PublishDiagnosticsParams {
                 uri,
@@ -334,7 +616,28 @@ impl LspState {
                 version,
             })
             .unwrap(),
-        );
+        )
+    }
+
+    fn on_change(&mut self, uri: Uri, version: Option<i32>, text: String) -> HandleResult {
+        let uri_key = uri.to_string();
+
+        if let Some(version) = version
+            && let Some(&prev_version) = self.document_versions.get(&uri_key)
+            && version < prev_version
+        {
+            return HandleResult::empty();
+        }
+
+        let state = DocumentState::new(text);
+        self.workspace.update(&uri, &state);
+        let notification = self.publish_diagnostics(uri, &state, version);
+
+        self.previews.remove(&uri_key);
+        self.documents.insert(uri_key.clone(), state);
+        if let Some(version) = version {
+            self.document_versions.insert(uri_key, version);
+        }
 
         HandleResult {
             response: None,
@@ -511,4 +814,302 @@ mod tests {
         let diags = &notif["params"]["diagnostics"];
         assert!(diags.as_array().unwrap().is_empty());
     }
+
+    #[test]
+    fn workspace_documents_enable_cross_document_checks() {
+        let mut state = LspState::new();
+        let open = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": {
+                "textDocument": {
+                    "uri": "file:///wiki/Document/Home.sm",
+                    "languageId": "sevenmark",
+                    "version": 1,
+                    "text": "[[#document=\"Rust\"]] [[#document=\"Go\"]]"
+                }
+            }
+        });
+        let result = state.handle_message(&open.to_string());
+        let notif: Value = serde_json::from_str(&result.notifications[0]).unwrap();
+        assert!(
+            notif["params"]["diagnostics"]
+                .as_array()
+                .unwrap()
+                .is_empty()
+        );
+
+        let pages = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "sevenmark/workspaceDocuments",
+            "params": {
+                "documents": [
+                    { "namespace": "Document", "title": "Rust", "uri": "file:///wiki/Document/Rust.sm", "text": "" },
+                    { "namespace": "Document", "title": "Home", "uri": "file:///wiki/Document/Home.sm", "text": "" }
+                ]
+            }
+        });
+        let result = state.handle_message(&pages.to_string());
+        assert_eq!(result.notifications.len(), 1);
+        let notif: Value = serde_json::from_str(&result.notifications[0]).unwrap();
+        let diags = notif["params"]["diagnostics"].as_array().unwrap();
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0]["code"], "missing-document");
+        assert!(
+            diags[0]["message"]
+                .as_str()
+                .unwrap()
+                .contains("Document:Go")
+        );
+    }
+
+    #[test]
+    fn preview_and_scroll_sync() {
+        let mut state = LspState::new();
+        let init = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": { "initializationOptions": { "preview": { "documentBaseUrl": "/w/" } } }
+        });
+        state.handle_message(&init.to_string());
+        let open = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": {
+                "textDocument": {
+                    "uri": "file:///test.sm",
+                    "languageId": "sevenmark",
+                    "version": 3,
+                    "text": "intro\n\n[[#document=\"Rust\" Rust]] **bold**"
+                }
+            }
+        });
+        state.handle_message(&open.to_string());
+
+        let preview = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "sevenmark/preview",
+            "params": { "textDocument": { "uri": "file:///test.sm" } }
+        });
+        let result = state.handle_message(&preview.to_string());
+        let resp: Value = serde_json::from_str(&result.response.unwrap()).unwrap();
+        assert_eq!(resp["result"]["version"], 3);
+        let html = resp["result"]["html"].as_str().unwrap();
+        assert!(html.contains("href=\"/w/Rust\""));
+        assert!(html.contains("<strong data-start=\"33\""));
+
+        let sync = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "sevenmark/scrollSync",
+            "params": { "textDocument": { "uri": "file:///test.sm" }, "position": { "line": 2, "character": 27 } }
+        });
+        let result = state.handle_message(&sync.to_string());
+        let resp: Value = serde_json::from_str(&result.response.unwrap()).unwrap();
+        assert_eq!(resp["result"]["offset"], 34);
+        assert_eq!(resp["result"]["anchor"]["start"], 33);
+
+        let back = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 4,
+            "method": "sevenmark/scrollSync",
+            "params": { "textDocument": { "uri": "file:///test.sm" }, "offset": 33 }
+        });
+        let result = state.handle_message(&back.to_string());
+        let resp: Value = serde_json::from_str(&result.response.unwrap()).unwrap();
+        assert_eq!(resp["result"]["position"]["line"], 2);
+        assert_eq!(resp["result"]["position"]["character"], 26);
+    }
+
+    #[test]
+    fn semantic_tokens_delta_after_edit() {
+        let mut state = LspState::new();
+        let open = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": {
+                "textDocument": {
+                    "uri": "file:///test.sm",
+                    "languageId": "sevenmark",
+                    "version": 1,
+                    "text": "**a**"
+                }
+            }
+        });
+        state.handle_message(&open.to_string());
+
+        let full = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "textDocument/semanticTokens/full",
+            "params": { "textDocument": { "uri": "file:///test.sm" } }
+        });
+        let result = state.handle_message(&full.to_string());
+        let resp: Value = serde_json::from_str(&result.response.unwrap()).unwrap();
+        let result_id = resp["result"]["resultId"].as_str().unwrap().to_string();
+
+        let change = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didChange",
+            "params": {
+                "textDocument": { "uri": "file:///test.sm", "version": 2 },
+                "contentChanges": [{ "text": "**a** ~~b~~" }]
+            }
+        });
+        state.handle_message(&change.to_string());
+
+        let delta = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "textDocument/semanticTokens/full/delta",
+            "params": { "textDocument": { "uri": "file:///test.sm" }, "previousResultId": result_id }
+        });
+        let result = state.handle_message(&delta.to_string());
+        let resp: Value = serde_json::from_str(&result.response.unwrap()).unwrap();
+        let edits = resp["result"]["edits"].as_array().unwrap();
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0]["start"], 5);
+        assert_eq!(edits[0]["deleteCount"], 0);
+        assert_ne!(resp["result"]["resultId"], result_id.as_str());
+    }
+
+    #[test]
+    fn inlay_hints_use_the_date_set_by_the_host() {
+        let mut state = LspState::new();
+        state.set_today(20_745); // 2026-10-19
+        let open = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": {
+                "textDocument": {
+                    "uri": "file:///test.sm",
+                    "languageId": "sevenmark",
+                    "version": 1,
+                    "text": "# Title\n[dday(2026-10-20)] {{{#if false :: x}}}"
+                }
+            }
+        });
+        state.handle_message(&open.to_string());
+
+        let hints = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "textDocument/inlayHint",
+            "params": {
+                "textDocument": { "uri": "file:///test.sm" },
+                "range": {
+                    "start": { "line": 0, "character": 0 },
+                    "end": { "line": 2, "character": 0 }
+                }
+            }
+        });
+        let result = state.handle_message(&hints.to_string());
+        let resp: Value = serde_json::from_str(&result.response.unwrap()).unwrap();
+        let labels: Vec<_> = resp["result"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|hint| hint["label"].as_str().unwrap())
+            .collect();
+        assert_eq!(labels, ["1", "= D-1"]);
+
+        let lenses = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "textDocument/codeLens",
+            "params": { "textDocument": { "uri": "file:///test.sm" } }
+        });
+        let result = state.handle_message(&lenses.to_string());
+        let resp: Value = serde_json::from_str(&result.response.unwrap()).unwrap();
+        assert_eq!(resp["result"][0]["command"]["title"], "condition is false");
+        assert_eq!(resp["result"][0]["range"]["start"]["line"], 1);
+    }
+
+    #[test]
+    fn document_links_use_preview_base_urls() {
+        let mut state = LspState::new();
+        let init = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "initializationOptions": {
+                    "preview": { "documentBaseUrl": "https://wiki.example/w/" }
+                }
+            }
+        });
+        let result = state.handle_message(&init.to_string());
+        let resp: Value = serde_json::from_str(&result.response.unwrap()).unwrap();
+        let capabilities = &resp["result"]["capabilities"];
+        assert_eq!(capabilities["selectionRangeProvider"], true);
+        assert_eq!(capabilities["linkedEditingRangeProvider"], true);
+
+        let open = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": {
+                "textDocument": {
+                    "uri": "file:///test.sm",
+                    "languageId": "sevenmark",
+                    "version": 1,
+                    "text": "[[#document=\"Rust\" Rust]]"
+                }
+            }
+        });
+        state.handle_message(&open.to_string());
+
+        let links = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "textDocument/documentLink",
+            "params": { "textDocument": { "uri": "file:///test.sm" } }
+        });
+        let result = state.handle_message(&links.to_string());
+        let resp: Value = serde_json::from_str(&result.response.unwrap()).unwrap();
+        assert_eq!(resp["result"][0]["target"], "https://wiki.example/w/Rust");
+    }
+
+    #[test]
+    fn include_graph_and_workspace_symbols() {
+        let mut state = LspState::new();
+        let pages = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "sevenmark/workspaceDocuments",
+            "params": {
+                "documents": [
+                    { "namespace": "Document", "title": "Infobox", "uri": "file:///wiki/Document/Infobox.sm", "text": "# Usage" },
+                    { "namespace": "Document", "title": "Rust", "uri": "file:///wiki/Document/Rust.sm", "text": "{{{#include Infobox}}}" }
+                ]
+            }
+        });
+        state.handle_message(&pages.to_string());
+
+        let graph = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "sevenmark/includeGraph",
+            "params": { "textDocument": { "uri": "file:///wiki/Document/Infobox.sm" } }
+        });
+        let result = state.handle_message(&graph.to_string());
+        let resp: Value = serde_json::from_str(&result.response.unwrap()).unwrap();
+        assert_eq!(resp["result"]["document"], "Document:Infobox");
+        assert_eq!(resp["result"]["includedBy"][0]["document"], "Document:Rust");
+        assert_eq!(
+            resp["result"]["includedBy"][0]["location"]["uri"],
+            "file:///wiki/Document/Rust.sm"
+        );
+
+        let symbols = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "workspace/symbol",
+            "params": { "query": "usage" }
+        });
+        let result = state.handle_message(&symbols.to_string());
+        let resp: Value = serde_json::from_str(&result.response.unwrap()).unwrap();
+        assert_eq!(resp["result"][0]["name"], "1 Usage");
+        assert_eq!(resp["result"][0]["containerName"], "Document:Infobox");
+    }
 }
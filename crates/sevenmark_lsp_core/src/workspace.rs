@@ -0,0 +1,21 @@
+//! Cross-document intelligence for a workspace of wiki pages.
+//!
+//! A [`WorkspaceIndex`] is built from a [`DocumentSource`] — the workspace
+//! folder's `Namespace/Title.sm` files ([`FolderSource`]) or documents handed
+//! over by the client ([`MemorySource`]). Open documents replace their indexed
+//! copy on every edit, and get it back from the source when closed.
+
+mod diagnostics;
+mod include_graph;
+mod index;
+mod links;
+mod source;
+mod symbols;
+
+pub use diagnostics::collect_workspace_diagnostics;
+pub use include_graph::{Include, IncludeEdge, IncludeGraph, IncludeGraphParams};
+pub use index::*;
+pub use source::*;
+pub use symbols::{PageSymbol, collect_workspace_symbols};
+
+pub(crate) use links::{Link, element_links, link_at, media_links};
@@ -0,0 +1,110 @@
+use ls_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
+use sevenmark_ast::Element;
+
+use super::index::WorkspaceIndex;
+use super::links::element_links;
+use super::source::Namespace;
+use crate::ast_walk::visit_elements;
+use crate::document::DocumentState;
+
+/// Warnings for links to pages or anchors that are not in the workspace.
+///
+/// A namespace is only checked once the workspace has pages in it, so a
+/// workspace without a `File/` folder does not flag every image. A category
+/// exists as soon as one page is filed under it.
+pub fn collect_workspace_diagnostics(
+    state: &DocumentState,
+    workspace: &WorkspaceIndex,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    if workspace.is_empty() {
+        return diagnostics;
+    }
+
+    visit_elements(&state.elements, &mut |element| {
+        // Filing a page under a new category is how categories are created
+        if matches!(element, Element::Category(_)) {
+            return;
+        }
+        for link in element_links(element) {
+            let message = if !workspace.contains(&link.target) {
+                if link.target.namespace != Namespace::Category
+                    && !workspace.has_namespace(link.target.namespace)
+                {
+                    continue;
+                }
+                (
+                    "missing-document",
+                    format!("`{}` does not exist in the workspace", link.target),
+                )
+            } else if let Some(anchor) = &link.anchor
+                && workspace
+                    .get(&link.target)
+                    .is_some_and(|page| page.anchor(anchor).is_none())
+            {
+                (
+                    "missing-anchor",
+                    format!("`{}` has no anchor `{anchor}`", link.target),
+                )
+            } else {
+                continue;
+            };
+
+            let (start, end) = state.line_index.span_to_range(&state.text, &link.span);
+            diagnostics.push(Diagnostic {
+                range: Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1)),
+                severity: Some(DiagnosticSeverity::WARNING),
+                code: Some(NumberOrString::String(message.0.to_string())),
+                source: Some("sevenmark".to_string()),
+                message: message.1,
+                ..Default::default()
+            });
+        }
+    });
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workspace::test_index;
+
+    fn messages(text: &str, workspace: &WorkspaceIndex) -> Vec<String> {
+        collect_workspace_diagnostics(&DocumentState::new(text.to_string()), workspace)
+            .into_iter()
+            .map(|d| d.message)
+            .collect()
+    }
+
+    #[test]
+    fn missing_pages_and_anchors_are_reported() {
+        let workspace = test_index(&[
+            (Namespace::Document, "Rust", "# Intro\n[anchor(traits)]"),
+            (Namespace::Document, "Infobox", "[var(name)]"),
+        ]);
+        let text = concat!(
+            "[[#document=\"Rust\" #anchor=\"traits\"]] [[#document=\"Rust\" #anchor=\"s-1\"]]\n",
+            "[[#document=\"Rust\" #anchor=\"lifetimes\"]] [[#document=\"Go\"]]\n",
+            "{{{#include Infobox}}} {{{#include Navbox}}}\n",
+            // No File/ or User/ pages in the workspace: not checked
+            "[[#file=\"logo.png\"]] [[#user=\"alice\"]]\n",
+            "{{{#category Languages}}} [[#category=\"Langauges\"]]",
+        );
+
+        assert_eq!(
+            messages(text, &workspace),
+            [
+                "`Document:Rust` has no anchor `lifetimes`",
+                "`Document:Go` does not exist in the workspace",
+                "`Document:Navbox` does not exist in the workspace",
+                "`Category:Langauges` does not exist in the workspace",
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_workspace_checks_nothing() {
+        assert!(messages("[[#document=\"Go\"]]", &WorkspaceIndex::new()).is_empty());
+    }
+}
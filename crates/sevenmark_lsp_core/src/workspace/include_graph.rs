@@ -0,0 +1,174 @@
+use ls_types::{Location, Position, Range, TextDocumentIdentifier, Uri};
+use serde::{Deserialize, Serialize};
+use sevenmark_ast::Element;
+
+use super::index::WorkspaceIndex;
+use super::links::element_links;
+use super::source::DocumentKey;
+use crate::ast_walk::visit_elements;
+use crate::document::DocumentState;
+
+/// `{{{#include}}}` written in a page
+#[derive(Debug, Clone)]
+pub struct Include {
+    pub target: DocumentKey,
+    pub range: Range,
+}
+
+pub(crate) fn collect_includes(state: &DocumentState) -> Vec<Include> {
+    let mut includes = Vec::new();
+    visit_elements(&state.elements, &mut |element| {
+        if !matches!(element, Element::Include(_)) {
+            return;
+        }
+        for link in element_links(element) {
+            let (start, end) = state.line_index.span_to_range(&state.text, &link.span);
+            includes.push(Include {
+                target: link.target,
+                range: Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1)),
+            });
+        }
+    });
+    includes
+}
+
+/// Params of `sevenmark/includeGraph`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncludeGraphParams {
+    pub text_document: TextDocumentIdentifier,
+}
+
+/// Result of `sevenmark/includeGraph`. Includes are substituted one level
+/// deep, so a page is only affected by the templates it includes directly.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncludeGraph {
+    /// `Namespace:Title` of the page, when it is part of the workspace
+    pub document: Option<String>,
+    /// Templates this page includes, in document order
+    pub includes: Vec<IncludeEdge>,
+    /// Pages that include this one, by namespace and title
+    pub included_by: Vec<IncludeEdge>,
+}
+
+/// One `{{{#include}}}` between this page and another
+#[derive(Debug, Clone, Serialize)]
+pub struct IncludeEdge {
+    /// `Namespace:Title` of the other page
+    pub document: String,
+    /// The other page's file; `None` for a template missing from the workspace
+    pub uri: Option<Uri>,
+    /// The include element, in the including page
+    pub location: Location,
+}
+
+impl IncludeGraph {
+    /// Graph of the page at `uri`, reading its includes from `state` when it
+    /// is open and from the workspace index otherwise
+    pub fn new(uri: &Uri, state: Option<&DocumentState>, workspace: &WorkspaceIndex) -> Self {
+        let key = workspace.key_for_uri(uri);
+
+        let includes = state
+            .map(collect_includes)
+            .or_else(|| Some(workspace.get(key.as_ref()?)?.includes.clone()))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|include| IncludeEdge {
+                document: include.target.to_string(),
+                uri: workspace.get(&include.target).map(|page| page.uri.clone()),
+                location: Location {
+                    uri: uri.clone(),
+                    range: include.range,
+                },
+            })
+            .collect();
+
+        let included_by = key
+            .as_ref()
+            .map(|key| {
+                workspace
+                    .documents()
+                    .flat_map(|(includer, page)| {
+                        page.includes
+                            .iter()
+                            .filter(|include| &include.target == key)
+                            .map(|include| IncludeEdge {
+                                document: includer.to_string(),
+                                uri: Some(page.uri.clone()),
+                                location: Location {
+                                    uri: page.uri.clone(),
+                                    range: include.range,
+                                },
+                            })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            document: key.map(|key| key.to_string()),
+            includes,
+            included_by,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workspace::{Namespace, test_index};
+
+    #[test]
+    fn graph_lists_both_directions() {
+        let workspace = test_index(&[
+            (Namespace::Document, "Infobox", "{{{#include Box}}}"),
+            (
+                Namespace::Document,
+                "Rust",
+                "{{{#include Infobox}}}\n{{{#include Infobox}}}",
+            ),
+            (
+                Namespace::Document,
+                "Go",
+                "{{{#include #namespace=\"User\" Infobox}}}",
+            ),
+            (Namespace::Document, "Box", ""),
+        ]);
+        let uri: Uri = "file:///wiki/Document/Infobox.sm".parse().unwrap();
+        let state = DocumentState::new("{{{#include Box}}} {{{#include Missing}}}".to_string());
+
+        let graph = IncludeGraph::new(&uri, Some(&state), &workspace);
+        assert_eq!(graph.document.as_deref(), Some("Document:Infobox"));
+
+        let includes: Vec<_> = graph
+            .includes
+            .iter()
+            .map(|edge| (edge.document.as_str(), edge.uri.is_some()))
+            .collect();
+        assert_eq!(
+            includes,
+            [("Document:Box", true), ("Document:Missing", false)]
+        );
+
+        let included_by: Vec<_> = graph
+            .included_by
+            .iter()
+            .map(|edge| (edge.document.as_str(), edge.location.range.start.line))
+            .collect();
+        assert_eq!(included_by, [("Document:Rust", 0), ("Document:Rust", 1)]);
+    }
+
+    #[test]
+    fn closed_pages_use_the_index() {
+        let workspace = test_index(&[
+            (Namespace::Document, "Rust", "{{{#include Infobox}}}"),
+            (Namespace::Document, "Infobox", ""),
+        ]);
+        let uri: Uri = "file:///wiki/Document/Rust.sm".parse().unwrap();
+        let graph = IncludeGraph::new(&uri, None, &workspace);
+        assert_eq!(graph.includes.len(), 1);
+        assert_eq!(graph.includes[0].document, "Document:Infobox");
+        assert!(graph.included_by.is_empty());
+    }
+}
@@ -0,0 +1,344 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use ls_types::{Position, Range, Uri};
+use sevenmark_ast::{Element, HeaderElement, SECTION_ID_PREFIX, Span};
+use sevenmark_utils::extract_plain_text;
+
+use super::include_graph::{Include, collect_includes};
+use super::source::{DocumentKey, DocumentSource, Namespace};
+use super::symbols::{PageSymbol, collect_page_symbols};
+use crate::ast_walk::visit_elements;
+use crate::document::DocumentState;
+
+/// Link target inside a page: `[anchor(name)]` or a header's section id
+#[derive(Debug, Clone)]
+pub struct Anchor {
+    pub name: String,
+    /// Header text for section anchors
+    pub heading: Option<String>,
+    pub range: Range,
+}
+
+/// Variable a page reads with `[var(name)]`; include parameters set it
+#[derive(Debug, Clone)]
+pub struct TemplateVariable {
+    pub name: String,
+    /// Plain-text value of the page's own `{{{#define}}}`, used when the
+    /// include does not pass the variable
+    pub default: Option<String>,
+}
+
+/// What the index keeps about one page
+#[derive(Debug, Clone)]
+pub struct IndexedDocument {
+    pub uri: Uri,
+    pub anchors: Vec<Anchor>,
+    pub variables: Vec<TemplateVariable>,
+    pub categories: Vec<String>,
+    pub includes: Vec<Include>,
+    /// What `workspace/symbol` finds in the page
+    pub symbols: Vec<PageSymbol>,
+}
+
+impl IndexedDocument {
+    pub fn new(uri: Uri, state: &DocumentState) -> Self {
+        let mut categories = Vec::new();
+        let mut variables: Vec<TemplateVariable> = Vec::new();
+        let mut defaults = BTreeMap::new();
+
+        visit_elements(&state.elements, &mut |element| match element {
+            Element::Category(c) => {
+                let name = extract_plain_text(&c.children).trim().to_string();
+                if !name.is_empty() && !categories.contains(&name) {
+                    categories.push(name);
+                }
+            }
+            Element::Variable(v) if !variables.iter().any(|var| var.name == v.name) => {
+                variables.push(TemplateVariable {
+                    name: v.name.clone(),
+                    default: None,
+                });
+            }
+            Element::Define(d) => {
+                for (name, param) in &d.parameters {
+                    defaults
+                        .entry(name.clone())
+                        .or_insert_with(|| extract_plain_text(&param.value));
+                }
+            }
+            _ => {}
+        });
+        for variable in &mut variables {
+            variable.default = defaults.remove(&variable.name);
+        }
+
+        Self {
+            uri,
+            anchors: collect_anchors(state),
+            variables,
+            categories,
+            includes: collect_includes(state),
+            symbols: collect_page_symbols(state),
+        }
+    }
+
+    pub fn anchor(&self, name: &str) -> Option<&Anchor> {
+        self.anchors.iter().find(|anchor| anchor.name == name)
+    }
+}
+
+/// Section ids (`s-1.2`, as rendered for top-level headers) and `[anchor(name)]`s
+pub(crate) fn collect_anchors(state: &DocumentState) -> Vec<Anchor> {
+    let range = |span: &Span| {
+        let (start, end) = state.line_index.span_to_range(&state.text, span);
+        Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1))
+    };
+
+    let mut anchors: Vec<_> = section_paths(&state.elements)
+        .into_iter()
+        .map(|(header, path)| Anchor {
+            name: format!("{SECTION_ID_PREFIX}{path}"),
+            heading: Some(extract_plain_text(&header.children).trim().to_string()),
+            range: range(&header.span),
+        })
+        .collect();
+
+    visit_elements(&state.elements, &mut |element| {
+        if let Element::Anchor(a) = element {
+            anchors.push(Anchor {
+                name: a.name.clone(),
+                heading: None,
+                range: range(&a.span),
+            });
+        }
+    });
+    anchors
+}
+
+/// Section number (`2.1.3`) of every top-level header, in document order
+pub(crate) fn section_paths(elements: &[Element]) -> Vec<(&HeaderElement, String)> {
+    let mut sections = Vec::new();
+    // (level, path, child count) of the open sections
+    let mut open: Vec<(usize, String, usize)> = Vec::new();
+    let mut top_level_count = 0;
+    for element in elements {
+        let Element::Header(header) = element else {
+            continue;
+        };
+        while open
+            .last()
+            .is_some_and(|(level, _, _)| *level >= header.level)
+        {
+            open.pop();
+        }
+        let path = match open.last_mut() {
+            Some((_, parent, children)) => {
+                *children += 1;
+                format!("{parent}.{children}")
+            }
+            None => {
+                top_level_count += 1;
+                top_level_count.to_string()
+            }
+        };
+        open.push((header.level, path.clone(), 0));
+        sections.push((header, path));
+    }
+    sections
+}
+
+/// Pages of the workspace, by namespace and title
+#[derive(Default)]
+pub struct WorkspaceIndex {
+    source: Option<Box<dyn DocumentSource>>,
+    documents: BTreeMap<DocumentKey, IndexedDocument>,
+}
+
+impl WorkspaceIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_source(source: impl DocumentSource + 'static) -> Self {
+        let mut index = Self {
+            source: Some(Box::new(source)),
+            documents: BTreeMap::new(),
+        };
+        index.reload();
+        index
+    }
+
+    /// Re-read every page from the source
+    pub fn reload(&mut self) {
+        self.documents.clear();
+        let Some(source) = &self.source else {
+            return;
+        };
+        for document in source.documents() {
+            let state = DocumentState::new(document.text);
+            self.documents
+                .insert(document.key, IndexedDocument::new(document.uri, &state));
+        }
+    }
+
+    /// Re-index an edited document. Returns `false` when the URI is not part
+    /// of the workspace.
+    pub fn update(&mut self, uri: &Uri, state: &DocumentState) -> bool {
+        let Some(key) = self.key_for_uri(uri) else {
+            return false;
+        };
+        self.documents
+            .insert(key, IndexedDocument::new(uri.clone(), state));
+        true
+    }
+
+    /// Re-read one page from the source, e.g. when the editor closes it
+    /// without saving or the file changes on disk. Pages the source no longer
+    /// has are dropped. Returns `false` when the URI is not part of the
+    /// workspace.
+    pub fn restore(&mut self, uri: &Uri) -> bool {
+        let Some(key) = self.key_for_uri(uri) else {
+            return false;
+        };
+        match self.source.as_ref().and_then(|source| source.document(uri)) {
+            Some(document) => {
+                let state = DocumentState::new(document.text);
+                self.documents
+                    .insert(key, IndexedDocument::new(document.uri, &state));
+            }
+            None => {
+                self.documents.remove(&key);
+            }
+        }
+        true
+    }
+
+    pub fn key_for_uri(&self, uri: &Uri) -> Option<DocumentKey> {
+        self.source.as_ref()?.key_for_uri(uri)
+    }
+
+    /// No pages are known, so cross-document checks are skipped
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    pub fn get(&self, key: &DocumentKey) -> Option<&IndexedDocument> {
+        self.documents.get(key)
+    }
+
+    /// Every page, ordered by namespace and title
+    pub fn documents(&self) -> impl Iterator<Item = (&DocumentKey, &IndexedDocument)> {
+        self.documents.iter()
+    }
+
+    pub fn has_namespace(&self, namespace: Namespace) -> bool {
+        self.documents.keys().any(|key| key.namespace == namespace)
+    }
+
+    pub fn titles(&self, namespace: Namespace) -> impl Iterator<Item = &str> {
+        self.documents
+            .keys()
+            .filter(move |key| key.namespace == namespace)
+            .map(|key| key.title.as_str())
+    }
+
+    /// Category pages plus every category a page is filed under
+    pub fn categories(&self) -> BTreeSet<&str> {
+        let mut categories: BTreeSet<&str> = self.titles(Namespace::Category).collect();
+        for document in self.documents.values() {
+            categories.extend(document.categories.iter().map(String::as_str));
+        }
+        categories
+    }
+
+    /// Whether a link to `key` resolves. Categories exist once a page uses them.
+    pub fn contains(&self, key: &DocumentKey) -> bool {
+        match key.namespace {
+            Namespace::Category => self.categories().contains(key.title.as_str()),
+            _ => self.documents.contains_key(key),
+        }
+    }
+}
+
+/// Index over in-memory pages at `file:///wiki/<Namespace>/<Title>.sm`
+#[cfg(test)]
+pub(crate) fn test_index(pages: &[(Namespace, &str, &str)]) -> WorkspaceIndex {
+    use super::source::{MemorySource, SourceDocument};
+
+    let documents = pages
+        .iter()
+        .map(|&(namespace, title, text)| SourceDocument {
+            key: DocumentKey::new(namespace, title),
+            uri: format!("file:///wiki/{namespace}/{title}.sm")
+                .parse()
+                .unwrap(),
+            text: text.to_string(),
+        })
+        .collect();
+    WorkspaceIndex::from_source(MemorySource::new(documents))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indexes_anchors_variables_and_categories() {
+        let index = test_index(&[(
+            Namespace::Document,
+            "Infobox",
+            "{{{#define #color=\"gray\"}}}\n# Intro\n## Usage\n[anchor(params)]\n# Notes\n[var(name)] [var(color)] [var(name)]\n{{{#category Templates}}}",
+        )]);
+        let page = index
+            .get(&DocumentKey::new(Namespace::Document, "Infobox"))
+            .unwrap();
+
+        let anchors: Vec<_> = page.anchors.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(anchors, ["s-1", "s-1.1", "s-2", "params"]);
+        assert_eq!(
+            page.anchor("s-1.1").unwrap().heading.as_deref(),
+            Some("Usage")
+        );
+
+        let variables: Vec<_> = page
+            .variables
+            .iter()
+            .map(|v| (v.name.as_str(), v.default.as_deref()))
+            .collect();
+        assert_eq!(variables, [("name", None), ("color", Some("gray"))]);
+
+        assert!(index.contains(&DocumentKey::new(Namespace::Category, "Templates")));
+        assert!(!index.contains(&DocumentKey::new(Namespace::Document, "Missing")));
+    }
+
+    #[test]
+    fn edits_replace_the_indexed_copy() {
+        let mut index = test_index(&[(Namespace::Document, "Page", "[anchor(old)]")]);
+        let uri: Uri = "file:///wiki/Document/Page.sm".parse().unwrap();
+        assert!(index.update(&uri, &DocumentState::new("[anchor(new)]".to_string())));
+
+        let key = DocumentKey::new(Namespace::Document, "Page");
+        assert!(index.get(&key).unwrap().anchor("new").is_some());
+        assert!(index.get(&key).unwrap().anchor("old").is_none());
+
+        let other: Uri = "file:///elsewhere/Page.sm".parse().unwrap();
+        assert!(!index.update(&other, &DocumentState::new(String::new())));
+    }
+
+    #[test]
+    fn restore_drops_unsaved_edits() {
+        let mut index = test_index(&[(Namespace::Document, "Page", "[anchor(saved)]")]);
+        let uri: Uri = "file:///wiki/Document/Page.sm".parse().unwrap();
+        index.update(&uri, &DocumentState::new("[anchor(unsaved)]".to_string()));
+
+        assert!(index.restore(&uri));
+        let page = index
+            .get(&DocumentKey::new(Namespace::Document, "Page"))
+            .unwrap();
+        assert!(page.anchor("saved").is_some());
+        assert!(page.anchor("unsaved").is_none());
+
+        let other: Uri = "file:///elsewhere/Page.sm".parse().unwrap();
+        assert!(!index.restore(&other));
+    }
+}
@@ -0,0 +1,101 @@
+use sevenmark_ast::{Element, Parameters, Span};
+use sevenmark_utils::extract_plain_text;
+
+use super::source::{DocumentKey, Namespace};
+use crate::ast_walk::visit_elements;
+
+/// A reference from one page to another
+#[derive(Debug, Clone)]
+pub(crate) struct Link {
+    pub target: DocumentKey,
+    /// `#anchor` of a document link
+    pub anchor: Option<String>,
+    /// The parameter naming the target, or the whole element
+    pub span: Span,
+}
+
+/// Links written by one element: include/redirect/category targets and the
+/// `#file`/`#document`/`#category`/`#user` parameters of media.
+pub(crate) fn element_links(element: &Element) -> Vec<Link> {
+    let titled = |namespace, children: &[Element], span: &Span| {
+        plain(children).map(|title| Link {
+            target: DocumentKey::new(namespace, title),
+            anchor: None,
+            span: span.clone(),
+        })
+    };
+
+    match element {
+        Element::Include(e) => {
+            let namespace = Namespace::from_param(param(&e.parameters, "namespace").as_deref());
+            titled(namespace, &e.children, &e.span)
+                .into_iter()
+                .collect()
+        }
+        Element::Redirect(e) => {
+            let namespace = Namespace::from_param(param(&e.parameters, "namespace").as_deref());
+            titled(namespace, &e.children, &e.span)
+                .into_iter()
+                .collect()
+        }
+        Element::Category(e) => titled(Namespace::Category, &e.children, &e.span)
+            .into_iter()
+            .collect(),
+        Element::Media(e) => media_links(&e.parameters),
+        _ => Vec::new(),
+    }
+}
+
+pub(crate) fn media_links(parameters: &Parameters) -> Vec<Link> {
+    [
+        ("document", Namespace::Document),
+        ("file", Namespace::File),
+        ("category", Namespace::Category),
+        ("user", Namespace::User),
+    ]
+    .into_iter()
+    .filter_map(|(key, namespace)| {
+        let parameter = parameters.get(key)?;
+        Some(Link {
+            target: DocumentKey::new(namespace, plain(&parameter.value)?),
+            anchor: (namespace == Namespace::Document)
+                .then(|| param(parameters, "anchor"))
+                .flatten(),
+            span: parameter.span.clone(),
+        })
+    })
+    .collect()
+}
+
+/// Innermost link whose span contains `byte_offset`
+pub(crate) fn link_at(elements: &[Element], byte_offset: usize) -> Option<Link> {
+    let mut best: Option<Link> = None;
+    visit_elements(elements, &mut |element| {
+        let span = element.span();
+        if byte_offset < span.start || span.end <= byte_offset {
+            return;
+        }
+        for link in element_links(element) {
+            if link.span.start <= byte_offset
+                && byte_offset < link.span.end
+                && best
+                    .as_ref()
+                    .is_none_or(|b| link.span.end - link.span.start < b.span.end - b.span.start)
+            {
+                best = Some(link);
+            }
+        }
+    });
+    best
+}
+
+fn param(parameters: &Parameters, key: &str) -> Option<String> {
+    parameters.get(key).and_then(|p| plain(&p.value))
+}
+
+/// Trimmed plain text, `None` when empty
+fn plain(elements: &[Element]) -> Option<String> {
+    let text = extract_plain_text(elements);
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
@@ -0,0 +1,276 @@
+use std::fmt;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use ls_types::{InitializeParams, Uri};
+
+/// Wiki namespace; also the top-level folder name in a workspace
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Namespace {
+    Document,
+    File,
+    Category,
+    User,
+}
+
+impl Namespace {
+    pub const ALL: [Namespace; 4] = [
+        Namespace::Document,
+        Namespace::File,
+        Namespace::Category,
+        Namespace::User,
+    ];
+
+    /// Name as written in `#namespace="..."`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Namespace::Document => "Document",
+            Namespace::File => "File",
+            Namespace::Category => "Category",
+            Namespace::User => "User",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|ns| ns.as_str() == name)
+    }
+
+    /// `#namespace` value of an include/redirect; unknown names fall back to
+    /// `Document` like the server-side preprocessor does.
+    pub fn from_param(name: Option<&str>) -> Self {
+        name.and_then(Self::parse).unwrap_or(Namespace::Document)
+    }
+}
+
+impl fmt::Display for Namespace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Namespace and title of a wiki page
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DocumentKey {
+    pub namespace: Namespace,
+    pub title: String,
+}
+
+impl DocumentKey {
+    pub fn new(namespace: Namespace, title: impl Into<String>) -> Self {
+        Self {
+            namespace,
+            title: title.into(),
+        }
+    }
+}
+
+impl fmt::Display for DocumentKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.namespace, self.title)
+    }
+}
+
+/// A page supplied by a [`DocumentSource`]
+#[derive(Debug, Clone)]
+pub struct SourceDocument {
+    pub key: DocumentKey,
+    pub uri: Uri,
+    pub text: String,
+}
+
+/// Where the workspace index reads pages that are not open in the editor
+pub trait DocumentSource: Send + Sync {
+    fn documents(&self) -> Vec<SourceDocument>;
+
+    /// Current content of the page at `uri`, if the source has it
+    fn document(&self, uri: &Uri) -> Option<SourceDocument>;
+
+    /// Page an editor URI belongs to, if it is part of this source
+    fn key_for_uri(&self, uri: &Uri) -> Option<DocumentKey>;
+}
+
+/// `Namespace/Title.sm` files under a workspace folder.
+///
+/// Subfolders become `/` in the title: `Document/Rust/Traits.sm` is the
+/// document `Rust/Traits`.
+#[derive(Debug, Clone)]
+pub struct FolderSource {
+    root: PathBuf,
+}
+
+impl FolderSource {
+    pub const EXTENSION: &str = "sm";
+
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Folder of a `file://` workspace URI
+    pub fn from_uri(uri: &Uri) -> Option<Self> {
+        if uri.scheme().as_str() != "file" {
+            return None;
+        }
+        Some(Self::new(uri.to_file_path()?.into_owned()))
+    }
+
+    /// First workspace folder (or the older `rootUri`) of an `initialize` request
+    pub fn from_initialize_params(params: &InitializeParams) -> Option<Self> {
+        #[allow(deprecated)]
+        let root = params
+            .workspace_folders
+            .as_ref()
+            .and_then(|folders| folders.first())
+            .map(|folder| &folder.uri)
+            .or(params.root_uri.as_ref())?;
+        Self::from_uri(root)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn key_for_path(&self, path: &Path) -> Option<DocumentKey> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        if relative.extension()? != Self::EXTENSION {
+            return None;
+        }
+
+        let stem = relative.with_extension("");
+        let mut components = stem.iter();
+        let namespace = Namespace::parse(components.next()?.to_str()?)?;
+        let parts = components
+            .map(|part| part.to_str())
+            .collect::<Option<Vec<_>>>()?;
+        if parts.is_empty() {
+            return None;
+        }
+        Some(DocumentKey::new(namespace, parts.join("/")))
+    }
+}
+
+impl DocumentSource for FolderSource {
+    fn documents(&self) -> Vec<SourceDocument> {
+        let mut paths = Vec::new();
+        for namespace in Namespace::ALL {
+            collect_files(&self.root.join(namespace.as_str()), &mut paths);
+        }
+
+        paths
+            .into_iter()
+            .filter_map(|path| {
+                let key = self.key_for_path(&path)?;
+                let text = fs::read_to_string(&path).ok()?;
+                let uri = Uri::from_file_path(&path)?;
+                Some(SourceDocument { key, uri, text })
+            })
+            .collect()
+    }
+
+    fn document(&self, uri: &Uri) -> Option<SourceDocument> {
+        let key = self.key_for_uri(uri)?;
+        let text = fs::read_to_string(uri.to_file_path()?).ok()?;
+        Some(SourceDocument {
+            key,
+            uri: uri.clone(),
+            text,
+        })
+    }
+
+    fn key_for_uri(&self, uri: &Uri) -> Option<DocumentKey> {
+        if uri.scheme().as_str() != "file" {
+            return None;
+        }
+        let path = uri.to_file_path()?;
+        // Only plain relative components, so `..` cannot escape the root
+        let inside = path
+            .strip_prefix(&self.root)
+            .ok()?
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)));
+        if !inside {
+            return None;
+        }
+        self.key_for_path(&path)
+    }
+}
+
+/// Files below `dir`, depth-first in name order
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+/// Pages handed over by the client, e.g. a web editor without file access
+#[derive(Debug, Clone, Default)]
+pub struct MemorySource {
+    documents: Vec<SourceDocument>,
+}
+
+impl MemorySource {
+    pub fn new(documents: Vec<SourceDocument>) -> Self {
+        Self { documents }
+    }
+}
+
+impl DocumentSource for MemorySource {
+    fn documents(&self) -> Vec<SourceDocument> {
+        self.documents.clone()
+    }
+
+    fn document(&self, uri: &Uri) -> Option<SourceDocument> {
+        self.documents
+            .iter()
+            .find(|doc| doc.uri.as_str() == uri.as_str())
+            .cloned()
+    }
+
+    fn key_for_uri(&self, uri: &Uri) -> Option<DocumentKey> {
+        self.document(uri).map(|doc| doc.key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folder_keys_follow_namespace_and_subfolders() {
+        let source = FolderSource::new("/wiki");
+        let key = |path: &str| source.key_for_path(Path::new(path));
+
+        assert_eq!(
+            key("/wiki/Document/Rust/Traits.sm"),
+            Some(DocumentKey::new(Namespace::Document, "Rust/Traits"))
+        );
+        assert_eq!(
+            key("/wiki/Category/Languages.sm"),
+            Some(DocumentKey::new(Namespace::Category, "Languages"))
+        );
+        assert_eq!(key("/wiki/Document/notes.txt"), None);
+        assert_eq!(key("/wiki/Drafts/Page.sm"), None);
+        assert_eq!(key("/wiki/Document.sm"), None);
+        assert_eq!(key("/elsewhere/Document/Page.sm"), None);
+    }
+
+    #[test]
+    fn folder_uri_is_decoded() {
+        let source = FolderSource::new("/wiki");
+        let uri: Uri = "file:///wiki/Document/Hello%20World.sm".parse().unwrap();
+        assert_eq!(
+            source.key_for_uri(&uri),
+            Some(DocumentKey::new(Namespace::Document, "Hello World"))
+        );
+        let outside: Uri = "file:///wiki/Document/../../etc/Page.sm".parse().unwrap();
+        assert_eq!(source.key_for_uri(&outside), None);
+    }
+}
@@ -0,0 +1,180 @@
+use ls_types::{Location, OneOf, Position, Range, SymbolKind, Uri, WorkspaceSymbol};
+use sevenmark_ast::{Element, Span};
+use sevenmark_utils::extract_plain_text;
+
+use super::index::{WorkspaceIndex, section_paths};
+use crate::ast_walk::visit_elements;
+use crate::document::DocumentState;
+
+/// A named place in a page that `workspace/symbol` can find
+#[derive(Debug, Clone)]
+pub struct PageSymbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub range: Range,
+}
+
+/// Headers (named with their section number), then `[anchor(name)]`s,
+/// `{{{#define}}}` variables and footnotes with a `#name` in document order
+pub(crate) fn collect_page_symbols(state: &DocumentState) -> Vec<PageSymbol> {
+    let range = |span: &Span| {
+        let (start, end) = state.line_index.span_to_range(&state.text, span);
+        Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1))
+    };
+
+    let mut symbols: Vec<_> = section_paths(&state.elements)
+        .into_iter()
+        .filter_map(|(header, path)| {
+            let heading = extract_plain_text(&header.children).trim().to_string();
+            (!heading.is_empty()).then(|| PageSymbol {
+                name: format!("{path} {heading}"),
+                kind: SymbolKind::STRING,
+                range: range(&header.span),
+            })
+        })
+        .collect();
+
+    visit_elements(&state.elements, &mut |element| match element {
+        Element::Anchor(a) => symbols.push(PageSymbol {
+            name: a.name.clone(),
+            kind: SymbolKind::KEY,
+            range: range(&a.span),
+        }),
+        Element::Define(d) => {
+            for name in d.parameters.keys() {
+                symbols.push(PageSymbol {
+                    name: name.clone(),
+                    kind: SymbolKind::VARIABLE,
+                    range: range(&d.span),
+                });
+            }
+        }
+        Element::Footnote(f) => {
+            let name = f
+                .parameters
+                .get("name")
+                .map(|name| extract_plain_text(&name.value).trim().to_string())
+                .unwrap_or_default();
+            if !name.is_empty() {
+                symbols.push(PageSymbol {
+                    name,
+                    kind: SymbolKind::FIELD,
+                    range: range(&f.span),
+                });
+            }
+        }
+        _ => {}
+    });
+    symbols
+}
+
+/// `workspace/symbol` over every workspace page, plus `open` documents that
+/// are not part of the workspace. A symbol matches when the query's
+/// characters appear in its name in order, ignoring case.
+pub fn collect_workspace_symbols(
+    query: &str,
+    workspace: &WorkspaceIndex,
+    open: &[(Uri, &DocumentState)],
+) -> Vec<WorkspaceSymbol> {
+    let query = query.to_lowercase();
+    let mut result = Vec::new();
+    let mut push = |symbol: &PageSymbol, uri: &Uri, container: Option<String>| {
+        if fuzzy_match(&symbol.name.to_lowercase(), &query) {
+            result.push(WorkspaceSymbol {
+                name: symbol.name.clone(),
+                kind: symbol.kind,
+                tags: None,
+                container_name: container,
+                location: OneOf::Left(Location {
+                    uri: uri.clone(),
+                    range: symbol.range,
+                }),
+                data: None,
+            });
+        }
+    };
+
+    for (key, page) in workspace.documents() {
+        for symbol in &page.symbols {
+            push(symbol, &page.uri, Some(key.to_string()));
+        }
+    }
+    for (uri, state) in open {
+        if workspace.key_for_uri(uri).is_some() {
+            continue;
+        }
+        for symbol in collect_page_symbols(state) {
+            push(&symbol, uri, None);
+        }
+    }
+    result
+}
+
+fn fuzzy_match(name: &str, query: &str) -> bool {
+    let mut name = name.chars();
+    query.chars().all(|q| name.any(|c| c == q))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workspace::{Namespace, test_index};
+
+    fn names(symbols: &[WorkspaceSymbol]) -> Vec<(&str, Option<&str>)> {
+        symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.container_name.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn page_symbols_cover_headers_anchors_defines_and_named_footnotes() {
+        let state = DocumentState::new(
+            "# Intro\n[anchor(top)]{{{#define #color=\"red\" #size=\"1\"}}}\n## Usage\nx{{{#fn #name=\"src\" Source}}}{{{#fn plain}}}".to_string(),
+        );
+        let symbols: Vec<_> = collect_page_symbols(&state)
+            .into_iter()
+            .map(|s| (s.name, s.kind))
+            .collect();
+        assert_eq!(
+            symbols,
+            [
+                ("1 Intro".to_string(), SymbolKind::STRING),
+                ("1.1 Usage".to_string(), SymbolKind::STRING),
+                ("top".to_string(), SymbolKind::KEY),
+                ("color".to_string(), SymbolKind::VARIABLE),
+                ("size".to_string(), SymbolKind::VARIABLE),
+                ("src".to_string(), SymbolKind::FIELD),
+            ]
+        );
+    }
+
+    #[test]
+    fn query_matches_workspace_and_open_documents() {
+        let workspace = test_index(&[
+            (
+                Namespace::Document,
+                "Infobox",
+                "# Parameters\n[anchor(params)]",
+            ),
+            (Namespace::Document, "Rust", "# Traits"),
+        ]);
+        let draft = DocumentState::new("[anchor(param-notes)]".to_string());
+        let open = [("untitled:Draft".parse().unwrap(), &draft)];
+
+        let symbols = collect_workspace_symbols("PARAM", &workspace, &open);
+        assert_eq!(
+            names(&symbols),
+            [
+                ("1 Parameters", Some("Document:Infobox")),
+                ("params", Some("Document:Infobox")),
+                ("param-notes", None),
+            ]
+        );
+        assert_eq!(collect_workspace_symbols("", &workspace, &[]).len(), 3);
+        assert_eq!(
+            names(&collect_workspace_symbols("trt", &workspace, &[])),
+            [("1 Traits", Some("Document:Rust"))]
+        );
+    }
+}
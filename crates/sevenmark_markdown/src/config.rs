@@ -0,0 +1,16 @@
+//! Markdown export configuration
+
+/// Configuration for Markdown export
+///
+/// Markdown leaves the page, so base URLs should usually be absolute.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MarkdownConfig<'a> {
+    /// Base URL for file/media (e.g., Cloudflare CDN URL)
+    pub file_base_url: Option<&'a str>,
+    /// Base URL for document links (e.g., "https://wiki.example.com/Document/")
+    pub document_base_url: Option<&'a str>,
+    /// Base URL for category links
+    pub category_base_url: Option<&'a str>,
+    /// Base URL for user document links
+    pub user_base_url: Option<&'a str>,
+}
@@ -0,0 +1,119 @@
+//! Markdown export context
+
+use std::collections::{HashMap, HashSet};
+
+use crate::config::MarkdownConfig;
+
+/// A footnote definition waiting to be written out
+#[derive(Debug, Clone)]
+pub(crate) struct FootnoteEntry {
+    /// GFM footnote label (`1`, or the sanitized `#name`)
+    pub label: String,
+    /// Footnote content as Markdown
+    pub content: String,
+}
+
+/// Mutable state shared while exporting one document
+pub(crate) struct MarkdownContext<'a> {
+    pub config: &'a MarkdownConfig<'a>,
+    /// Footnote definitions not yet flushed by `[fn]` or the document end
+    pub footnotes: Vec<FootnoteEntry>,
+    pub in_footnote: bool,
+    pub list_depth: usize,
+    suppress_soft_breaks_depth: usize,
+    /// Label given to each `#name`
+    named_footnotes: HashMap<String, String>,
+    /// Every label handed out so far, named or numbered
+    taken_labels: HashSet<String>,
+    next_unnamed_footnote_number: usize,
+}
+
+impl<'a> MarkdownContext<'a> {
+    pub fn new(config: &'a MarkdownConfig<'a>) -> Self {
+        Self {
+            config,
+            footnotes: Vec::new(),
+            in_footnote: false,
+            list_depth: 0,
+            suppress_soft_breaks_depth: 0,
+            named_footnotes: HashMap::new(),
+            taken_labels: HashSet::new(),
+            next_unnamed_footnote_number: 1,
+        }
+    }
+
+    pub fn enter_suppress_soft_breaks(&mut self) {
+        self.suppress_soft_breaks_depth += 1;
+    }
+
+    pub fn exit_suppress_soft_breaks(&mut self) {
+        self.suppress_soft_breaks_depth = self.suppress_soft_breaks_depth.saturating_sub(1);
+    }
+
+    pub fn is_soft_break_suppressed(&self) -> bool {
+        self.suppress_soft_breaks_depth > 0
+    }
+
+    /// Run `f` with soft-break suppression reset, as included documents render naturally
+    pub fn with_soft_breaks_restored<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let saved_depth = self.suppress_soft_breaks_depth;
+        self.suppress_soft_breaks_depth = 0;
+        let result = f(self);
+        self.suppress_soft_breaks_depth = saved_depth;
+        result
+    }
+
+    /// Register a footnote and return its label.
+    ///
+    /// Unnamed footnotes are numbered contiguously; repeated names reuse
+    /// the first definition. Numbers skip labels already taken by a name, and
+    /// a name whose label is already taken by a number gets a `-2`, `-3`...
+    /// suffix, so distinct footnotes never share a label.
+    pub fn add_footnote(&mut self, name: Option<&str>, content: String) -> String {
+        let label = match name {
+            Some(name) => {
+                if let Some(label) = self.named_footnotes.get(name) {
+                    return label.clone();
+                }
+                let base = footnote_label(name);
+                let label = (1..)
+                    .map(|n| match n {
+                        1 => base.clone(),
+                        n => format!("{base}-{n}"),
+                    })
+                    .find(|label| !self.taken_labels.contains(label))
+                    .expect("unbounded label candidates");
+                self.named_footnotes.insert(name.to_string(), label.clone());
+                label
+            }
+            None => loop {
+                let label = self.next_unnamed_footnote_number.to_string();
+                self.next_unnamed_footnote_number += 1;
+                if !self.taken_labels.contains(&label) {
+                    break label;
+                }
+            },
+        };
+
+        self.taken_labels.insert(label.clone());
+        self.footnotes.push(FootnoteEntry {
+            label: label.clone(),
+            content,
+        });
+        label
+    }
+}
+
+/// GFM labels cannot contain whitespace or brackets
+fn footnote_label(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(|c| {
+            if c.is_whitespace() || matches!(c, '[' | ']' | '^' | '\\') {
+                '-'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
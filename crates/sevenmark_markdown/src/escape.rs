@@ -0,0 +1,63 @@
+//! Markdown and inline HTML escaping
+
+/// Backslash-escape characters that start Markdown/GFM inline constructs
+pub(crate) fn escape_markdown(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '~' | '$'
+        ) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Escape text placed inside raw HTML (`<summary>`, attributes)
+pub(crate) fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Escape a leading block marker (`#`, `-`, `+`, `=`, `1.`) so a text line
+/// is not reinterpreted as a heading, list item or setext underline.
+pub(crate) fn escape_line_start(s: &str) -> String {
+    if s.starts_with(['#', '-', '+', '=']) {
+        return format!("\\{s}");
+    }
+
+    let digits = s.bytes().take_while(u8::is_ascii_digit).count();
+    if digits > 0 && s[digits..].starts_with(['.', ')']) {
+        return format!("{}\\{}", &s[..digits], &s[digits..]);
+    }
+
+    s.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_inline_markers() {
+        assert_eq!(escape_markdown("a*b_[c]|d"), "a\\*b\\_\\[c\\]\\|d");
+    }
+
+    #[test]
+    fn escapes_block_markers_at_line_start() {
+        assert_eq!(escape_line_start("# not a heading"), "\\# not a heading");
+        assert_eq!(escape_line_start("2024. year"), "2024\\. year");
+        assert_eq!(escape_line_start("C# is fine"), "C# is fine");
+    }
+}
@@ -0,0 +1,24 @@
+//! sevenmark-markdown - GitHub-flavoured Markdown exporter for SevenMark AST
+//!
+//! Converts SevenMark AST to Markdown for GitHub issues, READMEs and other
+//! documentation systems. Constructs without a Markdown equivalent degrade to
+//! the closest GFM form (`<details>` folds, `<ol type>` lists, padded tables).
+//!
+//! # Example
+//!
+//! ```rust
+//! use sevenmark_parser::core::parse_document;
+//! use sevenmark_markdown::{MarkdownConfig, render_markdown};
+//!
+//! let ast = parse_document("# Hello\n\nThis is **bold** text.");
+//! let markdown = render_markdown(&ast, &MarkdownConfig::default());
+//! ```
+
+mod config;
+mod context;
+mod escape;
+mod render;
+mod writer;
+
+pub use config::MarkdownConfig;
+pub use render::render_markdown;
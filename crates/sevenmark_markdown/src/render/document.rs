@@ -0,0 +1,113 @@
+//! Document-level Markdown rendering
+
+use sevenmark_ast::Element;
+
+use super::element::{render_elements, render_footnote_definitions};
+use crate::config::MarkdownConfig;
+use crate::context::MarkdownContext;
+use crate::writer::MarkdownWriter;
+
+/// Render a document to GitHub-flavoured Markdown
+///
+/// # Arguments
+/// * `ast` - The parsed (or processed) AST elements
+/// * `config` - Base URLs used for media and links
+pub fn render_markdown(ast: &[Element], config: &MarkdownConfig) -> String {
+    let mut ctx = MarkdownContext::new(config);
+    let mut w = MarkdownWriter::default();
+
+    render_elements(ast, &mut w, &mut ctx);
+
+    let footnotes = std::mem::take(&mut ctx.footnotes);
+    render_footnote_definitions(&footnotes, &mut w);
+
+    let mut output = w.finish();
+    output.push('\n');
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sevenmark_parser::core::parse_document;
+
+    fn markdown(input: &str) -> String {
+        render_markdown(&parse_document(input), &MarkdownConfig::default())
+    }
+
+    #[test]
+    fn test_headers_and_styles() {
+        assert_eq!(
+            markdown("# Title\n**bold** *italic* ~~gone~~"),
+            "# Title\n\n**bold** *italic* ~~gone~~\n"
+        );
+    }
+
+    #[test]
+    fn test_text_is_escaped() {
+        assert_eq!(markdown("a_b [x] 1*2"), "a\\_b \\[x\\] 1\\*2\n");
+    }
+
+    #[test]
+    fn test_ordered_list_kinds() {
+        assert_eq!(markdown("1. one\n2. two"), "1. one\n2. two\n");
+        let alpha = markdown("a. one\nb. two");
+        assert!(alpha.starts_with("<ol type=\"a\">"), "{alpha}");
+        assert!(alpha.contains("<li>\n\none\n\n</li>"), "{alpha}");
+    }
+
+    #[test]
+    fn test_nested_list_is_indented() {
+        assert_eq!(
+            markdown("- one\n  - nested\n- two"),
+            "- one\n  - nested\n- two\n"
+        );
+    }
+
+    #[test]
+    fn test_footnotes() {
+        assert_eq!(
+            markdown("A{{{#fn first}}} B{{{#fn #name=\"my note\" named}}}"),
+            "A[^1] B[^my-note]\n\n[^1]: first\n[^my-note]: named\n"
+        );
+    }
+
+    #[test]
+    fn test_footnote_labels_do_not_collide() {
+        assert_eq!(
+            markdown("A{{{#fn #name=\"1\" named}}} B{{{#fn first}}}"),
+            "A[^1] B[^2]\n\n[^1]: named\n[^2]: first\n"
+        );
+        assert_eq!(
+            markdown("A{{{#fn first}}} B{{{#fn #name=\"1\" named}}} C{{{#fn #name=\"1\" again}}}"),
+            "A[^1] B[^1-2] C[^1-2]\n\n[^1]: first\n[^1-2]: named\n"
+        );
+    }
+
+    #[test]
+    fn test_undefined_variable_is_dropped() {
+        assert_eq!(markdown("a [var(missing)] b"), "a b\n");
+    }
+
+    #[test]
+    fn test_fold_becomes_details() {
+        assert_eq!(
+            markdown("{{{#fold [[more <info>]] [[**hidden**]]}}}"),
+            "<details>\n<summary>more &lt;info&gt;</summary>\n\n**hidden**\n\n</details>\n"
+        );
+    }
+
+    #[test]
+    fn test_code_fence_outgrows_backticks() {
+        assert_eq!(
+            markdown("{{{#code #lang=\"md\" ```x```}}}"),
+            "````md\n```x```\n````\n"
+        );
+    }
+
+    #[test]
+    fn test_tex() {
+        assert_eq!(markdown("{{{#tex x^2}}}"), "$x^2$\n");
+        assert_eq!(markdown("{{{#tex #block x^2}}}"), "```math\nx^2\n```\n");
+    }
+}
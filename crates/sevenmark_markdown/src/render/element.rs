@@ -0,0 +1,238 @@
+//! Element-level Markdown rendering
+
+use sevenmark_ast::{
+    BlockQuoteElement, CodeElement, Element, FoldElement, FootnoteElement, HeaderElement,
+    TeXElement,
+};
+
+use super::{list, media, table, utils};
+use crate::context::{FootnoteEntry, MarkdownContext};
+use crate::escape::escape_html;
+use crate::writer::MarkdownWriter;
+
+/// Render multiple elements into the writer
+pub fn render_elements(elements: &[Element], w: &mut MarkdownWriter, ctx: &mut MarkdownContext) {
+    for el in elements {
+        render_element(el, w, ctx);
+    }
+}
+
+/// Render elements into a standalone Markdown string (list items, quotes, footnotes)
+pub fn render_to_string(elements: &[Element], ctx: &mut MarkdownContext) -> String {
+    let mut w = MarkdownWriter::default();
+    render_elements(elements, &mut w, ctx);
+    w.finish()
+}
+
+/// Render a single element
+fn render_element(el: &Element, w: &mut MarkdownWriter, ctx: &mut MarkdownContext) {
+    match el {
+        Element::Text(text) => w.push_text(&text.value),
+        Element::Escape(escape) => w.push_text(&escape.value),
+        Element::Error(error) => w.push_text(&error.value),
+        // Variables are substituted before export; leftovers are undefined
+        Element::Variable(_) | Element::Comment(_) => {}
+
+        Element::Bold(style) => render_wrapped("**", "**", &style.children, w, ctx),
+        Element::Italic(style) => render_wrapped("*", "*", &style.children, w, ctx),
+        Element::Strikethrough(style) => render_wrapped("~~", "~~", &style.children, w, ctx),
+        Element::Underline(style) => render_wrapped("<ins>", "</ins>", &style.children, w, ctx),
+        Element::Superscript(style) => render_wrapped("<sup>", "</sup>", &style.children, w, ctx),
+        Element::Subscript(style) => render_wrapped("<sub>", "</sub>", &style.children, w, ctx),
+
+        Element::Header(header) => render_header(header, w, ctx),
+
+        Element::Literal(literal) => render_elements(&literal.children, w, ctx),
+        Element::Styled(styled) => render_elements(&styled.children, w, ctx),
+        Element::BlockQuote(bq) => render_blockquote(bq, w, ctx),
+        Element::Fold(fold) => render_fold(fold, w, ctx),
+        Element::Ruby(ruby) => {
+            w.push_str("<ruby>");
+            render_inline(&ruby.children, w, ctx);
+            if let Some(ruby_text) = utils::get_param(&ruby.parameters, "ruby") {
+                w.push_str(&format!("<rt>{}</rt>", escape_html(&ruby_text)));
+            }
+            w.push_str("</ruby>");
+        }
+        Element::Code(code) => render_code(code, w),
+        Element::TeX(tex) => render_tex(tex, w),
+        Element::Css(_) => {}
+
+        Element::List(list) => list::render(list, w, ctx),
+        Element::Table(table) => table::render(table, w, ctx),
+
+        Element::Media(media) => media::render(media, w, ctx),
+        Element::ExternalMedia(ext) => media::render_external(&ext.provider, &ext.parameters, w),
+
+        Element::Footnote(footnote) => render_footnote(footnote, w, ctx),
+        Element::FootnoteRef(_) => {
+            let footnotes = std::mem::take(&mut ctx.footnotes);
+            render_footnote_definitions(&footnotes, w);
+        }
+
+        Element::SoftBreak(_) => {
+            if ctx.is_soft_break_suppressed() {
+                w.space();
+            } else {
+                w.line_break();
+            }
+        }
+        Element::HardBreak(_) => w.hard_break(),
+        Element::HLine(_) => {
+            w.block_break();
+            w.push_str("---");
+            w.block_break();
+        }
+        Element::Anchor(anchor) => {
+            w.push_str(&format!("<a id=\"{}\"></a>", escape_html(&anchor.name)))
+        }
+
+        Element::Include(include) => {
+            ctx.with_soft_breaks_restored(|ctx| render_elements(&include.children, w, ctx))
+        }
+
+        // Client-side or metadata-only elements have no Markdown form
+        Element::Clear(_)
+        | Element::Toc(_)
+        | Element::TimeNow(_)
+        | Element::Date(_)
+        | Element::DateTime(_)
+        | Element::Dday(_)
+        | Element::Age(_)
+        | Element::PageCount(_)
+        | Element::Mention(_)
+        | Element::Category(_)
+        | Element::Redirect(_)
+        | Element::Define(_)
+        | Element::If(_)
+        | Element::Null(_) => {}
+    }
+}
+
+/// Render children with soft breaks turned into spaces (inline-only contexts)
+pub fn render_inline(children: &[Element], w: &mut MarkdownWriter, ctx: &mut MarkdownContext) {
+    ctx.enter_suppress_soft_breaks();
+    render_elements(children, w, ctx);
+    ctx.exit_suppress_soft_breaks();
+}
+
+fn render_wrapped(
+    open: &str,
+    close: &str,
+    children: &[Element],
+    w: &mut MarkdownWriter,
+    ctx: &mut MarkdownContext,
+) {
+    w.push_str(open);
+    render_elements(children, w, ctx);
+    w.push_closing(close);
+}
+
+fn render_header(header: &HeaderElement, w: &mut MarkdownWriter, ctx: &mut MarkdownContext) {
+    w.block_break();
+    w.push_str(&"#".repeat(header.level.clamp(1, 6)));
+    w.push_str(" ");
+    render_inline(&header.children, w, ctx);
+    w.block_break();
+}
+
+fn render_blockquote(bq: &BlockQuoteElement, w: &mut MarkdownWriter, ctx: &mut MarkdownContext) {
+    let content = render_to_string(&bq.children, ctx);
+    if content.is_empty() {
+        return;
+    }
+
+    w.block_break();
+    for line in content.lines() {
+        if line.is_empty() {
+            w.push_raw(">");
+        } else {
+            w.push_raw("> ");
+            w.push_raw(line);
+        }
+        w.line_break();
+    }
+    w.block_break();
+}
+
+/// Folds map to `<details>`; the summary is plain text because GitHub does
+/// not render Markdown inside `<summary>`.
+fn render_fold(fold: &FoldElement, w: &mut MarkdownWriter, ctx: &mut MarkdownContext) {
+    let summary = utils::collapse_whitespace(&utils::extract_text(&fold.summary.children));
+    let details = render_to_string(&fold.details.children, ctx);
+
+    w.block_break();
+    w.push_raw("<details>\n");
+    w.push_raw(&format!("<summary>{}</summary>", escape_html(&summary)));
+    w.block_break();
+    if !details.is_empty() {
+        w.push_raw(&details);
+        w.block_break();
+    }
+    w.push_raw("</details>");
+    w.block_break();
+}
+
+fn render_code(code: &CodeElement, w: &mut MarkdownWriter) {
+    let value = code.value.trim_matches('\n');
+    let fence = "`".repeat(longest_backtick_run(value).max(2) + 1);
+    let lang = utils::get_param(&code.parameters, "lang").unwrap_or_default();
+
+    w.block_break();
+    w.push_raw(&format!("{fence}{lang}\n{value}\n{fence}"));
+    w.block_break();
+}
+
+fn longest_backtick_run(s: &str) -> usize {
+    s.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
+
+fn render_tex(tex: &TeXElement, w: &mut MarkdownWriter) {
+    let value = tex.value.trim();
+    if tex.is_block {
+        w.block_break();
+        w.push_raw(&format!("```math\n{value}\n```"));
+        w.block_break();
+    } else {
+        w.push_str(&format!("${value}$"));
+    }
+}
+
+fn render_footnote(footnote: &FootnoteElement, w: &mut MarkdownWriter, ctx: &mut MarkdownContext) {
+    if ctx.in_footnote {
+        // GFM has no nested footnotes; keep the text inline
+        render_inline(&footnote.children, w, ctx);
+        return;
+    }
+
+    ctx.in_footnote = true;
+    let content = render_to_string(&footnote.children, ctx);
+    ctx.in_footnote = false;
+
+    let name = utils::get_param(&footnote.parameters, "name");
+    let label = ctx.add_footnote(name.as_deref(), content);
+    w.push_str(&format!("[^{label}]"));
+}
+
+/// Write `[^label]: content` definitions, indenting continuation lines
+pub fn render_footnote_definitions(entries: &[FootnoteEntry], w: &mut MarkdownWriter) {
+    if entries.is_empty() {
+        return;
+    }
+
+    w.block_break();
+    for entry in entries {
+        w.push_raw(&format!("[^{}]:", entry.label));
+        for (i, line) in entry.content.lines().enumerate() {
+            if i == 0 {
+                w.push_raw(" ");
+            } else if !line.is_empty() {
+                w.push_raw("    ");
+            }
+            w.push_raw(line);
+            w.line_break();
+        }
+        w.line_break();
+    }
+    w.block_break();
+}
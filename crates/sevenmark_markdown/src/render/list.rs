@@ -0,0 +1,89 @@
+//! List rendering
+//!
+//! Unordered and numeric lists use Markdown markers. Alphabetic and roman
+//! lists have no Markdown syntax, so they are emitted as `<ol type="…">` with
+//! blank lines around each item's content to keep it parsed as Markdown.
+
+use sevenmark_ast::{ListContentItem, ListElement, ListItemElement, ListKind};
+
+use super::element::render_to_string;
+use crate::context::MarkdownContext;
+use crate::writer::MarkdownWriter;
+
+pub fn render(list: &ListElement, w: &mut MarkdownWriter, ctx: &mut MarkdownContext) {
+    let items: Vec<&ListItemElement> = list
+        .children
+        .iter()
+        .flat_map(|item| match item {
+            ListContentItem::Item(item) => std::slice::from_ref(item),
+            ListContentItem::Conditional(cond) => cond.items.as_slice(),
+        })
+        .collect();
+
+    // Nested lists hug their parent item so the outer list stays tight
+    let nested = ctx.list_depth > 0;
+    if nested {
+        w.line_break();
+    } else {
+        w.block_break();
+    }
+
+    ctx.list_depth += 1;
+    match list.kind {
+        ListKind::Unordered => render_markdown_items(&items, |_| "-".to_string(), w, ctx),
+        ListKind::OrderedNumeric => render_markdown_items(&items, |n| format!("{n}."), w, ctx),
+        ListKind::OrderedAlphaLower
+        | ListKind::OrderedAlphaUpper
+        | ListKind::OrderedRomanLower
+        | ListKind::OrderedRomanUpper => render_html_items(list.kind, &items, w, ctx),
+    }
+    ctx.list_depth -= 1;
+
+    if nested {
+        w.line_break();
+    } else {
+        w.block_break();
+    }
+}
+
+fn render_markdown_items(
+    items: &[&ListItemElement],
+    marker: impl Fn(usize) -> String,
+    w: &mut MarkdownWriter,
+    ctx: &mut MarkdownContext,
+) {
+    for (i, item) in items.iter().enumerate() {
+        let marker = marker(i + 1);
+        let indent = " ".repeat(marker.len() + 1);
+        let content = render_to_string(&item.children, ctx);
+
+        w.push_raw(&marker);
+        for (line_no, line) in content.lines().enumerate() {
+            if line_no == 0 {
+                w.push_raw(" ");
+            } else if !line.is_empty() {
+                w.push_raw(&indent);
+            }
+            w.push_raw(line);
+            w.line_break();
+        }
+        w.line_break();
+    }
+}
+
+fn render_html_items(
+    kind: ListKind,
+    items: &[&ListItemElement],
+    w: &mut MarkdownWriter,
+    ctx: &mut MarkdownContext,
+) {
+    let list_type = kind.ordered_type_attr().unwrap_or("1");
+    w.push_raw(&format!("<ol type=\"{list_type}\">\n"));
+    for item in items {
+        let content = render_to_string(&item.children, ctx);
+        w.push_raw("<li>\n\n");
+        w.push_raw(&content);
+        w.push_raw("\n\n</li>\n");
+    }
+    w.push_raw("</ol>");
+}
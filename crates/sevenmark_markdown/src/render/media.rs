@@ -0,0 +1,220 @@
+//! Media rendering
+//!
+//! Images become `![alt](src)`, links `[label](href)`, and linked images
+//! `[![alt](src)](href)`. Targets come from the resolved info when the AST
+//! was processed, otherwise from the raw `#url`/`#document`/… parameters.
+
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+use sevenmark_ast::{MediaElement, Parameters};
+
+use super::element::render_to_string;
+use super::utils;
+use crate::context::MarkdownContext;
+use crate::escape::escape_markdown;
+use crate::writer::MarkdownWriter;
+
+// Encode path segment with RFC 3986 unreserved characters left as-is.
+const PATH_SEGMENT_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+fn sanitize_external_url(raw: &str) -> Option<String> {
+    let trimmed = raw.trim();
+    let lower = trimmed.to_ascii_lowercase();
+    if lower.starts_with("http://") || lower.starts_with("https://") {
+        Some(trimmed.to_string())
+    } else {
+        None
+    }
+}
+
+fn build_internal_href(base: &str, title: &str) -> String {
+    let encoded = utf8_percent_encode(title, PATH_SEGMENT_ENCODE_SET).to_string();
+    format!("{}{}", base, encoded)
+}
+
+/// Resolve the link target and a readable fallback label
+fn resolve_link(media: &MediaElement, ctx: &MarkdownContext) -> Option<(String, String)> {
+    let config = ctx.config;
+    let internal = |base: Option<&str>, title: &str| {
+        base.map(|base| (build_internal_href(base, title), title.to_string()))
+    };
+
+    let link = match &media.resolved_info {
+        Some(info) => info
+            .url
+            .as_deref()
+            .and_then(sanitize_external_url)
+            .map(|url| (url.clone(), url))
+            .or_else(|| {
+                info.document
+                    .as_ref()
+                    .and_then(|d| internal(config.document_base_url, &d.title))
+            })
+            .or_else(|| {
+                info.category
+                    .as_ref()
+                    .and_then(|c| internal(config.category_base_url, &c.title))
+            })
+            .or_else(|| {
+                info.user
+                    .as_ref()
+                    .and_then(|u| internal(config.user_base_url, &u.title))
+            }),
+        None => {
+            let param = |key| utils::get_param(&media.parameters, key);
+            param("url")
+                .and_then(|url| sanitize_external_url(&url))
+                .map(|url| (url.clone(), url))
+                .or_else(|| param("document").and_then(|t| internal(config.document_base_url, &t)))
+                .or_else(|| param("category").and_then(|t| internal(config.category_base_url, &t)))
+                .or_else(|| param("user").and_then(|t| internal(config.user_base_url, &t)))
+        }
+    };
+
+    link.map(|(href, label)| (with_anchor(href, &media.parameters), label))
+}
+
+fn with_anchor(href: String, parameters: &Parameters) -> String {
+    match utils::get_param(parameters, "anchor") {
+        Some(anchor) => {
+            let base = href.split_once('#').map(|(b, _)| b).unwrap_or(&href);
+            let encoded = utf8_percent_encode(&anchor, PATH_SEGMENT_ENCODE_SET);
+            format!("{base}#{encoded}")
+        }
+        None => href,
+    }
+}
+
+fn image_src(media: &MediaElement, ctx: &MarkdownContext) -> Option<String> {
+    let file = media.resolved_info.as_ref()?.file.as_ref()?;
+    if !file.is_valid {
+        return None;
+    }
+    Some(match ctx.config.file_base_url {
+        Some(base) => format!("{}{}", base, file.url),
+        None => file.url.clone(),
+    })
+}
+
+pub fn render(media: &MediaElement, w: &mut MarkdownWriter, ctx: &mut MarkdownContext) {
+    let link = resolve_link(media, ctx);
+
+    if let Some(src) = image_src(media, ctx) {
+        let alt = if media.children.is_empty() {
+            utils::get_param(&media.parameters, "file").unwrap_or_default()
+        } else {
+            utils::collapse_whitespace(&utils::extract_text(&media.children))
+        };
+        let image = format!(
+            "![{}]({})",
+            escape_markdown(&alt),
+            utils::link_destination(&src)
+        );
+        match link {
+            Some((href, _)) => {
+                w.push_str(&format!("[{image}]({})", utils::link_destination(&href)))
+            }
+            None => w.push_str(&image),
+        }
+        return;
+    }
+
+    ctx.enter_suppress_soft_breaks();
+    let label = render_to_string(&media.children, ctx);
+    ctx.exit_suppress_soft_breaks();
+
+    match link {
+        Some((href, fallback)) => {
+            let label = if label.is_empty() {
+                escape_markdown(&fallback)
+            } else {
+                label
+            };
+            w.push_str(&format!("[{label}]({})", utils::link_destination(&href)));
+        }
+        None => w.push_str(&label),
+    }
+}
+
+/// External embeds degrade to an autolink to the provider page
+pub fn render_external(provider: &str, parameters: &Parameters, w: &mut MarkdownWriter) {
+    let param = |key| {
+        utils::get_param(parameters, key)
+            .map(|id| utf8_percent_encode(&id, PATH_SEGMENT_ENCODE_SET).to_string())
+    };
+    let url = match provider {
+        "youtube" => param("id")
+            .map(|id| format!("https://www.youtube.com/watch?v={id}"))
+            .or_else(|| {
+                param("playlist")
+                    .map(|list| format!("https://www.youtube.com/playlist?list={list}"))
+            }),
+        "vimeo" => param("id").map(|id| format!("https://vimeo.com/{id}")),
+        "nicovideo" => param("id").map(|id| format!("https://www.nicovideo.jp/watch/{id}")),
+        "spotify" => ["track", "album", "playlist", "artist", "episode", "show"]
+            .into_iter()
+            .find_map(|kind| param(kind).map(|id| format!("https://open.spotify.com/{kind}/{id}"))),
+        "discord" => param("id").map(|id| format!("https://discord.com/widget?id={id}")),
+        _ => None,
+    };
+
+    if let Some(url) = url {
+        w.push_str(&format!("<{url}>"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{MarkdownConfig, render_markdown};
+    use sevenmark_ast::{Element, ResolvedFile, ResolvedMediaInfo};
+    use sevenmark_parser::core::parse_document;
+
+    const CONFIG: MarkdownConfig<'static> = MarkdownConfig {
+        file_base_url: Some("https://cdn.example.com/"),
+        document_base_url: Some("https://wiki.example.com/Document/"),
+        category_base_url: None,
+        user_base_url: None,
+    };
+
+    #[test]
+    fn document_link_uses_base_url() {
+        let ast = parse_document("[[#document=\"A B\" #anchor=\"s-1\" label]]");
+        assert_eq!(
+            render_markdown(&ast, &CONFIG),
+            "[label](https://wiki.example.com/Document/A%20B#s-1)\n"
+        );
+    }
+
+    #[test]
+    fn resolved_image_uses_file_url() {
+        let mut ast = parse_document("[[#file=\"cat.png\"]]");
+        let Element::Media(media) = &mut ast[0] else {
+            panic!("expected media element");
+        };
+        media.resolved_info = Some(ResolvedMediaInfo {
+            file: Some(ResolvedFile {
+                url: "files/cat.png".to_string(),
+                is_valid: true,
+                width: None,
+                height: None,
+            }),
+            ..Default::default()
+        });
+        assert_eq!(
+            render_markdown(&ast, &CONFIG),
+            "![cat.png](https://cdn.example.com/files/cat.png)\n"
+        );
+    }
+
+    #[test]
+    fn youtube_becomes_autolink() {
+        let ast = parse_document("[[#youtube #id=\"abc\"]]");
+        assert_eq!(
+            render_markdown(&ast, &CONFIG),
+            "<https://www.youtube.com/watch?v=abc>\n"
+        );
+    }
+}
@@ -0,0 +1,10 @@
+//! Markdown rendering for SevenMark AST
+
+mod document;
+mod element;
+mod list;
+mod media;
+mod table;
+mod utils;
+
+pub use document::render_markdown;
@@ -0,0 +1,172 @@
+//! Table rendering
+//!
+//! GFM tables need a header row and cannot merge cells, so the first row
+//! becomes the header and `#x`/`#y` spans are expanded into empty cells that
+//! keep the remaining columns aligned.
+
+use sevenmark_ast::{TableCellElement, TableCellItem, TableElement, TableRowItem};
+
+use super::element::render_to_string;
+use super::utils;
+use crate::context::MarkdownContext;
+use crate::writer::MarkdownWriter;
+
+/// Largest `#x`, as browsers clamp `colspan`
+const MAX_COLSPAN: usize = 1000;
+/// Largest `#y`, as browsers clamp `rowspan`
+const MAX_ROWSPAN: usize = 65534;
+
+pub fn render(table: &TableElement, w: &mut MarkdownWriter, ctx: &mut MarkdownContext) {
+    let grid = build_grid(table, ctx);
+    let Some(columns) = grid.iter().map(Vec::len).max().filter(|&n| n > 0) else {
+        return;
+    };
+
+    w.block_break();
+    if let Some(caption) = utils::get_param(&table.parameters, "caption") {
+        w.push_text(&utils::collapse_whitespace(&caption));
+        w.block_break();
+    }
+
+    for (i, row) in grid.iter().enumerate() {
+        let cells: Vec<&str> = (0..columns)
+            .map(|col| row.get(col).map(String::as_str).unwrap_or(""))
+            .collect();
+        w.push_raw(&format!("| {} |", cells.join(" | ")));
+        w.line_break();
+        if i == 0 {
+            w.push_raw(&format!("|{}", " --- |".repeat(columns)));
+            w.line_break();
+        }
+    }
+    w.block_break();
+}
+
+/// Lay out cells on a grid, leaving blanks where merged cells extend
+fn build_grid(table: &TableElement, ctx: &mut MarkdownContext) -> Vec<Vec<String>> {
+    let rows = table.children.iter().flat_map(|row| match row {
+        TableRowItem::Row(row) => std::slice::from_ref(row),
+        TableRowItem::Conditional(cond) => cond.rows.as_slice(),
+    });
+
+    // Remaining rows each column is still covered by a `#y` span from above
+    let mut pending_rowspans: Vec<usize> = Vec::new();
+    let mut grid = Vec::new();
+
+    for row in rows {
+        let mut out: Vec<String> = Vec::new();
+        let cells = row.children.iter().flat_map(|cell| match cell {
+            TableCellItem::Cell(cell) => std::slice::from_ref(cell),
+            TableCellItem::Conditional(cond) => cond.cells.as_slice(),
+        });
+
+        for cell in cells {
+            skip_covered_columns(&mut out, &mut pending_rowspans, false);
+
+            let col = out.len();
+            let colspan = span_value(&cell.x, MAX_COLSPAN);
+            let rowspan = span_value(&cell.y, MAX_ROWSPAN);
+            out.push(render_cell(cell, ctx));
+            out.resize(col + colspan, String::new());
+
+            if pending_rowspans.len() < col + colspan {
+                pending_rowspans.resize(col + colspan, 0);
+            }
+            for pending in &mut pending_rowspans[col..col + colspan] {
+                *pending = rowspan - 1;
+            }
+        }
+        skip_covered_columns(&mut out, &mut pending_rowspans, true);
+
+        grid.push(out);
+    }
+
+    grid
+}
+
+/// Emit blank cells for columns occupied by rowspans from earlier rows.
+/// With `to_end`, also fills covered columns after the last authored cell.
+fn skip_covered_columns(out: &mut Vec<String>, pending: &mut [usize], to_end: bool) {
+    loop {
+        let col = out.len();
+        let covered = pending.get(col).is_some_and(|&n| n > 0);
+        let later_covered = to_end && pending[col.min(pending.len())..].iter().any(|&n| n > 0);
+        if !covered && !later_covered {
+            return;
+        }
+        if covered {
+            pending[col] -= 1;
+        }
+        out.push(String::new());
+    }
+}
+
+fn span_value(elements: &[sevenmark_ast::Element], max: usize) -> usize {
+    utils::extract_text(elements)
+        .trim()
+        .parse::<usize>()
+        .unwrap_or(1)
+        .clamp(1, max)
+}
+
+/// Cells are single-line in GFM: line breaks become `<br>`
+fn render_cell(cell: &TableCellElement, ctx: &mut MarkdownContext) -> String {
+    ctx.enter_suppress_soft_breaks();
+    let content = render_to_string(&cell.children, ctx);
+    ctx.exit_suppress_soft_breaks();
+
+    content
+        .lines()
+        .map(|line| line.trim_end_matches('\\').trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{MarkdownConfig, render_markdown};
+    use sevenmark_parser::core::parse_document;
+
+    fn markdown(input: &str) -> String {
+        render_markdown(&parse_document(input), &MarkdownConfig::default())
+    }
+
+    #[test]
+    fn first_row_becomes_header() {
+        assert_eq!(
+            markdown("{{{#table\n[[[[a]] [[b]]]]\n[[[[c]] [[d]]]]\n}}}"),
+            "| a | b |\n| --- | --- |\n| c | d |\n"
+        );
+    }
+
+    #[test]
+    fn merged_cells_are_padded() {
+        let input = "{{{#table\n[[[[#x=\"2\" wide]] [[c]]]]\n[[[[#y=\"2\" tall]] [[e]] [[f]]]]\n[[[[g]] [[h]]]]\n}}}";
+        assert_eq!(
+            markdown(input),
+            "| wide |  | c |\n| --- | --- | --- |\n| tall | e | f |\n|  | g | h |\n"
+        );
+    }
+
+    #[test]
+    fn huge_spans_are_clamped() {
+        let md = markdown(&format!(
+            "{{{{{{#table\n[[[[#x=\"4000000000\" #y=\"{}\" a]]]]\n[[[[b]]]]\n}}}}}}",
+            usize::MAX
+        ));
+        let lines: Vec<_> = md.lines().collect();
+        // `b` comes after the columns still covered by `a`
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[2].matches('|').count(), super::MAX_COLSPAN + 2);
+        assert!(lines[2].ends_with("|  | b |"));
+    }
+
+    #[test]
+    fn pipes_in_cells_are_escaped() {
+        assert_eq!(
+            markdown("{{{#table\n[[[[a|b]]]]\n}}}"),
+            "| a\\|b |\n| --- |\n"
+        );
+    }
+}
@@ -0,0 +1,42 @@
+//! Common rendering utilities
+
+use sevenmark_ast::{Element, Parameters, Traversable};
+
+/// Extract plain text from elements, recursing into nested children.
+pub fn extract_text(elements: &[Element]) -> String {
+    fn collect(el: &Element, out: &mut String) {
+        match el {
+            Element::Text(text) => out.push_str(&text.value),
+            Element::Escape(escape) => out.push_str(&escape.value),
+            other => other.traverse_children_ref(&mut |child| collect(child, out)),
+        }
+    }
+
+    let mut result = String::new();
+    for el in elements {
+        collect(el, &mut result);
+    }
+    result
+}
+
+/// Get a trimmed, non-empty parameter value as text
+pub fn get_param(params: &Parameters, key: &str) -> Option<String> {
+    params
+        .get(key)
+        .map(|p| extract_text(&p.value).trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Collapse all runs of whitespace (including newlines) into single spaces
+pub fn collapse_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Link destination, wrapped in `<...>` when it would otherwise break the link syntax
+pub fn link_destination(url: &str) -> String {
+    if url.contains([' ', '(', ')']) {
+        format!("<{}>", url.replace('<', "%3C").replace('>', "%3E"))
+    } else {
+        url.to_string()
+    }
+}
@@ -0,0 +1,107 @@
+//! Markdown output buffer with line and block boundary tracking
+
+use crate::escape::{escape_line_start, escape_markdown};
+
+/// Markdown buffer that tracks line starts so text can be escaped safely
+#[derive(Debug, Default)]
+pub(crate) struct MarkdownWriter {
+    buf: String,
+}
+
+impl MarkdownWriter {
+    fn at_line_start(&self) -> bool {
+        self.buf.is_empty() || self.buf.ends_with('\n')
+    }
+
+    /// Append document text, escaping Markdown syntax
+    pub fn push_text(&mut self, s: &str) {
+        let escaped = escape_markdown(s);
+        if self.at_line_start() {
+            let trimmed = escaped.trim_start_matches([' ', '\t']);
+            self.buf.push_str(&escape_line_start(trimmed));
+        } else if self.buf.ends_with(' ') {
+            self.buf.push_str(escaped.trim_start_matches([' ', '\t']));
+        } else {
+            self.buf.push_str(&escaped);
+        }
+    }
+
+    /// Append a closing emphasis delimiter. Trailing spaces are moved after
+    /// it, since `*text *` does not close in CommonMark.
+    pub fn push_closing(&mut self, close: &str) {
+        let trimmed = self.buf.trim_end_matches(' ').len();
+        let spaces = self.buf.len() - trimmed;
+        self.buf.truncate(trimmed);
+        self.buf.push_str(close);
+        self.buf.push_str(&" ".repeat(spaces));
+    }
+
+    /// Append generated Markdown syntax, dropping indentation at line start
+    pub fn push_str(&mut self, s: &str) {
+        if self.at_line_start() {
+            self.buf.push_str(s.trim_start_matches([' ', '\t']));
+        } else {
+            self.buf.push_str(s);
+        }
+    }
+
+    /// Append text verbatim (fenced code, pre-rendered blocks)
+    pub fn push_raw(&mut self, s: &str) {
+        self.buf.push_str(s);
+    }
+
+    /// Insert a single space unless the buffer already ends in whitespace
+    pub fn space(&mut self) {
+        if !self.buf.is_empty() && !self.buf.ends_with([' ', '\n']) {
+            self.buf.push(' ');
+        }
+    }
+
+    /// End the current line
+    pub fn line_break(&mut self) {
+        self.trim_trailing_spaces();
+        if !self.at_line_start() {
+            self.buf.push('\n');
+        }
+    }
+
+    /// GFM hard line break (backslash before the newline)
+    pub fn hard_break(&mut self) {
+        self.trim_trailing_spaces();
+        if !self.at_line_start() {
+            self.buf.push_str("\\\n");
+        }
+    }
+
+    /// End the current block, leaving exactly one blank line
+    pub fn block_break(&mut self) {
+        self.line_break();
+        if !self.buf.is_empty() && !self.buf.ends_with("\n\n") {
+            self.buf.push('\n');
+        }
+    }
+
+    fn trim_trailing_spaces(&mut self) {
+        let trimmed = self.buf.trim_end_matches([' ', '\t']).len();
+        self.buf.truncate(trimmed);
+    }
+
+    pub fn finish(self) -> String {
+        self.buf.trim_end().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn closing_delimiter_moves_trailing_space_out() {
+        let mut w = MarkdownWriter::default();
+        w.push_str("*");
+        w.push_text("text ");
+        w.push_closing("*");
+        w.push_text("after");
+        assert_eq!(w.finish(), "*text* after");
+    }
+}
@@ -44,6 +44,12 @@ pub struct ParseContext {
     pub footnote_counter: usize,
 }
 
+impl Default for ParseContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ParseContext {
     /// 새 컨텍스트 생성
     pub fn new() -> Self {
@@ -15,11 +15,11 @@ pub fn block_document_parser(parser_input: &mut ParserInput) -> Result<Vec<Eleme
     let mut elements = Vec::new();
 
     while !parser_input.input.is_empty() {
-        if parser_input.input.is_at_line_start() {
-            if let Some(element) = parse_line_block(parser_input)? {
-                elements.push(element);
-                continue;
-            }
+        if parser_input.input.is_at_line_start()
+            && let Some(element) = parse_line_block(parser_input)?
+        {
+            elements.push(element);
+            continue;
         }
 
         let checkpoint = parser_input.checkpoint();
@@ -22,9 +22,16 @@ pub fn brace_blockquote_parser(parser_input: &mut ParserInput) -> Result<Element
     multispace0.parse_next(parser_input)?;
     let content_start = parser_input.current_token_start();
     let raw_content = parse_raw_until_balanced_triple_brace(parser_input)?;
-    let content = raw_content
+    let trimmed = raw_content
         .value
         .trim_end_matches(|c: char| c.is_ascii_whitespace());
+    // Keep whitespace escaped by an odd run of backslashes (`\ }}}`)
+    let backslashes = trimmed.len() - trimmed.trim_end_matches('\\').len();
+    let content = if backslashes % 2 == 1 {
+        &raw_content.value[..trimmed.len() + 1]
+    } else {
+        trimmed
+    };
 
     let mut child_input = ParserInput {
         input: InputSource::new_at(content, content_start),
@@ -58,3 +65,49 @@ pub fn brace_blockquote_parser(parser_input: &mut ParserInput) -> Result<Element
         children: parsed_content,
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::core::parse_document;
+    use sevenmark_ast::Element;
+
+    /// Text and escape values inside the single blockquote of `input`
+    fn quote_values(input: &str) -> Vec<String> {
+        let elements = parse_document(input);
+        let [Element::BlockQuote(quote)] = elements.as_slice() else {
+            panic!("expected one blockquote in {input:?}");
+        };
+        quote
+            .children
+            .iter()
+            .map(|child| match child {
+                Element::Text(text) => text.value.clone(),
+                Element::Escape(escape) => format!("escape:{}", escape.value),
+                other => panic!("unexpected {}", other.name()),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn trailing_whitespace_is_trimmed() {
+        assert_eq!(quote_values("{{{#quote a \n}}}"), ["a"]);
+    }
+
+    #[test]
+    fn odd_backslashes_keep_escaped_whitespace() {
+        assert_eq!(quote_values("{{{#quote a\\ }}}"), ["a", "escape: "]);
+        assert_eq!(
+            quote_values("{{{#quote a\\\\\\ \n}}}"),
+            ["a", "escape:\\", "escape: "]
+        );
+    }
+
+    #[test]
+    fn even_backslashes_drop_trailing_whitespace() {
+        assert_eq!(quote_values("{{{#quote a\\\\ }}}"), ["a", "escape:\\"]);
+        assert_eq!(
+            quote_values("{{{#quote a\\\\\\\\ \n}}}"),
+            ["a", "escape:\\", "escape:\\"]
+        );
+    }
+}
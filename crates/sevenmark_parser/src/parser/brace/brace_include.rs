@@ -39,5 +39,6 @@ pub fn brace_include_parser(parser_input: &mut ParserInput) -> Result<Element> {
         },
         parameters: parameters.unwrap_or_default(),
         children: parsed_content,
+        failure: None,
     }))
 }
@@ -41,7 +41,7 @@ pub fn markdown_blockquote_parser(parser_input: &mut ParserInput) -> Result<Elem
                 len: line.content.len(),
             });
         }
-        logical.push_str(&line.content);
+        logical.push_str(line.content);
 
         if let Some(ending) = line.ending {
             if let Some(ending_start) = line.ending_start {
@@ -0,0 +1,82 @@
+use crate::elements::{ELEMENTS, ElementSpec, Syntax};
+
+/// Path of the generated reference, relative to the workspace root
+pub const REFERENCE_DOC_PATH: &str = "docs/grammar/reference.md";
+
+/// Markdown reference of every element and parameter
+pub fn reference_markdown() -> String {
+    let mut out = String::new();
+    out.push_str(
+        "<!-- Generated by `cargo xtask gen-docs` from sevenmark_registry. Do not edit. -->\n\n",
+    );
+    out.push_str("# Element Reference\n\n<div v-pre>\n\n");
+    out.push_str(
+        "Every element and the parameters its renderer understands. \
+         The language server reports unknown parameters and invalid values using this list.\n",
+    );
+
+    for (syntax, title) in [
+        (Syntax::Brace, "Brace Elements"),
+        (Syntax::Bracket, "Bracket Elements"),
+        (Syntax::Part, "Nested Parts"),
+        (Syntax::Macro, "Macros"),
+    ] {
+        out.push_str(&format!("\n## {title}\n"));
+        for spec in ELEMENTS.iter().filter(|e| e.syntax == syntax) {
+            write_element(&mut out, spec);
+        }
+    }
+
+    out.push_str("\n</div>\n");
+    out
+}
+
+fn write_element(out: &mut String, spec: &ElementSpec) {
+    out.push_str(&format!(
+        "\n### {}\n\n{}\n\n`{}`\n",
+        spec.name, spec.summary, spec.usage
+    ));
+
+    if let Some(argument) = &spec.argument {
+        out.push_str(&format!(
+            "\nArgument `{}`: {} ({})\n",
+            argument.name,
+            argument.doc,
+            argument.value.describe()
+        ));
+    }
+
+    if spec.params.is_empty() {
+        if spec.open_params {
+            out.push_str("\nAny `#name=\"value\"` parameter is accepted.\n");
+        }
+        return;
+    }
+
+    out.push_str("\n| Parameter | Value | Description |\n|---|---|---|\n");
+    for param in spec.all_params() {
+        out.push_str(&format!(
+            "| `#{}` | {} | {} |\n",
+            param.name,
+            param.value.describe(),
+            param.doc.replace('|', "\\|")
+        ));
+    }
+    if spec.open_params {
+        out.push_str("\nOther parameters are passed through as variables.\n");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reference_doc_is_up_to_date() {
+        let committed = include_str!("../../../docs/grammar/reference.md");
+        assert!(
+            committed == reference_markdown(),
+            "{REFERENCE_DOC_PATH} is stale; run `cargo xtask gen-docs`"
+        );
+    }
+}
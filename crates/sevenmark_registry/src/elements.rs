@@ -0,0 +1,521 @@
+use crate::value::ValueType;
+
+/// How an element is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Syntax {
+    /// `{{{#name ...}}}`
+    Brace,
+    /// `[[#name ...]]`
+    Bracket,
+    /// `[name]` / `[name(argument)]`
+    Macro,
+    /// `[[...]]` nested inside another element (table row, list item, ...)
+    Part,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParamSpec {
+    pub name: &'static str,
+    pub value: ValueType,
+    pub doc: &'static str,
+}
+
+const fn param(name: &'static str, value: ValueType, doc: &'static str) -> ParamSpec {
+    ParamSpec { name, value, doc }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElementSpec {
+    pub name: &'static str,
+    pub syntax: Syntax,
+    /// How the element is written, for docs
+    pub usage: &'static str,
+    pub summary: &'static str,
+    /// Parameter groups; a name in a later group never overrides an earlier one
+    pub params: &'static [&'static [ParamSpec]],
+    /// Accepts parameters beyond `params` (variables for define/include)
+    pub open_params: bool,
+    /// `[name(argument)]` argument of a macro
+    pub argument: Option<ParamSpec>,
+    /// The macro may also be written without its argument
+    pub optional_argument: bool,
+}
+
+impl ElementSpec {
+    /// All parameters, in declaration order without duplicates
+    pub fn all_params(&self) -> impl Iterator<Item = &'static ParamSpec> {
+        let groups = self.params;
+        groups.iter().enumerate().flat_map(move |(i, group)| {
+            group.iter().filter(move |p| {
+                !groups[..i]
+                    .iter()
+                    .any(|earlier| earlier.iter().any(|e| e.name == p.name))
+            })
+        })
+    }
+
+    pub fn param(&self, name: &str) -> Option<&'static ParamSpec> {
+        self.all_params().find(|p| p.name == name)
+    }
+
+    /// Closest known parameter name, for "did you mean" hints
+    pub fn suggest(&self, name: &str) -> Option<&'static str> {
+        self.all_params()
+            .map(|p| (edit_distance(name, p.name), p.name))
+            .filter(|&(distance, _)| distance <= 2 && distance < name.chars().count())
+            .min_by_key(|&(distance, _)| distance)
+            .map(|(_, name)| name)
+    }
+}
+
+/// Look up an element by syntax and name
+pub fn element(syntax: Syntax, name: &str) -> Option<&'static ElementSpec> {
+    ELEMENTS
+        .iter()
+        .find(|e| e.syntax == syntax && e.name == name)
+}
+
+/// Elements of one syntax, in registry order
+pub fn elements(syntax: Syntax) -> impl Iterator<Item = &'static ElementSpec> {
+    ELEMENTS.iter().filter(move |e| e.syntax == syntax)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous + usize::from(ca != cb);
+            previous = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(previous + 1);
+        }
+    }
+    row[b.len()]
+}
+
+// === Parameter groups ===
+
+pub const NAMESPACES: &[&str] = &["Document", "File", "Category", "User"];
+
+pub const STYLE_PARAMS: &[ParamSpec] = &[
+    param("style", ValueType::Css, "Raw CSS declarations"),
+    param("color", ValueType::Color, "Text color"),
+    param("bgcolor", ValueType::Color, "Background color"),
+    param("size", ValueType::FontSize, "Font size"),
+    param("opacity", ValueType::Number, "Opacity"),
+    param("width", ValueType::Length, "CSS width"),
+    param("height", ValueType::Length, "CSS height"),
+    param("dark-style", ValueType::Css, "Dark-mode CSS declarations"),
+    param("dark-color", ValueType::Color, "Dark-mode text color"),
+    param(
+        "dark-bgcolor",
+        ValueType::Color,
+        "Dark-mode background color",
+    ),
+    param("dark-size", ValueType::FontSize, "Dark-mode font size"),
+    param("dark-opacity", ValueType::Number, "Dark-mode opacity"),
+    param("dark-width", ValueType::Length, "Dark-mode CSS width"),
+    param("dark-height", ValueType::Length, "Dark-mode CSS height"),
+];
+
+pub const CLASS_PARAMS: &[ParamSpec] = &[param("class", ValueType::Text, "Extra CSS classes")];
+
+const TABLE_PARAMS: &[ParamSpec] = &[
+    param("caption", ValueType::Text, "Table caption"),
+    param(
+        "wrapper-align",
+        ValueType::Enum(&["left", "center", "right"]),
+        "Table wrapper alignment (left/center/right)",
+    ),
+    param("wrapper-width", ValueType::Length, "Table wrapper width"),
+    param(
+        "wrapper-style",
+        ValueType::Css,
+        "CSS style for the table wrapper",
+    ),
+    param(
+        "wrapper-dark-style",
+        ValueType::Css,
+        "Dark-mode CSS style for the table wrapper",
+    ),
+    param("sortable", ValueType::Flag, "Enable column sorting"),
+];
+
+const TABLE_ROW_PARAMS: &[ParamSpec] = &[param(
+    "head",
+    ValueType::Flag,
+    "Header row (renders as <thead>/<th>)",
+)];
+
+const TABLE_CELL_PARAMS: &[ParamSpec] = &[
+    param("x", ValueType::Integer, "Column span"),
+    param("y", ValueType::Integer, "Row span"),
+];
+
+const LIST_KIND_PARAMS: &[ParamSpec] = &[
+    param("1", ValueType::Flag, "Ordered list: 1, 2, 3"),
+    param("a", ValueType::Flag, "Ordered list: a, b, c"),
+    param("A", ValueType::Flag, "Ordered list: A, B, C"),
+    param("i", ValueType::Flag, "Ordered list: i, ii, iii"),
+    param("I", ValueType::Flag, "Ordered list: I, II, III"),
+];
+
+const CODE_PARAMS: &[ParamSpec] = &[param("lang", ValueType::Text, "Programming language")];
+const TEX_PARAMS: &[ParamSpec] = &[param("block", ValueType::Flag, "Display-style TeX block")];
+const RUBY_PARAMS: &[ParamSpec] = &[param("ruby", ValueType::Text, "Ruby text annotation")];
+const FOOTNOTE_PARAMS: &[ParamSpec] = &[
+    param("display", ValueType::Text, "Custom marker text"),
+    param(
+        "name",
+        ValueType::Text,
+        "Reusable named footnote identifier",
+    ),
+];
+const NAMESPACE_PARAMS: &[ParamSpec] = &[param(
+    "namespace",
+    ValueType::Enum(NAMESPACES),
+    "Target namespace",
+)];
+
+pub const MEDIA_TARGET_PARAMS: &[ParamSpec] = &[
+    param("file", ValueType::Text, "File path"),
+    param("document", ValueType::Text, "Document path"),
+    param("category", ValueType::Text, "Category name"),
+    param("user", ValueType::Text, "User name"),
+    param("url", ValueType::Text, "External URL"),
+];
+
+const MEDIA_EXTRA_PARAMS: &[ParamSpec] = &[
+    param("anchor", ValueType::Text, "Anchor fragment"),
+    param(
+        "theme",
+        ValueType::Enum(&["light", "dark"]),
+        "Theme visibility (light/dark)",
+    ),
+];
+
+const YOUTUBE_PARAMS: &[ParamSpec] = &[
+    param("id", ValueType::Text, "Video ID"),
+    param("playlist", ValueType::Text, "Playlist ID"),
+    param("width", ValueType::Length, "Player width"),
+    param("height", ValueType::Length, "Player height"),
+    param("start", ValueType::Integer, "Start time (seconds)"),
+    param("end", ValueType::Integer, "End time (seconds)"),
+    param("autoplay", ValueType::Flag, "Auto-play"),
+    param("loop", ValueType::Flag, "Loop playback"),
+    param("mute", ValueType::Flag, "Muted"),
+    param("nocontrols", ValueType::Flag, "Hide controls"),
+];
+
+const VIMEO_PARAMS: &[ParamSpec] = &[
+    param("id", ValueType::Text, "Video ID"),
+    param("h", ValueType::Text, "Privacy hash"),
+    param("width", ValueType::Length, "Player width"),
+    param("height", ValueType::Length, "Player height"),
+    param("autoplay", ValueType::Flag, "Auto-play"),
+    param("loop", ValueType::Flag, "Loop playback"),
+    param("mute", ValueType::Flag, "Muted"),
+    param("color", ValueType::Color, "Player accent color"),
+    param("dnt", ValueType::Flag, "Do-not-track"),
+];
+
+const NICOVIDEO_PARAMS: &[ParamSpec] = &[
+    param("id", ValueType::Text, "Video ID"),
+    param("width", ValueType::Length, "Player width"),
+    param("height", ValueType::Length, "Player height"),
+    param("from", ValueType::Integer, "Start time (seconds)"),
+    param("autoplay", ValueType::Flag, "Auto-play"),
+];
+
+const SPOTIFY_PARAMS: &[ParamSpec] = &[
+    param("track", ValueType::Text, "Track ID"),
+    param("album", ValueType::Text, "Album ID"),
+    param("playlist", ValueType::Text, "Playlist ID"),
+    param("artist", ValueType::Text, "Artist ID"),
+    param("episode", ValueType::Text, "Episode ID"),
+    param("show", ValueType::Text, "Show / podcast ID"),
+    param("width", ValueType::Length, "Player width"),
+    param("height", ValueType::Length, "Player height"),
+    param("dark", ValueType::Flag, "Dark theme"),
+    param("compact", ValueType::Flag, "Compact layout"),
+];
+
+const DISCORD_PARAMS: &[ParamSpec] = &[
+    param("id", ValueType::Text, "Widget / server ID"),
+    param("width", ValueType::Length, "Widget width"),
+    param("height", ValueType::Length, "Widget height"),
+    param("dark", ValueType::Flag, "Dark theme"),
+];
+
+const fn brace(
+    name: &'static str,
+    usage: &'static str,
+    summary: &'static str,
+    params: &'static [&'static [ParamSpec]],
+) -> ElementSpec {
+    ElementSpec {
+        name,
+        syntax: Syntax::Brace,
+        usage,
+        summary,
+        params,
+        open_params: false,
+        argument: None,
+        optional_argument: false,
+    }
+}
+
+const fn bracket(
+    name: &'static str,
+    usage: &'static str,
+    summary: &'static str,
+    params: &'static [&'static [ParamSpec]],
+) -> ElementSpec {
+    ElementSpec {
+        syntax: Syntax::Bracket,
+        ..brace(name, usage, summary, params)
+    }
+}
+
+const fn part(
+    name: &'static str,
+    usage: &'static str,
+    summary: &'static str,
+    params: &'static [&'static [ParamSpec]],
+) -> ElementSpec {
+    ElementSpec {
+        syntax: Syntax::Part,
+        ..brace(name, usage, summary, params)
+    }
+}
+
+const fn macro_(
+    name: &'static str,
+    usage: &'static str,
+    summary: &'static str,
+    argument: Option<ParamSpec>,
+) -> ElementSpec {
+    ElementSpec {
+        syntax: Syntax::Macro,
+        argument,
+        ..brace(name, usage, summary, &[])
+    }
+}
+
+const STYLED: &[&[ParamSpec]] = &[STYLE_PARAMS, CLASS_PARAMS];
+const EMBED: &[ParamSpec] = STYLE_PARAMS;
+
+/// Every element known to the parser and renderers
+pub static ELEMENTS: &[ElementSpec] = &[
+    // Brace elements
+    brace(
+        "code",
+        "{{{#code #lang=\"...\" ...}}}",
+        "Code block",
+        &[CODE_PARAMS, STYLE_PARAMS, CLASS_PARAMS],
+    ),
+    brace("tex", "{{{#tex ...}}}", "TeX block", &[TEX_PARAMS]),
+    brace("css", "{{{#css ...}}}", "CSS block", &[]),
+    brace(
+        "table",
+        "{{{#table [[...]] }}}",
+        "Table",
+        &[TABLE_PARAMS, STYLE_PARAMS, CLASS_PARAMS],
+    ),
+    brace(
+        "list",
+        "{{{#list [[...]] }}}",
+        "List",
+        &[LIST_KIND_PARAMS, STYLE_PARAMS, CLASS_PARAMS],
+    ),
+    brace(
+        "fold",
+        "{{{#fold [[title]] [[content]] }}}",
+        "Fold (collapsible)",
+        STYLED,
+    ),
+    brace("quote", "{{{#quote ...}}}", "Block quote", STYLED),
+    ElementSpec {
+        open_params: true,
+        ..brace(
+            "define",
+            "{{{#define #name=\"value\"}}}",
+            "Variable definition",
+            &[],
+        )
+    },
+    brace("if", "{{{#if condition :: ...}}}", "Conditional block", &[]),
+    ElementSpec {
+        open_params: true,
+        ..brace(
+            "include",
+            "{{{#include #namespace=\"...\" #var=\"value\" Title}}}",
+            "Document inclusion",
+            &[NAMESPACE_PARAMS],
+        )
+    },
+    brace("category", "{{{#category Name}}}", "Category", &[]),
+    brace(
+        "redirect",
+        "{{{#redirect #namespace=\"...\" Title}}}",
+        "Redirect",
+        &[NAMESPACE_PARAMS],
+    ),
+    brace(
+        "ruby",
+        "{{{#ruby #ruby=\"...\" ...}}}",
+        "Ruby annotation",
+        &[RUBY_PARAMS, STYLE_PARAMS, CLASS_PARAMS],
+    ),
+    brace("fn", "{{{#fn ...}}}", "Footnote", &[FOOTNOTE_PARAMS]),
+    brace("styled", "{{{#style=\"...\" ...}}}", "Styled block", STYLED),
+    brace("literal", "{{{ ... }}}", "Literal (raw output)", &[]),
+    // Bracket elements
+    bracket(
+        "media",
+        "[[#file=\"...\" ...]]",
+        "Link or media (file, document, category, user, url)",
+        &[
+            MEDIA_TARGET_PARAMS,
+            MEDIA_EXTRA_PARAMS,
+            STYLE_PARAMS,
+            CLASS_PARAMS,
+        ],
+    ),
+    bracket(
+        "youtube",
+        "[[#youtube #id=\"...\"]]",
+        "YouTube embed",
+        &[YOUTUBE_PARAMS, EMBED],
+    ),
+    bracket(
+        "vimeo",
+        "[[#vimeo #id=\"...\"]]",
+        "Vimeo embed",
+        &[VIMEO_PARAMS, EMBED],
+    ),
+    bracket(
+        "nicovideo",
+        "[[#nicovideo #id=\"...\"]]",
+        "NicoVideo embed",
+        &[NICOVIDEO_PARAMS, EMBED],
+    ),
+    bracket(
+        "spotify",
+        "[[#spotify #track=\"...\"]]",
+        "Spotify embed",
+        &[SPOTIFY_PARAMS, EMBED],
+    ),
+    bracket(
+        "discord",
+        "[[#discord #id=\"...\"]]",
+        "Discord embed",
+        &[DISCORD_PARAMS, EMBED],
+    ),
+    // Nested parts
+    part(
+        "table-row",
+        "[[ [[cell]] ... ]] in {{{#table}}}",
+        "Table row",
+        &[TABLE_ROW_PARAMS, STYLE_PARAMS, CLASS_PARAMS],
+    ),
+    part(
+        "table-cell",
+        "[[...]] in a table row",
+        "Table cell",
+        &[TABLE_CELL_PARAMS, STYLE_PARAMS, CLASS_PARAMS],
+    ),
+    part("list-item", "[[...]] in {{{#list}}}", "List item", STYLED),
+    part(
+        "fold-section",
+        "[[...]] in {{{#fold}}}",
+        "Fold section (title or content)",
+        STYLED,
+    ),
+    // Macros
+    macro_(
+        "var",
+        "[var(name)]",
+        "Variable reference",
+        Some(param("name", ValueType::Text, "Variable name")),
+    ),
+    macro_("br", "[br]", "Line break", None),
+    macro_("clear", "[clear]", "Float clear", None),
+    macro_("null", "[null]", "Null (no output)", None),
+    macro_("fn", "[fn]", "Render collected footnotes", None),
+    macro_("now", "[now]", "Current time", None),
+    macro_(
+        "age",
+        "[age(YYYY-MM-DD)]",
+        "Age calculation",
+        Some(param("date", ValueType::Date, "Birth date")),
+    ),
+    macro_(
+        "anchor",
+        "[anchor(name)]",
+        "Named anchor",
+        Some(param("name", ValueType::Text, "Anchor name")),
+    ),
+    macro_("date", "[date]", "Current date", None),
+    macro_("datetime", "[datetime]", "Current date and time", None),
+    macro_(
+        "dday",
+        "[dday(YYYY-MM-DD)]",
+        "D-day counter",
+        Some(param("date", ValueType::Date, "Target date")),
+    ),
+    ElementSpec {
+        optional_argument: true,
+        ..macro_(
+            "pagecount",
+            "[pagecount] / [pagecount(namespace)]",
+            "Total page count",
+            Some(param(
+                "namespace",
+                ValueType::Enum(NAMESPACES),
+                "Namespace to count (optional)",
+            )),
+        )
+    },
+    macro_("toc", "[toc]", "Table of contents", None),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_are_unique_per_syntax() {
+        for (i, a) in ELEMENTS.iter().enumerate() {
+            for b in &ELEMENTS[i + 1..] {
+                assert!(
+                    !(a.syntax == b.syntax && a.name == b.name),
+                    "duplicate element {:?} {}",
+                    a.syntax,
+                    a.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn groups_do_not_repeat_names() {
+        let youtube = element(Syntax::Bracket, "youtube").unwrap();
+        let widths = youtube.all_params().filter(|p| p.name == "width").count();
+        assert_eq!(widths, 1);
+        // The provider-specific doc wins over the generic style one
+        assert_eq!(youtube.param("width").unwrap().doc, "Player width");
+    }
+
+    #[test]
+    fn suggestions_for_typos() {
+        let table = element(Syntax::Brace, "table").unwrap();
+        assert_eq!(table.suggest("wraper-align"), Some("wrapper-align"));
+        assert_eq!(table.suggest("bgcolour"), Some("bgcolor"));
+        assert_eq!(table.suggest("completely-unrelated"), None);
+        // Single letters are not "close" to other single letters
+        assert_eq!(table.suggest("q"), None);
+    }
+}
@@ -0,0 +1,14 @@
+//! sevenmark-registry - Elements and parameters known to SevenMark
+//!
+//! One description of every brace/bracket/macro element, its parameters and
+//! their value types. The language server uses it for completion, hover and
+//! parameter diagnostics; `cargo xtask gen-docs` renders it into the grammar
+//! reference.
+
+mod docs;
+mod elements;
+mod value;
+
+pub use docs::*;
+pub use elements::*;
+pub use value::*;
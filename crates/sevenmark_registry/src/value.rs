@@ -0,0 +1,428 @@
+/// What a parameter value (or macro argument) may contain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    /// Any text
+    Text,
+    /// Presence only: `#sortable`. A value is ignored.
+    Flag,
+    /// Non-negative integer
+    Integer,
+    /// Number or percentage (`0.5`, `50%`)
+    Number,
+    /// CSS length (`120px`, `50%`, `auto`, `calc(...)`)
+    Length,
+    /// CSS font size: a length or a keyword such as `large`
+    FontSize,
+    /// CSS color (`#f00`, `rgb(...)`, `red`)
+    Color,
+    /// CSS declarations (`color:red; margin:0`)
+    Css,
+    /// One of the listed words (case-insensitive)
+    Enum(&'static [&'static str]),
+    /// Calendar date `YYYY-MM-DD`
+    Date,
+}
+
+/// Why a value does not match its [`ValueType`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueError {
+    /// A flag was given a value, which renderers ignore
+    FlagWithValue,
+    /// The value is not of the expected type
+    Expected(String),
+}
+
+impl ValueType {
+    /// Short description for hover and docs
+    pub fn describe(self) -> String {
+        match self {
+            ValueType::Text => "text".to_string(),
+            ValueType::Flag => "flag".to_string(),
+            ValueType::Integer => "integer".to_string(),
+            ValueType::Number => "number or percentage".to_string(),
+            ValueType::Length => "CSS length".to_string(),
+            ValueType::FontSize => "CSS font size".to_string(),
+            ValueType::Color => "CSS color".to_string(),
+            ValueType::Css => "CSS declarations".to_string(),
+            ValueType::Enum(values) => values
+                .iter()
+                .map(|v| format!("`{v}`"))
+                .collect::<Vec<_>>()
+                .join(" \\| "),
+            ValueType::Date => "date (YYYY-MM-DD)".to_string(),
+        }
+    }
+
+    pub fn is_flag(self) -> bool {
+        self == ValueType::Flag
+    }
+
+    /// Check a value written in a document (already reduced to plain text)
+    pub fn validate(self, value: &str) -> Result<(), ValueError> {
+        let value = value.trim();
+        let expected = |what: &str| Err(ValueError::Expected(what.to_string()));
+        match self {
+            ValueType::Text | ValueType::Css => Ok(()),
+            ValueType::Flag if value.is_empty() => Ok(()),
+            ValueType::Flag => Err(ValueError::FlagWithValue),
+            ValueType::Integer if is_integer(value) => Ok(()),
+            ValueType::Integer => expected("an integer"),
+            ValueType::Number if is_number(value.strip_suffix('%').unwrap_or(value)) => Ok(()),
+            ValueType::Number => expected("a number or percentage"),
+            ValueType::Length if is_length(value) => Ok(()),
+            ValueType::Length => expected("a CSS length such as 120px or 50%"),
+            ValueType::FontSize if is_length(value) || is_font_size_keyword(value) => Ok(()),
+            ValueType::FontSize => expected("a CSS font size such as 1.2em or large"),
+            ValueType::Color if is_color(value) => Ok(()),
+            ValueType::Color => expected("a CSS color such as #ff0000 or red"),
+            ValueType::Enum(values) if values.iter().any(|v| v.eq_ignore_ascii_case(value)) => {
+                Ok(())
+            }
+            ValueType::Enum(values) => expected(&format!("one of {}", values.join(", "))),
+            ValueType::Date if is_date(value) => Ok(()),
+            ValueType::Date => expected("a date in YYYY-MM-DD format"),
+        }
+    }
+}
+
+const LENGTH_UNITS: &[&str] = &[
+    "px", "%", "em", "rem", "ex", "ch", "vw", "vh", "vmin", "vmax", "dvw", "dvh", "svw", "svh",
+    "lvw", "lvh", "cm", "mm", "in", "pt", "pc", "q",
+];
+
+const LENGTH_KEYWORDS: &[&str] = &[
+    "auto",
+    "fit-content",
+    "max-content",
+    "min-content",
+    "inherit",
+    "initial",
+    "unset",
+];
+
+const FONT_SIZE_KEYWORDS: &[&str] = &[
+    "xx-small",
+    "x-small",
+    "small",
+    "medium",
+    "large",
+    "x-large",
+    "xx-large",
+    "xxx-large",
+    "smaller",
+    "larger",
+];
+
+/// CSS functions accepted as-is for lengths and colors
+const CSS_FUNCTIONS: &[&str] = &["calc(", "var(", "min(", "max(", "clamp(", "env("];
+
+const COLOR_FUNCTIONS: &[&str] = &[
+    "rgb(",
+    "rgba(",
+    "hsl(",
+    "hsla(",
+    "hwb(",
+    "lab(",
+    "lch(",
+    "oklab(",
+    "oklch(",
+    "color(",
+    "color-mix(",
+    "light-dark(",
+    "var(",
+];
+
+/// CSS named colors (CSS Color Module Level 4) plus `transparent` and `currentcolor`
+const NAMED_COLORS: &[&str] = &[
+    "aliceblue",
+    "antiquewhite",
+    "aqua",
+    "aquamarine",
+    "azure",
+    "beige",
+    "bisque",
+    "black",
+    "blanchedalmond",
+    "blue",
+    "blueviolet",
+    "brown",
+    "burlywood",
+    "cadetblue",
+    "chartreuse",
+    "chocolate",
+    "coral",
+    "cornflowerblue",
+    "cornsilk",
+    "crimson",
+    "cyan",
+    "darkblue",
+    "darkcyan",
+    "darkgoldenrod",
+    "darkgray",
+    "darkgreen",
+    "darkgrey",
+    "darkkhaki",
+    "darkmagenta",
+    "darkolivegreen",
+    "darkorange",
+    "darkorchid",
+    "darkred",
+    "darksalmon",
+    "darkseagreen",
+    "darkslateblue",
+    "darkslategray",
+    "darkslategrey",
+    "darkturquoise",
+    "darkviolet",
+    "deeppink",
+    "deepskyblue",
+    "dimgray",
+    "dimgrey",
+    "dodgerblue",
+    "firebrick",
+    "floralwhite",
+    "forestgreen",
+    "fuchsia",
+    "gainsboro",
+    "ghostwhite",
+    "gold",
+    "goldenrod",
+    "gray",
+    "green",
+    "greenyellow",
+    "grey",
+    "honeydew",
+    "hotpink",
+    "indianred",
+    "indigo",
+    "ivory",
+    "khaki",
+    "lavender",
+    "lavenderblush",
+    "lawngreen",
+    "lemonchiffon",
+    "lightblue",
+    "lightcoral",
+    "lightcyan",
+    "lightgoldenrodyellow",
+    "lightgray",
+    "lightgreen",
+    "lightgrey",
+    "lightpink",
+    "lightsalmon",
+    "lightseagreen",
+    "lightskyblue",
+    "lightslategray",
+    "lightslategrey",
+    "lightsteelblue",
+    "lightyellow",
+    "lime",
+    "limegreen",
+    "linen",
+    "magenta",
+    "maroon",
+    "mediumaquamarine",
+    "mediumblue",
+    "mediumorchid",
+    "mediumpurple",
+    "mediumseagreen",
+    "mediumslateblue",
+    "mediumspringgreen",
+    "mediumturquoise",
+    "mediumvioletred",
+    "midnightblue",
+    "mintcream",
+    "mistyrose",
+    "moccasin",
+    "navajowhite",
+    "navy",
+    "oldlace",
+    "olive",
+    "olivedrab",
+    "orange",
+    "orangered",
+    "orchid",
+    "palegoldenrod",
+    "palegreen",
+    "paleturquoise",
+    "palevioletred",
+    "papayawhip",
+    "peachpuff",
+    "peru",
+    "pink",
+    "plum",
+    "powderblue",
+    "purple",
+    "rebeccapurple",
+    "red",
+    "rosybrown",
+    "royalblue",
+    "saddlebrown",
+    "salmon",
+    "sandybrown",
+    "seagreen",
+    "seashell",
+    "sienna",
+    "silver",
+    "skyblue",
+    "slateblue",
+    "slategray",
+    "slategrey",
+    "snow",
+    "springgreen",
+    "steelblue",
+    "tan",
+    "teal",
+    "thistle",
+    "tomato",
+    "turquoise",
+    "violet",
+    "wheat",
+    "white",
+    "whitesmoke",
+    "yellow",
+    "yellowgreen",
+    "transparent",
+    "currentcolor",
+    "inherit",
+    "initial",
+    "unset",
+];
+
+fn is_integer(value: &str) -> bool {
+    !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn is_number(value: &str) -> bool {
+    let digits = value.strip_prefix(['-', '+']).unwrap_or(value);
+    let mut parts = digits.splitn(2, '.');
+    let whole = parts.next().unwrap_or_default();
+    let fraction = parts.next();
+    let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    match fraction {
+        None => is_integer(whole),
+        Some(fraction) => {
+            all_digits(whole) && all_digits(fraction) && !(whole.is_empty() && fraction.is_empty())
+        }
+    }
+}
+
+fn is_css_function(value: &str, functions: &[&str]) -> bool {
+    let lower = value.to_ascii_lowercase();
+    value.ends_with(')') && functions.iter().any(|f| lower.starts_with(f))
+}
+
+fn is_length(value: &str) -> bool {
+    if value == "0"
+        || LENGTH_KEYWORDS
+            .iter()
+            .any(|k| k.eq_ignore_ascii_case(value))
+        || is_css_function(value, CSS_FUNCTIONS)
+    {
+        return true;
+    }
+    let lower = value.to_ascii_lowercase();
+    LENGTH_UNITS
+        .iter()
+        .any(|unit| lower.strip_suffix(unit).is_some_and(is_number))
+}
+
+fn is_font_size_keyword(value: &str) -> bool {
+    FONT_SIZE_KEYWORDS
+        .iter()
+        .any(|k| k.eq_ignore_ascii_case(value))
+}
+
+fn is_color(value: &str) -> bool {
+    if let Some(hex) = value.strip_prefix('#') {
+        return matches!(hex.len(), 3 | 4 | 6 | 8) && hex.bytes().all(|b| b.is_ascii_hexdigit());
+    }
+    is_css_function(value, COLOR_FUNCTIONS)
+        || NAMED_COLORS.iter().any(|c| c.eq_ignore_ascii_case(value))
+}
+
+fn is_date(value: &str) -> bool {
+    let parts: Vec<&str> = value.split('-').collect();
+    let [year, month, day] = parts.as_slice() else {
+        return false;
+    };
+    if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return false;
+    }
+    let (Ok(_), Ok(month), Ok(day)) = (
+        year.parse::<u32>(),
+        month.parse::<u32>(),
+        day.parse::<u32>(),
+    ) else {
+        return false;
+    };
+    (1..=12).contains(&month) && (1..=31).contains(&day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lengths() {
+        for ok in [
+            "120px",
+            "50%",
+            "1.5em",
+            ".5rem",
+            "0",
+            "auto",
+            "calc(100% - 2px)",
+        ] {
+            assert_eq!(ValueType::Length.validate(ok), Ok(()), "{ok}");
+        }
+        for bad in ["100", "px", "wide", "10 px", "1.2.3em"] {
+            assert!(ValueType::Length.validate(bad).is_err(), "{bad}");
+        }
+        assert_eq!(ValueType::FontSize.validate("x-large"), Ok(()));
+    }
+
+    #[test]
+    fn colors() {
+        for ok in [
+            "#f00",
+            "#FF000080",
+            "red",
+            "RebeccaPurple",
+            "rgb(0 0 0 / 50%)",
+        ] {
+            assert_eq!(ValueType::Color.validate(ok), Ok(()), "{ok}");
+        }
+        for bad in ["#ff00f", "redd", "rgb(0,0,0", "ff0000"] {
+            assert!(ValueType::Color.validate(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn flags_enums_numbers_and_dates() {
+        assert_eq!(ValueType::Flag.validate(""), Ok(()));
+        assert_eq!(
+            ValueType::Flag.validate("false"),
+            Err(ValueError::FlagWithValue)
+        );
+
+        let align = ValueType::Enum(&["left", "center", "right"]);
+        assert_eq!(align.validate(" Center "), Ok(()));
+        assert_eq!(
+            align.validate("middle"),
+            Err(ValueError::Expected(
+                "one of left, center, right".to_string()
+            ))
+        );
+
+        assert_eq!(ValueType::Integer.validate("90"), Ok(()));
+        assert!(ValueType::Integer.validate("1.5").is_err());
+        assert_eq!(ValueType::Number.validate("0.5"), Ok(()));
+        assert_eq!(ValueType::Number.validate("50%"), Ok(()));
+        assert!(ValueType::Number.validate("half").is_err());
+
+        assert_eq!(ValueType::Date.validate("2024-02-29"), Ok(()));
+        assert!(ValueType::Date.validate("2024-13-01").is_err());
+        assert!(ValueType::Date.validate("24-1-1").is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use sevenmark_parser::core::parse_document;
 use sevenmark_server::connection::database_conn::establish_connection;
-use sevenmark_server::connection::r2_conn::establish_revision_storage_connection;
+use sevenmark_server::connection::revision_storage_conn::establish_revision_storage_connection;
+use sevenmark_server::server_config::ServerConfig;
 use sevenmark_transform::process_sevenmark;
 use std::fs;
 use std::time::Instant;
@@ -15,11 +16,13 @@ async fn main() -> anyhow::Result<()> {
 
     println!("Input ({} bytes):\n{}\n", document_len, "=".repeat(50));
 
+    let config = ServerConfig::load()?;
+
     // Establish database connection
-    let db = establish_connection().await?;
+    let db = establish_connection(&config).await?;
 
-    // Establish R2 revision storage connection
-    let revision_storage = establish_revision_storage_connection().await?;
+    // Establish revision storage connection
+    let revision_storage = establish_revision_storage_connection(&config).await?;
 
     println!("Using database connection\n");
 
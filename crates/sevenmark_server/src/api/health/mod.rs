@@ -1,3 +1,5 @@
 pub mod health_check;
 pub mod openapi;
+pub mod readiness_check;
 pub mod routes;
+pub mod startup_check;
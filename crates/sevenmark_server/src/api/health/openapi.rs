@@ -1,10 +1,14 @@
+use crate::readiness::{DependencyStatus, ReadinessReport};
 use utoipa::OpenApi;
 
 #[derive(OpenApi)]
 #[openapi(
     paths(
         super::health_check::health_check,
+        super::readiness_check::readiness_check,
+        super::startup_check::startup_check,
     ),
+    components(schemas(ReadinessReport, DependencyStatus)),
     tags(
         (name = "Health", description = "Health endpoints")
     )
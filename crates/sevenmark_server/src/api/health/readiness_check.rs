@@ -0,0 +1,29 @@
+use crate::readiness::ReadinessReport;
+use crate::state::AppState;
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+
+pub(super) fn report_response(report: ReadinessReport) -> impl IntoResponse {
+    let status = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
+
+/// Checks Postgres and the R2 revision bucket (cached for `READINESS_CACHE_MS`)
+#[utoipa::path(
+    get,
+    path = "/readiness-check",
+    responses(
+        (status = 200, description = "All dependencies are reachable", body = ReadinessReport),
+        (status = 503, description = "A dependency is unreachable", body = ReadinessReport),
+    ),
+    tag = "Health"
+)]
+pub async fn readiness_check(State(state): State<AppState>) -> impl IntoResponse {
+    report_response(state.readiness.check(&state).await)
+}
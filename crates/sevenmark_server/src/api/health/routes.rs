@@ -1,7 +1,12 @@
 use super::health_check::health_check;
+use super::readiness_check::readiness_check;
+use super::startup_check::startup_check;
 use crate::state::AppState;
 use axum::{Router, routing::get};
 
 pub fn health_routes() -> Router<AppState> {
-    Router::new().route("/health-check", get(health_check))
+    Router::new()
+        .route("/health-check", get(health_check))
+        .route("/readiness-check", get(readiness_check))
+        .route("/startup-check", get(startup_check))
 }
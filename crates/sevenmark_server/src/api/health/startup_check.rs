@@ -0,0 +1,24 @@
+use super::readiness_check::report_response;
+use crate::readiness::ReadinessReport;
+use crate::state::AppState;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+
+/// Runs the readiness checks uncached until they pass once, then always passes
+#[utoipa::path(
+    get,
+    path = "/startup-check",
+    responses(
+        (status = 200, description = "Dependencies became reachable on this check", body = ReadinessReport),
+        (status = 204, description = "Startup already completed"),
+        (status = 503, description = "A dependency is unreachable", body = ReadinessReport),
+    ),
+    tag = "Health"
+)]
+pub async fn startup_check(State(state): State<AppState>) -> Response {
+    match state.readiness.check_startup(&state).await {
+        Some(report) => report_response(report).into_response(),
+        None => StatusCode::NO_CONTENT.into_response(),
+    }
+}
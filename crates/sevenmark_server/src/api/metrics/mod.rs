@@ -0,0 +1,3 @@
+pub mod openapi;
+pub mod prometheus_metrics;
+pub mod routes;
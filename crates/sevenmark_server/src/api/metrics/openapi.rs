@@ -0,0 +1,12 @@
+use utoipa::OpenApi;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        super::prometheus_metrics::prometheus_metrics,
+    ),
+    tags(
+        (name = "Metrics", description = "Prometheus metrics")
+    )
+)]
+pub struct MetricsApiDoc;
@@ -0,0 +1,7 @@
+use super::prometheus_metrics::prometheus_metrics;
+use crate::state::AppState;
+use axum::{Router, routing::get};
+
+pub fn metrics_routes() -> Router<AppState> {
+    Router::new().route("/metrics", get(prometheus_metrics))
+}
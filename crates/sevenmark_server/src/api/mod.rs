@@ -1,4 +1,5 @@
 pub mod health;
+pub mod metrics;
 pub mod openapi;
 pub mod routes;
 pub mod v0;
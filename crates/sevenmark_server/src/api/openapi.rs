@@ -1,4 +1,5 @@
 use super::health::openapi::HealthApiDoc;
+use super::metrics::openapi::MetricsApiDoc;
 use super::v0::routes::openapi::V0ApiDoc;
 use crate::errors::errors::ErrorResponse;
 use utoipa::OpenApi;
@@ -11,6 +12,7 @@ impl ApiDoc {
     pub fn merged() -> utoipa::openapi::OpenApi {
         let mut openapi = Self::openapi();
         openapi.merge(HealthApiDoc::openapi());
+        openapi.merge(MetricsApiDoc::openapi());
         openapi.merge(V0ApiDoc::merged());
         openapi
     }
@@ -1,12 +1,16 @@
 use super::health::routes::health_routes;
+use super::metrics::routes::metrics_routes;
 use super::openapi::ApiDoc;
 use super::v0::routes::routes::v0_routes;
 use crate::errors::errors::handler_404;
 use crate::state::AppState;
+use crate::telemetry::track_requests;
 use axum::Router;
+use axum::extract::DefaultBodyLimit;
+use axum::middleware;
 use utoipa_swagger_ui::SwaggerUi;
 
-/// 최상위 API 라우터 (health + versioned APIs)
+/// 최상위 API 라우터 (health + metrics + versioned APIs)
 pub fn api_routes(state: AppState) -> Router<AppState> {
     let mut router = Router::new();
 
@@ -15,8 +19,14 @@ pub fn api_routes(state: AppState) -> Router<AppState> {
         router = router.merge(SwaggerUi::new("/docs").url("/swagger.json", ApiDoc::merged()));
     }
 
+    let max_body_bytes = state.config.max_body_bytes;
     router
         .merge(health_routes())
-        .nest("/v0", v0_routes(state))
+        .merge(metrics_routes())
+        .nest(
+            "/v0",
+            v0_routes(state).layer(DefaultBodyLimit::max(max_body_bytes)),
+        )
         .fallback(handler_404)
+        .layer(middleware::from_fn(track_requests))
 }
@@ -0,0 +1,126 @@
+use crate::errors::errors::Errors;
+use crate::state::AppState;
+use axum::Json;
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::http::header::AUTHORIZATION;
+use serde::{Deserialize, Serialize};
+use sevenmark_transform::preprocessor::DocumentReference;
+use sevenmark_transform::wiki::DocumentNamespace;
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct InvalidateRequest {
+    /// Documents that were created, edited, moved or deleted
+    pub documents: Vec<InvalidatedDocument>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct InvalidatedDocument {
+    pub namespace: DocumentNamespace,
+    pub title: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InvalidateResponse {
+    /// Number of evicted cache entries
+    pub evicted: usize,
+}
+
+/// Check the `Authorization: Bearer` token against `INVALIDATE_TOKEN`.
+/// Without a configured token every call is rejected.
+fn authorize(headers: &HeaderMap, expected: Option<&str>) -> Result<(), Errors> {
+    let Some(expected) = expected else {
+        return Err(Errors::Unauthorized(
+            "INVALIDATE_TOKEN is not configured".to_string(),
+        ));
+    };
+    let token = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or_else(|| Errors::Unauthorized("missing bearer token".to_string()))?;
+    if !constant_time_eq(token.as_bytes(), expected.as_bytes()) {
+        return Err(Errors::Unauthorized("invalid bearer token".to_string()));
+    }
+    Ok(())
+}
+
+/// Compare without stopping at the first difference
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Evict the renders that depend on changed documents. Requires
+/// `Authorization: Bearer <INVALIDATE_TOKEN>`.
+#[utoipa::path(
+    post,
+    path = "/v0/invalidate",
+    request_body = InvalidateRequest,
+    responses(
+        (status = 200, description = "Dependent renders evicted", body = InvalidateResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Missing or wrong token, or INVALIDATE_TOKEN is not configured")
+    ),
+    security(("invalidate_token" = [])),
+    tag = "Cache"
+)]
+pub async fn invalidate(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<InvalidateRequest>,
+) -> Result<Json<InvalidateResponse>, Errors> {
+    authorize(&headers, state.config.invalidate_token.as_deref())?;
+
+    let changed: Vec<_> = payload
+        .documents
+        .into_iter()
+        .map(|doc| DocumentReference {
+            namespace: doc.namespace,
+            title: doc.title,
+        })
+        .collect();
+
+    let evicted = state.render_cache.invalidate(&changed).await;
+    Ok(Json(InvalidateResponse { evicted }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn bearer(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {token}")).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn matching_token_is_accepted() {
+        assert!(authorize(&bearer("s3cret"), Some("s3cret")).is_ok());
+    }
+
+    #[test]
+    fn missing_or_wrong_token_is_rejected() {
+        assert!(matches!(
+            authorize(&HeaderMap::new(), Some("s3cret")),
+            Err(Errors::Unauthorized(_))
+        ));
+        assert!(matches!(
+            authorize(&bearer("s3cre"), Some("s3cret")),
+            Err(Errors::Unauthorized(_))
+        ));
+    }
+
+    #[test]
+    fn unconfigured_token_rejects_every_call() {
+        assert!(matches!(
+            authorize(&bearer(""), None),
+            Err(Errors::Unauthorized(_))
+        ));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod invalidate;
+pub mod openapi;
+pub mod routes;
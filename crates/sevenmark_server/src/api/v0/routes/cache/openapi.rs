@@ -0,0 +1,34 @@
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+/// Bearer token for `POST /v0/invalidate` (`INVALIDATE_TOKEN`)
+struct InvalidateToken;
+
+impl Modify for InvalidateToken {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "invalidate_token",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+    }
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        super::invalidate::invalidate,
+    ),
+    components(
+        schemas(
+            super::invalidate::InvalidateRequest,
+            super::invalidate::InvalidatedDocument,
+            super::invalidate::InvalidateResponse,
+        )
+    ),
+    modifiers(&InvalidateToken),
+    tags(
+        (name = "Cache", description = "Render cache endpoints")
+    )
+)]
+pub struct CacheApiDoc;
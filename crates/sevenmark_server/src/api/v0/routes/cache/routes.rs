@@ -0,0 +1,8 @@
+use super::invalidate::invalidate;
+use crate::state::AppState;
+use axum::Router;
+use axum::routing::post;
+
+pub fn cache_routes(_state: AppState) -> Router<AppState> {
+    Router::new().route("/invalidate", post(invalidate))
+}
@@ -1,3 +1,6 @@
+mod cache;
 pub mod openapi;
 mod render;
+#[allow(clippy::module_inception)]
 pub mod routes;
+mod source;
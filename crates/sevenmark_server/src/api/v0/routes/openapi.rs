@@ -1,4 +1,6 @@
+use super::cache::openapi::CacheApiDoc;
 use super::render::openapi::RenderApiDoc;
+use super::source::openapi::SourceApiDoc;
 use utoipa::OpenApi;
 
 #[derive(OpenApi)]
@@ -9,6 +11,8 @@ impl V0ApiDoc {
     pub fn merged() -> utoipa::openapi::OpenApi {
         let mut openapi = Self::openapi();
         openapi.merge(RenderApiDoc::openapi());
+        openapi.merge(SourceApiDoc::openapi());
+        openapi.merge(CacheApiDoc::openapi());
         openapi
     }
 }
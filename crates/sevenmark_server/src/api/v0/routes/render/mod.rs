@@ -1,8 +1,11 @@
+use sevenmark_html::RenderConfig;
 use std::collections::HashSet;
 
 pub mod openapi;
+pub mod render_batch;
 pub mod render_discussion;
 pub mod render_document;
+pub mod render_document_by_title;
 pub mod routes;
 
 fn sort_strings(values: HashSet<String>) -> Vec<String> {
@@ -10,3 +13,32 @@ fn sort_strings(values: HashSet<String>) -> Vec<String> {
     values.sort();
     values
 }
+
+/// Owned copy of the render base URLs, for moving into blocking tasks
+#[derive(Debug, Clone, Default)]
+struct BaseUrls {
+    file: Option<String>,
+    document: Option<String>,
+    category: Option<String>,
+    user: Option<String>,
+}
+
+impl BaseUrls {
+    fn new(config: &RenderConfig<'_>) -> Self {
+        Self {
+            file: config.file_base_url.map(str::to_string),
+            document: config.document_base_url.map(str::to_string),
+            category: config.category_base_url.map(str::to_string),
+            user: config.user_base_url.map(str::to_string),
+        }
+    }
+
+    fn config(&self) -> RenderConfig<'_> {
+        RenderConfig {
+            file_base_url: self.file.as_deref(),
+            document_base_url: self.document.as_deref(),
+            category_base_url: self.category.as_deref(),
+            user_base_url: self.user.as_deref(),
+        }
+    }
+}
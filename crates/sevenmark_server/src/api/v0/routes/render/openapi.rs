@@ -4,14 +4,20 @@ use utoipa::OpenApi;
 #[openapi(
     paths(
         super::render_document::render_document,
+        super::render_document_by_title::render_document_by_title,
         super::render_discussion::render_discussion,
+        super::render_batch::render_batch,
     ),
     components(
         schemas(
             super::render_document::RenderDocumentRequest,
-            super::render_document::RenderedDocument,
+            crate::rendered::RenderedDocument,
             super::render_discussion::RenderDiscussionRequest,
             super::render_discussion::RenderedDiscussion,
+            super::render_batch::RenderBatchRequest,
+            super::render_batch::RenderBatchDocument,
+            super::render_batch::RenderBatchResult,
+            sevenmark_transform::ProcessWarning,
         )
     ),
     tags(
@@ -0,0 +1,250 @@
+use super::BaseUrls;
+use super::render_document::rendered_document;
+use crate::errors::errors::Errors;
+use crate::limits::{
+    Deadline, check_ast_size, check_content_size, process_error, process_limits, run_blocking,
+};
+use crate::rendered::RenderedDocument;
+use crate::state::AppState;
+use axum::Json;
+use axum::body::{Body, Bytes};
+use axum::extract::State;
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use futures::StreamExt;
+use futures::future::join_all;
+use futures::stream::{self, FuturesUnordered};
+use serde::{Deserialize, Serialize};
+use sevenmark_html::render_document_with_spans;
+use sevenmark_parser::core::parse_document;
+use sevenmark_transform::process_sevenmark_batch;
+use sevenmark_transform::telemetry::{Phase, time_phase};
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RenderBatchRequest {
+    /// Documents to render (at most `RENDER_BATCH_MAX_DOCUMENTS`)
+    pub documents: Vec<RenderBatchDocument>,
+    /// Base URL for file/media (e.g., Cloudflare CDN URL)
+    pub file_base_url: String,
+    /// Base URL for document links (e.g., "/Document/")
+    pub document_base_url: String,
+    /// Base URL for category links (e.g., "/Category/")
+    pub category_base_url: String,
+    /// Base URL for user document links (e.g., "/User/")
+    pub user_base_url: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RenderBatchDocument {
+    /// Caller-defined identifier echoed back in the result
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Raw SevenMark content to render
+    pub content: String,
+}
+
+/// One NDJSON line of the response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RenderBatchResult {
+    /// Position of the document in the request
+    pub index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub document: Option<RenderedDocument>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl RenderBatchResult {
+    fn failed(index: usize, id: Option<String>, error: String) -> Self {
+        Self {
+            index,
+            id,
+            document: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// Per-item message for an error that would otherwise fail the request
+fn item_error(error: Errors) -> String {
+    match error {
+        Errors::DocumentTooLarge(msg) | Errors::SysInternalError(msg) => msg,
+        Errors::DocumentProcessingTimeout => "processing timed out".to_string(),
+        other => format!("{other:?}"),
+    }
+}
+
+fn ndjson_line(result: &RenderBatchResult) -> Result<Bytes, std::io::Error> {
+    let mut line = serde_json::to_vec(result).map_err(std::io::Error::other)?;
+    line.push(b'\n');
+    Ok(Bytes::from(line))
+}
+
+/// Render many documents at once for bulk reindexing.
+///
+/// Includes and media of the whole batch are looked up together, then every
+/// document is rendered on the blocking pool. Results are streamed as NDJSON
+/// in completion order; `index` refers to the request order. Size limits,
+/// lookup errors and the processing timeout fail only the affected items.
+/// Parsing and rendering of each document have their own timeout. Batch
+/// renders bypass the render cache.
+#[utoipa::path(
+    post,
+    path = "/v0/render-batch",
+    request_body = RenderBatchRequest,
+    responses(
+        (status = 200, description = "One RenderBatchResult per line", body = RenderBatchResult, content_type = "application/x-ndjson"),
+        (status = 400, description = "Bad request or too many documents"),
+        (status = 413, description = "Request body over the configured limit"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Render"
+)]
+pub async fn render_batch(
+    State(state): State<AppState>,
+    Json(payload): Json<RenderBatchRequest>,
+) -> Result<Response, Errors> {
+    let max_documents = state.config.render_batch_max_documents;
+    if payload.documents.len() > max_documents {
+        return Err(Errors::BadRequestError(format!(
+            "Batch has {} documents, at most {max_documents} are allowed",
+            payload.documents.len()
+        )));
+    }
+
+    let RenderBatchRequest {
+        documents,
+        file_base_url,
+        document_base_url,
+        category_base_url,
+        user_base_url,
+    } = payload;
+    let urls = Arc::new(BaseUrls {
+        file: Some(file_base_url),
+        document: Some(document_base_url),
+        category: Some(category_base_url),
+        user: Some(user_base_url),
+    });
+    let state = Arc::new(state);
+
+    // Parse
+    let parsed = join_all(documents.into_iter().map(|doc| {
+        let state = &state;
+        async move {
+            let content = doc.content;
+            let result = async {
+                check_content_size(&state.config, &content)?;
+                let server_config = state.config.clone();
+                run_blocking(state, &Deadline::new(&state.config), move || {
+                    let ast = time_phase(Phase::Parse, || parse_document(&content));
+                    check_ast_size(&server_config, &ast).map(|()| (content, ast))
+                })
+                .await?
+            }
+            .await;
+            (doc.id, result)
+        }
+    }))
+    .await;
+
+    let mut failed = Vec::new();
+    let mut items = Vec::new();
+    let mut asts = Vec::new();
+    for (index, (id, result)) in parsed.into_iter().enumerate() {
+        match result {
+            Ok((content, ast)) => {
+                items.push((index, id, content));
+                asts.push(ast);
+            }
+            Err(e) => failed.push(RenderBatchResult::failed(index, id, item_error(e))),
+        }
+    }
+
+    // Process (includes and media of the whole batch in one lookup each)
+    let processed = process_sevenmark_batch(
+        asts,
+        &state.conn,
+        &state.revision_storage,
+        &process_limits(&state),
+    )
+    .await;
+
+    let mut accepted = Vec::new();
+    match processed {
+        Ok(processed) => {
+            for (result, (index, id, content)) in processed.into_iter().zip(items) {
+                match result {
+                    Ok(processed) => accepted.push((index, id, content, processed)),
+                    Err(e) => failed.push(RenderBatchResult::failed(index, id, e.to_string())),
+                }
+            }
+        }
+        // A failed lookup fails every document that was waiting for it
+        Err(e) => {
+            let error = item_error(process_error(e));
+            for (index, id, _) in items {
+                failed.push(RenderBatchResult::failed(index, id, error.clone()));
+            }
+        }
+    }
+
+    // Render
+    let rendered: FuturesUnordered<_> = accepted
+        .into_iter()
+        .map(|(index, id, content, processed)| {
+            let state = state.clone();
+            let urls = urls.clone();
+            async move {
+                let result = run_blocking(&state, &Deadline::new(&state.config), move || {
+                    let html = time_phase(Phase::Render, || {
+                        render_document_with_spans(&processed.ast, &urls.config(), &content)
+                    });
+                    rendered_document(processed, html)
+                })
+                .await;
+                match result {
+                    Ok(document) => RenderBatchResult {
+                        index,
+                        id,
+                        document: Some(document),
+                        error: None,
+                    },
+                    Err(e) => RenderBatchResult::failed(index, id, item_error(e)),
+                }
+            }
+        })
+        .collect();
+
+    let lines = stream::iter(failed)
+        .chain(rendered)
+        .map(|result| ndjson_line(&result));
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(lines),
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ndjson_line_omits_missing_fields() {
+        let line = ndjson_line(&RenderBatchResult::failed(
+            3,
+            Some("doc-3".to_string()),
+            "boom".to_string(),
+        ))
+        .unwrap();
+        assert_eq!(
+            &line[..],
+            b"{\"index\":3,\"id\":\"doc-3\",\"error\":\"boom\"}\n"
+        );
+    }
+}
@@ -1,13 +1,16 @@
-use super::sort_strings;
+use super::{BaseUrls, sort_strings};
 use crate::errors::errors::Errors;
+use crate::limits::{
+    Deadline, check_ast_size, check_content_size, process_error, process_limits, run_blocking,
+};
 use crate::state::AppState;
 use axum::Json;
 use axum::extract::State;
 use serde::{Deserialize, Serialize};
-use sevenmark_html::{RenderConfig, render_document as render_html};
+use sevenmark_html::render_document as render_html;
 use sevenmark_parser::core::parse_document;
-use sevenmark_transform::process_sevenmark;
-use tokio::task::spawn_blocking;
+use sevenmark_transform::telemetry::{Phase, time_phase};
+use sevenmark_transform::{ProcessWarning, process_sevenmark_with_limits};
 use utoipa::ToSchema;
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -30,6 +33,8 @@ pub struct RenderedDiscussion {
     pub html: String,
     /// User mention UUIDs collected from the content
     pub user_mentions: Vec<String>,
+    /// Includes and media that could not be resolved (shown as placeholders)
+    pub warnings: Vec<ProcessWarning>,
 }
 
 #[utoipa::path(
@@ -39,6 +44,8 @@ pub struct RenderedDiscussion {
     responses(
         (status = 200, description = "Discussion rendered successfully", body = RenderedDiscussion),
         (status = 400, description = "Bad request"),
+        (status = 413, description = "Content, element count or includes over the configured limits"),
+        (status = 503, description = "Processing timed out"),
         (status = 500, description = "Internal server error")
     ),
     tag = "Render"
@@ -55,28 +62,53 @@ pub async fn render_discussion(
         user_base_url,
     } = payload;
 
-    // Parse
-    let ast = spawn_blocking(move || parse_document(&content))
-        .await
-        .map_err(|e| Errors::SysInternalError(format!("Parser task failed: {e}")))?;
+    check_content_size(&state.config, &content)?;
 
-    // Process (resolve includes, media, etc.)
-    let processed = process_sevenmark(ast, &state.conn, &state.revision_storage)
-        .await
-        .map_err(|e| Errors::SysInternalError(e.to_string()))?;
+    let urls = BaseUrls {
+        file: Some(file_base_url),
+        document: Some(document_base_url),
+        category: Some(category_base_url),
+        user: Some(user_base_url),
+    };
+    let (html, user_mentions, warnings) = {
+        let deadline = Deadline::new(&state.config);
+
+        // Parse
+        let server_config = state.config.clone();
+        let ast = run_blocking(&state, &deadline, move || {
+            let ast = time_phase(Phase::Parse, || parse_document(&content));
+            check_ast_size(&server_config, &ast).map(|()| ast)
+        })
+        .await??;
+
+        // Process (resolve includes, media, etc.)
+        let processed = deadline
+            .run(async {
+                process_sevenmark_with_limits(
+                    ast,
+                    &state.conn,
+                    &state.revision_storage,
+                    &process_limits(&state),
+                )
+                .await
+                .map_err(process_error)
+            })
+            .await?;
 
-    // Render to HTML
-    let config = RenderConfig {
-        file_base_url: Some(&file_base_url),
-        document_base_url: Some(&document_base_url),
-        category_base_url: Some(&category_base_url),
-        user_base_url: Some(&user_base_url),
+        // Render to HTML
+        run_blocking(&state, &deadline, move || {
+            let html = time_phase(Phase::Render, || {
+                render_html(&processed.ast, &urls.config())
+            });
+            (html, processed.user_mentions, processed.warnings)
+        })
+        .await?
     };
-    let html = render_html(&processed.ast, &config);
 
     Ok(Json(RenderedDiscussion {
         html,
-        user_mentions: sort_strings(processed.user_mentions),
+        user_mentions: sort_strings(user_mentions),
+        warnings,
     }))
 }
 
@@ -1,16 +1,22 @@
-use super::sort_strings;
+use super::{BaseUrls, sort_strings};
 use crate::errors::errors::Errors;
+use crate::limits::{
+    Deadline, check_ast_size, check_content_size, process_error, process_limits, run_blocking,
+};
+use crate::render_cache::{CachedRender, RenderCacheKey, render_dependencies};
+use crate::rendered::RenderedDocument;
 use crate::state::AppState;
+use crate::telemetry::record_cache_lookup;
 use axum::Json;
 use axum::extract::State;
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use sevenmark_html::{RenderConfig, render_document_with_spans};
 use sevenmark_parser::core::parse_document;
-use sevenmark_transform::preprocessor::{DocumentReference, RedirectReference, SectionInfo};
-use sevenmark_transform::process_sevenmark;
+use sevenmark_transform::preprocessor::DocumentReference;
+use sevenmark_transform::telemetry::{Phase, time_phase};
 use sevenmark_transform::wiki::DocumentNamespace;
+use sevenmark_transform::{ProcessWarning, ProcessedDocument, process_sevenmark_with_limits};
 use std::collections::HashSet;
-use tokio::task::spawn_blocking;
 use utoipa::ToSchema;
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -27,24 +33,6 @@ pub struct RenderDocumentRequest {
     pub user_base_url: String,
 }
 
-#[derive(Debug, Clone, Serialize, ToSchema)]
-pub struct RenderedDocument {
-    /// Rendered HTML content
-    pub html: String,
-    /// Document categories
-    pub categories: Vec<String>,
-    /// Redirect target if this is a redirect page
-    #[schema(value_type = Option<Object>)]
-    pub redirect: Option<RedirectReference>,
-    /// Referenced documents (for backlinks)
-    #[schema(value_type = Vec<Object>)]
-    pub references: Vec<DocumentReference>,
-    /// User mention UUIDs collected from the document
-    pub user_mentions: Vec<String>,
-    /// Section information with byte offsets for section editing
-    pub sections: Vec<SectionInfo>,
-}
-
 fn namespace_sort_key(namespace: &DocumentNamespace) -> u8 {
     match namespace {
         DocumentNamespace::Document => 0,
@@ -71,6 +59,8 @@ fn sort_references(references: HashSet<DocumentReference>) -> Vec<DocumentRefere
     responses(
         (status = 200, description = "Document rendered successfully", body = RenderedDocument),
         (status = 400, description = "Bad request"),
+        (status = 413, description = "Content, element count or includes over the configured limits"),
+        (status = 503, description = "Processing timed out"),
         (status = 500, description = "Internal server error")
     ),
     tag = "Render"
@@ -87,36 +77,94 @@ pub async fn render_document(
         user_base_url,
     } = payload;
 
-    // Parse
-    let (content, ast) = spawn_blocking(move || {
-        let ast = parse_document(&content);
-        (content, ast)
-    })
-    .await
-    .map_err(|e| Errors::SysInternalError(format!("Parser task failed: {e}")))?;
-
-    // Process (resolve includes, media, etc.)
-    let processed = process_sevenmark(ast, &state.conn, &state.revision_storage)
-        .await
-        .map_err(|e| Errors::SysInternalError(e.to_string()))?;
-
-    // Render to HTML with span data attributes for editor sync
     let config = RenderConfig {
         file_base_url: Some(&file_base_url),
         document_base_url: Some(&document_base_url),
         category_base_url: Some(&category_base_url),
         user_base_url: Some(&user_base_url),
     };
-    let html = render_document_with_spans(&processed.ast, &config, &content);
+    render_content(&state, content, &config, None)
+        .await
+        .map(Json)
+}
 
-    Ok(Json(RenderedDocument {
+/// Response for a processed document and its HTML
+pub(super) fn rendered_document(processed: ProcessedDocument, html: String) -> RenderedDocument {
+    RenderedDocument {
         html,
         categories: sort_strings(processed.categories),
         redirect: processed.redirect,
         references: sort_references(processed.references),
         user_mentions: sort_strings(processed.user_mentions),
         sections: processed.sections,
-    }))
+        redirected_from: None,
+        warnings: processed.warnings,
+    }
+}
+
+/// Parse, process and render document content through the render cache.
+/// `source` is the page the content belongs to, if known.
+pub(super) async fn render_content(
+    state: &AppState,
+    content: String,
+    config: &RenderConfig<'_>,
+    source: Option<DocumentReference>,
+) -> Result<RenderedDocument, Errors> {
+    check_content_size(&state.config, &content)?;
+
+    let key = RenderCacheKey::new(&content, config);
+    let cached = state.render_cache.get(&key, source.as_ref()).await;
+    record_cache_lookup(cached.is_some());
+    if let Some(cached) = cached {
+        return Ok(cached);
+    }
+
+    let urls = BaseUrls::new(config);
+    let (rendered, dependencies) = {
+        let deadline = Deadline::new(&state.config);
+
+        // Parse
+        let server_config = state.config.clone();
+        let (content, ast) = run_blocking(state, &deadline, move || {
+            let ast = time_phase(Phase::Parse, || parse_document(&content));
+            check_ast_size(&server_config, &ast).map(|()| (content, ast))
+        })
+        .await??;
+
+        // Process (resolve includes, media, etc.)
+        let processed = deadline
+            .run(async {
+                process_sevenmark_with_limits(
+                    ast,
+                    &state.conn,
+                    &state.revision_storage,
+                    &process_limits(state),
+                )
+                .await
+                .map_err(process_error)
+            })
+            .await?;
+
+        // Render to HTML with span data attributes for editor sync
+        run_blocking(state, &deadline, move || {
+            let html = time_phase(Phase::Render, || {
+                render_document_with_spans(&processed.ast, &urls.config(), &content)
+            });
+            let dependencies = render_dependencies(&processed);
+            (rendered_document(processed, html), dependencies)
+        })
+        .await?
+    };
+
+    // Output missing content due to a failed lookup is not cached
+    if !rendered.warnings.iter().any(ProcessWarning::is_transient) {
+        let entry = CachedRender {
+            document: rendered.clone(),
+            dependencies,
+        };
+        state.render_cache.insert(key, entry, source).await;
+    }
+    Ok(rendered)
 }
 
 #[cfg(test)]
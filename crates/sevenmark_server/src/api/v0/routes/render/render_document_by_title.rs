@@ -0,0 +1,143 @@
+use super::render_document::render_content;
+use crate::config::server_config::ServerConfig;
+use crate::errors::errors::Errors;
+use crate::rendered::RenderedDocument;
+use crate::state::AppState;
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use serde::Deserialize;
+use sevenmark_html::RenderConfig;
+use sevenmark_transform::preprocessor::DocumentReference;
+use sevenmark_transform::wiki::{DocumentNamespace, RevisionLookup, fetch_document_revision};
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RenderDocumentByTitleQuery {
+    /// Revision to render instead of the current one
+    #[param(value_type = Option<String>)]
+    pub revision: Option<Uuid>,
+    /// Render the target of a redirect page instead of the page itself (one hop)
+    #[serde(default)]
+    pub follow_redirects: bool,
+}
+
+/// Namespace path segment (`document`, `file`, `category`, `user`; case-insensitive)
+fn parse_namespace(value: &str) -> Option<DocumentNamespace> {
+    match value.to_ascii_lowercase().as_str() {
+        "document" => Some(DocumentNamespace::Document),
+        "file" => Some(DocumentNamespace::File),
+        "category" => Some(DocumentNamespace::Category),
+        "user" => Some(DocumentNamespace::User),
+        _ => None,
+    }
+}
+
+/// Base URLs from the server configuration
+fn default_render_config(config: &ServerConfig) -> RenderConfig<'_> {
+    RenderConfig {
+        file_base_url: Some(&config.render_file_base_url),
+        document_base_url: Some(&config.render_document_base_url),
+        category_base_url: Some(&config.render_category_base_url),
+        user_base_url: Some(&config.render_user_base_url),
+    }
+}
+
+async fn load_content(
+    state: &AppState,
+    namespace: DocumentNamespace,
+    title: &str,
+    revision: Option<Uuid>,
+) -> Result<String, Errors> {
+    let lookup = fetch_document_revision(
+        &state.conn,
+        &state.revision_storage,
+        namespace,
+        title,
+        revision,
+    )
+    .await
+    .map_err(|e| Errors::SysInternalError(format!("{e:#}")))?;
+
+    match lookup {
+        RevisionLookup::Found { content, .. } => Ok(content),
+        RevisionLookup::DocumentNotFound => Err(Errors::DocumentNotFound),
+        RevisionLookup::RevisionNotFound => Err(Errors::DocumentRevisionNotFound),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v0/documents/{namespace}/{title}/render",
+    params(
+        ("namespace" = String, Path, description = "Document namespace (document, file, category, user)"),
+        ("title" = String, Path, description = "Document title (URL-encoded)"),
+        RenderDocumentByTitleQuery,
+    ),
+    responses(
+        (status = 200, description = "Document rendered successfully", body = RenderedDocument),
+        (status = 400, description = "Unknown namespace or invalid query"),
+        (status = 404, description = "Document or revision not found"),
+        (status = 413, description = "Content, element count or includes over the configured limits"),
+        (status = 503, description = "Processing timed out"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Render"
+)]
+pub async fn render_document_by_title(
+    State(state): State<AppState>,
+    Path((namespace, title)): Path<(String, String)>,
+    Query(query): Query<RenderDocumentByTitleQuery>,
+) -> Result<Json<RenderedDocument>, Errors> {
+    let namespace = parse_namespace(&namespace)
+        .ok_or_else(|| Errors::BadRequestError(format!("Unknown namespace: {namespace}")))?;
+    let config = default_render_config(&state.config);
+
+    let page = DocumentReference { namespace, title };
+    let content = load_content(&state, page.namespace.clone(), &page.title, query.revision).await?;
+    let rendered = render_content(&state, content, &config, Some(page.clone())).await?;
+
+    let target = match &rendered.redirect {
+        Some(target) if query.follow_redirects => target,
+        _ => return Ok(Json(rendered)),
+    };
+    if target.namespace == page.namespace && target.title == page.title {
+        return Ok(Json(rendered));
+    }
+
+    // A missing target still shows the redirect page itself
+    let content = match load_content(&state, target.namespace.clone(), &target.title, None).await {
+        Ok(content) => content,
+        Err(Errors::DocumentNotFound | Errors::DocumentRevisionNotFound) => {
+            return Ok(Json(rendered));
+        }
+        Err(e) => return Err(e),
+    };
+    let source = DocumentReference {
+        namespace: target.namespace.clone(),
+        title: target.title.clone(),
+    };
+    let mut target_rendered = render_content(&state, content, &config, Some(source)).await?;
+    target_rendered.redirected_from = Some(page);
+    Ok(Json(target_rendered))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_namespace_is_case_insensitive() {
+        assert_eq!(
+            parse_namespace("document"),
+            Some(DocumentNamespace::Document)
+        );
+        assert_eq!(
+            parse_namespace("Category"),
+            Some(DocumentNamespace::Category)
+        );
+        assert_eq!(parse_namespace("USER"), Some(DocumentNamespace::User));
+        assert_eq!(parse_namespace("talk"), None);
+    }
+}
@@ -1,11 +1,18 @@
+use super::render_batch::render_batch;
 use super::render_discussion::render_discussion;
 use super::render_document::render_document;
+use super::render_document_by_title::render_document_by_title;
 use crate::state::AppState;
 use axum::Router;
-use axum::routing::post;
+use axum::routing::{get, post};
 
 pub fn render_routes(_state: AppState) -> Router<AppState> {
     Router::new()
         .route("/render-document", post(render_document))
         .route("/render-discussion", post(render_discussion))
+        .route("/render-batch", post(render_batch))
+        .route(
+            "/documents/{namespace}/{title}/render",
+            get(render_document_by_title),
+        )
 }
@@ -1,8 +1,13 @@
+use super::cache::routes::cache_routes as CacheRoutes;
 use super::render::routes::render_routes as RenderRoutes;
+use super::source::routes::source_routes as SourceRoutes;
 use crate::state::AppState;
 use axum::Router;
 
 /// v0 API 라우터
 pub fn v0_routes(state: AppState) -> Router<AppState> {
-    Router::new().merge(RenderRoutes(state))
+    Router::new()
+        .merge(RenderRoutes(state.clone()))
+        .merge(SourceRoutes(state.clone()))
+        .merge(CacheRoutes(state))
 }
@@ -0,0 +1,89 @@
+use crate::errors::errors::Errors;
+use crate::limits::{Deadline, check_ast_size, check_content_size, run_blocking};
+use crate::state::AppState;
+use axum::Json;
+use axum::extract::State;
+use serde::{Deserialize, Serialize};
+use sevenmark_formatter::{FormatConfig, format_document};
+use sevenmark_parser::core::parse_document;
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct FormatRequest {
+    /// Raw SevenMark content to format
+    pub content: String,
+    #[serde(default)]
+    pub config: FormatOptions,
+}
+
+/// `FormatConfig` fields; omitted fields use the formatter defaults
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct FormatOptions {
+    /// Maximum line width before lines are broken (default 80)
+    pub width: Option<usize>,
+    /// Indentation width in spaces for structural blocks (default 2)
+    pub indent: Option<usize>,
+}
+
+impl From<FormatOptions> for FormatConfig {
+    fn from(options: FormatOptions) -> Self {
+        let defaults = FormatConfig::default();
+        FormatConfig {
+            width: options.width.unwrap_or(defaults.width),
+            indent: options.indent.unwrap_or(defaults.indent),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FormattedDocument {
+    /// Formatted SevenMark source
+    pub content: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v0/format",
+    request_body = FormatRequest,
+    responses(
+        (status = 200, description = "Document formatted successfully", body = FormattedDocument),
+        (status = 400, description = "Bad request"),
+        (status = 413, description = "Content over the configured limits"),
+        (status = 503, description = "Processing timed out"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Source"
+)]
+pub async fn format(
+    State(state): State<AppState>,
+    Json(payload): Json<FormatRequest>,
+) -> Result<Json<FormattedDocument>, Errors> {
+    let FormatRequest { content, config } = payload;
+    check_content_size(&state.config, &content)?;
+    let config = FormatConfig::from(config);
+
+    let server_config = state.config.clone();
+    let content = run_blocking(&state, &Deadline::new(&state.config), move || {
+        let ast = parse_document(&content);
+        check_ast_size(&server_config, &ast)?;
+        Ok::<_, Errors>(format_document(&ast, &config))
+    })
+    .await??;
+
+    Ok(Json(FormattedDocument { content }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn omitted_options_use_formatter_defaults() {
+        let config = FormatConfig::from(FormatOptions {
+            width: Some(120),
+            indent: None,
+        });
+        assert_eq!(config.width, 120);
+        assert_eq!(config.indent, FormatConfig::default().indent);
+    }
+}
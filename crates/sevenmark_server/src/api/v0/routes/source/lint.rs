@@ -0,0 +1,62 @@
+use crate::errors::errors::Errors;
+use crate::limits::{Deadline, check_ast_size, check_content_size, run_blocking};
+use crate::state::AppState;
+use axum::Json;
+use axum::extract::State;
+use ls_types::{Diagnostic, DiagnosticSeverity};
+use serde::{Deserialize, Serialize};
+use sevenmark_lsp_core::diagnostics::collect_diagnostics;
+use sevenmark_lsp_core::document::DocumentState;
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct LintRequest {
+    /// Raw SevenMark content to lint
+    pub content: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LintResult {
+    /// LSP diagnostics (0-based lines, UTF-16 characters)
+    #[schema(value_type = Vec<Object>)]
+    pub diagnostics: Vec<Diagnostic>,
+    /// Whether any diagnostic is an error
+    pub has_errors: bool,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v0/lint",
+    request_body = LintRequest,
+    responses(
+        (status = 200, description = "Document linted successfully", body = LintResult),
+        (status = 400, description = "Bad request"),
+        (status = 413, description = "Content over the configured limits"),
+        (status = 503, description = "Processing timed out"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Source"
+)]
+pub async fn lint(
+    State(state): State<AppState>,
+    Json(payload): Json<LintRequest>,
+) -> Result<Json<LintResult>, Errors> {
+    let content = payload.content;
+    check_content_size(&state.config, &content)?;
+
+    let server_config = state.config.clone();
+    let diagnostics = run_blocking(&state, &Deadline::new(&state.config), move || {
+        let document = DocumentState::new(content);
+        check_ast_size(&server_config, &document.elements)?;
+        Ok::<_, Errors>(collect_diagnostics(&document))
+    })
+    .await??;
+
+    let has_errors = diagnostics
+        .iter()
+        .any(|d| d.severity == Some(DiagnosticSeverity::ERROR));
+    Ok(Json(LintResult {
+        diagnostics,
+        has_errors,
+    }))
+}
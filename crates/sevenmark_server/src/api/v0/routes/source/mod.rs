@@ -0,0 +1,5 @@
+pub mod format;
+pub mod lint;
+pub mod openapi;
+pub mod parse;
+pub mod routes;
@@ -0,0 +1,28 @@
+use utoipa::OpenApi;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        super::parse::parse,
+        super::parse::parse_compact,
+        super::format::format,
+        super::lint::lint,
+    ),
+    components(
+        schemas(
+            super::parse::ParseRequest,
+            super::parse::ParsedDocument,
+            super::parse::ParseCompactRequest,
+            super::parse::CompactParsedDocument,
+            super::format::FormatRequest,
+            super::format::FormatOptions,
+            super::format::FormattedDocument,
+            super::lint::LintRequest,
+            super::lint::LintResult,
+        )
+    ),
+    tags(
+        (name = "Source", description = "Parse, format and lint SevenMark source")
+    )
+)]
+pub struct SourceApiDoc;
@@ -0,0 +1,110 @@
+use crate::errors::errors::Errors;
+use crate::limits::{Deadline, check_ast_size, check_content_size, run_blocking};
+use crate::state::AppState;
+use axum::Json;
+use axum::extract::State;
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+use sevenmark_parser::core::parse_document;
+use sevenmark_utils::{CompactAst, convert_ast_to_utf16_offset_json, encode_compact_ast};
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ParseRequest {
+    /// Raw SevenMark content to parse
+    pub content: String,
+    /// Report spans as UTF-16 code unit offsets (CodeMirror) instead of bytes
+    #[serde(default)]
+    pub utf16: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ParsedDocument {
+    /// Parsed AST elements
+    #[schema(value_type = Vec<Object>)]
+    pub ast: Box<RawValue>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v0/parse",
+    request_body = ParseRequest,
+    responses(
+        (status = 200, description = "Document parsed successfully", body = ParsedDocument),
+        (status = 400, description = "Bad request"),
+        (status = 413, description = "Content over the configured limits"),
+        (status = 503, description = "Processing timed out"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Source"
+)]
+pub async fn parse(
+    State(state): State<AppState>,
+    Json(payload): Json<ParseRequest>,
+) -> Result<Json<ParsedDocument>, Errors> {
+    let ParseRequest { content, utf16 } = payload;
+    check_content_size(&state.config, &content)?;
+
+    let server_config = state.config.clone();
+    let json = run_blocking(&state, &Deadline::new(&state.config), move || {
+        let ast = parse_document(&content);
+        check_ast_size(&server_config, &ast)?;
+        if utf16 {
+            Ok(convert_ast_to_utf16_offset_json(&ast, &content))
+        } else {
+            serde_json::to_string(&ast).map_err(|e| Errors::SysInternalError(e.to_string()))
+        }
+    })
+    .await??;
+
+    let ast = RawValue::from_string(json).map_err(|e| Errors::SysInternalError(e.to_string()))?;
+    Ok(Json(ParsedDocument { ast }))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ParseCompactRequest {
+    /// Raw SevenMark content to parse
+    pub content: String,
+}
+
+/// AST as a flat node table (see `sevenmark_utils::CompactAst`)
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CompactParsedDocument {
+    /// 4 numbers per node in document order: kind (index into `strings`),
+    /// parent node index (4294967295 for top-level nodes), UTF-16 start, UTF-16 end
+    pub nodes: Vec<u32>,
+    /// Node kind names
+    pub strings: Vec<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v0/parse/compact",
+    request_body = ParseCompactRequest,
+    responses(
+        (status = 200, description = "Document parsed successfully", body = CompactParsedDocument),
+        (status = 400, description = "Bad request"),
+        (status = 413, description = "Content over the configured limits"),
+        (status = 503, description = "Processing timed out"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Source"
+)]
+pub async fn parse_compact(
+    State(state): State<AppState>,
+    Json(payload): Json<ParseCompactRequest>,
+) -> Result<Json<CompactParsedDocument>, Errors> {
+    let content = payload.content;
+    check_content_size(&state.config, &content)?;
+
+    let server_config = state.config.clone();
+    let CompactAst { nodes, strings } =
+        run_blocking(&state, &Deadline::new(&state.config), move || {
+            let ast = parse_document(&content);
+            check_ast_size(&server_config, &ast)?;
+            Ok::<_, Errors>(encode_compact_ast(&ast, &content))
+        })
+        .await??;
+
+    Ok(Json(CompactParsedDocument { nodes, strings }))
+}
@@ -0,0 +1,14 @@
+use super::format::format;
+use super::lint::lint;
+use super::parse::{parse, parse_compact};
+use crate::state::AppState;
+use axum::Router;
+use axum::routing::post;
+
+pub fn source_routes(_state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/parse", post(parse))
+        .route("/parse/compact", post(parse_compact))
+        .route("/format", post(format))
+        .route("/lint", post(lint))
+}
@@ -0,0 +1,66 @@
+use std::fmt;
+use std::path::PathBuf;
+
+/// One invalid or missing setting
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigProblem {
+    /// Required setting not present in the file or environment
+    Missing { key: &'static str },
+    /// Present but not a valid value for the setting
+    Invalid {
+        key: String,
+        value: String,
+        expected: String,
+    },
+    /// Config file key that does not map to any setting
+    UnknownKey { path: String },
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigProblem::Missing { key } => write!(f, "{key} (missing)"),
+            ConfigProblem::Invalid {
+                key,
+                value,
+                expected,
+            } => write!(f, "{key} (invalid: {value:?}, expected {expected})"),
+            ConfigProblem::UnknownKey { path } => write!(f, "{path} (unknown config file key)"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    /// Config file could not be read or is not valid TOML
+    File { path: PathBuf, message: String },
+    /// All problems found while reading the settings
+    Invalid(Vec<ConfigProblem>),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::File { path, message } => {
+                write!(
+                    f,
+                    "Failed to read config file {}: {message}",
+                    path.display()
+                )
+            }
+            ConfigError::Invalid(problems) => {
+                write!(
+                    f,
+                    "Missing or invalid configuration ({} errors):",
+                    problems.len()
+                )?;
+                for problem in problems {
+                    write!(f, "\n  - {problem}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
@@ -0,0 +1,217 @@
+//! Layered configuration values
+//!
+//! Settings are looked up by their environment variable name. An environment
+//! variable wins over the config file; the file fills in everything else.
+//!
+//! In the TOML file, a key path joined with `_` and upper-cased is the
+//! variable it sets, so `[postgres] user = "..."` and
+//! `postgres_user = "..."` both set `POSTGRES_USER`.
+
+use super::config_error::{ConfigError, ConfigProblem};
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::path::{Path, PathBuf};
+use toml_edit::{Document, Item, Table, Value};
+
+/// Path of the config file
+pub const CONFIG_PATH_VAR: &str = "SEVENMARK_CONFIG";
+/// Read from the working directory when `SEVENMARK_CONFIG` is not set
+pub const DEFAULT_CONFIG_FILE: &str = "sevenmark.toml";
+
+#[derive(Debug, Clone)]
+struct FileValue {
+    /// Dotted key path in the file, for error messages
+    path: String,
+    value: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ConfigSource {
+    file: BTreeMap<String, FileValue>,
+    env: HashMap<String, String>,
+}
+
+impl ConfigSource {
+    /// Config file (if any) overridden by the process environment
+    pub fn load() -> Result<Self, ConfigError> {
+        let source = match env::var_os(CONFIG_PATH_VAR) {
+            Some(path) => Self::from_file(Path::new(&path))?,
+            None if Path::new(DEFAULT_CONFIG_FILE).is_file() => {
+                Self::from_file(Path::new(DEFAULT_CONFIG_FILE))?
+            }
+            None => Self::default(),
+        };
+        Ok(source.with_env(env::vars()))
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let file_error = |message: String| ConfigError::File {
+            path: PathBuf::from(path),
+            message,
+        };
+        let text = std::fs::read_to_string(path).map_err(|e| file_error(e.to_string()))?;
+        Self::from_toml(&text).map_err(|e| match e {
+            ConfigError::File { message, .. } => file_error(message),
+            other => other,
+        })
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, ConfigError> {
+        let document = Document::parse(text).map_err(|e| ConfigError::File {
+            path: PathBuf::new(),
+            message: e.to_string(),
+        })?;
+
+        let mut source = Self::default();
+        let mut problems = Vec::new();
+        source.flatten_table(document.as_table(), "", "", &mut problems);
+        if !problems.is_empty() {
+            return Err(ConfigError::Invalid(problems));
+        }
+        Ok(source)
+    }
+
+    /// Overlay variables (normally `std::env::vars()`) on top of the file
+    pub fn with_env(mut self, vars: impl IntoIterator<Item = (String, String)>) -> Self {
+        self.env.extend(vars);
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.env
+            .get(key)
+            .or_else(|| self.file.get(key).map(|entry| &entry.value))
+            .map(String::as_str)
+    }
+
+    /// File keys (as written in the file) whose variable is not in `known`
+    pub fn unknown_file_keys(&self, known: &[&str]) -> Vec<String> {
+        self.file
+            .iter()
+            .filter(|(key, _)| !known.contains(&key.as_str()))
+            .map(|(_, entry)| entry.path.clone())
+            .collect()
+    }
+
+    fn flatten_table(
+        &mut self,
+        table: &Table,
+        path: &str,
+        key: &str,
+        problems: &mut Vec<ConfigProblem>,
+    ) {
+        for (name, item) in table.iter() {
+            let (path, key) = join(path, key, name);
+            match item {
+                Item::Table(table) => self.flatten_table(table, &path, &key, problems),
+                Item::Value(Value::InlineTable(table)) => {
+                    for (name, value) in table.iter() {
+                        let (path, key) = join(&path, &key, name);
+                        self.insert_value(value, path, key, problems);
+                    }
+                }
+                Item::Value(value) => self.insert_value(value, path, key, problems),
+                Item::ArrayOfTables(_) | Item::None => problems.push(ConfigProblem::Invalid {
+                    key: path,
+                    value: item.type_name().to_string(),
+                    expected: "a string, number or boolean".to_string(),
+                }),
+            }
+        }
+    }
+
+    fn insert_value(
+        &mut self,
+        value: &Value,
+        path: String,
+        key: String,
+        problems: &mut Vec<ConfigProblem>,
+    ) {
+        let value = match value {
+            Value::String(s) => s.value().clone(),
+            Value::Integer(i) => i.value().to_string(),
+            Value::Float(f) => f.value().to_string(),
+            Value::Boolean(b) => b.value().to_string(),
+            other => {
+                problems.push(ConfigProblem::Invalid {
+                    key: path,
+                    value: other.type_name().to_string(),
+                    expected: "a string, number or boolean".to_string(),
+                });
+                return;
+            }
+        };
+        self.file.insert(key, FileValue { path, value });
+    }
+}
+
+/// Dotted path and variable name of `name` inside `path`/`key`
+fn join(path: &str, key: &str, name: &str) -> (String, String) {
+    if path.is_empty() {
+        (name.to_string(), name.to_uppercase())
+    } else {
+        (
+            format!("{path}.{name}"),
+            format!("{key}_{}", name.to_uppercase()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections_and_flat_keys_map_to_variables() {
+        let source = ConfigSource::from_toml(
+            r#"
+            port = 9000
+            revision_storage = "memory"
+
+            [postgres]
+            user = "wiki"
+            max_connection = 30
+
+            [render]
+            document_base_url = "/w/"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(source.get("PORT"), Some("9000"));
+        assert_eq!(source.get("REVISION_STORAGE"), Some("memory"));
+        assert_eq!(source.get("POSTGRES_USER"), Some("wiki"));
+        assert_eq!(source.get("POSTGRES_MAX_CONNECTION"), Some("30"));
+        assert_eq!(source.get("RENDER_DOCUMENT_BASE_URL"), Some("/w/"));
+        assert_eq!(source.get("HOST"), None);
+    }
+
+    #[test]
+    fn environment_overrides_file() {
+        let source = ConfigSource::from_toml("[postgres]\nuser = \"file\"\nhost = \"db\"")
+            .unwrap()
+            .with_env([("POSTGRES_USER".to_string(), "env".to_string())]);
+
+        assert_eq!(source.get("POSTGRES_USER"), Some("env"));
+        assert_eq!(source.get("POSTGRES_HOST"), Some("db"));
+    }
+
+    #[test]
+    fn unknown_and_unsupported_keys_are_reported() {
+        let source = ConfigSource::from_toml("[render]\nfile_base = \"/f/\"").unwrap();
+        assert_eq!(
+            source.unknown_file_keys(&["RENDER_FILE_BASE_URL"]),
+            vec!["render.file_base".to_string()]
+        );
+
+        let Err(ConfigError::Invalid(problems)) = ConfigSource::from_toml("hosts = [\"a\"]") else {
+            panic!("arrays should be rejected");
+        };
+        assert_eq!(problems.len(), 1);
+
+        assert!(matches!(
+            ConfigSource::from_toml("port = "),
+            Err(ConfigError::File { .. })
+        ));
+    }
+}
@@ -1 +1,3 @@
+pub mod config_error;
+pub mod config_source;
 pub mod server_config;
@@ -1,21 +1,38 @@
+use super::config_error::{ConfigError, ConfigProblem};
+use super::config_source::ConfigSource;
 use dotenvy::dotenv;
-use std::env;
-use std::sync::LazyLock;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::thread::available_parallelism;
+use std::time::Duration;
+
+/// Where revision content is read from (`REVISION_STORAGE`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevisionStorageBackend {
+    /// `r2` (default): the R2 revision bucket
+    R2,
+    /// `filesystem`: files under `REVISION_STORAGE_PATH`
+    Filesystem(PathBuf),
+    /// `memory`: empty in-process store
+    Memory,
+}
 
 #[derive(Debug, Clone)]
 pub struct ServerConfig {
     pub db_user: String,
     pub db_password: String,
     pub db_host: String,
-    pub db_port: String,
+    pub db_port: u16,
     pub db_name: String,
     pub db_max_connection: u32,
     pub db_min_connection: u32,
 
     pub server_host: String,
-    pub server_port: String,
+    pub server_port: u16,
+
+    pub revision_storage: RevisionStorageBackend,
 
-    // Cloudflare R2 (shared credentials)
+    // Cloudflare R2 (shared credentials, required only for the r2 backend)
     pub r2_endpoint: String,
     pub r2_region: String,
     pub r2_access_key_id: String,
@@ -25,75 +42,373 @@ pub struct ServerConfig {
     pub r2_assets_public_domain: String,
     // R2 Revision (private bucket - revision content)
     pub r2_revision_bucket_name: String,
+
+    // Render base URLs for endpoints that do not receive them in the request
+    pub render_file_base_url: String,
+    pub render_document_base_url: String,
+    pub render_category_base_url: String,
+    pub render_user_base_url: String,
+
+    // Rendered-output cache entries (0 disables the cache)
+    pub render_cache_capacity: usize,
+    // Maximum documents per POST /v0/render-batch request
+    pub render_batch_max_documents: usize,
+
+    // Request limits
+    pub max_body_bytes: usize,
+    // Per document content
+    pub max_content_bytes: usize,
+    pub max_ast_nodes: usize,
+    pub max_includes: usize,
+    pub max_include_bytes: usize,
+    pub processing_timeout: Duration,
+    // Concurrent parse/render tasks on the blocking pool
+    pub max_blocking_tasks: usize,
+
+    // Bearer token for POST /v0/invalidate (unset rejects every call)
+    pub invalidate_token: Option<String>,
+
+    // Readiness checks (Postgres ping + revision storage check)
+    pub readiness_cache_ttl: Duration,
+    pub readiness_timeout: Duration,
 }
 
-static CONFIG: LazyLock<ServerConfig> = LazyLock::new(|| {
-    dotenv().ok();
+/// Every setting name, as an environment variable
+pub const CONFIG_KEYS: &[&str] = &[
+    "POSTGRES_USER",
+    "POSTGRES_PASSWORD",
+    "POSTGRES_HOST",
+    "POSTGRES_PORT",
+    "POSTGRES_NAME",
+    "POSTGRES_MAX_CONNECTION",
+    "POSTGRES_MIN_CONNECTION",
+    "HOST",
+    "PORT",
+    "REVISION_STORAGE",
+    "REVISION_STORAGE_PATH",
+    "R2_ENDPOINT",
+    "R2_REGION",
+    "R2_ACCESS_KEY_ID",
+    "R2_SECRET_ACCESS_KEY",
+    "R2_ASSETS_BUCKET_NAME",
+    "R2_ASSETS_PUBLIC_DOMAIN",
+    "R2_REVISION_BUCKET_NAME",
+    "RENDER_FILE_BASE_URL",
+    "RENDER_DOCUMENT_BASE_URL",
+    "RENDER_CATEGORY_BASE_URL",
+    "RENDER_USER_BASE_URL",
+    "RENDER_CACHE_CAPACITY",
+    "RENDER_BATCH_MAX_DOCUMENTS",
+    "MAX_BODY_BYTES",
+    "MAX_CONTENT_BYTES",
+    "MAX_AST_NODES",
+    "MAX_INCLUDES",
+    "MAX_INCLUDE_BYTES",
+    "PROCESSING_TIMEOUT_MS",
+    "MAX_BLOCKING_TASKS",
+    "INVALIDATE_TOKEN",
+    "READINESS_CACHE_MS",
+    "READINESS_TIMEOUT_MS",
+];
 
-    let mut errors: Vec<String> = Vec::new();
+/// `RENDER_FILE_BASE_URL` default when no R2 public domain is configured
+pub const DEFAULT_FILE_BASE_URL: &str = "/files/";
+pub const DEFAULT_DOCUMENT_BASE_URL: &str = "/Document/";
+pub const DEFAULT_CATEGORY_BASE_URL: &str = "/Category/";
+pub const DEFAULT_USER_BASE_URL: &str = "/User/";
 
-    macro_rules! require {
-        ($name:expr) => {
-            env::var($name).unwrap_or_else(|_| {
-                errors.push(format!("  - {} (missing)", $name));
-                String::new()
-            })
+/// Reads settings from a [`ConfigSource`], collecting every problem
+struct Reader<'a> {
+    source: &'a ConfigSource,
+    problems: Vec<ConfigProblem>,
+}
+
+impl Reader<'_> {
+    fn optional_str(&self, key: &str) -> Option<String> {
+        self.source.get(key).map(str::to_string)
+    }
+
+    fn required(&mut self, key: &'static str) -> String {
+        self.optional_str(key).unwrap_or_else(|| {
+            self.problems.push(ConfigProblem::Missing { key });
+            String::new()
+        })
+    }
+
+    fn string_or(&self, key: &str, default: impl Into<String>) -> String {
+        self.optional_str(key).unwrap_or_else(|| default.into())
+    }
+
+    fn parse<T: FromStr>(&mut self, key: &str, expected: &str) -> Option<T> {
+        let value = self.source.get(key)?;
+        match value.trim().parse() {
+            Ok(parsed) => Some(parsed),
+            Err(_) => {
+                self.invalid(key, value, expected);
+                None
+            }
+        }
+    }
+
+    fn parse_or<T: FromStr>(&mut self, key: &str, expected: &str, default: T) -> T {
+        self.parse(key, expected).unwrap_or(default)
+    }
+
+    fn number_or<T: FromStr>(&mut self, key: &str, default: T) -> T {
+        self.parse_or(key, "a non-negative integer", default)
+    }
+
+    fn millis_or(&mut self, key: &str, default: u64) -> Duration {
+        Duration::from_millis(self.parse_or(key, "milliseconds", default))
+    }
+
+    fn required_port(&mut self, key: &'static str) -> u16 {
+        if self.source.get(key).is_none() {
+            self.problems.push(ConfigProblem::Missing { key });
+            return 0;
+        }
+        self.parse(key, "a port number").unwrap_or(0)
+    }
+
+    fn invalid(&mut self, key: &str, value: &str, expected: &str) {
+        self.problems.push(ConfigProblem::Invalid {
+            key: key.to_string(),
+            value: value.to_string(),
+            expected: expected.to_string(),
+        });
+    }
+}
+
+impl ServerConfig {
+    /// `.env`, the config file and the environment, validated
+    pub fn load() -> Result<Self, ConfigError> {
+        dotenv().ok();
+        Self::from_source(&ConfigSource::load()?)
+    }
+
+    pub fn from_source(source: &ConfigSource) -> Result<Self, ConfigError> {
+        let mut r = Reader {
+            source,
+            problems: source
+                .unknown_file_keys(CONFIG_KEYS)
+                .into_iter()
+                .map(|path| ConfigProblem::UnknownKey { path })
+                .collect(),
+        };
+
+        let db_user = r.required("POSTGRES_USER");
+        let db_password = r.required("POSTGRES_PASSWORD");
+        let db_host = r.required("POSTGRES_HOST");
+        let db_port = r.required_port("POSTGRES_PORT");
+        let db_name = r.required("POSTGRES_NAME");
+        let db_max_connection = r.number_or("POSTGRES_MAX_CONNECTION", 100);
+        let db_min_connection = r.number_or("POSTGRES_MIN_CONNECTION", 10);
+        if db_min_connection > db_max_connection {
+            r.invalid(
+                "POSTGRES_MIN_CONNECTION",
+                &db_min_connection.to_string(),
+                "at most POSTGRES_MAX_CONNECTION",
+            );
+        }
+
+        let server_host = r.required("HOST");
+        let server_port = r.required_port("PORT");
+
+        let revision_storage = match r.optional_str("REVISION_STORAGE").as_deref() {
+            None | Some("r2") => RevisionStorageBackend::R2,
+            Some("filesystem") => RevisionStorageBackend::Filesystem(PathBuf::from(
+                r.required("REVISION_STORAGE_PATH"),
+            )),
+            Some("memory") => RevisionStorageBackend::Memory,
+            Some(other) => {
+                r.invalid("REVISION_STORAGE", other, "r2, filesystem or memory");
+                RevisionStorageBackend::R2
+            }
+        };
+
+        // R2 settings are only needed when revisions are stored there
+        let uses_r2 = revision_storage == RevisionStorageBackend::R2;
+        let mut r2 = |key: &'static str| {
+            if uses_r2 {
+                r.required(key)
+            } else {
+                r.string_or(key, "")
+            }
+        };
+        let r2_endpoint = r2("R2_ENDPOINT");
+        let r2_access_key_id = r2("R2_ACCESS_KEY_ID");
+        let r2_secret_access_key = r2("R2_SECRET_ACCESS_KEY");
+        let r2_assets_bucket_name = r2("R2_ASSETS_BUCKET_NAME");
+        let r2_assets_public_domain = r2("R2_ASSETS_PUBLIC_DOMAIN");
+        let r2_revision_bucket_name = r2("R2_REVISION_BUCKET_NAME");
+
+        let default_file_base_url = if r2_assets_public_domain.is_empty() {
+            DEFAULT_FILE_BASE_URL.to_string()
+        } else {
+            format!("{}/", r2_assets_public_domain.trim_end_matches('/'))
+        };
+
+        let config = ServerConfig {
+            db_user,
+            db_password,
+            db_host,
+            db_port,
+            db_name,
+            db_max_connection,
+            db_min_connection,
+
+            server_host,
+            server_port,
+
+            revision_storage,
+
+            // Cloudflare R2
+            r2_endpoint,
+            r2_region: r.string_or("R2_REGION", "auto"),
+            r2_access_key_id,
+            r2_secret_access_key,
+            r2_assets_bucket_name,
+            r2_assets_public_domain,
+            r2_revision_bucket_name,
+
+            render_file_base_url: r.string_or("RENDER_FILE_BASE_URL", default_file_base_url),
+            render_document_base_url: r
+                .string_or("RENDER_DOCUMENT_BASE_URL", DEFAULT_DOCUMENT_BASE_URL),
+            render_category_base_url: r
+                .string_or("RENDER_CATEGORY_BASE_URL", DEFAULT_CATEGORY_BASE_URL),
+            render_user_base_url: r.string_or("RENDER_USER_BASE_URL", DEFAULT_USER_BASE_URL),
+            render_cache_capacity: r.number_or("RENDER_CACHE_CAPACITY", 1000),
+            render_batch_max_documents: r.number_or("RENDER_BATCH_MAX_DOCUMENTS", 500),
+
+            max_body_bytes: r.number_or("MAX_BODY_BYTES", 16 * 1024 * 1024),
+            max_content_bytes: r.number_or("MAX_CONTENT_BYTES", 2 * 1024 * 1024),
+            max_ast_nodes: r.number_or("MAX_AST_NODES", 200_000),
+            max_includes: r.number_or("MAX_INCLUDES", 50),
+            max_include_bytes: r.number_or("MAX_INCLUDE_BYTES", 4 * 1024 * 1024),
+            processing_timeout: r.millis_or("PROCESSING_TIMEOUT_MS", 10_000),
+            max_blocking_tasks: r.number_or(
+                "MAX_BLOCKING_TASKS",
+                available_parallelism().map_or(4, |n| n.get()),
+            ),
+
+            invalidate_token: r.optional_str("INVALIDATE_TOKEN").filter(|t| !t.is_empty()),
+
+            readiness_cache_ttl: r.millis_or("READINESS_CACHE_MS", 5_000),
+            readiness_timeout: r.millis_or("READINESS_TIMEOUT_MS", 2_000),
+        };
+
+        if !r.problems.is_empty() {
+            return Err(ConfigError::Invalid(r.problems));
+        }
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(toml: &str) -> ConfigSource {
+        ConfigSource::from_toml(toml).unwrap()
+    }
+
+    const MINIMAL: &str = r#"
+        host = "0.0.0.0"
+        port = 9000
+        revision_storage = "memory"
+
+        [postgres]
+        user = "wiki"
+        password = "secret"
+        host = "db"
+        port = 5432
+        name = "wiki"
+    "#;
+
+    #[test]
+    fn r2_is_optional_for_local_storage() {
+        let config = ServerConfig::from_source(&source(MINIMAL)).unwrap();
+
+        assert_eq!(config.revision_storage, RevisionStorageBackend::Memory);
+        assert_eq!(config.server_port, 9000);
+        assert_eq!(config.render_file_base_url, DEFAULT_FILE_BASE_URL);
+        assert_eq!(config.render_document_base_url, DEFAULT_DOCUMENT_BASE_URL);
+        assert_eq!(config.processing_timeout, Duration::from_secs(10));
+    }
+
+    #[test]
+    fn all_problems_are_reported_together() {
+        let source = source(MINIMAL).with_env([
+            ("REVISION_STORAGE".to_string(), "r2".to_string()),
+            ("PORT".to_string(), "http".to_string()),
+            ("MAX_INCLUDES".to_string(), "-1".to_string()),
+        ]);
+        let Err(ConfigError::Invalid(problems)) = ServerConfig::from_source(&source) else {
+            panic!("config should be invalid");
         };
+
+        let mut missing: Vec<_> = problems
+            .iter()
+            .filter_map(|p| match p {
+                ConfigProblem::Missing { key } => Some(*key),
+                _ => None,
+            })
+            .collect();
+        missing.sort_unstable();
+        assert_eq!(
+            missing,
+            [
+                "R2_ACCESS_KEY_ID",
+                "R2_ASSETS_BUCKET_NAME",
+                "R2_ASSETS_PUBLIC_DOMAIN",
+                "R2_ENDPOINT",
+                "R2_REVISION_BUCKET_NAME",
+                "R2_SECRET_ACCESS_KEY",
+            ]
+        );
+
+        let mut invalid: Vec<_> = problems
+            .iter()
+            .filter_map(|p| match p {
+                ConfigProblem::Invalid { key, .. } => Some(key.as_str()),
+                _ => None,
+            })
+            .collect();
+        invalid.sort_unstable();
+        assert_eq!(invalid, ["MAX_INCLUDES", "PORT"]);
+
+        assert!(
+            !problems
+                .iter()
+                .any(|p| matches!(p, ConfigProblem::UnknownKey { .. }))
+        );
     }
 
-    let db_user = require!("POSTGRES_USER");
-    let db_password = require!("POSTGRES_PASSWORD");
-    let db_host = require!("POSTGRES_HOST");
-    let db_port = require!("POSTGRES_PORT");
-    let db_name = require!("POSTGRES_NAME");
-    let server_host = require!("HOST");
-    let server_port = require!("PORT");
-    let r2_endpoint = require!("R2_ENDPOINT");
-    let r2_access_key_id = require!("R2_ACCESS_KEY_ID");
-    let r2_secret_access_key = require!("R2_SECRET_ACCESS_KEY");
-    let r2_assets_bucket_name = require!("R2_ASSETS_BUCKET_NAME");
-    let r2_assets_public_domain = require!("R2_ASSETS_PUBLIC_DOMAIN");
-    let r2_revision_bucket_name = require!("R2_REVISION_BUCKET_NAME");
-
-    if !errors.is_empty() {
-        panic!(
-            "\n\nMissing or invalid environment variables ({} errors):\n{}\n",
-            errors.len(),
-            errors.join("\n")
+    #[test]
+    fn example_config_file_is_valid() {
+        let example = source(include_str!("../../../../sevenmark.example.toml"));
+        assert!(example.unknown_file_keys(CONFIG_KEYS).is_empty());
+
+        let credentials = example.with_env(
+            [
+                "POSTGRES_PASSWORD",
+                "R2_ACCESS_KEY_ID",
+                "R2_SECRET_ACCESS_KEY",
+            ]
+            .map(|key| (key.to_string(), "secret".to_string())),
         );
+        let config = ServerConfig::from_source(&credentials).unwrap();
+        assert_eq!(config.render_file_base_url, "https://assets.example.com/");
     }
 
-    ServerConfig {
-        db_user,
-        db_password,
-        db_host,
-        db_port,
-        db_name,
-        db_max_connection: env::var("POSTGRES_MAX_CONNECTION")
-            .ok()
-            .and_then(|v| v.parse().ok())
-            .unwrap_or(100),
-        db_min_connection: env::var("POSTGRES_MIN_CONNECTION")
-            .ok()
-            .and_then(|v| v.parse().ok())
-            .unwrap_or(10),
-
-        server_host,
-        server_port,
-
-        // Cloudflare R2
-        r2_endpoint,
-        r2_region: env::var("R2_REGION").unwrap_or_else(|_| "auto".into()),
-        r2_access_key_id,
-        r2_secret_access_key,
-        r2_assets_bucket_name,
-        r2_assets_public_domain,
-        r2_revision_bucket_name,
-    }
-});
+    #[test]
+    fn public_domain_is_the_file_base_url_default() {
+        let source = source(MINIMAL).with_env([(
+            "R2_ASSETS_PUBLIC_DOMAIN".to_string(),
+            "https://assets.example.com/".to_string(),
+        )]);
+        let config = ServerConfig::from_source(&source).unwrap();
 
-impl ServerConfig {
-    pub fn get() -> &'static ServerConfig {
-        &CONFIG
+        assert_eq!(config.render_file_base_url, "https://assets.example.com/");
     }
 }
@@ -11,13 +11,12 @@ use tracing::{error, info};
 /// Establishes and returns a database connection.
 ///
 /// This function is called at application startup to connect to the PostgreSQL database.
-/// It reads connection information from the configuration file and sets up the connection pool.
+/// It reads connection information from `db_config` and sets up the connection pool.
 ///
 /// # Returns
 /// * `DatabaseConnection` - The successfully established database connection object.
-pub async fn establish_connection() -> Result<DatabaseConnection> {
-    // Retrieve database connection information from the environment and build the URL
-    let db_config = ServerConfig::get();
+pub async fn establish_connection(db_config: &ServerConfig) -> Result<DatabaseConnection> {
+    // Build the URL from the connection settings
     let database_url = format!(
         "postgres://{}:{}@{}:{}/{}",
         &db_config.db_user,
@@ -41,8 +40,8 @@ pub async fn establish_connection() -> Result<DatabaseConnection> {
     let mut options = ConnectOptions::new(database_url);
     options
         // Configure connection pool size
-        .max_connections(ServerConfig::get().db_max_connection) // Maximum number of connections
-        .min_connections(ServerConfig::get().db_min_connection) // Minimum number of connections
+        .max_connections(db_config.db_max_connection) // Maximum number of connections
+        .min_connections(db_config.db_min_connection) // Minimum number of connections
         // Configure timeouts
         .connect_timeout(Duration::from_secs(8)) // Connection timeout: 8 seconds
         .acquire_timeout(Duration::from_secs(30))
@@ -52,9 +51,8 @@ pub async fn establish_connection() -> Result<DatabaseConnection> {
 
     Database::connect(options)
         .await
-        .map(|connection| {
+        .inspect(|_connection| {
             info!("Successfully connected to the database.");
-            connection
         })
         .map_err(|err| {
             error!("Failed to connect to the database: {}", err);
@@ -1,2 +1,3 @@
 pub mod database_conn;
 pub mod r2_conn;
+pub mod revision_storage_conn;
@@ -2,12 +2,10 @@ use crate::config::server_config::ServerConfig;
 use anyhow::Result;
 use aws_config::{BehaviorVersion, Region};
 use aws_sdk_s3::Client;
-use sevenmark_transform::wiki::RevisionStorageClient;
+use sevenmark_transform::wiki::R2RevisionStore;
 use tracing::info;
 
-pub async fn establish_revision_storage_connection() -> Result<RevisionStorageClient> {
-    let config = ServerConfig::get();
-
+pub async fn establish_r2_revision_store(config: &ServerConfig) -> Result<R2RevisionStore> {
     info!(
         "Connecting to R2 revision storage at: {} (region: {})",
         config.r2_endpoint, config.r2_region
@@ -31,9 +29,8 @@ pub async fn establish_revision_storage_connection() -> Result<RevisionStorageCl
         .build();
 
     let client = Client::from_conf(s3_config);
-    let revision_storage =
-        RevisionStorageClient::new(client, config.r2_revision_bucket_name.clone());
+    let store = R2RevisionStore::new(client, config.r2_revision_bucket_name.clone());
 
     info!("Successfully connected to R2 revision storage");
-    Ok(revision_storage)
+    Ok(store)
 }
@@ -0,0 +1,28 @@
+use super::r2_conn::establish_r2_revision_store;
+use crate::config::server_config::{RevisionStorageBackend, ServerConfig};
+use anyhow::Result;
+use sevenmark_transform::wiki::{
+    FilesystemRevisionStore, MemoryRevisionStore, RevisionStorageClient,
+};
+use tracing::{info, warn};
+
+/// Revision storage selected by `REVISION_STORAGE`
+pub async fn establish_revision_storage_connection(
+    config: &ServerConfig,
+) -> Result<RevisionStorageClient> {
+    match &config.revision_storage {
+        RevisionStorageBackend::R2 => Ok(RevisionStorageClient::new(
+            establish_r2_revision_store(config).await?,
+        )),
+        RevisionStorageBackend::Filesystem(root) => {
+            info!("Using filesystem revision storage at: {}", root.display());
+            Ok(RevisionStorageClient::new(FilesystemRevisionStore::new(
+                root,
+            )))
+        }
+        RevisionStorageBackend::Memory => {
+            warn!("Using in-memory revision storage; included documents will have no content");
+            Ok(RevisionStorageClient::new(MemoryRevisionStore::new()))
+        }
+    }
+}
@@ -46,10 +46,13 @@ pub enum Errors {
     // Document
     DocumentNotFound,
     DocumentRevisionNotFound,
+    DocumentTooLarge(String), // 설정된 한도 초과 (크기, 노드 수, include)
+    DocumentProcessingTimeout,
 
     // 일반 오류
     BadRequestError(String), // 잘못된 요청 (추가 정보 포함)
     ValidationError(String), // 유효성 검사 오류 (추가 정보 포함)
+    Unauthorized(String),    // 인증 실패 (공유 토큰 누락 또는 불일치)
 
     // 시스템 오류
     SysInternalError(String),
@@ -10,6 +10,10 @@ pub fn log_error(error: &Errors) {
         Errors::DocumentNotFound | Errors::DocumentRevisionNotFound => {
             warn!("Resource not found: {:?}", error);
         }
+        // 한도 초과 - warn! 레벨 (반복되면 악용 가능성)
+        Errors::DocumentTooLarge(_) | Errors::DocumentProcessingTimeout => {
+            warn!("Document rejected: {:?}", error);
+        }
         _ => {}
     }
 }
@@ -21,6 +25,16 @@ pub fn map_response(error: &Errors) -> Option<(StatusCode, &'static str, Option<
         Errors::DocumentRevisionNotFound => {
             Some((StatusCode::NOT_FOUND, DOCUMENT_REVISION_NOT_FOUND, None))
         }
+        Errors::DocumentTooLarge(msg) => Some((
+            StatusCode::PAYLOAD_TOO_LARGE,
+            DOCUMENT_TOO_LARGE,
+            Some(msg.clone()),
+        )),
+        Errors::DocumentProcessingTimeout => Some((
+            StatusCode::SERVICE_UNAVAILABLE,
+            DOCUMENT_PROCESSING_TIMEOUT,
+            None,
+        )),
         _ => None, // 다른 도메인의 에러는 None 반환
     }
 }
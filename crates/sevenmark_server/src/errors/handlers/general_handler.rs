@@ -7,7 +7,7 @@ use tracing::debug;
 pub fn log_error(error: &Errors) {
     match error {
         // 비즈니스 로직 에러 - debug! 레벨 (클라이언트 실수)
-        Errors::BadRequestError(_) | Errors::ValidationError(_) => {
+        Errors::BadRequestError(_) | Errors::ValidationError(_) | Errors::Unauthorized(_) => {
             debug!("Client error: {:?}", error);
         }
         _ => {}
@@ -23,6 +23,9 @@ pub fn map_response(error: &Errors) -> Option<(StatusCode, &'static str, Option<
         Errors::ValidationError(msg) => {
             Some((StatusCode::BAD_REQUEST, VALIDATION_ERROR, Some(msg.clone())))
         }
+        Errors::Unauthorized(msg) => {
+            Some((StatusCode::UNAUTHORIZED, UNAUTHORIZED, Some(msg.clone())))
+        }
         _ => None, // 다른 도메인의 에러는 None 반환
     }
 }
@@ -1,11 +1,14 @@
 pub mod general {
     pub const BAD_REQUEST: &str = "general:bad_request";
     pub const VALIDATION_ERROR: &str = "general:validation_error";
+    pub const UNAUTHORIZED: &str = "general:unauthorized";
 }
 
 pub mod document {
     pub const DOCUMENT_NOT_FOUND: &str = "document:not_found";
     pub const DOCUMENT_REVISION_NOT_FOUND: &str = "document:revision_not_found";
+    pub const DOCUMENT_TOO_LARGE: &str = "document:too_large";
+    pub const DOCUMENT_PROCESSING_TIMEOUT: &str = "document:processing_timeout";
 }
 
 pub mod system {
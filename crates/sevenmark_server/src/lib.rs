@@ -2,11 +2,17 @@ pub mod api;
 pub mod config;
 pub mod connection;
 pub mod errors;
+pub mod limits;
+pub mod readiness;
+pub mod render_cache;
+pub mod rendered;
 pub mod state;
+pub mod telemetry;
 pub mod utils;
 
 pub use api::*;
 pub use config::*;
 pub use connection::*;
+pub use render_cache::*;
 pub use state::*;
 pub use utils::*;
@@ -0,0 +1,143 @@
+//! Request limits from [`ServerConfig`]
+
+use crate::config::server_config::ServerConfig;
+use crate::errors::errors::Errors;
+use crate::state::AppState;
+use sevenmark_ast::Element;
+use sevenmark_ast::visit::{VisitPath, Visitor, walk_element};
+use sevenmark_transform::{LimitExceeded, ProcessLimits};
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::task::spawn_blocking;
+use tokio::time::{Instant, timeout_at};
+
+/// Include limits for `process_sevenmark_with_limits`. Included documents are
+/// parsed under the same permits as [`run_blocking`].
+pub fn process_limits(state: &AppState) -> ProcessLimits {
+    let config = &state.config;
+    ProcessLimits {
+        max_includes: config.max_includes,
+        max_include_bytes: config.max_include_bytes,
+        blocking_permits: Some(state.blocking_permits.clone()),
+    }
+}
+
+pub fn check_content_size(config: &ServerConfig, content: &str) -> Result<(), Errors> {
+    let max = config.max_content_bytes;
+    if content.len() > max {
+        return Err(Errors::DocumentTooLarge(format!(
+            "content is {} bytes, at most {max} are allowed",
+            content.len()
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct NodeCounter(usize);
+
+impl Visitor for NodeCounter {
+    fn visit_element(&mut self, element: &Element, path: &mut VisitPath) {
+        self.0 += 1;
+        walk_element(self, element, path);
+    }
+}
+
+/// Number of elements in the tree, including parameter values and nested content
+pub fn count_nodes(ast: &[Element]) -> usize {
+    let mut counter = NodeCounter::default();
+    counter.visit_document(ast);
+    counter.0
+}
+
+pub fn check_ast_size(config: &ServerConfig, ast: &[Element]) -> Result<(), Errors> {
+    let max = config.max_ast_nodes;
+    let nodes = count_nodes(ast);
+    if nodes > max {
+        return Err(Errors::DocumentTooLarge(format!(
+            "document has {nodes} elements, at most {max} are allowed"
+        )));
+    }
+    Ok(())
+}
+
+/// Processing errors, with include limits reported as [`Errors::DocumentTooLarge`]
+pub fn process_error(error: anyhow::Error) -> Errors {
+    match error.downcast::<LimitExceeded>() {
+        Ok(exceeded) => Errors::DocumentTooLarge(exceeded.to_string()),
+        Err(error) => Errors::SysInternalError(error.to_string()),
+    }
+}
+
+/// Processing deadline of one request, `PROCESSING_TIMEOUT_MS` long. It
+/// starts once the request holds its first blocking permit, so time spent
+/// queued behind other requests does not count.
+#[derive(Debug)]
+pub struct Deadline {
+    end: OnceLock<Instant>,
+    timeout: Duration,
+}
+
+impl Deadline {
+    pub fn new(config: &ServerConfig) -> Self {
+        Self {
+            end: OnceLock::new(),
+            timeout: config.processing_timeout,
+        }
+    }
+
+    /// Start the deadline if it has not started yet
+    fn start(&self) -> Instant {
+        *self.end.get_or_init(|| Instant::now() + self.timeout)
+    }
+
+    /// Fail `work` with [`Errors::DocumentProcessingTimeout`] once the
+    /// deadline has passed, starting it if needed
+    pub async fn run<T>(&self, work: impl Future<Output = Result<T, Errors>>) -> Result<T, Errors> {
+        timeout_at(self.start(), work)
+            .await
+            .unwrap_or(Err(Errors::DocumentProcessingTimeout))
+    }
+}
+
+/// Run CPU-bound work on the blocking pool under `deadline`, at most
+/// `MAX_BLOCKING_TASKS` at a time. The deadline only runs once the permit is
+/// acquired. The permit is held until the work finishes, even if the caller
+/// has stopped waiting.
+pub async fn run_blocking<T, F>(state: &AppState, deadline: &Deadline, work: F) -> Result<T, Errors>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let permit = state
+        .blocking_permits
+        .clone()
+        .acquire_owned()
+        .await
+        .map_err(|e| Errors::SysInternalError(format!("Blocking pool closed: {e}")))?;
+
+    deadline
+        .run(async {
+            spawn_blocking(move || {
+                let _permit = permit;
+                work()
+            })
+            .await
+            .map_err(|e| Errors::SysInternalError(format!("Blocking task failed: {e}")))
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sevenmark_parser::core::parse_document;
+
+    #[test]
+    fn count_nodes_includes_nested_elements() {
+        assert_eq!(count_nodes(&parse_document("plain")), 1);
+        // Bold, its text and the text after it
+        assert_eq!(count_nodes(&parse_document("**a** b")), 3);
+    }
+}
@@ -1,32 +1,47 @@
 use axum::Router;
 use sevenmark_server::database_conn::establish_connection;
 use sevenmark_server::logger::init_tracing;
-use sevenmark_server::r2_conn::establish_revision_storage_connection;
+use sevenmark_server::readiness::Readiness;
+use sevenmark_server::revision_storage_conn::establish_revision_storage_connection;
 use sevenmark_server::server_config::ServerConfig;
-use sevenmark_server::{AppState, api_routes};
+use sevenmark_server::telemetry::install_metrics_recorder;
+use sevenmark_server::{AppState, MemoryRenderCache, NoRenderCache, RenderCache, api_routes};
 use std::net::SocketAddr;
+use std::num::NonZeroUsize;
 use std::process::ExitCode;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
 use tracing::error;
 
-pub async fn run_server() -> anyhow::Result<()> {
+pub async fn run_server(config: ServerConfig) -> anyhow::Result<()> {
+    let metrics = install_metrics_recorder()?;
+
     // Establish database connection
-    let conn = establish_connection().await?;
+    let conn = establish_connection(&config).await?;
+
+    // Establish revision storage connection
+    let revision_storage = establish_revision_storage_connection(&config)
+        .await
+        .map_err(|e| {
+            error!("Failed to establish revision storage connection: {}", e);
+            anyhow::anyhow!("Revision storage connection failed: {}", e)
+        })?;
 
-    // Establish R2 revision storage connection
-    let revision_storage = establish_revision_storage_connection().await.map_err(|e| {
-        error!("Failed to establish revision storage connection: {}", e);
-        anyhow::anyhow!("Revision storage connection failed: {}", e)
-    })?;
+    let server_url = format!("{}:{}", config.server_host, config.server_port);
 
-    let server_url = format!(
-        "{}:{}",
-        &ServerConfig::get().server_host,
-        &ServerConfig::get().server_port
-    );
+    let render_cache: Arc<dyn RenderCache> = match NonZeroUsize::new(config.render_cache_capacity) {
+        Some(capacity) => Arc::new(MemoryRenderCache::new(capacity)),
+        None => Arc::new(NoRenderCache),
+    };
 
     let state = AppState {
         conn,
         revision_storage,
+        render_cache,
+        blocking_permits: Arc::new(Semaphore::new(config.max_blocking_tasks.max(1))),
+        metrics,
+        readiness: Arc::new(Readiness::new()),
+        config: Arc::new(config),
     };
 
     let app = Router::new()
@@ -46,11 +61,17 @@ pub async fn run_server() -> anyhow::Result<()> {
 
 #[tokio::main]
 async fn main() -> ExitCode {
-    dotenvy::dotenv().ok();
-
     init_tracing();
 
-    match run_server().await {
+    let config = match ServerConfig::load() {
+        Ok(config) => config,
+        Err(err) => {
+            error!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run_server(config).await {
         Ok(()) => ExitCode::SUCCESS,
         Err(err) => {
             error!("Application error: {err:#}");
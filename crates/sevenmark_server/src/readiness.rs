@@ -0,0 +1,167 @@
+//! Dependency checks for the readiness and startup probes
+
+use crate::state::AppState;
+use serde::Serialize;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tokio::time::timeout;
+use tracing::warn;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DependencyStatus {
+    pub ok: bool,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReadinessReport {
+    pub ready: bool,
+    /// Postgres pool ping
+    pub database: DependencyStatus,
+    /// Revision storage backend check (HEAD on the R2 bucket, root directory, ...)
+    pub revision_storage: DependencyStatus,
+}
+
+/// Run one check with the `READINESS_TIMEOUT_MS` `limit`
+async fn check_dependency<E: ToString>(
+    limit: Duration,
+    check: impl Future<Output = Result<(), E>>,
+) -> DependencyStatus {
+    let start = Instant::now();
+    let error = match timeout(limit, check).await {
+        Ok(Ok(())) => None,
+        Ok(Err(e)) => Some(e.to_string()),
+        Err(_) => Some(format!("timed out after {}ms", limit.as_millis())),
+    };
+    DependencyStatus {
+        ok: error.is_none(),
+        latency_ms: start.elapsed().as_millis() as u64,
+        error,
+    }
+}
+
+async fn check_dependencies(state: &AppState) -> ReadinessReport {
+    let limit = state.config.readiness_timeout;
+    let (database, revision_storage) = tokio::join!(
+        check_dependency(limit, state.conn.ping()),
+        check_dependency(limit, state.revision_storage.check()),
+    );
+    let report = ReadinessReport {
+        ready: database.ok && revision_storage.ok,
+        database,
+        revision_storage,
+    };
+    if !report.ready {
+        warn!(?report, "Readiness check failed");
+    }
+    report
+}
+
+/// Readiness results, cached for `READINESS_CACHE_MS` so frequent probes
+/// from several kubelets do not hit Postgres and R2 each time
+pub struct Readiness {
+    cached: Mutex<Option<(Instant, ReadinessReport)>>,
+    started: AtomicBool,
+}
+
+impl Default for Readiness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Readiness {
+    pub fn new() -> Self {
+        Self {
+            cached: Mutex::new(None),
+            started: AtomicBool::new(false),
+        }
+    }
+
+    pub async fn check(&self, state: &AppState) -> ReadinessReport {
+        let ttl = state.config.readiness_cache_ttl;
+        self.cached_or(ttl, || check_dependencies(state)).await
+    }
+
+    /// Startup probe: checks without the cache until the first success,
+    /// then always passes (readiness takes over from there)
+    pub async fn check_startup(&self, state: &AppState) -> Option<ReadinessReport> {
+        if self.started.load(Ordering::Acquire) {
+            return None;
+        }
+        let report = self
+            .cached_or(Duration::ZERO, || check_dependencies(state))
+            .await;
+        Some(report)
+    }
+
+    /// Cached report if younger than `ttl`, otherwise run `check`.
+    /// Concurrent callers wait for the same check.
+    async fn cached_or<F, Fut>(&self, ttl: Duration, check: F) -> ReadinessReport
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = ReadinessReport>,
+    {
+        let mut cached = self.cached.lock().await;
+        if let Some((checked_at, report)) = cached.as_ref()
+            && checked_at.elapsed() < ttl
+        {
+            return report.clone();
+        }
+
+        let report = check().await;
+        if report.ready {
+            self.started.store(true, Ordering::Release);
+        }
+        *cached = Some((Instant::now(), report.clone()));
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    fn report(ready: bool) -> ReadinessReport {
+        let status = DependencyStatus {
+            ok: ready,
+            latency_ms: 0,
+            error: None,
+        };
+        ReadinessReport {
+            ready,
+            database: status.clone(),
+            revision_storage: status,
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_are_cached_for_ttl() {
+        let readiness = Readiness::new();
+        let runs = AtomicUsize::new(0);
+        let check = || async {
+            runs.fetch_add(1, Ordering::SeqCst);
+            report(false)
+        };
+
+        let ttl = Duration::from_secs(60);
+        assert!(!readiness.cached_or(ttl, check).await.ready);
+        assert!(!readiness.cached_or(ttl, check).await.ready);
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+        readiness.cached_or(Duration::ZERO, check).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        assert!(!readiness.started.load(Ordering::SeqCst));
+
+        readiness
+            .cached_or(Duration::ZERO, || async { report(true) })
+            .await;
+        assert!(readiness.started.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,242 @@
+use super::{CachedRender, RenderCache, RenderCacheKey};
+use crate::rendered::RenderedDocument;
+use async_trait::async_trait;
+use lru::LruCache;
+use sevenmark_transform::preprocessor::DocumentReference;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+
+struct Entry {
+    render: CachedRender,
+    /// Pages that were rendered from this entry
+    sources: HashSet<DocumentReference>,
+}
+
+impl Entry {
+    fn documents(&self) -> impl Iterator<Item = &DocumentReference> {
+        self.render.dependencies.iter().chain(&self.sources)
+    }
+}
+
+struct Inner {
+    entries: LruCache<RenderCacheKey, Entry>,
+    /// Reverse index: document -> entries that depend on it or were rendered as it
+    dependents: HashMap<DocumentReference, HashSet<RenderCacheKey>>,
+}
+
+impl Inner {
+    fn link(&mut self, key: RenderCacheKey, document: &DocumentReference) {
+        self.dependents
+            .entry(document.clone())
+            .or_default()
+            .insert(key);
+    }
+
+    fn unlink(&mut self, key: &RenderCacheKey, entry: &Entry) {
+        for document in entry.documents() {
+            if let Some(keys) = self.dependents.get_mut(document) {
+                keys.remove(key);
+                if keys.is_empty() {
+                    self.dependents.remove(document);
+                }
+            }
+        }
+    }
+}
+
+/// In-process LRU render cache
+pub struct MemoryRenderCache {
+    inner: Mutex<Inner>,
+}
+
+impl MemoryRenderCache {
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                entries: LruCache::new(capacity),
+                dependents: HashMap::new(),
+            }),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl RenderCache for MemoryRenderCache {
+    async fn get(
+        &self,
+        key: &RenderCacheKey,
+        source: Option<&DocumentReference>,
+    ) -> Option<RenderedDocument> {
+        let mut inner = self.lock();
+        let entry = inner.entries.get_mut(key)?;
+        let document = entry.render.document.clone();
+        let new_source = source.filter(|source| entry.sources.insert((*source).clone()));
+        if let Some(source) = new_source {
+            inner.link(*key, source);
+        }
+        Some(document)
+    }
+
+    async fn insert(
+        &self,
+        key: RenderCacheKey,
+        render: CachedRender,
+        source: Option<DocumentReference>,
+    ) {
+        let mut inner = self.lock();
+        let mut entry = Entry {
+            render,
+            sources: source.into_iter().collect(),
+        };
+        if let Some(old) = inner.entries.pop(&key) {
+            inner.unlink(&key, &old);
+            entry.sources.extend(old.sources);
+        }
+        for document in entry.documents() {
+            inner.link(key, document);
+        }
+        if let Some((evicted_key, evicted)) = inner.entries.push(key, entry) {
+            inner.unlink(&evicted_key, &evicted);
+        }
+    }
+
+    async fn invalidate(&self, changed: &[DocumentReference]) -> usize {
+        let mut inner = self.lock();
+        let mut queue: VecDeque<DocumentReference> = changed.iter().cloned().collect();
+        let mut seen = HashSet::new();
+        let mut evicted = 0;
+
+        while let Some(document) = queue.pop_front() {
+            if !seen.insert(document.clone()) {
+                continue;
+            }
+            let Some(keys) = inner.dependents.remove(&document) else {
+                continue;
+            };
+            for key in keys {
+                if let Some(entry) = inner.entries.pop(&key) {
+                    inner.unlink(&key, &entry);
+                    evicted += 1;
+                    // The output of these pages changed as well
+                    queue.extend(entry.sources);
+                }
+            }
+        }
+        evicted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sevenmark_html::RenderConfig;
+    use sevenmark_transform::wiki::DocumentNamespace;
+
+    fn doc(title: &str) -> DocumentReference {
+        DocumentReference {
+            namespace: DocumentNamespace::Document,
+            title: title.to_string(),
+        }
+    }
+
+    fn key(content: &str) -> RenderCacheKey {
+        RenderCacheKey::new(content, &RenderConfig::default())
+    }
+
+    fn render(html: &str, dependencies: &[&str]) -> CachedRender {
+        CachedRender {
+            document: RenderedDocument {
+                html: html.to_string(),
+                categories: Vec::new(),
+                redirect: None,
+                references: Vec::new(),
+                user_mentions: Vec::new(),
+                sections: Vec::new(),
+                redirected_from: None,
+                warnings: Vec::new(),
+            },
+            dependencies: dependencies.iter().map(|title| doc(title)).collect(),
+        }
+    }
+
+    #[test]
+    fn key_depends_on_content_and_config() {
+        let config = RenderConfig {
+            document_base_url: Some("/w/"),
+            ..Default::default()
+        };
+        assert_eq!(key("a"), key("a"));
+        assert_ne!(key("a"), key("b"));
+        assert_ne!(key("a"), RenderCacheKey::new("a", &config));
+    }
+
+    #[tokio::test]
+    async fn invalidate_evicts_transitive_dependents() {
+        let cache = MemoryRenderCache::new(NonZeroUsize::new(8).unwrap());
+        // Page includes Template; Home includes Page; Other is unrelated
+        cache
+            .insert(
+                key("page"),
+                render("page", &["Template"]),
+                Some(doc("Page")),
+            )
+            .await;
+        cache
+            .insert(key("home"), render("home", &["Page"]), Some(doc("Home")))
+            .await;
+        cache
+            .insert(key("other"), render("other", &["Elsewhere"]), None)
+            .await;
+
+        assert_eq!(cache.invalidate(&[doc("Template")]).await, 2);
+        assert!(cache.get(&key("page"), None).await.is_none());
+        assert!(cache.get(&key("home"), None).await.is_none());
+        assert!(cache.get(&key("other"), None).await.is_some());
+        assert_eq!(cache.invalidate(&[doc("Template")]).await, 0);
+    }
+
+    #[tokio::test]
+    async fn get_records_source_for_invalidation() {
+        let cache = MemoryRenderCache::new(NonZeroUsize::new(8).unwrap());
+        cache
+            .insert(key("shared"), render("shared", &[]), None)
+            .await;
+        cache
+            .insert(key("home"), render("home", &["Copy"]), None)
+            .await;
+
+        assert!(
+            cache
+                .get(&key("shared"), Some(&doc("Copy")))
+                .await
+                .is_some()
+        );
+        assert_eq!(cache.invalidate(&[doc("Copy")]).await, 2);
+        assert!(cache.is_empty());
+    }
+
+    #[tokio::test]
+    async fn lru_eviction_cleans_up_index() {
+        let cache = MemoryRenderCache::new(NonZeroUsize::new(1).unwrap());
+        cache.insert(key("a"), render("a", &["X"]), None).await;
+        cache.insert(key("b"), render("b", &["Y"]), None).await;
+
+        assert_eq!(cache.len(), 1);
+        assert!(!cache.lock().dependents.contains_key(&doc("X")));
+        assert_eq!(cache.invalidate(&[doc("Y")]).await, 1);
+        assert!(cache.lock().dependents.is_empty());
+    }
+}
@@ -0,0 +1,117 @@
+//! Rendered-output cache
+//!
+//! Entries are keyed by content + render config and remember which documents
+//! the output was built from, so a document change can evict every page that
+//! depends on it.
+
+mod memory;
+
+pub use memory::*;
+
+use crate::rendered::RenderedDocument;
+use async_trait::async_trait;
+use sevenmark_html::RenderConfig;
+use sevenmark_transform::ProcessedDocument;
+use sevenmark_transform::preprocessor::DocumentReference;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+
+/// SHA-256 of the content and every render base URL
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RenderCacheKey([u8; 32]);
+
+impl RenderCacheKey {
+    pub fn new(content: &str, config: &RenderConfig<'_>) -> Self {
+        let mut hasher = Sha256::new();
+        for part in [
+            Some(content),
+            config.file_base_url,
+            config.document_base_url,
+            config.category_base_url,
+            config.user_base_url,
+        ] {
+            // Length-prefixed so ("ab", "c") and ("a", "bc") differ
+            match part {
+                Some(value) => {
+                    hasher.update([1]);
+                    hasher.update((value.len() as u64).to_le_bytes());
+                    hasher.update(value);
+                }
+                None => hasher.update([0]),
+            }
+        }
+        Self(hasher.finalize().into())
+    }
+}
+
+/// Rendered output with the documents it was built from
+#[derive(Debug, Clone)]
+pub struct CachedRender {
+    pub document: RenderedDocument,
+    /// See [`render_dependencies`]
+    pub dependencies: HashSet<DocumentReference>,
+}
+
+/// Referenced, included and media documents of a processed page
+pub fn render_dependencies(processed: &ProcessedDocument) -> HashSet<DocumentReference> {
+    let media = processed.media.iter().map(|media| DocumentReference {
+        namespace: media.namespace.clone(),
+        title: media.title.clone(),
+    });
+    processed
+        .references
+        .iter()
+        .chain(&processed.includes)
+        .cloned()
+        .chain(media)
+        .collect()
+}
+
+#[async_trait]
+pub trait RenderCache: Send + Sync {
+    /// Cached output for `key`. `source` is the page being rendered, if known;
+    /// it is recorded so that pages depending on it are evicted with it.
+    async fn get(
+        &self,
+        key: &RenderCacheKey,
+        source: Option<&DocumentReference>,
+    ) -> Option<RenderedDocument>;
+
+    async fn insert(
+        &self,
+        key: RenderCacheKey,
+        entry: CachedRender,
+        source: Option<DocumentReference>,
+    );
+
+    /// Evict every entry that depends on one of `changed`, then every entry
+    /// that depends on a page whose entry was evicted, and so on.
+    /// Returns the number of evicted entries.
+    async fn invalidate(&self, changed: &[DocumentReference]) -> usize;
+}
+
+/// Cache that stores nothing (`RENDER_CACHE_CAPACITY=0`)
+pub struct NoRenderCache;
+
+#[async_trait]
+impl RenderCache for NoRenderCache {
+    async fn get(
+        &self,
+        _key: &RenderCacheKey,
+        _source: Option<&DocumentReference>,
+    ) -> Option<RenderedDocument> {
+        None
+    }
+
+    async fn insert(
+        &self,
+        _key: RenderCacheKey,
+        _entry: CachedRender,
+        _source: Option<DocumentReference>,
+    ) {
+    }
+
+    async fn invalidate(&self, _changed: &[DocumentReference]) -> usize {
+        0
+    }
+}
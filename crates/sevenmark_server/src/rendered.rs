@@ -0,0 +1,30 @@
+//! Render output, shared by the render routes and the render cache
+
+use serde::Serialize;
+use sevenmark_transform::ProcessWarning;
+use sevenmark_transform::preprocessor::{DocumentReference, RedirectReference, SectionInfo};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RenderedDocument {
+    /// Rendered HTML content
+    pub html: String,
+    /// Document categories
+    pub categories: Vec<String>,
+    /// Redirect target if this is a redirect page
+    #[schema(value_type = Option<Object>)]
+    pub redirect: Option<RedirectReference>,
+    /// Referenced documents (for backlinks)
+    #[schema(value_type = Vec<Object>)]
+    pub references: Vec<DocumentReference>,
+    /// User mention UUIDs collected from the document
+    pub user_mentions: Vec<String>,
+    /// Section information with byte offsets for section editing
+    pub sections: Vec<SectionInfo>,
+    /// Redirect page that was followed to reach this document
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub redirected_from: Option<DocumentReference>,
+    /// Includes and media that could not be resolved (shown as placeholders)
+    pub warnings: Vec<ProcessWarning>,
+}
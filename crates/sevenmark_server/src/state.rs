@@ -1,8 +1,22 @@
+use crate::config::server_config::ServerConfig;
+use crate::readiness::Readiness;
+use crate::render_cache::RenderCache;
+use metrics_exporter_prometheus::PrometheusHandle;
 use sea_orm::DatabaseConnection as PostgresqlClient;
 use sevenmark_transform::wiki::RevisionStorageClient;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
 
 #[derive(Clone)]
 pub struct AppState {
     pub conn: PostgresqlClient,
     pub revision_storage: RevisionStorageClient,
+    pub render_cache: Arc<dyn RenderCache>,
+    /// Permits for parse/render work on the blocking pool
+    pub blocking_permits: Arc<Semaphore>,
+    /// Renders the `/metrics` response
+    pub metrics: PrometheusHandle,
+    pub readiness: Arc<Readiness>,
+    /// Validated settings, loaded once at startup
+    pub config: Arc<ServerConfig>,
 }
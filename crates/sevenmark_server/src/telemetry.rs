@@ -0,0 +1,94 @@
+//! Prometheus metrics for `/metrics`
+//!
+//! Pipeline metrics come from [`sevenmark_transform::telemetry`]; this module
+//! adds HTTP and render cache metrics and installs the recorder.
+
+use axum::extract::{MatchedPath, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+use metrics::{counter, histogram};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use std::time::{Duration, Instant};
+
+pub const HTTP_REQUESTS_TOTAL: &str = "sevenmark_http_requests_total";
+pub const HTTP_REQUEST_DURATION_SECONDS: &str = "sevenmark_http_request_duration_seconds";
+/// Render cache lookups, labelled `result="hit"|"miss"`
+pub const RENDER_CACHE_LOOKUPS_TOTAL: &str = "sevenmark_render_cache_lookups_total";
+
+/// 1ms ~ 30s
+const DURATION_BUCKETS: &[f64] = &[
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+const UPKEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Install the global Prometheus recorder and start its upkeep task.
+/// Must be called once, from within the Tokio runtime.
+pub fn install_metrics_recorder() -> anyhow::Result<PrometheusHandle> {
+    let handle = PrometheusBuilder::new()
+        .set_buckets_for_metric(Matcher::Suffix("_seconds".to_string()), DURATION_BUCKETS)?
+        .install_recorder()?;
+
+    // Drains histogram samples even when nobody scrapes
+    let upkeep = handle.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(UPKEEP_INTERVAL);
+        loop {
+            interval.tick().await;
+            upkeep.run_upkeep();
+        }
+    });
+
+    Ok(handle)
+}
+
+/// Count requests and record their latency per matched route
+pub async fn track_requests(request: Request, next: Next) -> Response {
+    // Unmatched paths share one label to keep cardinality bounded
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| "unmatched".to_string(), |path| path.as_str().to_string());
+    let method = request.method().to_string();
+
+    let start = Instant::now();
+    let response = next.run(request).await;
+    let elapsed = start.elapsed().as_secs_f64();
+
+    let status = response.status().as_u16().to_string();
+    counter!(
+        HTTP_REQUESTS_TOTAL,
+        "method" => method.clone(),
+        "route" => route.clone(),
+        "status" => status
+    )
+    .increment(1);
+    histogram!(HTTP_REQUEST_DURATION_SECONDS, "method" => method, "route" => route).record(elapsed);
+
+    response
+}
+
+pub fn record_cache_lookup(hit: bool) {
+    let result = if hit { "hit" } else { "miss" };
+    counter!(RENDER_CACHE_LOOKUPS_TOTAL, "result" => result).increment(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_lookups_are_labelled_by_result() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        metrics::with_local_recorder(&recorder, || {
+            record_cache_lookup(true);
+            record_cache_lookup(false);
+            record_cache_lookup(true);
+        });
+
+        let output = handle.render();
+        assert!(output.contains("sevenmark_render_cache_lookups_total{result=\"hit\"} 2"));
+        assert!(output.contains("sevenmark_render_cache_lookups_total{result=\"miss\"} 1"));
+    }
+}
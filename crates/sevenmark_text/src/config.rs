@@ -0,0 +1,27 @@
+/// Configuration for plain-text and search-index export
+#[derive(Debug, Clone, Copy)]
+pub struct TextConfig {
+    /// Prefix section headings with their hierarchical path (e.g. `1.2. Heading`)
+    pub section_numbers: bool,
+    /// Emit inline footnote markers (`[1]`) and the collected footnote list.
+    /// The search index always keeps footnote text in a separate field.
+    pub footnotes: bool,
+    /// Search weight for heading text
+    pub heading_weight: f32,
+    /// Search weight for section body text
+    pub body_weight: f32,
+    /// Search weight for footnote text referenced from a section
+    pub footnote_weight: f32,
+}
+
+impl Default for TextConfig {
+    fn default() -> Self {
+        Self {
+            section_numbers: true,
+            footnotes: true,
+            heading_weight: 3.0,
+            body_weight: 1.0,
+            footnote_weight: 0.5,
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! Text export context
+
+use std::collections::HashSet;
+
+use crate::config::TextConfig;
+
+/// A collected footnote waiting to be written out
+#[derive(Debug, Clone)]
+pub(crate) struct FootnoteEntry {
+    /// Marker text shown inline (`1`, custom `#display`, or `#name`)
+    pub display: String,
+    /// Flattened footnote content
+    pub text: String,
+}
+
+/// Mutable state shared while exporting one document
+pub(crate) struct TextContext<'a> {
+    pub config: &'a TextConfig,
+    /// Footnotes not yet flushed by `[fn]` or the document end
+    pub footnotes: Vec<FootnoteEntry>,
+    /// Footnote text referenced from the section currently being exported
+    pub section_footnotes: Vec<String>,
+    /// `[anchor(...)]` names found in the current section
+    pub anchors: Vec<String>,
+    pub in_footnote: bool,
+    pub list_depth: usize,
+    named_footnotes: HashSet<String>,
+    next_unnamed_footnote_number: usize,
+}
+
+impl<'a> TextContext<'a> {
+    pub fn new(config: &'a TextConfig) -> Self {
+        Self {
+            config,
+            footnotes: Vec::new(),
+            section_footnotes: Vec::new(),
+            anchors: Vec::new(),
+            in_footnote: false,
+            list_depth: 0,
+            named_footnotes: HashSet::new(),
+            next_unnamed_footnote_number: 1,
+        }
+    }
+
+    /// Register a footnote and return its inline marker text.
+    ///
+    /// Numbering follows the HTML renderer: unnamed footnotes are numbered
+    /// contiguously, named footnotes display their name and repeated names
+    /// only reference the first entry.
+    pub fn add_footnote(
+        &mut self,
+        name: Option<String>,
+        display: Option<String>,
+        text: String,
+    ) -> String {
+        if let Some(name) = name {
+            if self.named_footnotes.insert(name.clone()) {
+                self.push_footnote(name.clone(), text);
+            }
+            return name;
+        }
+
+        let number = self.next_unnamed_footnote_number;
+        self.next_unnamed_footnote_number += 1;
+        let display = display.unwrap_or_else(|| number.to_string());
+        self.push_footnote(display.clone(), text);
+        display
+    }
+
+    fn push_footnote(&mut self, display: String, text: String) {
+        self.section_footnotes.push(text.clone());
+        self.footnotes.push(FootnoteEntry { display, text });
+    }
+}
@@ -0,0 +1,90 @@
+//! Document-level plain-text rendering
+
+use sevenmark_ast::Element;
+
+use crate::config::TextConfig;
+use crate::context::TextContext;
+use crate::render::render_footnote_list;
+use crate::section::collect_sections;
+use crate::writer::TextWriter;
+
+/// Render a document to readable plain text
+///
+/// Sections are separated by blank lines and start with their heading line.
+/// Footnotes are listed where `[fn]` appears, remaining ones at the end.
+///
+/// # Arguments
+/// * `ast` - The parsed (or processed) AST elements
+/// * `config` - Export configuration
+pub fn render_text(ast: &[Element], config: &TextConfig) -> String {
+    let mut ctx = TextContext::new(config);
+    let sections = collect_sections(ast, &mut ctx);
+
+    let mut w = TextWriter::default();
+    for section in &sections {
+        w.block_break();
+        if let Some(path) = &section.section_path {
+            if config.section_numbers {
+                w.push_str(&format!("{path}. {}", section.heading));
+            } else {
+                w.push_str(&section.heading);
+            }
+            w.line_break();
+        }
+        w.push_raw(&section.body);
+    }
+
+    if config.footnotes {
+        render_footnote_list(&ctx.footnotes, &mut w);
+    }
+
+    w.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sevenmark_parser::core::parse_document;
+
+    fn text(input: &str) -> String {
+        render_text(&parse_document(input), &TextConfig::default())
+    }
+
+    #[test]
+    fn test_sections_are_separated() {
+        assert_eq!(
+            text("intro\n# A\nbody a\n## B\nbody b"),
+            "intro\n\n1. A\nbody a\n\n1.1. B\nbody b"
+        );
+    }
+
+    #[test]
+    fn test_table_rows_become_lines() {
+        let output = text("{{{#table\n[[[[a]] [[b]]]]\n[[[[c]] [[d]]]]\n}}}");
+        assert_eq!(output, "a | b\nc | d");
+    }
+
+    #[test]
+    fn test_nested_list_is_indented() {
+        let output = text("- one\n  - nested\n- two");
+        assert_eq!(output, "- one\n  - nested\n- two");
+    }
+
+    #[test]
+    fn test_footnotes_are_listed_at_end() {
+        let output = text("A{{{#fn first}}} B{{{#fn #name=\"n\" named}}}");
+        assert_eq!(output, "A[1] B[n]\n\n[1] first\n[n] named");
+    }
+
+    #[test]
+    fn test_footnote_macro_flushes_list() {
+        let output = text("A{{{#fn first}}}\n[fn]\nB{{{#fn second}}}");
+        assert_eq!(output, "A[1]\n\n[1] first\n\nB[2]\n\n[2] second");
+    }
+
+    #[test]
+    fn test_fold_summary_precedes_details() {
+        let output = text("{{{#fold [[summary]] [[details]]}}}");
+        assert_eq!(output, "summary\ndetails");
+    }
+}
@@ -0,0 +1,116 @@
+//! Structured search-index export
+
+use serde::Serialize;
+use sevenmark_ast::Element;
+
+use crate::config::TextConfig;
+use crate::context::TextContext;
+use crate::section::collect_sections;
+
+/// Search-index representation of a document
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchIndex {
+    /// Sections in document order (the preamble first, if it has content)
+    pub sections: Vec<IndexedSection>,
+}
+
+/// One indexable section
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexedSection {
+    /// Header section index (None for the preamble)
+    pub section_index: Option<usize>,
+    /// Hierarchical path (e.g., "1.2"), None for the preamble
+    pub section_path: Option<String>,
+    /// Header level (0 for the preamble)
+    pub level: usize,
+    /// Headings from the top-level ancestor down to this section
+    pub heading_path: Vec<String>,
+    /// Fragment ids that jump into this section: the section id first,
+    /// then `[anchor(...)]` names
+    pub anchors: Vec<String>,
+    pub heading: IndexField,
+    /// Section text without child sections and without footnote markers
+    pub body: IndexField,
+    /// Footnotes referenced from this section, one per line
+    pub footnotes: IndexField,
+}
+
+/// Text with its relative search weight
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexField {
+    pub text: String,
+    pub weight: f32,
+}
+
+/// Build a search index from the AST
+///
+/// # Arguments
+/// * `ast` - The parsed (or processed) AST elements
+/// * `config` - Export configuration; `section_numbers` and `footnotes` are ignored
+pub fn build_search_index(ast: &[Element], config: &TextConfig) -> SearchIndex {
+    let index_config = TextConfig {
+        footnotes: false,
+        ..*config
+    };
+    let mut ctx = TextContext::new(&index_config);
+
+    let sections = collect_sections(ast, &mut ctx)
+        .into_iter()
+        .map(|section| IndexedSection {
+            section_index: section.section_index,
+            section_path: section.section_path,
+            level: section.level,
+            heading_path: section.heading_path,
+            anchors: section.anchors,
+            heading: IndexField {
+                text: section.heading,
+                weight: config.heading_weight,
+            },
+            body: IndexField {
+                text: section.body,
+                weight: config.body_weight,
+            },
+            footnotes: IndexField {
+                text: section.footnotes.join("\n"),
+                weight: config.footnote_weight,
+            },
+        })
+        .collect();
+
+    SearchIndex { sections }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sevenmark_parser::core::parse_document;
+
+    fn index(input: &str) -> SearchIndex {
+        build_search_index(&parse_document(input), &TextConfig::default())
+    }
+
+    #[test]
+    fn test_footnotes_are_split_from_body() {
+        let index = index("# Title\nText{{{#fn note body}}} after");
+        let section = &index.sections[0];
+        assert_eq!(section.body.text, "Text after");
+        assert_eq!(section.footnotes.text, "note body");
+        assert_eq!(section.heading.text, "Title");
+        assert!(section.heading.weight > section.body.weight);
+    }
+
+    #[test]
+    fn test_anchor_macro_is_attributed_to_section() {
+        let index = index("intro\n# A\n[anchor(here)]text");
+        assert_eq!(index.sections.len(), 2);
+        assert!(index.sections[0].anchors.is_empty());
+        assert_eq!(index.sections[1].anchors, vec!["s-1", "here"]);
+    }
+
+    #[test]
+    fn test_serializes_to_json() {
+        let json = serde_json::to_value(index("# A\nbody")).unwrap();
+        assert_eq!(json["sections"][0]["section_path"], "1");
+        assert_eq!(json["sections"][0]["body"]["text"], "body");
+    }
+}
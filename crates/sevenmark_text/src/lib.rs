@@ -0,0 +1,28 @@
+//! sevenmark-text - Plain-text and search-index exporter for SevenMark AST
+//!
+//! Produces readable plain text with section boundaries, and a structured
+//! search index with per-section text, heading paths, anchors and weights.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sevenmark_parser::core::parse_document;
+//! use sevenmark_text::{TextConfig, build_search_index, render_text};
+//!
+//! let ast = parse_document("# Hello\n\nThis is **bold** text.");
+//! let config = TextConfig::default();
+//! let text = render_text(&ast, &config);
+//! let index = build_search_index(&ast, &config);
+//! ```
+
+mod config;
+mod context;
+mod document;
+mod index;
+mod render;
+mod section;
+mod writer;
+
+pub use config::TextConfig;
+pub use document::render_text;
+pub use index::{IndexField, IndexedSection, SearchIndex, build_search_index};
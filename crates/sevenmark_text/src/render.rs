@@ -0,0 +1,442 @@
+//! Element-level plain-text rendering
+
+use sevenmark_ast::{
+    BlockQuoteElement, Element, FoldElement, FootnoteElement, ListContentItem, ListElement,
+    ListItemElement, ListKind, MediaElement, Parameters, TableCellElement, TableCellItem,
+    TableElement, TableRowElement, TableRowItem, Traversable,
+};
+
+use crate::context::{FootnoteEntry, TextContext};
+use crate::writer::TextWriter;
+
+/// Render multiple elements into the writer
+pub(crate) fn render_elements(elements: &[Element], w: &mut TextWriter, ctx: &mut TextContext) {
+    for el in elements {
+        render_element(el, w, ctx);
+    }
+}
+
+/// Render elements into a standalone string (list items)
+pub(crate) fn render_to_string(elements: &[Element], ctx: &mut TextContext) -> String {
+    let mut w = TextWriter::default();
+    render_elements(elements, &mut w, ctx);
+    w.finish()
+}
+
+/// Render elements as a single line of text (headings, table cells, footnotes)
+pub(crate) fn render_inline(elements: &[Element], ctx: &mut TextContext) -> String {
+    collapse_whitespace(&render_to_string(elements, ctx))
+}
+
+/// Render a single element
+fn render_element(el: &Element, w: &mut TextWriter, ctx: &mut TextContext) {
+    match el {
+        Element::Text(text) => w.push_str(&text.value),
+        Element::Escape(escape) => w.push_str(&escape.value),
+        Element::Error(error) => w.push_str(&error.value),
+        // Variables are substituted before export; leftovers are undefined
+        Element::Variable(_) | Element::Comment(_) => {}
+
+        Element::Bold(style)
+        | Element::Italic(style)
+        | Element::Strikethrough(style)
+        | Element::Underline(style)
+        | Element::Superscript(style)
+        | Element::Subscript(style) => render_elements(&style.children, w, ctx),
+
+        // Top-level headers are split into sections; nested ones become a line
+        Element::Header(header) => {
+            w.block_break();
+            w.push_str(&render_inline(&header.children, ctx));
+            w.block_break();
+        }
+
+        Element::Literal(literal) => render_elements(&literal.children, w, ctx),
+        // Unlike HTML, styled blocks keep their line breaks: without the
+        // block layout they are the only structure left in plain text.
+        Element::Styled(styled) => render_elements(&styled.children, w, ctx),
+        Element::BlockQuote(bq) => render_blockquote(bq, w, ctx),
+        Element::Fold(fold) => render_fold(fold, w, ctx),
+        Element::Ruby(ruby) => {
+            w.push_str(&render_inline(&ruby.children, ctx));
+            if let Some(ruby_text) = param_text(&ruby.parameters, "ruby")
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+            {
+                w.push_str(&format!("({ruby_text})"));
+            }
+        }
+        Element::Code(code) => {
+            w.block_break();
+            w.push_raw(code.value.trim_matches('\n'));
+            w.block_break();
+        }
+        Element::TeX(tex) => {
+            if tex.is_block {
+                w.block_break();
+                w.push_raw(tex.value.trim());
+                w.block_break();
+            } else {
+                w.push_str(tex.value.trim());
+            }
+        }
+        Element::Css(_) => {}
+
+        Element::List(list) => render_list(list, w, ctx),
+        Element::Table(table) => render_table(table, w, ctx),
+
+        Element::Media(media) => render_media(media, w, ctx),
+        Element::ExternalMedia(_) => {}
+
+        Element::Footnote(footnote) => render_footnote(footnote, w, ctx),
+        Element::FootnoteRef(_) => {
+            if ctx.config.footnotes {
+                let footnotes = std::mem::take(&mut ctx.footnotes);
+                render_footnote_list(&footnotes, w);
+            }
+        }
+
+        Element::SoftBreak(_) | Element::HardBreak(_) => w.line_break(),
+        Element::HLine(_) => w.block_break(),
+        Element::Anchor(anchor) => ctx.anchors.push(anchor.name.clone()),
+
+        Element::Include(include) => render_elements(&include.children, w, ctx),
+
+        // Client-side or metadata-only elements carry no readable text
+        Element::Clear(_)
+        | Element::Toc(_)
+        | Element::TimeNow(_)
+        | Element::Date(_)
+        | Element::DateTime(_)
+        | Element::Dday(_)
+        | Element::Age(_)
+        | Element::PageCount(_)
+        | Element::Mention(_)
+        | Element::Category(_)
+        | Element::Redirect(_)
+        | Element::Define(_)
+        | Element::If(_)
+        | Element::Null(_) => {}
+    }
+}
+
+fn render_blockquote(bq: &BlockQuoteElement, w: &mut TextWriter, ctx: &mut TextContext) {
+    w.block_break();
+    render_elements(&bq.children, w, ctx);
+    w.block_break();
+}
+
+fn render_fold(fold: &FoldElement, w: &mut TextWriter, ctx: &mut TextContext) {
+    let summary = render_inline(&fold.summary.children, ctx);
+    let details = render_to_string(&fold.details.children, ctx);
+    w.block_break();
+    w.push_str(&summary);
+    w.line_break();
+    w.push_raw(&details);
+    w.block_break();
+}
+
+fn render_list(list: &ListElement, w: &mut TextWriter, ctx: &mut TextContext) {
+    let items = list.children.iter().flat_map(|item| match item {
+        ListContentItem::Item(item) => std::slice::from_ref(item),
+        ListContentItem::Conditional(cond) => cond.items.as_slice(),
+    });
+
+    // Nested lists hug their parent item instead of opening a new paragraph
+    let nested = ctx.list_depth > 0;
+    if nested {
+        w.line_break();
+    } else {
+        w.block_break();
+    }
+
+    ctx.list_depth += 1;
+    for (i, item) in items.enumerate() {
+        render_list_item(item, &list_marker(list.kind, i + 1), w, ctx);
+    }
+    ctx.list_depth -= 1;
+
+    if nested {
+        w.line_break();
+    } else {
+        w.block_break();
+    }
+}
+
+fn render_list_item(
+    item: &ListItemElement,
+    marker: &str,
+    w: &mut TextWriter,
+    ctx: &mut TextContext,
+) {
+    let content = render_to_string(&item.children, ctx);
+    let indent = " ".repeat(marker.chars().count() + 1);
+
+    w.push_raw(marker);
+    for (i, line) in content.lines().enumerate() {
+        if i == 0 {
+            w.push_raw(" ");
+        } else if !line.is_empty() {
+            w.push_raw(&indent);
+        }
+        w.push_raw(line);
+        w.line_break();
+    }
+    w.line_break();
+}
+
+/// Marker for the `n`-th (1-based) item of a list
+fn list_marker(kind: ListKind, n: usize) -> String {
+    match kind {
+        ListKind::Unordered => "-".to_string(),
+        ListKind::OrderedNumeric => format!("{n}."),
+        ListKind::OrderedAlphaLower => format!("{}.", to_alpha(n)),
+        ListKind::OrderedAlphaUpper => format!("{}.", to_alpha(n).to_uppercase()),
+        ListKind::OrderedRomanLower => format!("{}.", to_roman(n)),
+        ListKind::OrderedRomanUpper => format!("{}.", to_roman(n).to_uppercase()),
+    }
+}
+
+/// 1 → a, 26 → z, 27 → aa (spreadsheet-style, same as `<ol type="a">`)
+fn to_alpha(mut n: usize) -> String {
+    let mut out = Vec::new();
+    while n > 0 {
+        n -= 1;
+        out.push(b'a' + (n % 26) as u8);
+        n /= 26;
+    }
+    out.reverse();
+    String::from_utf8(out).unwrap_or_default()
+}
+
+fn to_roman(mut n: usize) -> String {
+    const TABLE: [(usize, &str); 13] = [
+        (1000, "m"),
+        (900, "cm"),
+        (500, "d"),
+        (400, "cd"),
+        (100, "c"),
+        (90, "xc"),
+        (50, "l"),
+        (40, "xl"),
+        (10, "x"),
+        (9, "ix"),
+        (5, "v"),
+        (4, "iv"),
+        (1, "i"),
+    ];
+
+    let mut out = String::new();
+    for (value, numeral) in TABLE {
+        while n >= value {
+            out.push_str(numeral);
+            n -= value;
+        }
+    }
+    out
+}
+
+/// Tables become one line per row with ` | ` between cells.
+/// Merged cells (`#x`/`#y`) are written once, in the row where they start.
+fn render_table(table: &TableElement, w: &mut TextWriter, ctx: &mut TextContext) {
+    let rows = table.children.iter().flat_map(|row| match row {
+        TableRowItem::Row(row) => std::slice::from_ref(row),
+        TableRowItem::Conditional(cond) => cond.rows.as_slice(),
+    });
+
+    w.block_break();
+    if let Some(caption) = param_text(&table.parameters, "caption")
+        .map(|s| collapse_whitespace(&s))
+        .filter(|s| !s.is_empty())
+    {
+        w.push_str(&caption);
+        w.line_break();
+    }
+    for row in rows {
+        render_table_row(row, w, ctx);
+    }
+    w.block_break();
+}
+
+fn render_table_row(row: &TableRowElement, w: &mut TextWriter, ctx: &mut TextContext) {
+    let cells: Vec<&TableCellElement> = row
+        .children
+        .iter()
+        .flat_map(|cell| match cell {
+            TableCellItem::Cell(cell) => std::slice::from_ref(cell),
+            TableCellItem::Conditional(cond) => cond.cells.as_slice(),
+        })
+        .collect();
+
+    let texts: Vec<String> = cells
+        .iter()
+        .map(|cell| render_inline(&cell.children, ctx))
+        .collect();
+    if texts.iter().all(String::is_empty) {
+        return;
+    }
+
+    w.push_str(&texts.join(" | "));
+    w.line_break();
+}
+
+/// Media shows its label; unlabeled links fall back to the target title.
+/// Images without a label carry no readable text.
+fn render_media(media: &MediaElement, w: &mut TextWriter, ctx: &mut TextContext) {
+    if !media.children.is_empty() {
+        render_elements(&media.children, w, ctx);
+        return;
+    }
+    if media.parameters.contains_key("file") {
+        return;
+    }
+    if let Some(title) = ["document", "category", "user", "url"]
+        .iter()
+        .filter_map(|key| param_text(&media.parameters, key))
+        .map(|s| s.trim().to_string())
+        .find(|s| !s.is_empty())
+    {
+        w.push_str(&title);
+    }
+}
+
+fn render_footnote(footnote: &FootnoteElement, w: &mut TextWriter, ctx: &mut TextContext) {
+    if ctx.in_footnote {
+        // Nested footnotes are flattened into the outer footnote text
+        w.push_str(&render_inline(&footnote.children, ctx));
+        return;
+    }
+
+    ctx.in_footnote = true;
+    let text = render_inline(&footnote.children, ctx);
+    ctx.in_footnote = false;
+
+    let marker = ctx.add_footnote(
+        param_text(&footnote.parameters, "name"),
+        param_text(&footnote.parameters, "display"),
+        text,
+    );
+    if ctx.config.footnotes {
+        w.push_str(&format!("[{marker}]"));
+    }
+}
+
+/// Write collected footnotes as `[marker] text` lines
+pub(crate) fn render_footnote_list(entries: &[FootnoteEntry], w: &mut TextWriter) {
+    if entries.is_empty() {
+        return;
+    }
+
+    w.block_break();
+    for entry in entries {
+        w.push_str(&format!("[{}] {}", entry.display, entry.text));
+        w.line_break();
+    }
+    w.block_break();
+}
+
+/// Get parameter value as text
+fn param_text(params: &Parameters, key: &str) -> Option<String> {
+    fn collect(el: &Element, out: &mut String) {
+        match el {
+            Element::Text(text) => out.push_str(&text.value),
+            Element::Escape(escape) => out.push_str(&escape.value),
+            other => other.traverse_children_ref(&mut |child| collect(child, out)),
+        }
+    }
+
+    params.get(key).map(|param| {
+        let mut out = String::new();
+        for el in &param.value {
+            collect(el, &mut out);
+        }
+        out
+    })
+}
+
+/// Collapse all runs of whitespace (including newlines) into single spaces
+pub(crate) fn collapse_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TextConfig, render_text};
+    use sevenmark_parser::core::parse_document;
+
+    fn text(input: &str) -> String {
+        render_text(&parse_document(input), &TextConfig::default())
+    }
+
+    #[test]
+    fn inline_styles_keep_only_their_text() {
+        assert_eq!(
+            text("**bold** *it* ~~del~~ __u__ ^^sup^^ ,,sub,, /* note */end"),
+            "bold it del u sup sub end"
+        );
+        assert_eq!(text("{{{#ruby #ruby=\"かんじ\" 漢字}}}"), "漢字(かんじ)");
+        assert_eq!(text("a {{{#tex x^2 }}} b"), "a x^2 b");
+    }
+
+    #[test]
+    fn table_cells_are_single_line_and_empty_rows_skipped() {
+        let output = text(
+            "{{{#table #caption=\"Prices\"\n[[[[#x=\"2\" wide\ncell]]]]\n[[[[c]] [[d]]]]\n[[[[/* a */]] [[/* b */]]]]\n}}}",
+        );
+        assert_eq!(output, "Prices\nwide cell\nc | d");
+    }
+
+    #[test]
+    fn fold_and_blockquote_keep_their_lines() {
+        assert_eq!(
+            text("{{{#fold [[more **info**]] [[line 1\nline 2]]}}}"),
+            "more info\nline 1\nline 2"
+        );
+        assert_eq!(text("{{{#quote first\nsecond}}}"), "first\nsecond");
+    }
+
+    #[test]
+    fn undefined_variables_are_dropped() {
+        assert_eq!(text("a [var(missing)] b"), "a b");
+    }
+
+    #[test]
+    fn nested_footnotes_are_flattened() {
+        assert_eq!(
+            text("A{{{#fn outer {{{#fn inner}}}}}}"),
+            "A[1]\n\n[1] outer inner"
+        );
+    }
+
+    #[test]
+    fn footnote_markers_are_omitted_when_disabled() {
+        let config = TextConfig {
+            footnotes: false,
+            ..TextConfig::default()
+        };
+        let output = render_text(&parse_document("A{{{#fn note}}} B\n[fn]"), &config);
+        assert_eq!(output, "A B");
+    }
+
+    #[test]
+    fn media_shows_label_or_target_title() {
+        assert_eq!(text("[[#document=\"Main Page\" home]]"), "home");
+        assert_eq!(text("[[#document=\"Go\"]]"), "Go");
+        assert_eq!(
+            text("[[#url=\"https://example.com\"]]"),
+            "https://example.com"
+        );
+        assert_eq!(text("a [[#file=\"Logo.png\"]] b"), "a b");
+    }
+
+    #[test]
+    fn list_markers_follow_kind() {
+        assert_eq!(list_marker(ListKind::Unordered, 3), "-");
+        assert_eq!(list_marker(ListKind::OrderedNumeric, 3), "3.");
+        assert_eq!(list_marker(ListKind::OrderedAlphaLower, 28), "ab.");
+        assert_eq!(list_marker(ListKind::OrderedAlphaUpper, 2), "B.");
+        assert_eq!(list_marker(ListKind::OrderedRomanLower, 14), "xiv.");
+        assert_eq!(list_marker(ListKind::OrderedRomanUpper, 9), "IX.");
+    }
+}
@@ -0,0 +1,154 @@
+//! Section splitting for text export
+//!
+//! Splits the top-level AST at headers, numbering sections the same way as the
+//! HTML renderer's section tree so paths and anchors line up with rendered pages.
+
+use sevenmark_ast::Element;
+use sevenmark_ast::SECTION_ID_PREFIX;
+
+use crate::context::TextContext;
+use crate::render::{render_elements, render_inline};
+use crate::writer::TextWriter;
+
+/// A document section rendered to plain text
+#[derive(Debug)]
+pub(crate) struct TextSection {
+    /// Header level (0 for the preamble before the first header)
+    pub level: usize,
+    /// Header section index (None for the preamble)
+    pub section_index: Option<usize>,
+    /// Hierarchical path (e.g., "1.2"), None for the preamble
+    pub section_path: Option<String>,
+    /// Heading text
+    pub heading: String,
+    /// Headings from the top-level ancestor down to this section
+    pub heading_path: Vec<String>,
+    /// Section anchor followed by `[anchor(...)]` names in the section
+    pub anchors: Vec<String>,
+    /// Body text, excluding child sections
+    pub body: String,
+    /// Text of footnotes referenced from this section
+    pub footnotes: Vec<String>,
+}
+
+/// Open header on the path from the document root to the current section
+struct OpenHeader {
+    level: usize,
+    path: String,
+    heading: String,
+    child_count: usize,
+}
+
+/// Render the document into a flat list of sections in document order
+pub(crate) fn collect_sections(ast: &[Element], ctx: &mut TextContext) -> Vec<TextSection> {
+    let mut sections = Vec::new();
+    let mut stack: Vec<OpenHeader> = Vec::new();
+    let mut top_level_count = 0;
+
+    let mut current = TextSection {
+        level: 0,
+        section_index: None,
+        section_path: None,
+        heading: String::new(),
+        heading_path: Vec::new(),
+        anchors: Vec::new(),
+        body: String::new(),
+        footnotes: Vec::new(),
+    };
+    let mut body = TextWriter::default();
+
+    for el in ast {
+        let Element::Header(header) = el else {
+            render_elements(std::slice::from_ref(el), &mut body, ctx);
+            continue;
+        };
+
+        finish_section(&mut current, std::mem::take(&mut body), ctx);
+        sections.push(current);
+
+        // A header closes every open section of the same or deeper level
+        while stack.last().is_some_and(|open| open.level >= header.level) {
+            stack.pop();
+        }
+        let path = match stack.last_mut() {
+            Some(parent) => {
+                parent.child_count += 1;
+                format!("{}.{}", parent.path, parent.child_count)
+            }
+            None => {
+                top_level_count += 1;
+                top_level_count.to_string()
+            }
+        };
+
+        let heading = render_inline(&header.children, ctx);
+        let mut heading_path: Vec<String> = stack.iter().map(|open| open.heading.clone()).collect();
+        heading_path.push(heading.clone());
+
+        current = TextSection {
+            level: header.level,
+            section_index: Some(header.section_index),
+            section_path: Some(path.clone()),
+            heading: heading.clone(),
+            heading_path,
+            anchors: vec![format!("{SECTION_ID_PREFIX}{path}")],
+            body: String::new(),
+            footnotes: Vec::new(),
+        };
+        stack.push(OpenHeader {
+            level: header.level,
+            path,
+            heading,
+            child_count: 0,
+        });
+    }
+
+    finish_section(&mut current, body, ctx);
+    sections.push(current);
+
+    // The preamble is only kept when it has content
+    if sections[0].body.is_empty() && sections[0].anchors.is_empty() {
+        sections.remove(0);
+    }
+    sections
+}
+
+fn finish_section(section: &mut TextSection, body: TextWriter, ctx: &mut TextContext) {
+    section.body = body.finish();
+    section.anchors.append(&mut ctx.anchors);
+    section.footnotes.append(&mut ctx.section_footnotes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TextConfig;
+    use sevenmark_parser::core::parse_document;
+
+    fn sections(input: &str) -> Vec<TextSection> {
+        let ast = parse_document(input);
+        let config = TextConfig::default();
+        let mut ctx = TextContext::new(&config);
+        collect_sections(&ast, &mut ctx)
+    }
+
+    #[test]
+    fn test_paths_match_section_tree() {
+        let sections = sections("intro\n# A\na\n### B\nb\n## C\nc\n# D\nd");
+        let paths: Vec<_> = sections.iter().map(|s| s.section_path.as_deref()).collect();
+        assert_eq!(
+            paths,
+            vec![None, Some("1"), Some("1.1"), Some("1.2"), Some("2")]
+        );
+        assert_eq!(sections[3].heading_path, vec!["A", "C"]);
+        assert_eq!(sections[3].anchors, vec!["s-1.2"]);
+    }
+
+    #[test]
+    fn test_empty_preamble_is_dropped() {
+        let sections = sections("# Only\nbody");
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].heading, "Only");
+        assert_eq!(sections[0].body, "body");
+    }
+}
@@ -0,0 +1,71 @@
+//! Plain-text output buffer with line and block boundary tracking
+
+/// Text buffer that collapses redundant whitespace at line and block boundaries
+#[derive(Debug, Default)]
+pub(crate) struct TextWriter {
+    buf: String,
+}
+
+impl TextWriter {
+    fn at_line_start(&self) -> bool {
+        self.buf.is_empty() || self.buf.ends_with('\n')
+    }
+
+    /// Append inline text, dropping indentation at line start and doubled spaces
+    pub fn push_str(&mut self, s: &str) {
+        let s = if self.at_line_start() || self.buf.ends_with(' ') {
+            s.trim_start_matches([' ', '\t'])
+        } else {
+            s
+        };
+        self.buf.push_str(s);
+    }
+
+    /// Append text verbatim (code blocks, pre-rendered sections)
+    pub fn push_raw(&mut self, s: &str) {
+        self.buf.push_str(s);
+    }
+
+    /// End the current line
+    pub fn line_break(&mut self) {
+        self.trim_trailing_spaces();
+        if !self.at_line_start() {
+            self.buf.push('\n');
+        }
+    }
+
+    /// End the current block, leaving exactly one blank line
+    pub fn block_break(&mut self) {
+        self.line_break();
+        if !self.buf.is_empty() && !self.buf.ends_with("\n\n") {
+            self.buf.push('\n');
+        }
+    }
+
+    fn trim_trailing_spaces(&mut self) {
+        let trimmed = self.buf.trim_end_matches([' ', '\t']).len();
+        self.buf.truncate(trimmed);
+    }
+
+    pub fn finish(self) -> String {
+        self.buf.trim_end().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_break_leaves_single_blank_line() {
+        let mut w = TextWriter::default();
+        w.block_break();
+        w.push_str("  first");
+        w.block_break();
+        w.block_break();
+        w.line_break();
+        w.push_str("second  ");
+        w.line_break();
+        assert_eq!(w.finish(), "first\n\nsecond");
+    }
+}
@@ -1,10 +1,13 @@
-pub mod expression_evaluator;
 pub mod postprocessor;
 pub mod preprocessor;
 pub mod processor;
-pub(crate) mod text_utils;
+pub mod telemetry;
+mod warnings;
 pub mod wiki;
 
+pub use sevenmark_utils::expression_evaluator;
+
 pub use postprocessor::*;
 pub use preprocessor::*;
 pub use processor::*;
+pub use warnings::ProcessWarning;
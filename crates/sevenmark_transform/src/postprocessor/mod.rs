@@ -2,15 +2,17 @@ mod resolver;
 #[cfg(test)]
 mod tests;
 
-use crate::PreProcessedDocument;
-use crate::preprocessor::{DocumentReference, RedirectReference, SectionInfo};
+use crate::preprocessor::{DocumentReference, MediaReference, RedirectReference, SectionInfo};
+use crate::warnings::push_warning;
 use crate::wiki::{DocumentNamespace, check_documents_exist};
+use crate::{PreProcessedDocument, ProcessWarning};
 use anyhow::Result;
+use rayon::prelude::*;
 use sea_orm::DatabaseConnection;
 use serde::Serialize;
 use sevenmark_ast::Element;
 use std::collections::{HashMap, HashSet};
-use tracing::debug;
+use tracing::{debug, warn};
 use utoipa::ToSchema;
 
 use resolver::resolve_media_elements;
@@ -25,76 +27,159 @@ pub struct ProcessedDocument {
     pub categories: HashSet<String>,
     pub redirect: Option<RedirectReference>,
     pub references: HashSet<DocumentReference>,
+    /// Documents whose content was included (1-depth)
+    pub includes: HashSet<DocumentReference>,
+    /// Media targets whose existence was checked
+    pub media: HashSet<MediaReference>,
     /// User mention UUIDs collected from the document
     pub user_mentions: HashSet<String>,
     #[schema(value_type = Vec<Object>)]
     pub ast: Vec<Element>,
     pub sections: Vec<SectionInfo>,
+    /// Failed includes and unresolved media; the document was still rendered
+    pub warnings: Vec<ProcessWarning>,
 }
 
-/// Processes document with media resolution
-pub async fn postprocess_sevenmark(
-    preprocessed: PreProcessedDocument,
+/// Check existence of the media targets in one batch
+async fn resolve_media<'a>(
+    media: impl IntoIterator<Item = &'a MediaReference>,
     db: &DatabaseConnection,
-) -> Result<ProcessedDocument> {
-    let mut ast = preprocessed.ast;
+) -> Result<MediaResolutionMap> {
+    // Convert MediaReference to (namespace, title) tuples for batch request
+    let requests: Vec<_> = media
+        .into_iter()
+        .map(|m| (m.namespace.clone(), m.title.clone()))
+        .collect();
+    if requests.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    debug!(
+        "Checking existence of {} unique media references",
+        requests.len()
+    );
+
+    // Check document existence (lightweight - no content fetching)
+    let existence_results = check_documents_exist(db, requests).await?;
 
-    // Build resolution map from DB (only if there are media references)
-    let resolved_map: MediaResolutionMap = if preprocessed.media.is_empty() {
-        HashMap::new()
-    } else {
-        // Convert MediaReference to (namespace, title) tuples for batch request
-        let requests: Vec<_> = preprocessed
-            .media
-            .into_iter()
-            .map(|m| (m.namespace, m.title))
-            .collect();
-
-        debug!(
-            "Checking existence of {} unique media references",
-            requests.len()
-        );
-
-        // Check document existence (lightweight - no content fetching)
-        let existence_results = check_documents_exist(db, requests).await?;
-
-        let mut map = HashMap::new();
-
-        for result in existence_results {
-            let key = (result.namespace.clone(), result.title.clone());
-            let value = match result.namespace {
-                DocumentNamespace::File => {
-                    // File: store file_url, width, height and validity
-                    let is_valid = result.file_url.is_some();
-                    (
-                        result.file_url,
-                        result.file_width,
-                        result.file_height,
-                        is_valid,
-                    )
-                }
-                DocumentNamespace::Document
-                | DocumentNamespace::Category
-                | DocumentNamespace::User => {
-                    // Document/Category/User: just store validity (title is in key)
-                    (None, None, None, result.exists)
-                }
-            };
-            map.insert(key, value);
+    let mut map = HashMap::new();
+
+    for result in existence_results {
+        let key = (result.namespace.clone(), result.title.clone());
+        let value = match result.namespace {
+            DocumentNamespace::File => {
+                // File: store file_url, width, height and validity
+                let is_valid = result.file_url.is_some();
+                (
+                    result.file_url,
+                    result.file_width,
+                    result.file_height,
+                    is_valid,
+                )
+            }
+            DocumentNamespace::Document | DocumentNamespace::Category | DocumentNamespace::User => {
+                // Document/Category/User: just store validity (title is in key)
+                (None, None, None, result.exists)
+            }
+        };
+        map.insert(key, value);
+    }
+
+    Ok(map)
+}
+
+/// [`resolve_media`] that reports a failed lookup as a warning instead of an
+/// error; every media target is then treated as missing
+async fn resolve_media_or_warn<'a>(
+    media: impl IntoIterator<Item = &'a MediaReference>,
+    db: &DatabaseConnection,
+) -> (MediaResolutionMap, Option<ProcessWarning>) {
+    match resolve_media(media, db).await {
+        Ok(map) => (map, None),
+        Err(e) => {
+            let reason = format!("{e:#}");
+            warn!(error = %reason, "Failed to check media targets");
+            (
+                HashMap::new(),
+                Some(ProcessWarning::MediaLookupFailed { reason }),
+            )
         }
+    }
+}
 
-        map
-    };
+fn resolve_document(
+    preprocessed: PreProcessedDocument,
+    resolved_map: &MediaResolutionMap,
+    lookup_warning: Option<&ProcessWarning>,
+) -> ProcessedDocument {
+    let mut ast = preprocessed.ast;
+    let mut warnings = preprocessed.warnings;
 
     // Always traverse AST to resolve MediaElement references (including #url)
-    resolve_media_elements(&mut ast, &resolved_map);
+    resolve_media_elements(&mut ast, resolved_map);
+
+    match lookup_warning {
+        Some(warning) if !preprocessed.media.is_empty() => {
+            push_warning(&mut warnings, warning.clone());
+        }
+        Some(_) => {}
+        None => {
+            let mut missing_files: Vec<_> = preprocessed
+                .media
+                .iter()
+                .filter(|m| m.namespace == DocumentNamespace::File)
+                .filter(|m| {
+                    !resolved_map
+                        .get(&(m.namespace.clone(), m.title.clone()))
+                        .is_some_and(|(_, _, _, is_valid)| *is_valid)
+                })
+                .map(|m| m.title.clone())
+                .collect();
+            missing_files.sort();
+            warnings.extend(
+                missing_files
+                    .into_iter()
+                    .map(|title| ProcessWarning::FileNotFound { title }),
+            );
+        }
+    }
 
-    Ok(ProcessedDocument {
+    ProcessedDocument {
         categories: preprocessed.categories,
         redirect: preprocessed.redirect,
         references: preprocessed.references,
+        includes: preprocessed.includes,
+        media: preprocessed.media,
         user_mentions: preprocessed.user_mentions,
         ast,
         sections: preprocessed.sections,
-    })
+        warnings,
+    }
+}
+
+/// Processes document with media resolution
+pub async fn postprocess_sevenmark(
+    preprocessed: PreProcessedDocument,
+    db: &DatabaseConnection,
+) -> Result<ProcessedDocument> {
+    let (resolved_map, lookup_warning) = resolve_media_or_warn(&preprocessed.media, db).await;
+    Ok(resolve_document(
+        preprocessed,
+        &resolved_map,
+        lookup_warning.as_ref(),
+    ))
+}
+
+/// [`postprocess_sevenmark`] for several documents, checking the media of
+/// all of them in a single batch. Results are in input order.
+pub async fn postprocess_sevenmark_batch(
+    preprocessed: Vec<PreProcessedDocument>,
+    db: &DatabaseConnection,
+) -> Result<Vec<ProcessedDocument>> {
+    let media: HashSet<_> = preprocessed.iter().flat_map(|d| &d.media).collect();
+    let (resolved_map, lookup_warning) = resolve_media_or_warn(media, db).await;
+    Ok(preprocessed
+        .into_par_iter()
+        .map(|document| resolve_document(document, &resolved_map, lookup_warning.as_ref()))
+        .collect())
 }
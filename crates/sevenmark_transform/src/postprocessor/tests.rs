@@ -1,7 +1,7 @@
 use super::resolver::resolve_media_elements;
 use super::*;
-use crate::text_utils::normalized_plain_text;
 use sevenmark_ast::{MediaElement, Parameter, Parameters, Span, TextElement};
+use sevenmark_utils::normalized_plain_text;
 
 fn span() -> Span {
     Span::synthesized()
@@ -113,3 +113,75 @@ fn resolve_media_elements_sets_file_dimensions_from_map() {
         Some(("files/poster.png", Some(640), Some(360), true))
     );
 }
+
+fn preprocessed_with_files(titles: &[&str]) -> PreProcessedDocument {
+    PreProcessedDocument {
+        media: titles
+            .iter()
+            .map(|title| MediaReference {
+                namespace: DocumentNamespace::File,
+                title: title.to_string(),
+            })
+            .collect(),
+        categories: HashSet::new(),
+        redirect: None,
+        references: HashSet::new(),
+        includes: HashSet::new(),
+        user_mentions: HashSet::new(),
+        ast: Vec::new(),
+        sections: Vec::new(),
+        warnings: Vec::new(),
+    }
+}
+
+#[test]
+fn resolve_document_reports_missing_files() {
+    let mut resolved_map = HashMap::new();
+    resolved_map.insert(
+        (DocumentNamespace::File, "found".to_string()),
+        (Some("files/found.png".to_string()), None, None, true),
+    );
+    resolved_map.insert(
+        (DocumentNamespace::File, "no-file".to_string()),
+        (None, None, None, false),
+    );
+
+    let processed = resolve_document(
+        preprocessed_with_files(&["found", "no-file", "absent"]),
+        &resolved_map,
+        None,
+    );
+
+    assert_eq!(
+        processed.warnings,
+        vec![
+            ProcessWarning::FileNotFound {
+                title: "absent".to_string()
+            },
+            ProcessWarning::FileNotFound {
+                title: "no-file".to_string()
+            },
+        ]
+    );
+}
+
+#[test]
+fn resolve_document_reports_media_lookup_failure_instead_of_files() {
+    let lookup_failed = ProcessWarning::MediaLookupFailed {
+        reason: "timed out".to_string(),
+    };
+
+    let processed = resolve_document(
+        preprocessed_with_files(&["a", "b"]),
+        &HashMap::new(),
+        Some(&lookup_failed),
+    );
+    assert_eq!(processed.warnings, vec![lookup_failed.clone()]);
+
+    let processed = resolve_document(
+        preprocessed_with_files(&[]),
+        &HashMap::new(),
+        Some(&lookup_failed),
+    );
+    assert!(processed.warnings.is_empty());
+}
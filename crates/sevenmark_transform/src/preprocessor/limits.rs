@@ -0,0 +1,106 @@
+use super::DocumentReference;
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::spawn_blocking;
+
+/// Limits on include resolution. The default is unlimited.
+#[derive(Debug, Clone)]
+pub struct ProcessLimits {
+    /// Distinct documents a page may include
+    pub max_includes: usize,
+    /// Total source size of the included documents
+    pub max_include_bytes: usize,
+    /// Permits for parsing included documents on the blocking pool, shared
+    /// with the caller's other CPU-bound work. `None` does not wait.
+    pub blocking_permits: Option<Arc<Semaphore>>,
+}
+
+impl Default for ProcessLimits {
+    fn default() -> Self {
+        Self {
+            max_includes: usize::MAX,
+            max_include_bytes: usize::MAX,
+            blocking_permits: None,
+        }
+    }
+}
+
+impl ProcessLimits {
+    /// Run `work` on the blocking pool once a permit is free. The permit is
+    /// held until the work finishes, even if the caller has stopped waiting.
+    pub(super) async fn run_blocking<T, F>(&self, work: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let permit = match &self.blocking_permits {
+            Some(permits) => Some(
+                permits
+                    .clone()
+                    .acquire_owned()
+                    .await
+                    .context("Blocking pool closed")?,
+            ),
+            None => None,
+        };
+
+        spawn_blocking(move || {
+            let _permit = permit;
+            work()
+        })
+        .await
+        .context("Blocking task failed")
+    }
+
+    pub(super) fn check_include_count(
+        &self,
+        includes: &HashSet<DocumentReference>,
+    ) -> Result<(), LimitExceeded> {
+        if includes.len() > self.max_includes {
+            return Err(LimitExceeded::Includes {
+                count: includes.len(),
+                max: self.max_includes,
+            });
+        }
+        Ok(())
+    }
+
+    pub(super) fn check_include_bytes(&self, bytes: usize) -> Result<(), LimitExceeded> {
+        if bytes > self.max_include_bytes {
+            return Err(LimitExceeded::IncludeBytes {
+                bytes,
+                max: self.max_include_bytes,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// A document exceeded one of the [`ProcessLimits`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LimitExceeded {
+    Includes { count: usize, max: usize },
+    IncludeBytes { bytes: usize, max: usize },
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitExceeded::Includes { count, max } => {
+                write!(
+                    f,
+                    "document includes {count} documents, at most {max} are allowed"
+                )
+            }
+            LimitExceeded::IncludeBytes { bytes, max } => write!(
+                f,
+                "included documents total {bytes} bytes, at most {max} are allowed"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LimitExceeded {}
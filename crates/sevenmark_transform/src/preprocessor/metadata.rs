@@ -1,9 +1,10 @@
 use super::{
-    DEFAULT_NAMESPACE, MediaReference, RedirectReference, SectionInfo, normalized_plain_text,
-    parse_namespace,
+    MediaReference, RedirectReference, SectionInfo, normalized_plain_text, parse_namespace,
 };
 use crate::wiki::DocumentNamespace;
-use sevenmark_ast::{Element, MentionType, Traversable};
+use sevenmark_ast::visit::{VisitPath, Visitor, walk_element};
+use sevenmark_ast::{Element, MentionType};
+use sevenmark_utils::include::DEFAULT_NAMESPACE;
 use std::collections::HashSet;
 
 struct MetadataCollector<'a> {
@@ -37,9 +38,7 @@ pub(super) fn collect_metadata(
         collect_categories_redirect,
     };
 
-    for element in elements {
-        collect_metadata_recursive(element, &mut collector);
-    }
+    collector.visit_document(elements);
 
     // Remaining headers in stack end at document end
     for mut section in collector.section_stack {
@@ -48,98 +47,98 @@ pub(super) fn collect_metadata(
     }
 }
 
-fn collect_metadata_recursive(element: &Element, c: &mut MetadataCollector) {
-    // Track max span.end for document length
-    let span = element.span();
-    if span.end > c.max_end {
-        c.max_end = span.end;
-    }
+impl Visitor for MetadataCollector<'_> {
+    fn visit_element(&mut self, element: &Element, path: &mut VisitPath) {
+        // Track max span.end for document length
+        let span = element.span();
+        if span.end > self.max_end {
+            self.max_end = span.end;
+        }
 
-    match element {
-        Element::Header(header) => {
-            let start = span.start;
-            let level = header.level;
+        match element {
+            Element::Header(header) => {
+                let start = span.start;
+                let level = header.level;
 
-            // Pop headers with level >= current (same or lower priority)
-            while let Some(mut section) = c.section_stack.pop() {
-                if section.level >= level {
-                    section.end = start;
-                    c.sections.push(section);
-                } else {
-                    c.section_stack.push(section);
-                    break;
+                // Pop headers with level >= current (same or lower priority)
+                while let Some(mut section) = self.section_stack.pop() {
+                    if section.level >= level {
+                        section.end = start;
+                        self.sections.push(section);
+                    } else {
+                        self.section_stack.push(section);
+                        break;
+                    }
                 }
-            }
 
-            c.section_stack.push(SectionInfo {
-                section_index: header.section_index,
-                level,
-                start,
-                end: 0,
-            });
-        }
-        Element::Media(media_elem) => {
-            if let Some(file_param) = media_elem.parameters.get("file")
-                && let Some(title) = normalized_plain_text(&file_param.value)
-            {
-                c.media.insert(MediaReference {
-                    namespace: DocumentNamespace::File,
-                    title,
+                self.section_stack.push(SectionInfo {
+                    section_index: header.section_index,
+                    level,
+                    start,
+                    end: 0,
                 });
             }
+            Element::Media(media_elem) => {
+                if let Some(file_param) = media_elem.parameters.get("file")
+                    && let Some(title) = normalized_plain_text(&file_param.value)
+                {
+                    self.media.insert(MediaReference {
+                        namespace: DocumentNamespace::File,
+                        title,
+                    });
+                }
 
-            if let Some(doc_param) = media_elem.parameters.get("document")
-                && let Some(title) = normalized_plain_text(&doc_param.value)
-            {
-                c.media.insert(MediaReference {
-                    namespace: DocumentNamespace::Document,
-                    title,
-                });
-            }
+                if let Some(doc_param) = media_elem.parameters.get("document")
+                    && let Some(title) = normalized_plain_text(&doc_param.value)
+                {
+                    self.media.insert(MediaReference {
+                        namespace: DocumentNamespace::Document,
+                        title,
+                    });
+                }
 
-            if let Some(cat_param) = media_elem.parameters.get("category")
-                && let Some(title) = normalized_plain_text(&cat_param.value)
-            {
-                c.media.insert(MediaReference {
-                    namespace: DocumentNamespace::Category,
-                    title,
-                });
-            }
+                if let Some(cat_param) = media_elem.parameters.get("category")
+                    && let Some(title) = normalized_plain_text(&cat_param.value)
+                {
+                    self.media.insert(MediaReference {
+                        namespace: DocumentNamespace::Category,
+                        title,
+                    });
+                }
 
-            if let Some(user_param) = media_elem.parameters.get("user")
-                && let Some(title) = normalized_plain_text(&user_param.value)
-            {
-                c.media.insert(MediaReference {
-                    namespace: DocumentNamespace::User,
-                    title,
-                });
+                if let Some(user_param) = media_elem.parameters.get("user")
+                    && let Some(title) = normalized_plain_text(&user_param.value)
+                {
+                    self.media.insert(MediaReference {
+                        namespace: DocumentNamespace::User,
+                        title,
+                    });
+                }
             }
-        }
-        Element::Category(cat_elem) if c.collect_categories_redirect => {
-            if let Some(name) = normalized_plain_text(&cat_elem.children) {
-                c.categories.insert(name);
+            Element::Category(cat_elem) if self.collect_categories_redirect => {
+                if let Some(name) = normalized_plain_text(&cat_elem.children) {
+                    self.categories.insert(name);
+                }
             }
-        }
-        Element::Redirect(redirect_elem) if c.collect_categories_redirect => {
-            if let Some(title) = normalized_plain_text(&redirect_elem.children)
-                && c.redirect.is_none()
-            {
-                let namespace_str = redirect_elem
-                    .parameters
-                    .get("namespace")
-                    .and_then(|param| normalized_plain_text(&param.value))
-                    .unwrap_or_else(|| DEFAULT_NAMESPACE.to_string());
-                let namespace = parse_namespace(&namespace_str);
-                *c.redirect = Some(RedirectReference { namespace, title });
+            Element::Redirect(redirect_elem) if self.collect_categories_redirect => {
+                if let Some(title) = normalized_plain_text(&redirect_elem.children)
+                    && self.redirect.is_none()
+                {
+                    let namespace = redirect_elem
+                        .parameters
+                        .get("namespace")
+                        .and_then(|param| normalized_plain_text(&param.value));
+                    let namespace =
+                        parse_namespace(namespace.as_deref().unwrap_or(DEFAULT_NAMESPACE));
+                    *self.redirect = Some(RedirectReference { namespace, title });
+                }
             }
+            Element::Mention(mention_elem) if mention_elem.kind == MentionType::User => {
+                self.user_mentions.insert(mention_elem.id.clone());
+            }
+            _ => {}
         }
-        Element::Mention(mention_elem) if mention_elem.kind == MentionType::User => {
-            c.user_mentions.insert(mention_elem.id.clone());
-        }
-        _ => {}
-    }
 
-    element.traverse_children_ref(&mut |child| {
-        collect_metadata_recursive(child, c);
-    });
+        walk_element(self, element, path);
+    }
 }
@@ -1,26 +1,31 @@
-mod define_if;
+mod limits;
 mod metadata;
 mod references;
 #[cfg(test)]
 mod tests;
 
-pub(super) use crate::text_utils::normalized_plain_text;
-use crate::wiki::{DocumentNamespace, RevisionStorageClient, fetch_documents_batch};
+use crate::ProcessWarning;
+use crate::telemetry::record_includes_fetched;
+use crate::wiki::{DocumentNamespace, RevisionStorageClient, fetch_documents_batch_within};
 use anyhow::Result;
 use rayon::prelude::*;
 use sea_orm::DatabaseConnection;
 use serde::Serialize;
 use sevenmark_ast::Element;
 use sevenmark_parser::core::parse_document;
+use sevenmark_utils::define_if::process_defines_and_ifs;
+use sevenmark_utils::include::namespace_name;
+pub(super) use sevenmark_utils::normalized_plain_text;
 use std::collections::{HashMap, HashSet};
-use tracing::debug;
+use tracing::{debug, warn};
 
-use define_if::process_defines_and_ifs;
 use metadata::collect_metadata;
 use references::{collect_includes, collect_references, substitute_includes};
 
+pub use limits::{LimitExceeded, ProcessLimits};
+
 /// Media reference with namespace and title
-#[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash)]
+#[derive(utoipa::ToSchema, Debug, Clone, Serialize, PartialEq, Eq, Hash)]
 pub struct MediaReference {
     pub namespace: DocumentNamespace,
     pub title: String,
@@ -60,17 +65,20 @@ pub struct PreProcessedDocument {
     pub categories: HashSet<String>,
     pub redirect: Option<RedirectReference>,
     pub references: HashSet<DocumentReference>,
+    /// Documents whose content was substituted into the AST
+    pub includes: HashSet<DocumentReference>,
     /// User mention UUIDs collected from the document
     pub user_mentions: HashSet<String>,
     pub ast: Vec<Element>,
     pub sections: Vec<SectionInfo>,
+    /// Includes that could not be substituted, in document order
+    pub warnings: Vec<ProcessWarning>,
 }
 
-pub(super) const DEFAULT_NAMESPACE: &str = "Document";
-
+/// [`DocumentNamespace`] of a `#namespace` value, falling back to
+/// `Document` like [`namespace_name`]
 pub(super) fn parse_namespace(namespace: &str) -> DocumentNamespace {
-    match namespace {
-        "Document" => DocumentNamespace::Document,
+    match namespace_name(namespace) {
         "File" => DocumentNamespace::File,
         "User" => DocumentNamespace::User,
         "Category" => DocumentNamespace::Category,
@@ -78,12 +86,19 @@ pub(super) fn parse_namespace(namespace: &str) -> DocumentNamespace {
     }
 }
 
-/// Processes document with 1-depth include resolution
-pub async fn preprocess_sevenmark(
-    mut ast: Vec<Element>,
-    db: &DatabaseConnection,
-    revision_storage: &RevisionStorageClient,
-) -> Result<PreProcessedDocument> {
+/// Document state after its own metadata is collected, before includes
+/// are substituted
+struct CollectedDocument {
+    ast: Vec<Element>,
+    media: HashSet<MediaReference>,
+    categories: HashSet<String>,
+    redirect: Option<RedirectReference>,
+    user_mentions: HashSet<String>,
+    sections: Vec<SectionInfo>,
+    includes: HashSet<DocumentReference>,
+}
+
+fn collect_document(mut ast: Vec<Element>) -> CollectedDocument {
     // Process defines and ifs in document order (single pass)
     let mut variables = HashMap::new();
     process_defines_and_ifs(&mut ast, &mut variables);
@@ -91,7 +106,7 @@ pub async fn preprocess_sevenmark(
     // Collect metadata from main document
     let mut categories = HashSet::new();
     let mut redirect = None;
-    let mut all_media = HashSet::new();
+    let mut media = HashSet::new();
     let mut sections = Vec::new();
     let mut user_mentions = HashSet::new();
 
@@ -99,58 +114,308 @@ pub async fn preprocess_sevenmark(
         &ast,
         &mut categories,
         &mut redirect,
-        &mut all_media,
+        &mut media,
         &mut sections,
         &mut user_mentions,
         true,
     );
 
     // Collect unique includes for fetching (only Include elements need content fetching)
-    let mut includes_to_fetch = HashSet::new();
-    collect_includes(&ast, &mut includes_to_fetch);
-
-    if !includes_to_fetch.is_empty() {
-        // Prepare batch fetch requests
-        let requests: Vec<_> = includes_to_fetch
-            .iter()
-            .map(|r| (r.namespace.clone(), r.title.clone()))
-            .collect();
-
-        debug!("Fetching {} unique documents", requests.len());
-
-        // Fetch all documents
-        let fetched_docs = fetch_documents_batch(db, revision_storage, requests).await?;
-
-        // Parse fetched documents and store in map
-        let docs_map: HashMap<DocumentReference, Vec<Element>> = fetched_docs
-            .into_par_iter()
-            .map(|doc| {
-                let parsed_ast = parse_document(&doc.current_revision.content);
-                (
-                    DocumentReference {
-                        namespace: doc.namespace,
-                        title: doc.title,
-                    },
-                    parsed_ast,
-                )
-            })
-            .collect();
-
-        // Substitute includes with their content
-        substitute_includes(&mut ast, &docs_map, &mut all_media);
+    let mut includes = HashSet::new();
+    collect_includes(&ast, &mut includes);
+
+    CollectedDocument {
+        ast,
+        media,
+        categories,
+        redirect,
+        user_mentions,
+        sections,
+        includes,
+    }
+}
+
+/// Included documents with their source size
+#[derive(Default)]
+struct FetchedIncludes {
+    /// Downloaded sources, moved into `asts` by [`parse_includes`]
+    sources: HashMap<DocumentReference, String>,
+    asts: HashMap<DocumentReference, Vec<Element>>,
+    bytes: HashMap<DocumentReference, usize>,
+    /// Existing documents whose content could not be loaded, with the reason
+    failed: HashMap<DocumentReference, String>,
+    /// Set when the lookup itself failed; nothing was fetched
+    lookup_error: Option<String>,
+}
+
+impl FetchedIncludes {
+    fn total_bytes(&self, includes: &HashSet<DocumentReference>) -> usize {
+        includes.iter().filter_map(|r| self.bytes.get(r)).sum()
+    }
+}
+
+/// Fetch the included documents in one batch, downloading at most
+/// `max_bytes` of content. Failures are recorded in the result so the page
+/// still renders without them; only going over `max_bytes` is an error.
+async fn fetch_includes<'a>(
+    includes: impl IntoIterator<Item = &'a DocumentReference>,
+    db: &DatabaseConnection,
+    revision_storage: &RevisionStorageClient,
+    max_bytes: usize,
+) -> Result<FetchedIncludes> {
+    // Prepare batch fetch requests
+    let requests: Vec<_> = includes
+        .into_iter()
+        .map(|r| (r.namespace.clone(), r.title.clone()))
+        .collect();
+    if requests.is_empty() {
+        return Ok(FetchedIncludes::default());
+    }
+
+    debug!("Fetching {} unique documents", requests.len());
+
+    // Fetch all documents
+    let batch = match fetch_documents_batch_within(db, revision_storage, requests, max_bytes).await
+    {
+        Ok(batch) => batch,
+        Err(e) if e.is::<LimitExceeded>() => return Err(e),
+        Err(e) => {
+            let reason = format!("{e:#}");
+            warn!(error = %reason, "Failed to fetch included documents");
+            return Ok(FetchedIncludes {
+                lookup_error: Some(reason),
+                ..FetchedIncludes::default()
+            });
+        }
+    };
+    record_includes_fetched(batch.documents.len());
+
+    let mut fetched = FetchedIncludes {
+        sources: HashMap::with_capacity(batch.documents.len()),
+        bytes: HashMap::with_capacity(batch.documents.len()),
+        ..FetchedIncludes::default()
+    };
+    for doc in batch.documents {
+        let reference = DocumentReference {
+            namespace: doc.namespace,
+            title: doc.title,
+        };
+        let content = doc.current_revision.content;
+        fetched.bytes.insert(reference.clone(), content.len());
+        fetched.sources.insert(reference, content);
+    }
+    for failed in batch.failed {
+        let reference = DocumentReference {
+            namespace: failed.namespace,
+            title: failed.title,
+        };
+        fetched.failed.insert(reference, failed.reason);
+    }
+    Ok(fetched)
+}
+
+/// Parse the fetched sources on the blocking pool of `limits`
+async fn parse_includes(
+    mut fetched: FetchedIncludes,
+    limits: &ProcessLimits,
+) -> Result<FetchedIncludes> {
+    let sources = std::mem::take(&mut fetched.sources);
+    if sources.is_empty() {
+        return Ok(fetched);
+    }
+
+    let asts: HashMap<_, _> = limits
+        .run_blocking(move || {
+            sources
+                .into_par_iter()
+                .map(|(reference, content)| (reference, parse_document(&content)))
+                .collect()
+        })
+        .await?;
+    fetched.asts.extend(asts);
+    Ok(fetched)
+}
+
+/// Substitute the fetched includes and collect the references of the result
+fn substitute_document(
+    document: CollectedDocument,
+    fetched: &FetchedIncludes,
+) -> PreProcessedDocument {
+    let CollectedDocument {
+        mut ast,
+        mut media,
+        categories,
+        redirect,
+        user_mentions,
+        sections,
+        includes,
+    } = document;
+
+    // Substitute includes with their content
+    let mut warnings = Vec::new();
+    if !includes.is_empty() {
+        if let Some(reason) = &fetched.lookup_error {
+            warnings.push(ProcessWarning::IncludeLookupFailed {
+                reason: reason.clone(),
+            });
+        }
+        substitute_includes(&mut ast, fetched, &mut media, &mut warnings);
     }
 
     // Collect all references from final AST
-    let mut all_references = includes_to_fetch;
-    collect_references(&ast, &mut all_references);
+    let mut references = includes.clone();
+    collect_references(&ast, &mut references);
 
-    Ok(PreProcessedDocument {
+    PreProcessedDocument {
         ast,
-        media: all_media,
+        media,
         categories,
         redirect,
-        references: all_references,
+        references,
+        includes,
         user_mentions,
         sections,
-    })
+        warnings,
+    }
+}
+
+/// Processes document with 1-depth include resolution
+pub async fn preprocess_sevenmark(
+    ast: Vec<Element>,
+    db: &DatabaseConnection,
+    revision_storage: &RevisionStorageClient,
+) -> Result<PreProcessedDocument> {
+    preprocess_sevenmark_with_limits(ast, db, revision_storage, &ProcessLimits::default()).await
+}
+
+/// [`preprocess_sevenmark`] that fails with [`LimitExceeded`] when the
+/// includes exceed `limits`
+pub async fn preprocess_sevenmark_with_limits(
+    ast: Vec<Element>,
+    db: &DatabaseConnection,
+    revision_storage: &RevisionStorageClient,
+    limits: &ProcessLimits,
+) -> Result<PreProcessedDocument> {
+    let document = collect_document(ast);
+    limits.check_include_count(&document.includes)?;
+    let fetched = fetch_includes(
+        &document.includes,
+        db,
+        revision_storage,
+        limits.max_include_bytes,
+    )
+    .await?;
+    limits.check_include_bytes(fetched.total_bytes(&document.includes))?;
+    let fetched = parse_includes(fetched, limits).await?;
+    Ok(substitute_document(document, &fetched))
+}
+
+/// Fetch the includes of each document separately, failing the documents
+/// whose includes go over `max_include_bytes`
+async fn preprocess_one_by_one(
+    documents: Vec<Result<CollectedDocument, LimitExceeded>>,
+    db: &DatabaseConnection,
+    revision_storage: &RevisionStorageClient,
+    limits: &ProcessLimits,
+) -> Result<Vec<Result<PreProcessedDocument, LimitExceeded>>> {
+    let mut results = Vec::with_capacity(documents.len());
+    for document in documents {
+        let document = match document {
+            Ok(document) => document,
+            Err(e) => {
+                results.push(Err(e));
+                continue;
+            }
+        };
+        let fetched = match fetch_includes(
+            &document.includes,
+            db,
+            revision_storage,
+            limits.max_include_bytes,
+        )
+        .await
+        {
+            Ok(fetched) => fetched,
+            Err(e) => match e.downcast::<LimitExceeded>() {
+                Ok(exceeded) => {
+                    results.push(Err(exceeded));
+                    continue;
+                }
+                Err(e) => return Err(e),
+            },
+        };
+        if let Err(e) = limits.check_include_bytes(fetched.total_bytes(&document.includes)) {
+            results.push(Err(e));
+            continue;
+        }
+        let fetched = parse_includes(fetched, limits).await?;
+        results.push(Ok(substitute_document(document, &fetched)));
+    }
+    Ok(results)
+}
+
+/// [`preprocess_sevenmark_with_limits`] for several documents, fetching the
+/// includes of all of them in a single batch. Results are in input order;
+/// documents over the limits are not fetched and fail on their own. The batch
+/// downloads at most `max_include_bytes` per document that passed the count
+/// check; when it goes over, the documents are fetched one at a time so only
+/// those over their own limit fail.
+pub async fn preprocess_sevenmark_batch(
+    asts: Vec<Vec<Element>>,
+    db: &DatabaseConnection,
+    revision_storage: &RevisionStorageClient,
+    limits: &ProcessLimits,
+) -> Result<Vec<Result<PreProcessedDocument, LimitExceeded>>> {
+    let documents: Vec<_> = asts
+        .into_par_iter()
+        .map(|ast| {
+            let document = collect_document(ast);
+            limits.check_include_count(&document.includes)?;
+            Ok(document)
+        })
+        .collect();
+    let accepted = documents.iter().flatten().count();
+    let includes: HashSet<_> = documents
+        .iter()
+        .flatten()
+        .flat_map(|d| &d.includes)
+        .collect();
+    let mut fetched = match fetch_includes(
+        includes,
+        db,
+        revision_storage,
+        limits.max_include_bytes.saturating_mul(accepted),
+    )
+    .await
+    {
+        Ok(fetched) => fetched,
+        Err(e) if e.is::<LimitExceeded>() => {
+            return preprocess_one_by_one(documents, db, revision_storage, limits).await;
+        }
+        Err(e) => return Err(e),
+    };
+
+    // Check sizes before parsing, and only parse what is substituted
+    let documents: Vec<_> = documents
+        .into_iter()
+        .map(|document| {
+            let document = document?;
+            limits.check_include_bytes(fetched.total_bytes(&document.includes))?;
+            Ok(document)
+        })
+        .collect();
+    let used: HashSet<_> = documents
+        .iter()
+        .flatten()
+        .flat_map(|d| &d.includes)
+        .collect();
+    fetched
+        .sources
+        .retain(|reference, _| used.contains(reference));
+    let fetched = parse_includes(fetched, limits).await?;
+
+    Ok(documents
+        .into_par_iter()
+        .map(|document| document.map(|document| substitute_document(document, &fetched)))
+        .collect())
 }
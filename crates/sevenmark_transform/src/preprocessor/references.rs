@@ -1,49 +1,32 @@
-use super::define_if::process_defines_and_ifs_with_protected_keys;
 use super::{
-    DEFAULT_NAMESPACE, DocumentReference, MediaReference, collect_metadata, normalized_plain_text,
+    DocumentReference, FetchedIncludes, MediaReference, collect_metadata, normalized_plain_text,
     parse_namespace,
 };
+use crate::ProcessWarning;
+use crate::warnings::push_warning;
 use crate::wiki::DocumentNamespace;
-use sevenmark_ast::IncludeElement;
-use sevenmark_ast::{Element, Traversable};
-use sevenmark_utils::extract_plain_text;
-use std::collections::{HashMap, HashSet};
+use sevenmark_ast::visit::{VisitPath, Visitor, walk_element};
+use sevenmark_ast::{Element, IncludeFailure};
+use sevenmark_utils::include::{self, IncludeResolver, IncludeTarget};
+use std::collections::HashSet;
 use tracing::warn;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct IncludeCacheKey {
-    target: DocumentReference,
-    params: Vec<(String, String)>,
+pub(super) fn collect_includes(elements: &[Element], includes: &mut HashSet<DocumentReference>) {
+    IncludeCollector(includes).visit_document(elements);
 }
 
-#[derive(Debug, Clone)]
-struct IncludeCacheValue {
-    ast: Vec<Element>,
-    media: HashSet<MediaReference>,
-}
+struct IncludeCollector<'a>(&'a mut HashSet<DocumentReference>);
 
-pub(super) fn collect_includes(elements: &[Element], includes: &mut HashSet<DocumentReference>) {
-    for element in elements {
-        collect_includes_recursive(element, includes);
-    }
-}
+impl Visitor for IncludeCollector<'_> {
+    fn visit_element(&mut self, element: &Element, path: &mut VisitPath) {
+        if let Element::Include(include_elem) = element
+            && let Some(target) = IncludeTarget::of(include_elem)
+        {
+            self.0.insert(DocumentReference::from(target));
+        }
 
-fn collect_includes_recursive(element: &Element, includes: &mut HashSet<DocumentReference>) {
-    if let Element::Include(include_elem) = element
-        && let Some(title) = normalized_plain_text(&include_elem.children)
-    {
-        let namespace_str = include_elem
-            .parameters
-            .get("namespace")
-            .and_then(|param| normalized_plain_text(&param.value))
-            .unwrap_or_else(|| DEFAULT_NAMESPACE.to_string());
-        let namespace = parse_namespace(&namespace_str);
-        includes.insert(DocumentReference { namespace, title });
+        walk_element(self, element, path);
     }
-
-    element.traverse_children_ref(&mut |child| {
-        collect_includes_recursive(child, includes);
-    });
 }
 
 /// Collect all document references from AST
@@ -51,184 +34,148 @@ pub(super) fn collect_references(
     elements: &[Element],
     references: &mut HashSet<DocumentReference>,
 ) {
-    for element in elements {
-        collect_references_recursive(element, references);
-    }
+    ReferenceCollector(references).visit_document(elements);
 }
 
-fn collect_references_recursive(element: &Element, references: &mut HashSet<DocumentReference>) {
-    match element {
-        Element::Category(cat_elem) => {
-            if let Some(name) = normalized_plain_text(&cat_elem.children) {
-                references.insert(DocumentReference {
-                    namespace: DocumentNamespace::Category,
-                    title: name,
-                });
+struct ReferenceCollector<'a>(&'a mut HashSet<DocumentReference>);
+
+impl Visitor for ReferenceCollector<'_> {
+    fn visit_element(&mut self, element: &Element, path: &mut VisitPath) {
+        match element {
+            Element::Category(cat_elem) => {
+                if let Some(name) = normalized_plain_text(&cat_elem.children) {
+                    self.0.insert(DocumentReference {
+                        namespace: DocumentNamespace::Category,
+                        title: name,
+                    });
+                }
             }
-        }
-        Element::Media(media_elem) => {
-            if let Some(file_param) = media_elem.parameters.get("file")
-                && let Some(title) = normalized_plain_text(&file_param.value)
-            {
-                references.insert(DocumentReference {
-                    namespace: DocumentNamespace::File,
-                    title,
-                });
-            }
-
-            if let Some(doc_param) = media_elem.parameters.get("document")
-                && let Some(title) = normalized_plain_text(&doc_param.value)
-            {
-                references.insert(DocumentReference {
-                    namespace: DocumentNamespace::Document,
-                    title,
-                });
+            Element::Media(media_elem) => {
+                if let Some(file_param) = media_elem.parameters.get("file")
+                    && let Some(title) = normalized_plain_text(&file_param.value)
+                {
+                    self.0.insert(DocumentReference {
+                        namespace: DocumentNamespace::File,
+                        title,
+                    });
+                }
+
+                if let Some(doc_param) = media_elem.parameters.get("document")
+                    && let Some(title) = normalized_plain_text(&doc_param.value)
+                {
+                    self.0.insert(DocumentReference {
+                        namespace: DocumentNamespace::Document,
+                        title,
+                    });
+                }
+
+                if let Some(cat_param) = media_elem.parameters.get("category")
+                    && let Some(title) = normalized_plain_text(&cat_param.value)
+                {
+                    self.0.insert(DocumentReference {
+                        namespace: DocumentNamespace::Category,
+                        title,
+                    });
+                }
+
+                if let Some(user_param) = media_elem.parameters.get("user")
+                    && let Some(title) = normalized_plain_text(&user_param.value)
+                {
+                    self.0.insert(DocumentReference {
+                        namespace: DocumentNamespace::User,
+                        title,
+                    });
+                }
             }
+            _ => {}
+        }
 
-            if let Some(cat_param) = media_elem.parameters.get("category")
-                && let Some(title) = normalized_plain_text(&cat_param.value)
-            {
-                references.insert(DocumentReference {
-                    namespace: DocumentNamespace::Category,
-                    title,
-                });
-            }
+        walk_element(self, element, path);
+    }
+}
 
-            if let Some(user_param) = media_elem.parameters.get("user")
-                && let Some(title) = normalized_plain_text(&user_param.value)
-            {
-                references.insert(DocumentReference {
-                    namespace: DocumentNamespace::User,
-                    title,
-                });
-            }
+impl From<IncludeTarget> for DocumentReference {
+    fn from(target: IncludeTarget) -> Self {
+        Self {
+            namespace: parse_namespace(target.namespace),
+            title: target.title,
         }
-        _ => {}
     }
-
-    element.traverse_children_ref(&mut |child| {
-        collect_references_recursive(child, references);
-    });
 }
 
+/// Replace include contents with the fetched documents. Includes that cannot
+/// be substituted keep their title, get [`IncludeElement::failure`] set and
+/// are reported in `warnings`.
+///
+/// [`IncludeElement::failure`]: sevenmark_ast::IncludeElement::failure
 pub(super) fn substitute_includes(
     elements: &mut [Element],
-    docs_map: &HashMap<DocumentReference, Vec<Element>>,
+    fetched: &FetchedIncludes,
     all_media: &mut HashSet<MediaReference>,
+    warnings: &mut Vec<ProcessWarning>,
 ) {
-    let mut include_cache = HashMap::new();
-    for element in elements {
-        substitute_includes_recursive(element, docs_map, all_media, &mut include_cache);
-    }
+    include::substitute_includes(
+        elements,
+        &mut FetchedResolver {
+            fetched,
+            all_media,
+            warnings,
+        },
+    );
 }
 
-fn build_include_cache_key(
-    include_elem: &IncludeElement,
-    target: DocumentReference,
-) -> IncludeCacheKey {
-    let params = include_elem
-        .parameters
-        .iter()
-        .filter(|(k, _)| k.as_str() != "namespace")
-        .map(|(k, v)| (k.clone(), extract_plain_text(&v.value)))
-        .collect();
-
-    IncludeCacheKey { target, params }
+struct FetchedResolver<'a> {
+    fetched: &'a FetchedIncludes,
+    all_media: &'a mut HashSet<MediaReference>,
+    warnings: &'a mut Vec<ProcessWarning>,
 }
 
-fn build_include_param_context(
-    include_elem: &IncludeElement,
-) -> (HashSet<String>, HashMap<String, String>) {
-    let mut protected_keys = HashSet::new();
-    let mut params_map = HashMap::new();
-
-    for (key, parameter) in &include_elem.parameters {
-        if key == "namespace" {
-            continue;
+impl IncludeResolver for FetchedResolver<'_> {
+    fn resolve(&mut self, target: &IncludeTarget) -> Result<Vec<Element>, IncludeFailure> {
+        let doc_key = DocumentReference::from(target.clone());
+        if let Some(base_ast) = self.fetched.asts.get(&doc_key) {
+            return Ok(base_ast.clone());
         }
 
-        protected_keys.insert(key.clone());
-        params_map.insert(key.clone(), extract_plain_text(&parameter.value));
-    }
-
-    (protected_keys, params_map)
-}
-
-fn substitute_includes_recursive(
-    element: &mut Element,
-    docs_map: &HashMap<DocumentReference, Vec<Element>>,
-    all_media: &mut HashSet<MediaReference>,
-    include_cache: &mut HashMap<IncludeCacheKey, IncludeCacheValue>,
-) {
-    if let Element::Include(include_elem) = element
-        && let Some(title) = normalized_plain_text(&include_elem.children)
-    {
-        let namespace_str = include_elem
-            .parameters
-            .get("namespace")
-            .and_then(|param| normalized_plain_text(&param.value))
-            .unwrap_or_else(|| DEFAULT_NAMESPACE.to_string());
-        let namespace = parse_namespace(&namespace_str);
-        let doc_key = DocumentReference {
-            namespace: namespace.clone(),
-            title: title.clone(),
-        };
-
-        let cache_key = build_include_cache_key(include_elem, doc_key.clone());
-        if let Some(cached) = include_cache.get(&cache_key) {
-            all_media.extend(cached.media.iter().cloned());
-            include_elem.children = cached.ast.clone();
-            return;
-        }
-
-        if let Some(base_ast) = docs_map.get(&doc_key) {
-            // Clone the document AST
-            let mut included_ast = base_ast.clone();
-
-            let (protected_keys, mut params_map) = build_include_param_context(include_elem);
-
-            // Process defines and ifs (include parameters have priority)
-            process_defines_and_ifs_with_protected_keys(
-                &mut included_ast,
-                &mut params_map,
-                Some(&protected_keys),
-            );
-
-            // Collect media from included document
-            let mut categories = HashSet::new();
-            let mut redirect = None;
-            let mut ignored_sections = Vec::new();
-            let mut ignored_user_mentions = HashSet::new();
-            let mut included_media = HashSet::new();
-            collect_metadata(
-                &included_ast,
-                &mut categories,
-                &mut redirect,
-                &mut included_media,
-                &mut ignored_sections,
-                &mut ignored_user_mentions,
-                false,
-            );
-            all_media.extend(included_media.iter().cloned());
-
-            include_cache.insert(
-                cache_key,
-                IncludeCacheValue {
-                    ast: included_ast.clone(),
-                    media: included_media,
+        let failed = self.fetched.failed.get(&doc_key);
+        let DocumentReference { namespace, title } = doc_key;
+        if let Some(reason) = failed {
+            push_warning(
+                self.warnings,
+                ProcessWarning::IncludeFailed {
+                    namespace,
+                    title,
+                    reason: reason.clone(),
                 },
             );
-
-            // Replace include content
-            include_elem.children = included_ast;
-            return;
+            Err(IncludeFailure::Unavailable)
+        } else if self.fetched.lookup_error.is_some() {
+            // Reported once as IncludeLookupFailed
+            Err(IncludeFailure::Unavailable)
+        } else {
+            warn!(namespace = ?namespace, title = %title, "Include target not found");
+            push_warning(
+                self.warnings,
+                ProcessWarning::IncludeNotFound { namespace, title },
+            );
+            Err(IncludeFailure::NotFound)
         }
-
-        warn!(namespace = ?namespace, title = %title, "Include target not found");
     }
 
-    // Traverse children
-    element.traverse_children(&mut |child| {
-        substitute_includes_recursive(child, docs_map, all_media, include_cache);
-    });
+    /// Collect media from the included document
+    fn substituted(&mut self, _target: &IncludeTarget, content: &[Element]) {
+        let mut categories = HashSet::new();
+        let mut redirect = None;
+        let mut ignored_sections = Vec::new();
+        let mut ignored_user_mentions = HashSet::new();
+        collect_metadata(
+            content,
+            &mut categories,
+            &mut redirect,
+            self.all_media,
+            &mut ignored_sections,
+            &mut ignored_user_mentions,
+            false,
+        );
+    }
 }
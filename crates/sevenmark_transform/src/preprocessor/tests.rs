@@ -1,13 +1,17 @@
-use super::define_if::{process_defines_and_ifs, process_defines_and_ifs_with_protected_keys};
 use super::metadata::collect_metadata;
 use super::references::{collect_includes, substitute_includes};
 use super::*;
 use sevenmark_ast::{
-    CategoryElement, DefineElement, FoldElement, FoldInnerElement, IncludeElement, ListContentItem,
-    ListElement, ListItemElement, ListKind, Parameter, Parameters, RedirectElement, Span,
-    TableCellElement, TableCellItem, TableElement, TableRowElement, TableRowItem, TextElement,
-    VariableElement,
+    CategoryElement, DefineElement, FoldElement, FoldInnerElement, IncludeElement, IncludeFailure,
+    ListContentItem, ListElement, ListItemElement, ListKind, Parameter, Parameters,
+    RedirectElement, Span, TableCellElement, TableCellItem, TableElement, TableRowElement,
+    TableRowItem, TextElement, VariableElement,
 };
+use sevenmark_utils::define_if::{
+    process_defines_and_ifs, process_defines_and_ifs_with_protected_keys,
+};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
 
 fn span() -> Span {
     Span::synthesized()
@@ -92,6 +96,7 @@ fn include_with_params_elements(
         close_span: span(),
         parameters,
         children: vec![text(title)],
+        failure: None,
     })
 }
 
@@ -280,19 +285,35 @@ fn collect_metadata_trims_category_and_redirect() {
     );
 }
 
+fn template_reference() -> DocumentReference {
+    DocumentReference {
+        namespace: DocumentNamespace::Document,
+        title: "Template".to_string(),
+    }
+}
+
+/// `Template` fetched with the given content
+fn fetched_includes(template: Vec<Element>) -> FetchedIncludes {
+    FetchedIncludes {
+        asts: HashMap::from([(template_reference(), template)]),
+        ..FetchedIncludes::default()
+    }
+}
+
+fn include_failure(element: &Element) -> Option<IncludeFailure> {
+    match element {
+        Element::Include(include_elem) => include_elem.failure,
+        _ => panic!("expected include element"),
+    }
+}
+
 #[test]
 fn substitute_includes_uses_trimmed_target_title() {
     let mut elements = vec![include(" Template \n", None, &[("title", "caller")])];
-    let docs_map = HashMap::from([(
-        DocumentReference {
-            namespace: DocumentNamespace::Document,
-            title: "Template".to_string(),
-        },
-        vec![define("title", "template"), variable("title")],
-    )]);
+    let fetched = fetched_includes(vec![define("title", "template"), variable("title")]);
     let mut media = HashSet::new();
 
-    substitute_includes(&mut elements, &docs_map, &mut media);
+    substitute_includes(&mut elements, &fetched, &mut media, &mut Vec::new());
 
     let include_elem = match &elements[0] {
         Element::Include(include_elem) => include_elem,
@@ -377,18 +398,12 @@ fn include_parameter_variable_is_resolved() {
             vec![("title".to_string(), vec![variable("name")])],
         ),
     ];
-    let docs_map = HashMap::from([(
-        DocumentReference {
-            namespace: DocumentNamespace::Document,
-            title: "Template".to_string(),
-        },
-        vec![define("title", "template"), variable("title")],
-    )]);
+    let fetched = fetched_includes(vec![define("title", "template"), variable("title")]);
     let mut media = HashSet::new();
     let mut vars = HashMap::new();
 
     process_defines_and_ifs(&mut elements, &mut vars);
-    substitute_includes(&mut elements, &docs_map, &mut media);
+    substitute_includes(&mut elements, &fetched, &mut media, &mut Vec::new());
 
     let include_elem = match &elements[1] {
         Element::Include(include_elem) => include_elem,
@@ -489,3 +504,128 @@ fn fold_nested_parameters_follow_document_order() {
         "later"
     );
 }
+
+#[test]
+fn process_limits_reject_too_many_includes() {
+    let limits = ProcessLimits {
+        max_includes: 1,
+        ..Default::default()
+    };
+    let document = collect_document(vec![include("A", None, &[]), include("B", None, &[])]);
+
+    let err = limits.check_include_count(&document.includes).unwrap_err();
+    assert_eq!(err, LimitExceeded::Includes { count: 2, max: 1 });
+    assert_eq!(
+        err.to_string(),
+        "document includes 2 documents, at most 1 are allowed"
+    );
+    assert!(
+        ProcessLimits::default()
+            .check_include_count(&document.includes)
+            .is_ok()
+    );
+}
+
+#[test]
+fn process_limits_reject_large_includes() {
+    let limits = ProcessLimits {
+        max_include_bytes: 10,
+        ..Default::default()
+    };
+    assert!(limits.check_include_bytes(10).is_ok());
+    assert_eq!(
+        limits.check_include_bytes(11),
+        Err(LimitExceeded::IncludeBytes { bytes: 11, max: 10 })
+    );
+}
+
+#[tokio::test]
+async fn parse_includes_runs_under_the_blocking_permits() {
+    let permits = Arc::new(Semaphore::new(1));
+    let limits = ProcessLimits {
+        blocking_permits: Some(permits.clone()),
+        ..Default::default()
+    };
+    let fetched = FetchedIncludes {
+        sources: HashMap::from([(template_reference(), "**bold**".to_string())]),
+        ..FetchedIncludes::default()
+    };
+
+    let fetched = parse_includes(fetched, &limits).await.unwrap();
+    assert!(fetched.sources.is_empty());
+    assert!(matches!(
+        fetched.asts[&template_reference()].as_slice(),
+        [Element::Bold(_)]
+    ));
+    assert_eq!(permits.available_permits(), 1);
+
+    permits.close();
+    let fetched = FetchedIncludes {
+        sources: HashMap::from([(template_reference(), "text".to_string())]),
+        ..FetchedIncludes::default()
+    };
+    assert!(parse_includes(fetched, &limits).await.is_err());
+}
+
+#[test]
+fn substitute_includes_marks_missing_and_failed_targets() {
+    let mut elements = vec![
+        include("Missing", None, &[]),
+        include("Template", None, &[]),
+        include("Missing", None, &[]),
+    ];
+    let fetched = FetchedIncludes {
+        failed: HashMap::from([(template_reference(), "download failed".to_string())]),
+        ..FetchedIncludes::default()
+    };
+    let mut warnings = Vec::new();
+
+    substitute_includes(&mut elements, &fetched, &mut HashSet::new(), &mut warnings);
+
+    assert_eq!(
+        include_failure(&elements[0]),
+        Some(IncludeFailure::NotFound)
+    );
+    assert_eq!(
+        include_failure(&elements[1]),
+        Some(IncludeFailure::Unavailable)
+    );
+    assert_eq!(
+        warnings,
+        vec![
+            ProcessWarning::IncludeNotFound {
+                namespace: DocumentNamespace::Document,
+                title: "Missing".to_string(),
+            },
+            ProcessWarning::IncludeFailed {
+                namespace: DocumentNamespace::Document,
+                title: "Template".to_string(),
+                reason: "download failed".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn include_lookup_failure_is_reported_once() {
+    let document = collect_document(vec![include("A", None, &[]), include("B", None, &[])]);
+    let fetched = FetchedIncludes {
+        lookup_error: Some("timed out".to_string()),
+        ..FetchedIncludes::default()
+    };
+
+    let preprocessed = substitute_document(document, &fetched);
+
+    assert_eq!(
+        preprocessed.warnings,
+        vec![ProcessWarning::IncludeLookupFailed {
+            reason: "timed out".to_string(),
+        }]
+    );
+    assert!(
+        preprocessed
+            .ast
+            .iter()
+            .all(|el| include_failure(el) == Some(IncludeFailure::Unavailable))
+    );
+}
@@ -1,3 +1,3 @@
 mod pipeline;
 
-pub use pipeline::process_sevenmark;
+pub use pipeline::{process_sevenmark, process_sevenmark_batch, process_sevenmark_with_limits};
@@ -1,5 +1,10 @@
+use crate::telemetry::{Phase, time_phase_async};
 use crate::wiki::RevisionStorageClient;
-use crate::{ProcessedDocument, postprocess_sevenmark, preprocess_sevenmark};
+use crate::{
+    LimitExceeded, ProcessLimits, ProcessedDocument, postprocess_sevenmark,
+    postprocess_sevenmark_batch, preprocess_sevenmark, preprocess_sevenmark_batch,
+    preprocess_sevenmark_with_limits,
+};
 use anyhow::Result;
 use sea_orm::DatabaseConnection;
 use sevenmark_ast::Element;
@@ -15,10 +20,67 @@ pub async fn process_sevenmark(
     revision_storage: &RevisionStorageClient,
 ) -> Result<ProcessedDocument> {
     // Step 1: Preprocess - resolve includes and collect media references
-    let preprocessed = preprocess_sevenmark(ast, db, revision_storage).await?;
+    let preprocessed = time_phase_async(
+        Phase::Preprocess,
+        preprocess_sevenmark(ast, db, revision_storage),
+    )
+    .await?;
 
     // Step 2: Postprocess - resolve media references to URLs
-    let processed = postprocess_sevenmark(preprocessed, db).await?;
+    let processed =
+        time_phase_async(Phase::Postprocess, postprocess_sevenmark(preprocessed, db)).await?;
 
     Ok(processed)
 }
+
+/// [`process_sevenmark`] that fails with [`LimitExceeded`] when the
+/// includes exceed `limits`
+pub async fn process_sevenmark_with_limits(
+    ast: Vec<Element>,
+    db: &DatabaseConnection,
+    revision_storage: &RevisionStorageClient,
+    limits: &ProcessLimits,
+) -> Result<ProcessedDocument> {
+    let preprocessed = time_phase_async(
+        Phase::Preprocess,
+        preprocess_sevenmark_with_limits(ast, db, revision_storage, limits),
+    )
+    .await?;
+    time_phase_async(Phase::Postprocess, postprocess_sevenmark(preprocessed, db)).await
+}
+
+/// [`process_sevenmark_with_limits`] for several documents. Includes and
+/// media of the whole batch are looked up once (one `fetch_documents_batch`
+/// and one `check_documents_exist` call). Results are in input order.
+pub async fn process_sevenmark_batch(
+    asts: Vec<Vec<Element>>,
+    db: &DatabaseConnection,
+    revision_storage: &RevisionStorageClient,
+    limits: &ProcessLimits,
+) -> Result<Vec<Result<ProcessedDocument, LimitExceeded>>> {
+    let preprocessed = time_phase_async(
+        Phase::Preprocess,
+        preprocess_sevenmark_batch(asts, db, revision_storage, limits),
+    )
+    .await?;
+
+    let mut accepted = Vec::new();
+    let outcomes: Vec<_> = preprocessed
+        .into_iter()
+        .map(|result| result.map(|document| accepted.push(document)))
+        .collect();
+
+    let mut processed = time_phase_async(
+        Phase::Postprocess,
+        postprocess_sevenmark_batch(accepted, db),
+    )
+    .await?
+    .into_iter();
+    Ok(outcomes
+        .into_iter()
+        .map(|outcome| {
+            // postprocess_sevenmark_batch keeps the order of `accepted`
+            outcome.map(|()| processed.next().expect("one result per document"))
+        })
+        .collect())
+}
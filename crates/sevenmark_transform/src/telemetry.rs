@@ -0,0 +1,67 @@
+//! Metrics emitted through the [`metrics`] facade
+//!
+//! Nothing is recorded until the host application installs a recorder
+//! (e.g. the Prometheus exporter in `sevenmark_server`).
+
+use metrics::{counter, histogram};
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tracing::{Instrument, info_span};
+
+/// Histogram of pipeline phase durations, labelled by `phase`
+pub const PHASE_DURATION_SECONDS: &str = "sevenmark_phase_duration_seconds";
+/// Documents fetched for include substitution
+pub const INCLUDES_FETCHED_TOTAL: &str = "sevenmark_includes_fetched_total";
+/// Revision blobs that could not be downloaded from revision storage
+pub const REVISION_DOWNLOAD_FAILURES_TOTAL: &str = "sevenmark_revision_download_failures_total";
+
+/// Pipeline phases reported under [`PHASE_DURATION_SECONDS`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Parse,
+    Preprocess,
+    Postprocess,
+    Render,
+}
+
+impl Phase {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Phase::Parse => "parse",
+            Phase::Preprocess => "preprocess",
+            Phase::Postprocess => "postprocess",
+            Phase::Render => "render",
+        }
+    }
+}
+
+pub fn record_phase(phase: Phase, elapsed: Duration) {
+    histogram!(PHASE_DURATION_SECONDS, "phase" => phase.as_str()).record(elapsed.as_secs_f64());
+}
+
+/// Run synchronous work inside a `phase` span and record its duration
+pub fn time_phase<T>(phase: Phase, work: impl FnOnce() -> T) -> T {
+    let _span = info_span!("phase", phase = phase.as_str()).entered();
+    let start = Instant::now();
+    let output = work();
+    record_phase(phase, start.elapsed());
+    output
+}
+
+/// [`time_phase`] for async work
+pub async fn time_phase_async<T>(phase: Phase, work: impl Future<Output = T>) -> T {
+    let start = Instant::now();
+    let output = work
+        .instrument(info_span!("phase", phase = phase.as_str()))
+        .await;
+    record_phase(phase, start.elapsed());
+    output
+}
+
+pub(crate) fn record_includes_fetched(count: usize) {
+    counter!(INCLUDES_FETCHED_TOTAL).increment(count as u64);
+}
+
+pub(crate) fn record_revision_download_failure() {
+    counter!(REVISION_DOWNLOAD_FAILURES_TOTAL).increment(1);
+}
@@ -0,0 +1,46 @@
+use crate::wiki::DocumentNamespace;
+use serde::Serialize;
+
+/// Problem that did not stop processing but changed the output
+#[derive(utoipa::ToSchema, Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ProcessWarning {
+    /// Include target does not exist or has no current revision
+    IncludeNotFound {
+        namespace: DocumentNamespace,
+        title: String,
+    },
+    /// Include target exists but its content could not be loaded
+    IncludeFailed {
+        namespace: DocumentNamespace,
+        title: String,
+        reason: String,
+    },
+    /// Looking up the included documents failed (e.g. a database timeout);
+    /// no include was substituted
+    IncludeLookupFailed { reason: String },
+    /// File media whose target has no stored file
+    FileNotFound { title: String },
+    /// Checking media targets failed; media links are shown as missing
+    MediaLookupFailed { reason: String },
+}
+
+impl ProcessWarning {
+    /// Whether the warning comes from a failure that may not happen on the
+    /// next attempt. Output with such warnings should not be cached.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            ProcessWarning::IncludeFailed { .. }
+                | ProcessWarning::IncludeLookupFailed { .. }
+                | ProcessWarning::MediaLookupFailed { .. }
+        )
+    }
+}
+
+/// Push `warning` unless an equal one was already reported
+pub(crate) fn push_warning(warnings: &mut Vec<ProcessWarning>, warning: ProcessWarning) {
+    if !warnings.contains(&warning) {
+        warnings.push(warning);
+    }
+}
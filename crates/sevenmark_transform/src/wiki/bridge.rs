@@ -3,7 +3,12 @@ use super::entity::{
     DocumentRevisions, DocumentRevisionsColumn,
 };
 use super::revision_storage::RevisionStorageClient;
-use super::types::{DocumentExistence, DocumentNamespace, DocumentResponse, DocumentRevision};
+use super::types::{
+    DocumentBatch, DocumentExistence, DocumentNamespace, DocumentResponse, DocumentRevision,
+    FailedDocument, RevisionLookup,
+};
+use crate::preprocessor::LimitExceeded;
+use crate::telemetry::record_revision_download_failure;
 use anyhow::{Context, Result};
 use futures::stream::{self, StreamExt};
 use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
@@ -69,15 +74,29 @@ async fn fetch_metadata_batch(
     Ok(metadata_list)
 }
 
-/// Fetch multiple documents by namespace and title using Sea ORM
+/// Fetch multiple documents by namespace and title using Sea ORM.
+/// Documents whose content cannot be downloaded are returned in
+/// [`DocumentBatch::failed`]; missing documents are left out.
 pub async fn fetch_documents_batch(
     db: &DatabaseConnection,
     revision_storage: &RevisionStorageClient,
     requests: Vec<(DocumentNamespace, String)>,
-) -> Result<Vec<DocumentResponse>> {
+) -> Result<DocumentBatch> {
+    fetch_documents_batch_within(db, revision_storage, requests, usize::MAX).await
+}
+
+/// [`fetch_documents_batch`] that stops downloading and fails with
+/// [`LimitExceeded::IncludeBytes`] once the downloaded content exceeds
+/// `max_bytes`
+pub async fn fetch_documents_batch_within(
+    db: &DatabaseConnection,
+    revision_storage: &RevisionStorageClient,
+    requests: Vec<(DocumentNamespace, String)>,
+    max_bytes: usize,
+) -> Result<DocumentBatch> {
     if requests.is_empty() {
         debug!("No documents to fetch");
-        return Ok(Vec::new());
+        return Ok(DocumentBatch::default());
     }
 
     debug!("Fetching {} documents from database", requests.len());
@@ -87,7 +106,7 @@ pub async fn fetch_documents_batch(
 
     if metadata_list.is_empty() {
         debug!("No documents found");
-        return Ok(Vec::new());
+        return Ok(DocumentBatch::default());
     }
 
     // Extract revision IDs
@@ -100,6 +119,7 @@ pub async fn fetch_documents_batch(
 
     // Fetch revisions in batch (get storage_keys from DB, content from SeaweedFS)
     let mut revisions_map: HashMap<Uuid, String> = HashMap::new();
+    let mut download_errors: HashMap<Uuid, String> = HashMap::new();
     if !revision_ids.is_empty() {
         let revisions = DocumentRevisions::find()
             .filter(DocumentRevisionsColumn::Id.is_in(revision_ids))
@@ -122,14 +142,25 @@ pub async fn fetch_documents_batch(
         .buffer_unordered(MAX_REVISION_DOWNLOAD_CONCURRENCY);
 
         // Collect successful downloads
+        let mut downloaded_bytes = 0usize;
         while let Some((revision_id, result)) = content_stream.next().await {
             match result {
                 Ok(content) => {
+                    downloaded_bytes = downloaded_bytes.saturating_add(content.len());
+                    if downloaded_bytes > max_bytes {
+                        // Dropping the stream cancels the remaining downloads
+                        return Err(LimitExceeded::IncludeBytes {
+                            bytes: downloaded_bytes,
+                            max: max_bytes,
+                        }
+                        .into());
+                    }
                     revisions_map.insert(revision_id, content);
                 }
                 Err(e) => {
                     warn!(revision_id = %revision_id, error = %e, "Failed to download content");
-                    // Skip this revision if download fails
+                    record_revision_download_failure();
+                    download_errors.insert(revision_id, e.to_string());
                 }
             }
         }
@@ -159,7 +190,7 @@ pub async fn fetch_documents_batch(
     }
 
     // Build response
-    let mut documents = Vec::new();
+    let mut batch = DocumentBatch::default();
     for doc in metadata_list {
         let current_revision_id = match doc.current_revision_id {
             Some(id) => id,
@@ -168,10 +199,21 @@ pub async fn fetch_documents_batch(
 
         let content = match revisions_map.get(&current_revision_id) {
             Some(content) => content.clone(),
-            None => continue, // Skip if revision not found
+            None => {
+                let reason = download_errors
+                    .get(&current_revision_id)
+                    .cloned()
+                    .unwrap_or_else(|| format!("revision {current_revision_id} not found"));
+                batch.failed.push(FailedDocument {
+                    namespace: doc.namespace,
+                    title: doc.title,
+                    reason,
+                });
+                continue;
+            }
         };
 
-        documents.push(DocumentResponse {
+        batch.documents.push(DocumentResponse {
             id: doc.id.to_string(),
             namespace: doc.namespace,
             title: doc.title,
@@ -180,8 +222,59 @@ pub async fn fetch_documents_batch(
         });
     }
 
-    debug!("Successfully fetched {} documents", documents.len());
-    Ok(documents)
+    debug!(
+        "Successfully fetched {} documents ({} failed)",
+        batch.documents.len(),
+        batch.failed.len()
+    );
+    Ok(batch)
+}
+
+/// Fetch the content of one document revision.
+/// Without `revision_id`, the current revision is loaded.
+pub async fn fetch_document_revision(
+    db: &DatabaseConnection,
+    revision_storage: &RevisionStorageClient,
+    namespace: DocumentNamespace,
+    title: &str,
+    revision_id: Option<Uuid>,
+) -> Result<RevisionLookup> {
+    let Some(doc) = DocumentMetadata::find()
+        .filter(DocumentMetadataColumn::Namespace.eq(namespace))
+        .filter(DocumentMetadataColumn::Title.eq(title))
+        .one(db)
+        .await
+        .context("Failed to fetch document metadata")?
+    else {
+        return Ok(RevisionLookup::DocumentNotFound);
+    };
+
+    let Some(revision_id) = revision_id.or(doc.current_revision_id) else {
+        return Ok(RevisionLookup::RevisionNotFound);
+    };
+
+    // Only revisions of this document may be loaded through its title
+    let Some(revision) = DocumentRevisions::find_by_id(revision_id)
+        .filter(DocumentRevisionsColumn::DocumentId.eq(doc.id))
+        .one(db)
+        .await
+        .context("Failed to fetch document revision")?
+    else {
+        return Ok(RevisionLookup::RevisionNotFound);
+    };
+
+    let content = revision_storage
+        .download_content(&revision.storage_key)
+        .await
+        .map_err(|e| {
+            record_revision_download_failure();
+            anyhow::anyhow!("Failed to download revision {revision_id}: {e}")
+        })?;
+
+    Ok(RevisionLookup::Found {
+        revision_id,
+        content,
+    })
 }
 
 /// Check if documents exist without fetching content (lightweight)
@@ -3,6 +3,14 @@ pub mod entity;
 pub mod revision_storage;
 pub mod types;
 
-pub use bridge::{check_documents_exist, fetch_documents_batch};
-pub use revision_storage::RevisionStorageClient;
-pub use types::{DocumentExistence, DocumentNamespace};
+pub use bridge::{
+    check_documents_exist, fetch_document_revision, fetch_documents_batch,
+    fetch_documents_batch_within,
+};
+pub use revision_storage::{
+    FilesystemRevisionStore, MemoryRevisionStore, R2RevisionStore, RevisionStorageClient,
+    RevisionStore,
+};
+pub use types::{
+    DocumentBatch, DocumentExistence, DocumentNamespace, FailedDocument, RevisionLookup,
+};
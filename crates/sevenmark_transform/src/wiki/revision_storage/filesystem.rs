@@ -0,0 +1,91 @@
+//! Revision storage on the local filesystem
+
+use super::{RevisionStore, StorageError, decode_auto};
+use async_trait::async_trait;
+use std::path::{Component, Path, PathBuf};
+
+/// Revisions as files under `root`, named by their storage key.
+/// Files may be zstd-compressed (as in R2) or plain UTF-8.
+pub struct FilesystemRevisionStore {
+    root: PathBuf,
+}
+
+impl FilesystemRevisionStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Path of `key` under the root; keys may not leave it
+    fn path(&self, key: &str) -> Result<PathBuf, StorageError> {
+        let relative = Path::new(key);
+        let is_plain = relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+        if key.is_empty() || !is_plain {
+            return Err(format!("invalid storage key: {key:?}").into());
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait]
+impl RevisionStore for FilesystemRevisionStore {
+    async fn download_content(&self, key: &str) -> Result<String, StorageError> {
+        let path = self.path(key)?;
+        let bytes = tokio::fs::read(&path)
+            .await
+            .map_err(|e| format!("{}: {e}", path.display()))?;
+        decode_auto(bytes)
+    }
+
+    async fn check(&self) -> Result<(), StorageError> {
+        let metadata = tokio::fs::metadata(&self.root)
+            .await
+            .map_err(|e| format!("{}: {e}", self.root.display()))?;
+        if !metadata.is_dir() {
+            return Err(format!("{} is not a directory", self.root.display()).into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("sevenmark-revisions-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("nested")).unwrap();
+        root
+    }
+
+    #[tokio::test]
+    async fn reads_plain_and_zstd_files() {
+        let root = temp_root("read");
+        std::fs::write(root.join("plain"), "plain text").unwrap();
+        let compressed = zstd::encode_all("compressed text".as_bytes(), 0).unwrap();
+        std::fs::write(root.join("nested/compressed"), compressed).unwrap();
+        let store = FilesystemRevisionStore::new(&root);
+
+        assert_eq!(store.download_content("plain").await.unwrap(), "plain text");
+        assert_eq!(
+            store.download_content("nested/compressed").await.unwrap(),
+            "compressed text"
+        );
+        assert!(store.download_content("missing").await.is_err());
+        assert!(store.check().await.is_ok());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn keys_cannot_leave_the_root() {
+        let store = FilesystemRevisionStore::new("/srv/revisions");
+
+        assert!(store.path("ab/cd").is_ok());
+        assert!(store.path("../secret").is_err());
+        assert!(store.path("/etc/passwd").is_err());
+        assert!(store.path("").is_err());
+    }
+}
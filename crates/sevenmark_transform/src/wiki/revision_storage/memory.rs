@@ -0,0 +1,70 @@
+//! In-memory revision storage
+
+use super::{RevisionStore, StorageError};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// Revisions kept in a map, for tests and local experiments
+#[derive(Default)]
+pub struct MemoryRevisionStore {
+    contents: RwLock<HashMap<String, String>>,
+}
+
+impl MemoryRevisionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&self, key: impl Into<String>, content: impl Into<String>) {
+        self.contents
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key.into(), content.into());
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for MemoryRevisionStore {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let contents = iter
+            .into_iter()
+            .map(|(key, content)| (key.into(), content.into()))
+            .collect();
+        Self {
+            contents: RwLock::new(contents),
+        }
+    }
+}
+
+#[async_trait]
+impl RevisionStore for MemoryRevisionStore {
+    async fn download_content(&self, key: &str) -> Result<String, StorageError> {
+        self.contents
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(key)
+            .cloned()
+            .ok_or_else(|| format!("revision not found: {key}").into())
+    }
+
+    async fn check(&self) -> Result<(), StorageError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wiki::RevisionStorageClient;
+
+    #[tokio::test]
+    async fn client_reads_from_memory_store() {
+        let store: MemoryRevisionStore = [("rev-1", "content")].into_iter().collect();
+        store.insert("rev-2", "other");
+        let client = RevisionStorageClient::new(store);
+
+        assert_eq!(client.download_content("rev-1").await.unwrap(), "content");
+        assert_eq!(client.download_content("rev-2").await.unwrap(), "other");
+        assert!(client.download_content("rev-3").await.is_err());
+    }
+}
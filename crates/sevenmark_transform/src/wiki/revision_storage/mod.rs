@@ -0,0 +1,69 @@
+//! Revision content storage
+//!
+//! [`RevisionStorageClient`] is the cloneable handle used by the pipeline.
+//! It wraps one of the [`RevisionStore`] backends:
+//! - [`R2RevisionStore`]: Cloudflare R2 (S3 compatible), zstd-compressed objects
+//! - [`FilesystemRevisionStore`]: zstd or plain files under a directory
+//! - [`MemoryRevisionStore`]: in-process map, for tests
+
+mod filesystem;
+mod memory;
+mod r2;
+
+pub use filesystem::FilesystemRevisionStore;
+pub use memory::MemoryRevisionStore;
+pub use r2::R2RevisionStore;
+
+use async_trait::async_trait;
+use std::sync::Arc;
+
+pub type StorageError = Box<dyn std::error::Error + Send + Sync>;
+
+#[async_trait]
+pub trait RevisionStore: Send + Sync {
+    /// Revision content stored under `key`
+    async fn download_content(&self, key: &str) -> Result<String, StorageError>;
+
+    /// Lightweight connectivity check, for readiness probes
+    async fn check(&self) -> Result<(), StorageError>;
+}
+
+/// Shared handle to a [`RevisionStore`]
+#[derive(Clone)]
+pub struct RevisionStorageClient {
+    store: Arc<dyn RevisionStore>,
+}
+
+impl RevisionStorageClient {
+    pub fn new(store: impl RevisionStore + 'static) -> Self {
+        Self {
+            store: Arc::new(store),
+        }
+    }
+
+    pub async fn download_content(&self, key: &str) -> Result<String, StorageError> {
+        self.store.download_content(key).await
+    }
+
+    pub async fn check(&self) -> Result<(), StorageError> {
+        self.store.check().await
+    }
+}
+
+/// zstd frame magic number (RFC 8878)
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
+/// Decompress zstd content
+fn decode_zstd(bytes: &[u8]) -> Result<String, StorageError> {
+    let decompressed = zstd::decode_all(bytes)?;
+    Ok(String::from_utf8(decompressed)?)
+}
+
+/// zstd content if it starts with a zstd frame, plain UTF-8 otherwise
+fn decode_auto(bytes: Vec<u8>) -> Result<String, StorageError> {
+    if bytes.starts_with(&ZSTD_MAGIC) {
+        decode_zstd(&bytes)
+    } else {
+        Ok(String::from_utf8(bytes)?)
+    }
+}
@@ -0,0 +1,44 @@
+//! R2 revision storage (S3 compatible)
+
+use super::{RevisionStore, StorageError, decode_zstd};
+use async_trait::async_trait;
+use aws_sdk_s3::Client;
+
+/// Revision storage on Cloudflare R2 (S3 compatible API), zstd-compressed
+pub struct R2RevisionStore {
+    client: Client,
+    bucket: String,
+}
+
+impl R2RevisionStore {
+    pub fn new(client: Client, bucket: String) -> Self {
+        Self { client, bucket }
+    }
+}
+
+#[async_trait]
+impl RevisionStore for R2RevisionStore {
+    /// Download revision content (zstd decompression)
+    async fn download_content(&self, key: &str) -> Result<String, StorageError> {
+        let resp = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await?;
+
+        let data = resp.body.collect().await?;
+        decode_zstd(&data.into_bytes())
+    }
+
+    /// HEAD request on the bucket
+    async fn check(&self) -> Result<(), StorageError> {
+        self.client
+            .head_bucket()
+            .bucket(&self.bucket)
+            .send()
+            .await?;
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 use sea_orm::{DeriveActiveEnum, EnumIter};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
+use uuid::Uuid;
 
 /// 문서 namespace (백엔드 API 스펙 & DB enum)
 #[derive(
@@ -35,6 +36,34 @@ pub struct DocumentRevision {
     pub content: String,
 }
 
+/// 일괄 문서 조회 결과
+#[derive(Debug, Clone, Default)]
+pub struct DocumentBatch {
+    pub documents: Vec<DocumentResponse>,
+    /// 문서는 있지만 내용을 불러오지 못한 경우 (revision 다운로드 실패 등)
+    pub failed: Vec<FailedDocument>,
+}
+
+/// 내용을 불러오지 못한 문서와 사유
+#[derive(Debug, Clone)]
+pub struct FailedDocument {
+    pub namespace: DocumentNamespace,
+    pub title: String,
+    pub reason: String,
+}
+
+/// 단일 문서 revision 조회 결과
+#[derive(Debug, Clone)]
+pub enum RevisionLookup {
+    Found {
+        revision_id: Uuid,
+        content: String,
+    },
+    DocumentNotFound,
+    /// 문서는 있지만 요청한 revision이 없거나 다른 문서의 revision
+    RevisionNotFound,
+}
+
 /// 문서 존재 확인 응답 (경량 - 링크 색상 결정용)
 #[derive(Debug, Clone)]
 pub struct DocumentExistence {
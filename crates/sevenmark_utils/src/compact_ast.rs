@@ -0,0 +1,208 @@
+//! Flat AST encoding for clients that only need node kinds and positions
+
+use serde::Serialize;
+use sevenmark_ast::visit::{
+    VisitPath, Visitor, walk_element, walk_list_content_item, walk_list_item, walk_parameter,
+    walk_table_cell, walk_table_cell_item, walk_table_row, walk_table_row_item,
+};
+use sevenmark_ast::{
+    Element, ListContentItem, ListItemElement, Parameter, Span, TableCellElement, TableCellItem,
+    TableRowElement, TableRowItem,
+};
+use std::collections::HashMap;
+
+use crate::Utf16OffsetConverter;
+
+/// `u32`s per node in [`CompactAst::nodes`]: kind, parent, start, end
+pub const NODE_STRIDE: usize = 4;
+
+/// Parent of a top-level node
+pub const NO_PARENT: u32 = u32::MAX;
+
+/// AST as a node table, e.g. for CodeMirror highlighting in the browser.
+///
+/// Nodes are in document (pre-)order, so a parent always comes before its
+/// children. Each node is [`NODE_STRIDE`] numbers:
+/// - kind: index into `strings` ([`Element::name`], or `Parameter`,
+///   `TableRow`, `TableCell`, `ListItem`, `ConditionalRows`,
+///   `ConditionalCells`, `ConditionalItems`)
+/// - parent: node index, or [`NO_PARENT`]
+/// - start, end: 0-based UTF-16 code unit offsets
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CompactAst {
+    pub nodes: Vec<u32>,
+    /// Kind names, in order of first appearance
+    pub strings: Vec<String>,
+}
+
+impl CompactAst {
+    pub fn len(&self) -> usize {
+        self.nodes.len() / NODE_STRIDE
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+/// Encodes `elements` parsed from `input` as a [`CompactAst`]
+pub fn encode_compact_ast(elements: &[Element], input: &str) -> CompactAst {
+    let mut encoder = Encoder {
+        converter: Utf16OffsetConverter::new(input),
+        ast: CompactAst::default(),
+        kinds: HashMap::new(),
+        parents: Vec::new(),
+    };
+    encoder.visit_document(elements);
+    encoder.ast
+}
+
+struct Encoder {
+    converter: Utf16OffsetConverter,
+    ast: CompactAst,
+    kinds: HashMap<&'static str, u32>,
+    /// Index of each open ancestor node
+    parents: Vec<u32>,
+}
+
+impl Encoder {
+    /// Adds a node and makes it the parent of the nodes added by `walk`
+    fn node(&mut self, kind: &'static str, span: &Span, walk: impl FnOnce(&mut Self)) {
+        let next_kind = self.kinds.len() as u32;
+        let kind = *self.kinds.entry(kind).or_insert_with(|| {
+            self.ast.strings.push(kind.to_string());
+            next_kind
+        });
+        let index = self.ast.len() as u32;
+        let parent = self.parents.last().copied().unwrap_or(NO_PARENT);
+        self.ast.nodes.extend([
+            kind,
+            parent,
+            self.converter.convert(span.start),
+            self.converter.convert(span.end),
+        ]);
+
+        self.parents.push(index);
+        walk(self);
+        self.parents.pop();
+    }
+}
+
+impl Visitor for Encoder {
+    fn visit_element(&mut self, element: &Element, path: &mut VisitPath) {
+        self.node(element.name(), element.span(), |this| {
+            walk_element(this, element, path)
+        });
+    }
+
+    fn visit_parameter(&mut self, parameter: &Parameter, path: &mut VisitPath) {
+        self.node("Parameter", &parameter.span, |this| {
+            walk_parameter(this, parameter, path)
+        });
+    }
+
+    fn visit_table_row_item(&mut self, item: &TableRowItem, path: &mut VisitPath) {
+        match item {
+            TableRowItem::Row(_) => walk_table_row_item(self, item, path),
+            TableRowItem::Conditional(cond) => self.node("ConditionalRows", &cond.span, |this| {
+                walk_table_row_item(this, item, path)
+            }),
+        }
+    }
+
+    fn visit_table_row(&mut self, row: &TableRowElement, path: &mut VisitPath) {
+        self.node("TableRow", &row.span, |this| {
+            walk_table_row(this, row, path)
+        });
+    }
+
+    fn visit_table_cell_item(&mut self, item: &TableCellItem, path: &mut VisitPath) {
+        match item {
+            TableCellItem::Cell(_) => walk_table_cell_item(self, item, path),
+            TableCellItem::Conditional(cond) => self.node("ConditionalCells", &cond.span, |this| {
+                walk_table_cell_item(this, item, path)
+            }),
+        }
+    }
+
+    fn visit_table_cell(&mut self, cell: &TableCellElement, path: &mut VisitPath) {
+        self.node("TableCell", &cell.span, |this| {
+            walk_table_cell(this, cell, path)
+        });
+    }
+
+    fn visit_list_content_item(&mut self, item: &ListContentItem, path: &mut VisitPath) {
+        match item {
+            ListContentItem::Item(_) => walk_list_content_item(self, item, path),
+            ListContentItem::Conditional(cond) => {
+                self.node("ConditionalItems", &cond.span, |this| {
+                    walk_list_content_item(this, item, path)
+                })
+            }
+        }
+    }
+
+    fn visit_list_item(&mut self, item: &ListItemElement, path: &mut VisitPath) {
+        self.node("ListItem", &item.span, |this| {
+            walk_list_item(this, item, path)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sevenmark_parser::core::parse_document;
+
+    /// (kind, parent, start, end) per node
+    fn rows(ast: &CompactAst) -> Vec<(&str, Option<u32>, u32, u32)> {
+        ast.nodes
+            .chunks(NODE_STRIDE)
+            .map(|node| {
+                let parent = (node[1] != NO_PARENT).then_some(node[1]);
+                (
+                    ast.strings[node[0] as usize].as_str(),
+                    parent,
+                    node[2],
+                    node[3],
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn nodes_are_in_document_order_with_utf16_spans() {
+        let input = "가 **굵게**";
+        let ast = encode_compact_ast(&parse_document(input), input);
+        assert_eq!(
+            rows(&ast),
+            [
+                ("Text", None, 0, 2),
+                ("Bold", None, 2, 8),
+                ("Text", Some(1), 4, 6),
+            ]
+        );
+        assert_eq!(ast.strings, ["Text", "Bold"]);
+    }
+
+    #[test]
+    fn rows_cells_and_parameters_are_nodes() {
+        let input = "{{{#table #style=\"x\" [[ [[a]] ]] }}}";
+        let ast = encode_compact_ast(&parse_document(input), input);
+        let kinds: Vec<_> = rows(&ast)
+            .into_iter()
+            .map(|(kind, parent, _, _)| (kind, parent))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                ("Table", None),
+                ("Parameter", Some(0)),
+                ("Text", Some(1)),
+                ("TableRow", Some(0)),
+                ("TableCell", Some(3)),
+                ("Text", Some(4)),
+            ]
+        );
+    }
+}
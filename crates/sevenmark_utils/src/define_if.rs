@@ -1,18 +1,23 @@
 use crate::expression_evaluator::evaluate_condition;
+use crate::extract_plain_text;
 use sevenmark_ast::{
     Element, ListContentItem, Parameters, Span, TableCellItem, TableRowItem, TextElement,
     Traversable,
 };
 use std::collections::{HashMap, HashSet};
 
-pub(super) fn process_defines_and_ifs(
+/// `{{{#define}}}`를 문서 순서대로 등록하고, 변수를 치환하며, `{{{#if}}}`와
+/// 표/리스트 조건부를 펼치거나 제거합니다.
+pub fn process_defines_and_ifs(
     elements: &mut Vec<Element>,
     variables: &mut HashMap<String, String>,
 ) {
     process_defines_and_ifs_with_protected_keys(elements, variables, None);
 }
 
-pub(super) fn process_defines_and_ifs_with_protected_keys(
+/// [`process_defines_and_ifs`] where `protected_keys` (include parameters)
+/// cannot be overwritten by the document's own defines
+pub fn process_defines_and_ifs_with_protected_keys(
     elements: &mut Vec<Element>,
     variables: &mut HashMap<String, String>,
     protected_keys: Option<&HashSet<String>>,
@@ -24,7 +29,7 @@ pub(super) fn process_defines_and_ifs_with_protected_keys(
             for (key, param) in &mut define_elem.parameters {
                 // Allow define values to reference already-known variables.
                 substitute_variables_in_elements(&mut param.value, variables);
-                let value = sevenmark_utils::extract_plain_text(&param.value);
+                let value = extract_plain_text(&param.value);
                 if !value.is_empty() {
                     if protected_keys.is_some_and(|keys| keys.contains(key)) {
                         continue;
@@ -54,9 +54,7 @@ fn evaluate_expression(expr: &Expression, variables: &HashMap<String, String>) -
         Expression::FunctionCall {
             name, arguments, ..
         } => evaluate_function(name, arguments, variables),
-        Expression::StringLiteral { value, .. } => {
-            Value::String(sevenmark_utils::extract_plain_text(value))
-        }
+        Expression::StringLiteral { value, .. } => Value::String(crate::extract_plain_text(value)),
         Expression::NumberLiteral { value, .. } => Value::Number(*value),
         Expression::BoolLiteral { value, .. } => Value::Bool(*value),
         Expression::Null { .. } => Value::Null,
@@ -0,0 +1,182 @@
+//! 1-depth include substitution, shared by the server and the browser build
+
+use crate::define_if::process_defines_and_ifs_with_protected_keys;
+use crate::{extract_plain_text, normalized_plain_text};
+use sevenmark_ast::{Element, IncludeElement, IncludeFailure, Traversable};
+use std::collections::{HashMap, HashSet};
+
+/// Namespace of an include or redirect without `#namespace`
+pub const DEFAULT_NAMESPACE: &str = "Document";
+
+/// `Document`, `File`, `User` or `Category`; unknown namespaces fall back to
+/// `Document`
+pub fn namespace_name(namespace: &str) -> &'static str {
+    match namespace {
+        "File" => "File",
+        "User" => "User",
+        "Category" => "Category",
+        _ => DEFAULT_NAMESPACE,
+    }
+}
+
+/// Page an include points at
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IncludeTarget {
+    /// One of the names returned by [`namespace_name`]
+    pub namespace: &'static str,
+    pub title: String,
+}
+
+impl IncludeTarget {
+    /// Trimmed title and `#namespace` of an include, `None` when the title is blank
+    pub fn of(include: &IncludeElement) -> Option<Self> {
+        let title = normalized_plain_text(&include.children)?;
+        let namespace = include
+            .parameters
+            .get("namespace")
+            .and_then(|param| normalized_plain_text(&param.value));
+        Some(Self {
+            namespace: namespace
+                .as_deref()
+                .map_or(DEFAULT_NAMESPACE, namespace_name),
+            title,
+        })
+    }
+}
+
+/// Included documents for [`substitute_includes`]
+pub trait IncludeResolver {
+    /// AST of the included page, before its defines and conditionals run
+    fn resolve(&mut self, target: &IncludeTarget) -> Result<Vec<Element>, IncludeFailure>;
+
+    /// Called once per distinct include (target and parameters) with the
+    /// content it was replaced by
+    fn substituted(&mut self, _target: &IncludeTarget, _content: &[Element]) {}
+}
+
+/// Replace the content of every include with its target page. The page's
+/// defines and conditionals are evaluated with the include parameters taking
+/// priority; included pages are not searched for further includes. An
+/// include whose target fails keeps its title and gets
+/// [`IncludeElement::failure`].
+pub fn substitute_includes(elements: &mut [Element], resolver: &mut impl IncludeResolver) {
+    let mut cache = HashMap::new();
+    for element in elements {
+        substitute(element, resolver, &mut cache);
+    }
+}
+
+type CacheKey = (IncludeTarget, Vec<(String, String)>);
+
+fn substitute(
+    element: &mut Element,
+    resolver: &mut impl IncludeResolver,
+    cache: &mut HashMap<CacheKey, Vec<Element>>,
+) {
+    if let Element::Include(include_elem) = element
+        && let Some(target) = IncludeTarget::of(include_elem)
+    {
+        // Include parameters take priority over the included page's defines
+        let params: Vec<_> = include_elem
+            .parameters
+            .iter()
+            .filter(|(key, _)| key.as_str() != "namespace")
+            .map(|(key, param)| (key.clone(), extract_plain_text(&param.value)))
+            .collect();
+
+        let key = (target, params);
+        if let Some(cached) = cache.get(&key) {
+            include_elem.children = cached.clone();
+            return;
+        }
+
+        match resolver.resolve(&key.0) {
+            Ok(mut included) => {
+                let protected_keys: HashSet<_> = key.1.iter().map(|(k, _)| k.clone()).collect();
+                let mut variables = key.1.iter().cloned().collect();
+                process_defines_and_ifs_with_protected_keys(
+                    &mut included,
+                    &mut variables,
+                    Some(&protected_keys),
+                );
+                resolver.substituted(&key.0, &included);
+                include_elem.children = included.clone();
+                cache.insert(key, included);
+            }
+            Err(failure) => include_elem.failure = Some(failure),
+        }
+        return;
+    }
+
+    element.traverse_children(&mut |child| substitute(child, resolver, cache));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sevenmark_parser::core::parse_document;
+
+    /// Pages by `Namespace:Title`, counting lookups
+    #[derive(Default)]
+    struct Pages {
+        sources: HashMap<String, String>,
+        lookups: usize,
+    }
+
+    impl IncludeResolver for Pages {
+        fn resolve(&mut self, target: &IncludeTarget) -> Result<Vec<Element>, IncludeFailure> {
+            self.lookups += 1;
+            let key = format!("{}:{}", target.namespace, target.title);
+            self.sources
+                .get(&key)
+                .map(|source| parse_document(source))
+                .ok_or(IncludeFailure::NotFound)
+        }
+    }
+
+    fn include(ast: &[Element]) -> &IncludeElement {
+        ast.iter()
+            .find_map(|element| match element {
+                Element::Include(include) => Some(include),
+                _ => None,
+            })
+            .expect("an include")
+    }
+
+    #[test]
+    fn unknown_namespaces_fall_back_to_document() {
+        let ast = parse_document("{{{#include #namespace=\" Talk \" Page }}}");
+        let target = IncludeTarget::of(include(&ast)).unwrap();
+
+        assert_eq!(target.namespace, DEFAULT_NAMESPACE);
+        assert_eq!(target.title, "Page");
+        assert_eq!(namespace_name("Category"), "Category");
+    }
+
+    #[test]
+    fn parameters_override_defines_and_repeats_are_resolved_once() {
+        let mut pages = Pages::default();
+        pages.sources.insert(
+            "Document:Greeting".to_string(),
+            "{{{#define #name=\"world\"}}}[var(name)]".to_string(),
+        );
+        let mut ast = parse_document(
+            "{{{#include #name=\"you\" Greeting}}}{{{#include #name=\"you\" Greeting}}}",
+        );
+        substitute_includes(&mut ast, &mut pages);
+
+        assert_eq!(pages.lookups, 1);
+        let text = extract_plain_text(&include(&ast).children);
+        assert_eq!(text, "you");
+    }
+
+    #[test]
+    fn failed_targets_keep_their_title() {
+        let mut ast = parse_document("{{{#include Missing}}}");
+        substitute_includes(&mut ast, &mut Pages::default());
+
+        let include = include(&ast);
+        assert_eq!(include.failure, Some(IncludeFailure::NotFound));
+        assert_eq!(extract_plain_text(&include.children), "Missing");
+    }
+}
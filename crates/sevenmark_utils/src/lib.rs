@@ -1,9 +1,14 @@
 //! sevenmark-utils - Shared utilities for SevenMark crates
 
+mod compact_ast;
+pub mod define_if;
+pub mod expression_evaluator;
+pub mod include;
 mod line_index;
 mod text;
 mod utf16_offset_converter;
 
+pub use compact_ast::*;
 pub use line_index::*;
 pub use text::*;
 pub use utf16_offset_converter::*;
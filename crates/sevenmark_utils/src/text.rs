@@ -12,3 +12,10 @@ pub fn extract_plain_text(elements: &[Element]) -> String {
         })
         .collect::<String>()
 }
+
+/// [`extract_plain_text`] trimmed, `None` when it is blank
+pub fn normalized_plain_text(elements: &[Element]) -> Option<String> {
+    let text = extract_plain_text(elements);
+    let trimmed = text.trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
+}
@@ -0,0 +1,33 @@
+/** Numbers per node in `CompactAstData.nodes`: kind, parent, from, to */
+export declare const NODE_STRIDE: 4;
+
+/** Parent of a top-level node */
+export declare const NO_PARENT: 0xffffffff;
+
+/** Output of `parse_sevenmark_compact` or `POST /v0/parse/compact` */
+export interface CompactAstData {
+  nodes: Uint32Array | ArrayLike<number>;
+  strings: ArrayLike<string>;
+}
+
+export interface CompactNode {
+  /** Element variant name, or `Parameter`, `TableRow`, `TableCell`, `ListItem`, `Conditional*` */
+  kind: string;
+  /** 0-based UTF-16 offsets */
+  from: number;
+  to: number;
+  parent: CompactNode | null;
+  children: CompactNode[];
+}
+
+/**
+ * Calls `visit` for every node in document order without building a tree.
+ * `parent` is a node index, or `NO_PARENT`.
+ */
+export declare function forEachCompactNode(
+  ast: CompactAstData,
+  visit: (kind: string, from: number, to: number, parent: number, index: number) => void,
+): void;
+
+/** Builds the node tree; returns the top-level nodes */
+export declare function decodeCompactAst(ast: CompactAstData): CompactNode[];
@@ -0,0 +1,30 @@
+// Decoder for the compact AST of `parse_sevenmark_compact`.
+// Types are in compact-ast.d.ts.
+
+export const NODE_STRIDE = 4;
+export const NO_PARENT = 0xffffffff;
+
+export function forEachCompactNode(ast, visit) {
+  const { nodes, strings } = ast;
+  for (let i = 0, index = 0; i < nodes.length; i += NODE_STRIDE, index++) {
+    visit(strings[nodes[i]], nodes[i + 2], nodes[i + 3], nodes[i + 1], index);
+  }
+}
+
+export function decodeCompactAst(ast) {
+  const all = [];
+  const roots = [];
+  // Parents always come before their children
+  forEachCompactNode(ast, (kind, from, to, parent) => {
+    const node = {
+      kind,
+      from,
+      to,
+      parent: parent === NO_PARENT ? null : all[parent],
+      children: [],
+    };
+    all.push(node);
+    (node.parent ? node.parent.children : roots).push(node);
+  });
+  return roots;
+}
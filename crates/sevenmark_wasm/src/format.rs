@@ -0,0 +1,42 @@
+//! Formatting without the LSP worker
+
+use serde::Deserialize;
+use sevenmark_formatter::{FormatConfig, format_document};
+use sevenmark_parser::core::parse_document;
+
+/// `config` of `format_sevenmark`; missing fields use [`FormatConfig`]'s defaults
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct FormatOptions {
+    pub width: usize,
+    pub indent: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        let FormatConfig { width, indent } = FormatConfig::default();
+        Self { width, indent }
+    }
+}
+
+/// Parse `input` and print it back in canonical form
+pub fn format(input: &str, options: &FormatOptions) -> String {
+    let config = FormatConfig {
+        width: options.width,
+        indent: options.indent,
+    };
+    format_document(&parse_document(input), &config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formatting_is_stable() {
+        let input = "{{{#table [[ [[a]] [[b]] ]] }}}\n**bold**";
+        let options = FormatOptions::default();
+        let formatted = format(input, &options);
+        assert_eq!(format(&formatted, &options), formatted);
+    }
+}
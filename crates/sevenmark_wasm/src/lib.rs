@@ -1,5 +1,13 @@
+mod format;
+mod lint;
+mod render;
+
 use wasm_bindgen::prelude::*;
 
+pub use format::{FormatOptions, format};
+pub use lint::{LintDiagnostic, lint};
+pub use render::{RenderOptions, ResolvedReferences, ResolvedTarget, render};
+
 /// Parse sevenmark to AST with UTF-16 absolute offsets (for CodeMirror 6)
 #[wasm_bindgen]
 pub fn parse_sevenmark_to_codemirror(input: &str) -> String {
@@ -18,3 +26,78 @@ pub fn parse_sevenmark(input: &str) -> String {
     let elements = parse_document(input);
     serde_json::to_string(&elements).unwrap_or_else(|e| format!(r#"{{"error":"{}"}}"#, e))
 }
+
+/// Parse sevenmark to a flat node table with UTF-16 offsets. Much cheaper
+/// to build and transfer than [`parse_sevenmark_to_codemirror`] when only
+/// node kinds and positions are needed; decode with `compact-ast.js`.
+#[wasm_bindgen]
+pub fn parse_sevenmark_compact(input: &str) -> CompactAst {
+    use sevenmark_parser::core::parse_document;
+    use sevenmark_utils::encode_compact_ast;
+
+    CompactAst(encode_compact_ast(&parse_document(input), input))
+}
+
+/// [`sevenmark_utils::CompactAst`] handed to JavaScript
+#[wasm_bindgen]
+pub struct CompactAst(sevenmark_utils::CompactAst);
+
+#[wasm_bindgen]
+impl CompactAst {
+    /// `Uint32Array` of 4 numbers per node: kind, parent, UTF-16 start and end
+    #[wasm_bindgen(getter)]
+    pub fn nodes(&self) -> Vec<u32> {
+        self.0.nodes.clone()
+    }
+
+    /// Kind names, indexed by each node's kind
+    #[wasm_bindgen(getter)]
+    pub fn strings(&self) -> Vec<String> {
+        self.0.strings.clone()
+    }
+}
+
+/// Render sevenmark to HTML. `config_json` is a [`RenderOptions`] object
+/// (base URLs and `spans`); an empty string uses the defaults.
+#[wasm_bindgen]
+pub fn render_sevenmark(input: &str, config_json: &str) -> Result<String, JsError> {
+    let options = parse_json::<RenderOptions>(config_json)?;
+    Ok(render(input, &options, None))
+}
+
+/// [`render_sevenmark`] after evaluating defines and conditionals and
+/// substituting includes locally. `resolved_json` is a [`ResolvedReferences`]
+/// object with the include sources and existing media targets fetched by
+/// the caller.
+#[wasm_bindgen]
+pub fn render_sevenmark_with_resolved(
+    input: &str,
+    config_json: &str,
+    resolved_json: &str,
+) -> Result<String, JsError> {
+    let options = parse_json::<RenderOptions>(config_json)?;
+    let resolved = parse_json::<ResolvedReferences>(resolved_json)?;
+    Ok(render(input, &options, Some(&resolved)))
+}
+
+/// Format sevenmark source. `config_json` is a [`FormatOptions`] object
+/// (`width`, `indent`); an empty string uses the defaults.
+#[wasm_bindgen]
+pub fn format_sevenmark(input: &str, config_json: &str) -> Result<String, JsError> {
+    let options = parse_json::<FormatOptions>(config_json)?;
+    Ok(format(input, &options))
+}
+
+/// Diagnostics as a JSON array of LSP `Diagnostic`s (UTF-16 line/character
+/// ranges), each with `offsets`: absolute UTF-16 `start`/`end`
+#[wasm_bindgen]
+pub fn lint_sevenmark(input: &str) -> String {
+    serde_json::to_string(&lint(input)).unwrap_or_else(|e| format!(r#"{{"error":"{}"}}"#, e))
+}
+
+fn parse_json<T: Default + serde::de::DeserializeOwned>(json: &str) -> Result<T, JsError> {
+    if json.trim().is_empty() {
+        return Ok(T::default());
+    }
+    serde_json::from_str(json).map_err(|e| JsError::new(&e.to_string()))
+}
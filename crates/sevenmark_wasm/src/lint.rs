@@ -0,0 +1,61 @@
+//! Diagnostics without the LSP worker
+
+use ls_types::Diagnostic;
+use serde::Serialize;
+use sevenmark_lsp_core::diagnostics::collect_diagnostics;
+use sevenmark_lsp_core::document::DocumentState;
+use sevenmark_utils::{Utf16OffsetConverter, Utf16Position};
+
+/// LSP `Diagnostic` plus its range as absolute UTF-16 offsets (CodeMirror's
+/// `from`/`to`)
+#[derive(Debug, Clone, Serialize)]
+pub struct LintDiagnostic {
+    #[serde(flatten)]
+    pub diagnostic: Diagnostic,
+    pub offsets: Utf16Position,
+}
+
+/// The diagnostics the language server publishes for `input`
+pub fn lint(input: &str) -> Vec<LintDiagnostic> {
+    let state = DocumentState::new(input.to_string());
+    let converter = Utf16OffsetConverter::new(&state.text);
+    let offset = |position: ls_types::Position| {
+        let byte = state.line_index.position_to_byte_offset(
+            &state.text,
+            position.line,
+            position.character,
+        );
+        converter.convert(byte)
+    };
+
+    collect_diagnostics(&state)
+        .into_iter()
+        .map(|diagnostic| LintDiagnostic {
+            offsets: Utf16Position {
+                start: offset(diagnostic.range.start),
+                end: offset(diagnostic.range.end),
+            },
+            diagnostic,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diagnostics_have_lsp_shape_and_utf16_offsets() {
+        let diagnostics = lint("가나\n[var(missing)]");
+        assert_eq!(diagnostics.len(), 1);
+
+        let json = serde_json::to_value(&diagnostics[0]).unwrap();
+        assert_eq!(json["message"], "Undefined variable: missing");
+        assert_eq!(json["severity"], 2);
+        assert_eq!(
+            json["range"]["start"],
+            serde_json::json!({"line": 1, "character": 0})
+        );
+        assert_eq!(json["offsets"], serde_json::json!({"start": 3, "end": 17}));
+    }
+}
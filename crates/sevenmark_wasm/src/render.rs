@@ -0,0 +1,227 @@
+//! Rendering in the browser, without a server round-trip
+
+use serde::Deserialize;
+use sevenmark_ast::{
+    Element, IncludeFailure, MediaElement, ResolvedDoc, ResolvedFile, ResolvedMediaInfo,
+    Traversable,
+};
+use sevenmark_html::{RenderConfig, render_document, render_document_with_spans};
+use sevenmark_parser::core::parse_document;
+use sevenmark_utils::define_if::process_defines_and_ifs;
+use sevenmark_utils::include::{IncludeResolver, IncludeTarget, substitute_includes};
+use sevenmark_utils::normalized_plain_text;
+use std::collections::HashMap;
+
+/// `config` of `render_sevenmark`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RenderOptions {
+    pub file_base_url: Option<String>,
+    pub document_base_url: Option<String>,
+    pub category_base_url: Option<String>,
+    pub user_base_url: Option<String>,
+    /// Add `data-start`/`data-end` UTF-16 offsets to the rendered elements
+    pub spans: bool,
+}
+
+impl RenderOptions {
+    fn render_config(&self) -> RenderConfig<'_> {
+        RenderConfig {
+            file_base_url: self.file_base_url.as_deref(),
+            document_base_url: self.document_base_url.as_deref(),
+            category_base_url: self.category_base_url.as_deref(),
+            user_base_url: self.user_base_url.as_deref(),
+        }
+    }
+}
+
+/// Includes and media targets looked up by the host, keyed by
+/// `Namespace:Title` (e.g. `Document:Infobox`, `File:Logo.png`)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ResolvedReferences {
+    /// Source text of each included document
+    pub includes: HashMap<String, String>,
+    /// Media targets that exist; the others are rendered as broken links
+    pub media: HashMap<String, ResolvedTarget>,
+}
+
+/// An existing media target. Files need a `url` to be shown.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ResolvedTarget {
+    pub url: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+/// Parse and render `input`. With `resolved`, defines and conditionals are
+/// evaluated, includes substituted (1-depth, as on the server) and media
+/// resolved first.
+pub fn render(
+    input: &str,
+    options: &RenderOptions,
+    resolved: Option<&ResolvedReferences>,
+) -> String {
+    let mut ast = parse_document(input);
+
+    if let Some(resolved) = resolved {
+        let mut variables = HashMap::new();
+        process_defines_and_ifs(&mut ast, &mut variables);
+        substitute_includes(&mut ast, &mut HostIncludes(&resolved.includes));
+        for element in &mut ast {
+            resolve_media(element, &resolved.media);
+        }
+    }
+
+    let config = options.render_config();
+    if options.spans {
+        render_document_with_spans(&ast, &config, input)
+    } else {
+        render_document(&ast, &config)
+    }
+}
+
+/// Include sources sent by the host, keyed by `Namespace:Title`
+struct HostIncludes<'a>(&'a HashMap<String, String>);
+
+impl IncludeResolver for HostIncludes<'_> {
+    fn resolve(&mut self, target: &IncludeTarget) -> Result<Vec<Element>, IncludeFailure> {
+        let key = format!("{}:{}", target.namespace, target.title);
+        self.0
+            .get(&key)
+            .map(|source| parse_document(source))
+            .ok_or(IncludeFailure::NotFound)
+    }
+}
+
+fn resolve_media(element: &mut Element, targets: &HashMap<String, ResolvedTarget>) {
+    if let Element::Media(media_elem) = element {
+        media_elem.resolved_info = resolved_info(media_elem, targets);
+    }
+
+    element.traverse_children(&mut |child| resolve_media(child, targets));
+}
+
+fn resolved_info(
+    media_elem: &MediaElement,
+    targets: &HashMap<String, ResolvedTarget>,
+) -> Option<ResolvedMediaInfo> {
+    let title = |name: &str| {
+        media_elem
+            .parameters
+            .get(name)
+            .and_then(|param| normalized_plain_text(&param.value))
+    };
+    let doc = |namespace: &str, title: String| ResolvedDoc {
+        is_valid: targets.contains_key(&format!("{namespace}:{title}")),
+        title,
+    };
+
+    let resolved = ResolvedMediaInfo {
+        file: title("file").map(|title| {
+            let target = targets.get(&format!("File:{title}"));
+            let url = target.and_then(|t| t.url.clone());
+            ResolvedFile {
+                is_valid: url.is_some(),
+                url: url.unwrap_or_default(),
+                width: target.and_then(|t| t.width),
+                height: target.and_then(|t| t.height),
+            }
+        }),
+        document: title("document").map(|title| doc("Document", title)),
+        category: title("category").map(|title| doc("Category", title)),
+        user: title("user").map(|title| doc("User", title)),
+        url: title("url"),
+    };
+
+    (resolved.file.is_some()
+        || resolved.document.is_some()
+        || resolved.category.is_some()
+        || resolved.user.is_some()
+        || resolved.url.is_some())
+    .then_some(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sevenmark_html::classes;
+
+    fn resolved(includes: &[(&str, &str)], media: &[(&str, Option<&str>)]) -> ResolvedReferences {
+        ResolvedReferences {
+            includes: includes
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            media: media
+                .iter()
+                .map(|(k, url)| {
+                    let target = ResolvedTarget {
+                        url: url.map(str::to_string),
+                        ..Default::default()
+                    };
+                    (k.to_string(), target)
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn includes_are_substituted_with_their_parameters() {
+        let refs = resolved(
+            &[(
+                "Document:Greeting",
+                "{{{#define #name=\"world\"}}}Hello [var(name)]!",
+            )],
+            &[],
+        );
+        let input = "{{{#include #name=\"SevenMark\" Greeting}}} {{{#include Missing}}}";
+        let html = render(input, &RenderOptions::default(), Some(&refs));
+
+        assert!(html.contains("<span>SevenMark</span>"));
+        assert!(html.contains("data-failure=\"not_found\""));
+        assert!(html.contains("Include not found: "));
+    }
+
+    #[test]
+    fn defines_and_conditionals_run_only_with_resolved_references() {
+        let input = "{{{#define #mode=\"dark\"}}}{{{#if [var(mode)] == \"dark\" :: Dark}}}";
+        let options = RenderOptions::default();
+
+        assert!(render(input, &options, Some(&ResolvedReferences::default())).contains("Dark"));
+        assert!(!render(input, &options, None).contains("Dark"));
+    }
+
+    #[test]
+    fn media_targets_come_from_the_host() {
+        let refs = resolved(
+            &[],
+            &[
+                ("File:Logo.png", Some("https://cdn.example/logo.png")),
+                ("Document:Rust", None),
+            ],
+        );
+        let input =
+            "[[#file=\"Logo.png\" logo]] [[#document=\"Rust\" rust]] [[#document=\"Go\" go]]";
+        let options = RenderOptions {
+            document_base_url: Some("/Document/".to_string()),
+            ..Default::default()
+        };
+        let html = render(input, &options, Some(&refs));
+
+        assert!(html.contains("https://cdn.example/logo.png"));
+        assert!(html.contains("href=\"/Document/Rust\""));
+        assert_eq!(html.matches(classes::MEDIA_LINK_INVALID).count(), 1);
+    }
+
+    #[test]
+    fn spans_option_adds_utf16_offsets() {
+        let options = RenderOptions {
+            spans: true,
+            ..Default::default()
+        };
+        assert!(render("가 **b**", &options, None).contains("data-start=\"2\""));
+        assert!(!render("가 **b**", &RenderOptions::default(), None).contains("data-start"));
+    }
+}
@@ -7,6 +7,13 @@ thread_local! {
     static LSP_STATE: RefCell<LspState> = RefCell::new(LspState::new());
 }
 
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = Date, js_name = now)]
+    fn date_now() -> f64;
+}
+
 /// Process a JSON-RPC message for the LSP and return a JSON response.
 ///
 /// Returns a JSON object with:
@@ -15,7 +22,11 @@ thread_local! {
 #[wasm_bindgen]
 pub fn handle_lsp_message(json: &str) -> String {
     LSP_STATE.with(|s| {
-        let result = s.borrow_mut().handle_message(json);
+        let mut state = s.borrow_mut();
+        // No system clock on wasm32: `[age]`/`[dday]` hints use the JS date
+        #[cfg(target_arch = "wasm32")]
+        state.set_today((date_now() / 86_400_000.0).floor() as i64);
+        let result = state.handle_message(json);
         serde_json::to_string(&result).unwrap()
     })
 }
@@ -55,6 +55,7 @@ export default defineConfig({
             { text: "Macros", link: "/grammar/macros" },
             { text: "Conditionals", link: "/grammar/conditionals" },
             { text: "Parameters", link: "/grammar/parameters" },
+            { text: "Element Reference", link: "/grammar/reference" },
           ],
         },
         {
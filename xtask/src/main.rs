@@ -57,6 +57,10 @@ fn main() {
                 exit(1);
             });
         }
+        Some("gen-docs") => gen_docs().unwrap_or_else(|error| {
+            eprintln!("Failed to generate docs: {error}");
+            exit(1);
+        }),
         _ => {
             eprintln!("Usage: cargo xtask <command>");
             eprintln!();
@@ -65,6 +69,9 @@ fn main() {
             eprintln!("  publish-dry  Dry run publish");
             eprintln!("  wasm-npm-pack     Build the bundler-target npm package for a wasm crate");
             eprintln!("  wasm-npm-publish  Build and publish the bundler-target npm package");
+            eprintln!(
+                "  gen-docs          Regenerate the element reference from sevenmark_registry"
+            );
             exit(1);
         }
     }
@@ -233,6 +240,13 @@ fn env_var(key: &str) -> Option<String> {
         .filter(|value| !value.is_empty())
 }
 
+fn gen_docs() -> Result<(), Box<dyn std::error::Error>> {
+    let path = workspace_root().join(sevenmark_registry::REFERENCE_DOC_PATH);
+    fs::write(&path, sevenmark_registry::reference_markdown())?;
+    println!("Wrote {}", path.display());
+    Ok(())
+}
+
 fn normalize_scope(scope: &str) -> String {
     scope.trim().trim_start_matches('@').to_string()
 }
@@ -295,7 +309,8 @@ fn wasm_npm_pack(options: &WasmNpmOptions) -> Result<PathBuf, Box<dyn std::error
 
     run_command(build, "wasm-pack build")?;
 
-    rewrite_package_json(&output_dir, options)?;
+    let helpers = copy_js_helpers(&crate_dir, &output_dir)?;
+    rewrite_package_json(&output_dir, options, &helpers)?;
     write_package_readme(&crate_dir, &output_dir, &options.npm_package_name())?;
     fs::copy(workspace_root.join("LICENSE"), output_dir.join("LICENSE"))?;
 
@@ -321,9 +336,43 @@ fn wasm_npm_publish(options: &WasmNpmPublishOptions) -> Result<(), Box<dyn std::
     Ok(())
 }
 
+/// Copies the hand-written `js/<name>.js` + `js/<name>.d.ts` modules of the
+/// crate into the package and returns their names
+fn copy_js_helpers(
+    crate_dir: &Path,
+    output_dir: &Path,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let js_dir = crate_dir.join("js");
+    if !js_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut helpers = Vec::new();
+    for entry in fs::read_dir(&js_dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("js") {
+            continue;
+        }
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or("Invalid helper file name")?
+            .to_string();
+        fs::copy(&path, output_dir.join(format!("{name}.js")))?;
+        fs::copy(
+            js_dir.join(format!("{name}.d.ts")),
+            output_dir.join(format!("{name}.d.ts")),
+        )?;
+        helpers.push(name);
+    }
+    helpers.sort();
+    Ok(helpers)
+}
+
 fn rewrite_package_json(
     output_dir: &Path,
     options: &WasmNpmOptions,
+    helpers: &[String],
 ) -> Result<(), Box<dyn std::error::Error>> {
     let package_json_path = output_dir.join("package.json");
     let package_json = fs::read_to_string(&package_json_path)?;
@@ -373,15 +422,23 @@ fn rewrite_package_json(
             "access": "public"
         }),
     );
-    object.insert(
-        "exports".to_string(),
-        json!({
-            ".": {
-                "types": format!("./{types}"),
-                "default": format!("./{main}")
-            }
-        }),
-    );
+    let mut exports = json!({
+        ".": {
+            "types": format!("./{types}"),
+            "default": format!("./{main}")
+        }
+    });
+    for name in helpers {
+        exports[format!("./{name}")] = json!({
+            "types": format!("./{name}.d.ts"),
+            "default": format!("./{name}.js")
+        });
+        if let Some(files) = object.get_mut("files").and_then(Value::as_array_mut) {
+            files.push(Value::String(format!("{name}.js")));
+            files.push(Value::String(format!("{name}.d.ts")));
+        }
+    }
+    object.insert("exports".to_string(), exports);
     object.insert("module".to_string(), Value::String(main));
 
     fs::write(package_json_path, serde_json::to_string_pretty(&value)?)?;